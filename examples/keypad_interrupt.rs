@@ -0,0 +1,89 @@
+//! Waiting for key presses on the INT pin, with plain blocking embedded-hal.
+//!
+//! The ROW15/INT pin of the HT16K33 is configured as an active-low INT output, and wired to a GPIO input.
+//! [wait_for_keys()] checks the pin with a growing backoff while the keypad is idle, and only reads the key RAM
+//! over I2C once a key is pressed, and while keys are held.
+//!
+//! On a board, pass the GPIO pin and the HAL delay:
+//!
+//! ```ignore
+//! let mut int_pin = gpioa.pa0.into_pull_up_input();
+//! ht16k33.write_command(INT_ACTIVE_LOW_COMMAND)?;
+//!
+//! loop {
+//!     if let Some(event) = keypad.wait_for_keys(&mut ht16k33, &mut int_pin, 1000, &mut delay)? {
+//!         handle(event);
+//!     }
+//! }
+//! ```
+//!
+//! This example runs on the host: the pin reads the INT flag of the mock chip, and the delay advances the mock
+//! time, pressing and releasing a key along the way.
+//!
+//! [wait_for_keys()]: ../ht16k33/keypad/struct.Keypad.html#method.wait_for_keys
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::digital::v2::InputPin;
+use ht16k33::i2c_mock::{I2cMock, SharedI2cMock};
+use ht16k33::keypad::{Keypad, INT_ACTIVE_LOW_COMMAND};
+use ht16k33::{Address, HT16K33};
+
+use std::cell::RefCell;
+use std::convert::Infallible;
+
+// The INT pin, low while the mock chip has its INT flag set.
+struct IntPin<'a>(&'a RefCell<I2cMock>);
+
+impl InputPin for IntPin<'_> {
+    type Error = Infallible;
+
+    fn is_high(&self) -> Result<bool, Infallible> {
+        Ok(!self.0.borrow().int_flag())
+    }
+
+    fn is_low(&self) -> Result<bool, Infallible> {
+        Ok(self.0.borrow().int_flag())
+    }
+}
+
+// Advances the mock time, and holds key K3 on scan line KS0 from 150 ms to 400 ms.
+struct Delay<'a> {
+    bus: &'a RefCell<I2cMock>,
+    now: u32,
+}
+
+impl DelayMs<u16> for Delay<'_> {
+    fn delay_ms(&mut self, ms: u16) {
+        self.now += ms as u32;
+        let now = self.now;
+
+        let mut bus = self.bus.borrow_mut();
+        bus.advance(ms as u32);
+        if (150..400).contains(&now) {
+            bus.press_key(0, 2).unwrap();
+        } else {
+            bus.release_key(0, 2).unwrap();
+        }
+    }
+}
+
+fn main() {
+    let bus = RefCell::new(I2cMock::new());
+    let mut ht16k33 = HT16K33::new(SharedI2cMock::new(&bus), Address::DEFAULT)
+        .initialize()
+        .unwrap();
+    ht16k33.write_command(INT_ACTIVE_LOW_COMMAND).unwrap();
+
+    let mut int_pin = IntPin(&bus);
+    let mut delay = Delay { bus: &bus, now: 0 };
+    let mut keypad: Keypad<8> = Keypad::default();
+
+    for _ in 0..3 {
+        let event = keypad
+            .wait_for_keys(&mut ht16k33, &mut int_pin, 500, &mut delay)
+            .unwrap();
+        println!("{:4} ms: {:?}", delay.now, event);
+    }
+
+    // The key RAM is only read around the key press, not while waiting.
+    println!("{} I2C transactions", bus.borrow().transactions().len());
+}
@@ -0,0 +1,83 @@
+//! Sharing the driver between RTIC tasks.
+//!
+//! In an RTIC application the driver lives in a `#[shared]` resource, and each task accesses it with
+//! `cx.shared.display.lock(|display| ...)`, which blocks the higher priority tasks using the same resource
+//! for as long as the closure runs. The driver is `Send` when the I2C device is, as RTIC requires.
+//!
+//! Keep the locks short by splitting the buffer updates from the I2C writes:
+//!
+//! * The high-priority tasks only update the display buffer, which never touches the bus, and spawn the flush.
+//! * A low-priority task checks [is_dirty()] and [flush()]es the changed rows.
+//!
+//! Only the flush holds the lock during the I2C write, which is at most one auto-increment write of the display
+//! RAM. To keep even that out of the lock, share a `Frame` instead of the driver, make the driver a `#[local]`
+//! resource of the flush task, and copy the frame with [set_frame()] before flushing.
+//!
+//! This example runs on the host: `Shared` stands in for the RTIC resource proxy, and the tasks are plain
+//! functions.
+//!
+//! ```ignore
+//! #[shared]
+//! struct Shared {
+//!     display: HT16K33<I2c<I2C1>>,
+//! }
+//!
+//! #[task(binds = EXTI0, shared = [display], priority = 2)]
+//! fn button(mut cx: button::Context) {
+//!     cx.shared.display.lock(|display| display.update_display_buffer(LED, true));
+//!     flush::spawn().ok();
+//! }
+//!
+//! #[task(shared = [display], priority = 1)]
+//! fn flush(mut cx: flush::Context) {
+//!     cx.shared.display.lock(|display| display.flush()).ok();
+//! }
+//! ```
+//!
+//! [is_dirty()]: ../ht16k33/struct.HT16K33.html#method.is_dirty
+//! [flush()]: ../ht16k33/struct.HT16K33.html#method.flush
+//! [set_frame()]: ../ht16k33/struct.HT16K33.html#method.set_frame
+use ht16k33::i2c_mock::I2cMock;
+use ht16k33::{Address, LedLocation, HT16K33};
+
+use std::sync::Mutex;
+
+// Stands in for an RTIC shared resource proxy.
+struct Shared<T>(Mutex<T>);
+
+impl<T> Shared<T> {
+    fn lock<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.0.lock().unwrap())
+    }
+}
+
+// The high-priority task: update the display buffer, without any I2C access.
+fn button(display: &Shared<HT16K33<I2cMock>>, row: u8) {
+    let location = LedLocation::new(row, 0).unwrap();
+    display.lock(|display| display.update_display_buffer(location, true));
+}
+
+// The low-priority task: write the changes, if any.
+fn flush(display: &Shared<HT16K33<I2cMock>>) {
+    display.lock(|display| {
+        if display.is_dirty() {
+            display.flush().unwrap();
+        }
+    });
+}
+
+fn main() {
+    let driver = HT16K33::new(I2cMock::new(), Address::DEFAULT)
+        .initialize()
+        .unwrap();
+    let display = Shared(Mutex::new(driver));
+
+    button(&display, 1);
+    button(&display, 2);
+    flush(&display);
+    flush(&display);
+
+    let i2c = display.0.into_inner().unwrap().destroy();
+    // The initialization, and a single write of both rows.
+    println!("{} I2C transactions", i2c.transactions().len());
+}
@@ -0,0 +1,307 @@
+//! # bargraph
+//!
+//! Support for the Adafruit bicolor 24-bar bargraph backpack.
+//!
+//! Each bar is a red and a green LED. The bars are wired in a scrambled order: both halves of 12 bars share
+//! the same rows, and the second half uses the upper four commons.
+//!
+//! | Bar LED | Row                    | Common                         |
+//! |---------|------------------------|--------------------------------|
+//! | Red     | `2 * ((bar % 12) / 4)` | `bar % 4`, `+ 4` for bars 12-23 |
+//! | Green   | Red row `+ 1`          | Same as red                    |
+use crate::constants::COMMONS_SIZE;
+use crate::errors::ValidationError;
+use crate::hal::blocking::i2c::Write;
+use crate::types::{Color, LedLocation};
+use crate::HT16K33;
+
+/// The number of bars on the bargraph.
+pub const BARS_SIZE: u8 = 24;
+
+// The bargraph has one bar per common on each of its 3 cathode row pairs.
+const _: () = assert!(BARS_SIZE as usize <= 3 * COMMONS_SIZE);
+
+/// Return the (green, red) LED locations of a bar.
+///
+/// # Errors
+///
+/// If the `index` is not less than [`BARS_SIZE`] then [`ht16k33::ValidationError::ValueTooLarge`] is returned.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::bargraph::bar_locations;
+/// use ht16k33::LedLocation;
+/// # use ht16k33::ValidationError;
+/// # fn main() -> Result<(), ValidationError> {
+///
+/// let (green, red) = bar_locations(13)?;
+///
+/// assert_eq!(LedLocation::new(1, 5)?, green);
+/// assert_eq!(LedLocation::new(0, 5)?, red);
+///
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`BARS_SIZE`]: constant.BARS_SIZE.html
+/// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+pub fn bar_locations(index: u8) -> Result<(LedLocation, LedLocation), ValidationError> {
+    if index >= BARS_SIZE {
+        return Err(ValidationError::ValueTooLarge {
+            name: "index",
+            value: index,
+            limit: BARS_SIZE,
+            inclusive: false,
+        });
+    }
+
+    let half = index / 12;
+    let cathode = (index % 12) / 4;
+    let anode = index % 4 + half * 4;
+
+    let red = LedLocation::new(cathode * 2, anode)?;
+    let green = LedLocation::new(cathode * 2 + 1, anode)?;
+
+    Ok((green, red))
+}
+
+/// An Adafruit bicolor 24-bar bargraph backpack, wrapping an initialized [`HT16K33`] driver.
+///
+/// The bar helpers update the display buffer, which must be written using
+/// [write_display_buffer()](struct.Bargraph24.html#method.write_display_buffer) for the change to be
+/// displayed.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use ht16k33::bargraph::Bargraph24;
+/// use ht16k33::{Color, HT16K33};
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// let ht16k33 = HT16K33::new(i2c, address).initialize()?;
+///
+/// let mut bargraph = Bargraph24::new(ht16k33);
+/// bargraph.set_level(18, Color::Green)?;
+/// bargraph.set_bar(23, Color::Red)?;
+/// bargraph.write_display_buffer()?;
+///
+/// assert_eq!(Color::Green, bargraph.bar(17)?);
+/// assert_eq!(Color::Off, bargraph.bar(18)?);
+///
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`HT16K33`]: ../struct.HT16K33.html
+pub struct Bargraph24<I2C> {
+    driver: HT16K33<I2C>,
+}
+
+impl<I2C, E> Bargraph24<I2C>
+where
+    I2C: Write<Error = E>,
+{
+    /// Create a `Bargraph24` from the given driver.
+    pub fn new(driver: HT16K33<I2C>) -> Self {
+        Bargraph24 { driver }
+    }
+
+    /// Return the driver.
+    pub fn driver(&self) -> &HT16K33<I2C> {
+        &self.driver
+    }
+
+    /// Return the driver for modification, e.g. to change the dimming.
+    pub fn driver_mut(&mut self) -> &mut HT16K33<I2C> {
+        &mut self.driver
+    }
+
+    /// Return the driver, consuming the bargraph.
+    pub fn into_driver(self) -> HT16K33<I2C> {
+        self.driver
+    }
+
+    /// Write the display buffer to the HT16K33 chip.
+    pub fn write_display_buffer(&mut self) -> Result<(), E> {
+        self.driver.write_display_buffer()
+    }
+
+    /// Turn off all bars.
+    pub fn clear(&mut self) {
+        self.driver.clear_display_buffer();
+    }
+
+    /// Set the color of a bar.
+    ///
+    /// # Errors
+    ///
+    /// If the `index` is not less than [`BARS_SIZE`] then [`ht16k33::ValidationError::ValueTooLarge`] is
+    /// returned.
+    ///
+    /// [`BARS_SIZE`]: constant.BARS_SIZE.html
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    pub fn set_bar(&mut self, index: u8, color: Color) -> Result<(), ValidationError> {
+        let (green, red) = bar_locations(index)?;
+
+        self.driver.update_display_buffer(green, color.green());
+        self.driver.update_display_buffer(red, color.red());
+
+        Ok(())
+    }
+
+    /// Return the color of a bar.
+    ///
+    /// # Errors
+    ///
+    /// If the `index` is not less than [`BARS_SIZE`] then [`ht16k33::ValidationError::ValueTooLarge`] is
+    /// returned.
+    ///
+    /// [`BARS_SIZE`]: constant.BARS_SIZE.html
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    pub fn bar(&self, index: u8) -> Result<Color, ValidationError> {
+        let (green, red) = bar_locations(index)?;
+
+        let buffer = self.driver.display_buffer();
+        Ok(Color::from_leds(
+            buffer[green.row_as_index()].contains(green.common),
+            buffer[red.row_as_index()].contains(red.common),
+        ))
+    }
+
+    /// Fill the first `level` bars with the `fill_color`, and turn off the remaining bars.
+    ///
+    /// # Errors
+    ///
+    /// If the `level` is larger than [`BARS_SIZE`] then [`ht16k33::ValidationError::ValueTooLarge`] is
+    /// returned.
+    ///
+    /// [`BARS_SIZE`]: constant.BARS_SIZE.html
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    pub fn set_level(&mut self, level: u8, fill_color: Color) -> Result<(), ValidationError> {
+        if level > BARS_SIZE {
+            return Err(ValidationError::ValueTooLarge {
+                name: "level",
+                value: level,
+                limit: BARS_SIZE,
+                inclusive: true,
+            });
+        }
+
+        for index in 0..BARS_SIZE {
+            let color = if index < level {
+                fill_color
+            } else {
+                Color::Off
+            };
+            self.set_bar(index, color)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c_mock::I2cMock;
+    use crate::types::Address;
+
+    fn bargraph() -> Bargraph24<I2cMock> {
+        Bargraph24::new(
+            HT16K33::new(I2cMock::new(), Address::DEFAULT)
+                .initialize()
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn bar_locations_unique() {
+        let mut seen = [[false; 8]; 6];
+
+        for index in 0..BARS_SIZE {
+            let (green, red) = bar_locations(index).unwrap();
+
+            for location in [green, red].iter() {
+                let row = location.row_as_index();
+                let common = location.common.bits().trailing_zeros() as usize;
+                assert!(!seen[row][common], "bar {} reuses an LED", index);
+                seen[row][common] = true;
+            }
+        }
+    }
+
+    #[test]
+    fn bar_locations_first_and_last() {
+        assert_eq!(
+            (
+                LedLocation::new(1, 0).unwrap(),
+                LedLocation::new(0, 0).unwrap()
+            ),
+            bar_locations(0).unwrap()
+        );
+        assert_eq!(
+            (
+                LedLocation::new(5, 7).unwrap(),
+                LedLocation::new(4, 7).unwrap()
+            ),
+            bar_locations(23).unwrap()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn bar_locations_out_of_range() {
+        bar_locations(BARS_SIZE).unwrap();
+    }
+
+    #[test]
+    fn set_bar() {
+        let mut bargraph = bargraph();
+
+        bargraph.set_bar(0, Color::Red).unwrap();
+        bargraph.set_bar(12, Color::Green).unwrap();
+        bargraph.set_bar(5, Color::Yellow).unwrap();
+
+        let buffer = bargraph.driver().display_buffer();
+        assert_eq!(0b0000_0001, buffer[0].bits());
+        assert_eq!(0b0001_0000, buffer[1].bits());
+        assert_eq!(0b0000_0010, buffer[2].bits());
+        assert_eq!(0b0000_0010, buffer[3].bits());
+
+        assert_eq!(Color::Red, bargraph.bar(0).unwrap());
+        assert_eq!(Color::Green, bargraph.bar(12).unwrap());
+        assert_eq!(Color::Yellow, bargraph.bar(5).unwrap());
+        assert_eq!(Color::Off, bargraph.bar(1).unwrap());
+
+        bargraph.set_bar(5, Color::Off).unwrap();
+        assert_eq!(Color::Off, bargraph.bar(5).unwrap());
+    }
+
+    #[test]
+    fn set_level() {
+        let mut bargraph = bargraph();
+
+        bargraph.set_level(24, Color::Red).unwrap();
+        bargraph.set_level(10, Color::Yellow).unwrap();
+
+        for index in 0..BARS_SIZE {
+            let expected = if index < 10 {
+                Color::Yellow
+            } else {
+                Color::Off
+            };
+            assert_eq!(expected, bargraph.bar(index).unwrap(), "bar {}", index);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_level_too_large() {
+        bargraph().set_level(BARS_SIZE + 1, Color::Green).unwrap();
+    }
+}
@@ -0,0 +1,219 @@
+//! # bicolor
+//!
+//! Support for the Adafruit bicolor 8x8 LED matrix backpack.
+//!
+//! Each pixel is a green and a red LED, on the same common but in a pair of adjacent rows of the display RAM:
+//!
+//! | Row       | Contents                       |
+//! |-----------|--------------------------------|
+//! | `2*y`     | Green LEDs of pixel row `y`    |
+//! | `2*y + 1` | Red LEDs of pixel row `y`      |
+//!
+//! Within a row, the pixel column `x` is common `x`.
+use crate::chain::check_bounds;
+use crate::errors::ValidationError;
+use crate::hal::blocking::i2c::Write;
+use crate::types::{Color, DisplayData};
+use crate::HT16K33;
+
+/// The width and height of the matrix, in pixels.
+pub const MATRIX_SIZE: u8 = 8;
+
+/// An Adafruit bicolor 8x8 LED matrix backpack, wrapping an initialized [`HT16K33`] driver.
+///
+/// The pixel helpers update the display buffer, which must be written using
+/// [write_display_buffer()](struct.BicolorMatrix8x8.html#method.write_display_buffer) for the change to be
+/// displayed.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use ht16k33::bicolor::BicolorMatrix8x8;
+/// use ht16k33::{Color, HT16K33};
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// let ht16k33 = HT16K33::new(i2c, address).initialize()?;
+///
+/// let mut matrix = BicolorMatrix8x8::new(ht16k33);
+/// matrix.set_pixel_color(1, 2, Color::Yellow)?;
+/// matrix.write_display_buffer()?;
+///
+/// assert_eq!(Color::Yellow, matrix.pixel_color(1, 2)?);
+///
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`HT16K33`]: ../struct.HT16K33.html
+pub struct BicolorMatrix8x8<I2C> {
+    driver: HT16K33<I2C>,
+}
+
+impl<I2C, E> BicolorMatrix8x8<I2C>
+where
+    I2C: Write<Error = E>,
+{
+    /// Create a `BicolorMatrix8x8` from the given driver.
+    pub fn new(driver: HT16K33<I2C>) -> Self {
+        BicolorMatrix8x8 { driver }
+    }
+
+    /// Return the driver.
+    pub fn driver(&self) -> &HT16K33<I2C> {
+        &self.driver
+    }
+
+    /// Return the driver for modification, e.g. to change the dimming.
+    pub fn driver_mut(&mut self) -> &mut HT16K33<I2C> {
+        &mut self.driver
+    }
+
+    /// Return the driver, consuming the matrix.
+    pub fn into_driver(self) -> HT16K33<I2C> {
+        self.driver
+    }
+
+    /// Write the display buffer to the HT16K33 chip.
+    pub fn write_display_buffer(&mut self) -> Result<(), E> {
+        self.driver.write_display_buffer()
+    }
+
+    /// Turn off all pixels.
+    pub fn clear(&mut self) {
+        self.driver.clear_display_buffer();
+    }
+
+    /// Set the color of the pixel at (`x`, `y`), updating both its green and red LEDs.
+    ///
+    /// # Errors
+    ///
+    /// If `x` or `y` is not less than [`MATRIX_SIZE`] then [`ht16k33::ValidationError::ValueTooLarge`] is
+    /// returned.
+    ///
+    /// [`MATRIX_SIZE`]: constant.MATRIX_SIZE.html
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    pub fn set_pixel_color(&mut self, x: u8, y: u8, color: Color) -> Result<(), ValidationError> {
+        check_bounds(x, y, MATRIX_SIZE, MATRIX_SIZE)?;
+
+        let (green, red) = rows(y);
+        let common = DisplayData::from_bits_truncate(1 << x);
+
+        let buffer = self.driver.display_buffer_mut();
+        buffer[green].set(common, color.green());
+        buffer[red].set(common, color.red());
+
+        Ok(())
+    }
+
+    /// Return the color of the pixel at (`x`, `y`).
+    ///
+    /// # Errors
+    ///
+    /// If `x` or `y` is not less than [`MATRIX_SIZE`] then [`ht16k33::ValidationError::ValueTooLarge`] is
+    /// returned.
+    ///
+    /// [`MATRIX_SIZE`]: constant.MATRIX_SIZE.html
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    pub fn pixel_color(&self, x: u8, y: u8) -> Result<Color, ValidationError> {
+        check_bounds(x, y, MATRIX_SIZE, MATRIX_SIZE)?;
+
+        let (green, red) = rows(y);
+        let common = DisplayData::from_bits_truncate(1 << x);
+
+        let buffer = self.driver.display_buffer();
+        Ok(Color::from_leds(
+            buffer[green].contains(common),
+            buffer[red].contains(common),
+        ))
+    }
+
+    /// Fill all pixels with the given `color`.
+    pub fn fill(&mut self, color: Color) {
+        let buffer = self.driver.display_buffer_mut();
+
+        for y in 0..MATRIX_SIZE {
+            let (green, red) = rows(y);
+            buffer[green] = plane(color.green());
+            buffer[red] = plane(color.red());
+        }
+    }
+}
+
+// Return the green and red display RAM rows of the pixel row `y`.
+fn rows(y: u8) -> (usize, usize) {
+    let green = 2 * y as usize;
+    (green, green + 1)
+}
+
+// Return a full or empty row.
+fn plane(enabled: bool) -> DisplayData {
+    if enabled {
+        DisplayData::all()
+    } else {
+        DisplayData::COMMON_NONE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c_mock::I2cMock;
+    use crate::types::Address;
+
+    fn matrix() -> BicolorMatrix8x8<I2cMock> {
+        BicolorMatrix8x8::new(
+            HT16K33::new(I2cMock::new(), Address::DEFAULT)
+                .initialize()
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn set_pixel_color() {
+        let mut matrix = matrix();
+
+        matrix.set_pixel_color(3, 2, Color::Green).unwrap();
+        matrix.set_pixel_color(4, 2, Color::Red).unwrap();
+        matrix.set_pixel_color(5, 2, Color::Yellow).unwrap();
+
+        let buffer = matrix.driver().display_buffer();
+        assert_eq!(0b0010_1000, buffer[4].bits());
+        assert_eq!(0b0011_0000, buffer[5].bits());
+
+        assert_eq!(Color::Green, matrix.pixel_color(3, 2).unwrap());
+        assert_eq!(Color::Red, matrix.pixel_color(4, 2).unwrap());
+        assert_eq!(Color::Yellow, matrix.pixel_color(5, 2).unwrap());
+        assert_eq!(Color::Off, matrix.pixel_color(6, 2).unwrap());
+
+        // Changing the color clears the other plane.
+        matrix.set_pixel_color(5, 2, Color::Green).unwrap();
+        assert_eq!(Color::Green, matrix.pixel_color(5, 2).unwrap());
+
+        matrix.set_pixel_color(5, 2, Color::Off).unwrap();
+        assert_eq!(Color::Off, matrix.pixel_color(5, 2).unwrap());
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_pixel_color_out_of_bounds() {
+        matrix().set_pixel_color(0, 8, Color::Red).unwrap();
+    }
+
+    #[test]
+    fn fill_and_write() {
+        let mut matrix = matrix();
+
+        matrix.fill(Color::Red);
+        matrix.write_display_buffer().unwrap();
+
+        let i2c = matrix.into_driver().destroy();
+        for (row, value) in i2c.device(Address::DEFAULT).data_values.iter().enumerate() {
+            let expected = if row % 2 == 1 { 0xff } else { 0 };
+            assert_eq!(expected, *value, "row {}", row);
+        }
+    }
+}
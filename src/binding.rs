@@ -0,0 +1,491 @@
+//! # binding
+//!
+//! Bind a polled value to a widget.
+//!
+//! A [`Binding`](struct.Binding.html) reads a value from a closure once per refresh period and shows it on a
+//! [`Bindable`](trait.Bindable.html) widget, replacing the usual read-format-display loop:
+//!
+//! | Widget                                               | Values       | Shown as                         |
+//! |------------------------------------------------------|--------------|----------------------------------|
+//! | [`SevenSegment`](../seven_segment/struct.SevenSegment.html) | `i32`, `f32` | A number, or `----` if too large |
+//! | [`Bargraph24`](../bargraph/struct.Bargraph24.html)   | `i32`        | The number of lit bars           |
+//! | [`Gauge`](struct.Gauge.html)                         | `i32`        | A proportion of a range          |
+//!
+//! A [`Deadband`](struct.Deadband.html) in the closure keeps sensor jitter from flickering the last digit, and
+//! [`Smoothing`](struct.Smoothing.html) averages out noisy analog inputs.
+use crate::bargraph::{Bargraph24, BARS_SIZE};
+use crate::content::elapsed;
+use crate::hal::blocking::i2c::Write;
+use crate::progress::ProgressReporter;
+use crate::seven_segment::{Segments, SevenSegment, DIGITS_SIZE};
+use crate::types::Color;
+use crate::HT16K33;
+use core::ops::Sub;
+
+/// A widget that shows values of type `T`.
+pub trait Bindable<T> {
+    /// The error writing to the display.
+    type Error;
+
+    /// Show the `value` and write it to the display.
+    fn show(&mut self, value: T) -> Result<(), Self::Error>;
+}
+
+/// Shows the value of a closure on a widget, at most once per refresh period.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use ht16k33::HT16K33;
+/// use ht16k33::binding::Binding;
+/// use ht16k33::seven_segment::SevenSegment;
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// let mut display = SevenSegment::new(HT16K33::new(i2c, address).initialize()?);
+///
+/// # let read_temperature = || 21.5f32;
+/// let mut temperature = Binding::new(read_temperature, 1000);
+///
+/// let mut now = 0;
+/// while now < 5000 {
+///     temperature.poll(&mut display, now)?;
+///     now += 100;
+/// }
+///
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Binding<F> {
+    source: F,
+    period: u32,
+
+    // When the value was last shown, `None` before the first poll.
+    last: Option<u32>,
+}
+
+impl<F> Binding<F> {
+    /// Create a `Binding`.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The closure returning the value.
+    /// * `period` - The minimum time between refreshes, in milliseconds.
+    pub fn new(source: F, period: u32) -> Self {
+        Binding {
+            source,
+            period,
+            last: None,
+        }
+    }
+
+    /// Return the refresh period, in milliseconds.
+    pub fn period(&self) -> u32 {
+        self.period
+    }
+
+    /// Refresh on the next poll.
+    pub fn invalidate(&mut self) {
+        self.last = None;
+    }
+
+    /// Read the value and show it on the `widget` if a refresh is due at `now`, returning whether it was
+    /// shown.
+    pub fn poll<T, W>(&mut self, widget: &mut W, now: u32) -> Result<bool, W::Error>
+    where
+        F: FnMut() -> T,
+        W: Bindable<T>,
+    {
+        if let Some(last) = self.last {
+            if !elapsed(now, last, self.period) {
+                return Ok(false);
+            }
+        }
+
+        widget.show((self.source)())?;
+        self.last = Some(now);
+
+        Ok(true)
+    }
+}
+
+/// Holds the shown value until the input moves away from it by more than a threshold.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::binding::{Binding, Deadband};
+///
+/// # let read_temperature = || 21.52f32;
+/// let mut deadband = Deadband::new(0.2);
+/// let temperature = Binding::new(move || deadband.filter(read_temperature()), 1000);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Deadband<T> {
+    threshold: T,
+    shown: Option<T>,
+}
+
+impl<T> Deadband<T>
+where
+    T: Copy + PartialOrd + Sub<Output = T>,
+{
+    /// Create a `Deadband`, changes of at most `threshold` are ignored.
+    pub fn new(threshold: T) -> Self {
+        Deadband {
+            threshold,
+            shown: None,
+        }
+    }
+
+    /// Return the shown value, `None` before the first value.
+    pub fn shown(&self) -> Option<T> {
+        self.shown
+    }
+
+    /// Show the next value even if it is within the threshold.
+    pub fn reset(&mut self) {
+        self.shown = None;
+    }
+
+    /// Return the value to show for the input `value`.
+    pub fn filter(&mut self, value: T) -> T {
+        match self.shown {
+            Some(shown) => {
+                let change = if value > shown {
+                    value - shown
+                } else {
+                    shown - value
+                };
+
+                if change > self.threshold {
+                    self.shown = Some(value);
+                    value
+                } else {
+                    shown
+                }
+            }
+            None => {
+                self.shown = Some(value);
+                value
+            }
+        }
+    }
+}
+
+/// The `alpha` of [`Smoothing`](struct.Smoothing.html) that follows the input without smoothing.
+pub const ALPHA_ONE: u16 = 256;
+
+/// Exponential moving average of integer values, without floating point.
+///
+/// Each value moves the average by `alpha / ALPHA_ONE` of its distance from the average, so a smaller `alpha`
+/// smooths more but follows changes more slowly.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::binding::{Binding, Smoothing};
+///
+/// # let read_adc = || 512i32;
+/// // Each reading counts for a quarter.
+/// let mut smoothing = Smoothing::new(64);
+/// let level = Binding::new(move || smoothing.filter(read_adc()), 100);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Smoothing {
+    alpha: u16,
+
+    // The average scaled by `ALPHA_ONE`, to keep the fraction between values.
+    average: Option<i64>,
+}
+
+impl Smoothing {
+    /// Create a `Smoothing`, with `alpha` clamped to `1..=ALPHA_ONE`.
+    pub fn new(alpha: u16) -> Self {
+        Smoothing {
+            alpha: alpha.clamp(1, ALPHA_ONE),
+            average: None,
+        }
+    }
+
+    /// Return the smoothing factor, out of [`ALPHA_ONE`](constant.ALPHA_ONE.html).
+    pub fn alpha(&self) -> u16 {
+        self.alpha
+    }
+
+    /// Start again from the next value.
+    pub fn reset(&mut self) {
+        self.average = None;
+    }
+
+    /// Add the `value` to the average, returning the new average rounded to the nearest.
+    pub fn filter(&mut self, value: i32) -> i32 {
+        let one = ALPHA_ONE as i64;
+        let scaled = value as i64 * one;
+
+        let average = match self.average {
+            Some(average) => average + (scaled - average) * self.alpha as i64 / one,
+            None => scaled,
+        };
+        self.average = Some(average);
+
+        // Round half away from zero.
+        let half = if average < 0 { -one / 2 } else { one / 2 };
+        ((average + half) / one) as i32
+    }
+}
+
+impl<I2C, E> Bindable<f32> for SevenSegment<I2C>
+where
+    I2C: Write<Error = E>,
+{
+    type Error = E;
+
+    /// Show the value with as many decimals as fit.
+    fn show(&mut self, value: f32) -> Result<(), E> {
+        if self.display_float(value, DIGITS_SIZE as u8 - 1).is_err() {
+            show_overflow(self);
+        }
+
+        self.write_display_buffer()
+    }
+}
+
+impl<I2C, E> Bindable<i32> for SevenSegment<I2C>
+where
+    I2C: Write<Error = E>,
+{
+    type Error = E;
+
+    fn show(&mut self, value: i32) -> Result<(), E> {
+        // Values that fit on the display are exact as `f32`.
+        let fits = value > -1000 && value < 10_000;
+        if !fits || self.display_float(value as f32, 0).is_err() {
+            show_overflow(self);
+        }
+
+        self.write_display_buffer()
+    }
+}
+
+// Show `----` on every digit.
+fn show_overflow<I2C, E>(display: &mut SevenSegment<I2C>)
+where
+    I2C: Write<Error = E>,
+{
+    for index in 0..DIGITS_SIZE as u8 {
+        // Every digit index is valid.
+        let _ = display.set_segments(index, Segments::MINUS);
+    }
+}
+
+impl<I2C, E> Bindable<i32> for Bargraph24<I2C>
+where
+    I2C: Write<Error = E>,
+{
+    type Error = E;
+
+    /// Light the given number of bars in green, clamped to the bargraph.
+    fn show(&mut self, value: i32) -> Result<(), E> {
+        let level = value.max(0).min(BARS_SIZE as i32) as u8;
+
+        // The level is clamped to the bargraph.
+        let _ = self.set_level(level, Color::Green);
+
+        self.write_display_buffer()
+    }
+}
+
+/// Shows where a value is within a range, using a [`ProgressReporter`](../progress/enum.ProgressReporter.html).
+pub struct Gauge<'a, I2C> {
+    driver: HT16K33<I2C>,
+    reporter: ProgressReporter<'a>,
+    min: i32,
+    max: i32,
+}
+
+impl<'a, I2C, E> Gauge<'a, I2C>
+where
+    I2C: Write<Error = E>,
+{
+    /// Create a `Gauge`, showing `min` as empty and `max` as full.
+    pub fn new(driver: HT16K33<I2C>, reporter: ProgressReporter<'a>, min: i32, max: i32) -> Self {
+        Gauge {
+            driver,
+            reporter,
+            min,
+            max,
+        }
+    }
+
+    /// Return the driver.
+    pub fn driver(&self) -> &HT16K33<I2C> {
+        &self.driver
+    }
+
+    /// Return the driver, consuming the gauge.
+    pub fn into_driver(self) -> HT16K33<I2C> {
+        self.driver
+    }
+
+    /// Return the percentage of the range at `value`, clamped to the range.
+    pub fn percent(&self, value: i32) -> u8 {
+        if self.max <= self.min {
+            return if value >= self.max { 100 } else { 0 };
+        }
+
+        let value = value.max(self.min).min(self.max);
+        let offset = value as i64 - self.min as i64;
+        let range = self.max as i64 - self.min as i64;
+
+        (offset * 100 / range) as u8
+    }
+}
+
+impl<I2C, E> Bindable<i32> for Gauge<'_, I2C>
+where
+    I2C: Write<Error = E>,
+{
+    type Error = E;
+
+    fn show(&mut self, value: i32) -> Result<(), E> {
+        let percent = self.percent(value);
+        self.reporter.report(&mut self.driver, percent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c_mock::I2cMock;
+    use crate::seven_segment::DIGIT_ROWS;
+    use crate::types::Address;
+
+    fn seven_segment() -> SevenSegment<I2cMock> {
+        SevenSegment::new(
+            HT16K33::new(I2cMock::new(), Address::DEFAULT)
+                .initialize()
+                .unwrap(),
+        )
+    }
+
+    fn digits(display: &SevenSegment<I2cMock>) -> [u8; DIGITS_SIZE] {
+        let mut digits = [0; DIGITS_SIZE];
+        for (digit, row) in digits.iter_mut().zip(DIGIT_ROWS.iter()) {
+            *digit = display.driver().display_buffer()[*row].bits();
+        }
+        digits
+    }
+
+    #[test]
+    fn period() {
+        let mut display = seven_segment();
+        let mut value = 0;
+        let mut binding = Binding::new(
+            || {
+                value += 1;
+                value
+            },
+            100,
+        );
+
+        assert!(binding.poll(&mut display, 0).unwrap());
+        assert!(!binding.poll(&mut display, 99).unwrap());
+        assert!(binding.poll(&mut display, 100).unwrap());
+
+        binding.invalidate();
+        assert!(binding.poll(&mut display, 101).unwrap());
+
+        assert_eq!([0, 0, 0, Segments::DIGIT_3.bits()], digits(&display));
+    }
+
+    #[test]
+    fn seven_segment_overflow() {
+        let mut display = seven_segment();
+
+        display.show(12_345).unwrap();
+        assert_eq!([Segments::MINUS.bits(); DIGITS_SIZE], digits(&display));
+
+        display.show(1.25f32).unwrap();
+        assert_eq!(
+            [
+                (Segments::DIGIT_1 | Segments::DP).bits(),
+                Segments::DIGIT_2.bits(),
+                Segments::DIGIT_5.bits(),
+                Segments::DIGIT_0.bits()
+            ],
+            digits(&display)
+        );
+    }
+
+    #[test]
+    fn bargraph() {
+        let mut bargraph = Bargraph24::new(
+            HT16K33::new(I2cMock::new(), Address::DEFAULT)
+                .initialize()
+                .unwrap(),
+        );
+
+        bargraph.show(30).unwrap();
+        assert_eq!(Color::Green, bargraph.bar(23).unwrap());
+
+        bargraph.show(-1).unwrap();
+        assert_eq!(Color::Off, bargraph.bar(0).unwrap());
+    }
+
+    #[test]
+    fn gauge_percent() {
+        let driver = HT16K33::new(I2cMock::new(), Address::DEFAULT)
+            .initialize()
+            .unwrap();
+        let gauge = Gauge::new(driver, ProgressReporter::Bargraph24, -20, 30);
+
+        assert_eq!(0, gauge.percent(-50));
+        assert_eq!(40, gauge.percent(0));
+        assert_eq!(100, gauge.percent(i32::MAX));
+    }
+
+    #[test]
+    fn deadband() {
+        let mut deadband = Deadband::new(2);
+
+        assert_eq!(10, deadband.filter(10));
+        assert_eq!(10, deadband.filter(12));
+        assert_eq!(10, deadband.filter(8));
+        assert_eq!(13, deadband.filter(13));
+        assert_eq!(13, deadband.filter(11));
+
+        deadband.reset();
+        assert_eq!(11, deadband.filter(11));
+
+        let mut deadband = Deadband::new(0.25f32);
+        assert_eq!(21.5, deadband.filter(21.5));
+        assert_eq!(21.5, deadband.filter(21.7));
+        assert_eq!(21.2, deadband.filter(21.2));
+    }
+
+    #[test]
+    fn smoothing() {
+        let mut smoothing = Smoothing::new(ALPHA_ONE / 2);
+
+        assert_eq!(100, smoothing.filter(100));
+        assert_eq!(150, smoothing.filter(200));
+        assert_eq!(175, smoothing.filter(200));
+
+        // The fraction is kept, so the average converges.
+        for _ in 0..16 {
+            smoothing.filter(200);
+        }
+        assert_eq!(200, smoothing.filter(200));
+
+        smoothing.reset();
+        assert_eq!(-7, smoothing.filter(-7));
+
+        assert_eq!(1, Smoothing::new(0).alpha());
+        assert_eq!(42, Smoothing::new(ALPHA_ONE + 1).filter(42));
+    }
+}
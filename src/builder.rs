@@ -0,0 +1,158 @@
+//! # builder
+//!
+//! A chainable builder for the initial configuration of the [`HT16K33`](../struct.HT16K33.html) driver, see
+//! [builder()](../struct.HT16K33.html#method.builder).
+use crate::hal::blocking::i2c::Write;
+use crate::types::{
+    BlinkRate, Dimming, Display, Flip, FlushStrategy, Package, Rotation, WriteMode,
+};
+use crate::{Initialized, Uninitialized, HT16K33};
+
+/// Collects the initial configuration of an HT16K33 driver, written in one step by
+/// [build_and_init()](struct.Builder.html#method.build_and_init).
+///
+/// Every setting starts at the value used by [initialize()](../struct.HT16K33.html#method.initialize), so the
+/// display is off at full brightness unless configured otherwise.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use ht16k33::{BlinkRate, Dimming, Display, Rotation, HT16K33};
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// let ht16k33 = HT16K33::builder(i2c, address)
+///     .dimming(Dimming::BRIGHTNESS_8_16)
+///     .blink(BlinkRate::Off)
+///     .rotation(Rotation::Deg90)
+///     .build_and_init()?;
+///
+/// assert_eq!(Display::ON, *ht16k33.display());
+/// assert_eq!(Dimming::BRIGHTNESS_8_16, *ht16k33.dimming());
+///
+/// # Ok(())
+/// # }
+/// ```
+pub struct Builder<I2C> {
+    driver: HT16K33<I2C, Uninitialized>,
+    dimming: Dimming,
+    display: Display,
+}
+
+impl<I2C, E> Builder<I2C>
+where
+    I2C: Write<Error = E>,
+{
+    pub(crate) fn new(driver: HT16K33<I2C, Uninitialized>) -> Self {
+        Builder {
+            driver,
+            dimming: Dimming::BRIGHTNESS_MAX,
+            display: Display::OFF,
+        }
+    }
+
+    /// Set the dimming.
+    pub fn dimming(mut self, dimming: Dimming) -> Self {
+        self.dimming = dimming;
+        self
+    }
+
+    /// Set the display setting.
+    pub fn display(mut self, display: Display) -> Self {
+        self.display = display;
+        self
+    }
+
+    /// Turn the display on, blinking at the given rate.
+    pub fn blink(mut self, blink: BlinkRate) -> Self {
+        self.display = Display::new(true, blink);
+        self
+    }
+
+    /// Set the rotation, see [set_rotation()](../struct.HT16K33.html#method.set_rotation).
+    pub fn rotation(mut self, rotation: Rotation) -> Self {
+        self.driver.set_rotation(rotation);
+        self
+    }
+
+    /// Set the mirroring, see [set_flip()](../struct.HT16K33.html#method.set_flip).
+    pub fn flip(mut self, flip: Flip) -> Self {
+        self.driver.set_flip(flip);
+        self
+    }
+
+    /// Set how changed rows are written, see
+    /// [set_flush_strategy()](../struct.HT16K33.html#method.set_flush_strategy).
+    pub fn flush_strategy(mut self, flush_strategy: FlushStrategy) -> Self {
+        self.driver.set_flush_strategy(flush_strategy);
+        self
+    }
+
+    /// Set whether `set_led()` writes to the chip, see
+    /// [set_write_mode()](../struct.HT16K33.html#method.set_write_mode).
+    pub fn write_mode(mut self, write_mode: WriteMode) -> Self {
+        self.driver.set_write_mode(write_mode);
+        self
+    }
+
+    /// Set the package variant, see [set_package()](../struct.HT16K33.html#method.set_package).
+    pub fn package(mut self, package: Package) -> Self {
+        self.driver.set_package(package);
+        self
+    }
+
+    /// [initialize()](../struct.HT16K33.html#method.initialize) the HT16K33, then write the configured dimming
+    /// and display setting, returning the initialized driver.
+    pub fn build_and_init(self) -> Result<HT16K33<I2C, Initialized>, E> {
+        let mut driver = self.driver.initialize()?;
+
+        if self.dimming != *driver.dimming() {
+            driver.set_dimming(self.dimming)?;
+        }
+
+        if self.display != *driver.display() {
+            driver.set_display(self.display)?;
+        }
+
+        Ok(driver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c_mock::I2cMock;
+    use crate::types::Address;
+
+    #[test]
+    fn build_and_init() {
+        let mut i2c = I2cMock::new();
+
+        let ht16k33 = HT16K33::builder(&mut i2c, Address::DEFAULT)
+            .dimming(Dimming::BRIGHTNESS_MIN)
+            .blink(BlinkRate::Hz1)
+            .flip(Flip::HORIZONTAL)
+            .build_and_init()
+            .unwrap();
+
+        assert_eq!(Flip::HORIZONTAL, ht16k33.flip());
+
+        assert_eq!(Dimming::BRIGHTNESS_MIN, i2c.dimming());
+        assert_eq!(Display::ONE_HZ, i2c.display());
+    }
+
+    #[test]
+    fn defaults() {
+        let mut i2c = I2cMock::new();
+
+        HT16K33::builder(&mut i2c, Address::DEFAULT)
+            .build_and_init()
+            .unwrap();
+
+        // Only the writes of `initialize()`.
+        assert_eq!(4, i2c.transactions().len());
+    }
+}
@@ -0,0 +1,983 @@
+//! # chain
+//!
+//! Drive several HT16K33 chips as a single display.
+//!
+//! A [`DisplayChain`](struct.DisplayChain.html) owns one driver per chip, each at its own I2C address, and
+//! tiles their [layouts](../layouts/index.html) left to right into one wide [`Canvas`](trait.Canvas.html), e.g.
+//! a 32x8 scrolling sign from four 8x8 backpacks.
+//!
+//! A [`MixedChain`](struct.MixedChain.html) instead names each chip and gives it its own layout, e.g. a 7-segment
+//! backpack next to an 8x8 matrix on an instrument panel, with a single coordinated flush.
+//!
+//! A [`Panel`](struct.Panel.html) splits any canvas into named rectangular [regions](struct.Region.html), so
+//! that each widget draws in its own area with its own coordinates, e.g. `panel.region("speed")` of a chain
+//! turned into a panel with [into_panel()](struct.DisplayChain.html#method.into_panel). Regions also show text
+//! and raw segments, clipped to the region.
+use crate::errors::ValidationError;
+use crate::hal::blocking::i2c::Write;
+use crate::layouts::Layout;
+use crate::scroller::{ScrollDisplay, MAX_GLYPH_WIDTH};
+use crate::HT16K33;
+
+/// A pixel-addressed drawing surface.
+pub trait Canvas {
+    /// Return the width, in pixels.
+    fn width(&self) -> u8;
+
+    /// Return the height, in pixels.
+    fn height(&self) -> u8;
+
+    /// Return whether the pixel at `(x, y)` is enabled.
+    ///
+    /// # Errors
+    ///
+    /// If the pixel is outside the canvas then [`ht16k33::ValidationError::ValueTooLarge`] is returned.
+    ///
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    fn pixel(&self, x: u8, y: u8) -> Result<bool, ValidationError>;
+
+    /// Enable/disable the pixel at `(x, y)` in the display buffer.
+    ///
+    /// # Errors
+    ///
+    /// If the pixel is outside the canvas then [`ht16k33::ValidationError::ValueTooLarge`] is returned.
+    ///
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    fn set_pixel(&mut self, x: u8, y: u8, enabled: bool) -> Result<(), ValidationError>;
+
+    /// Disable all pixels.
+    fn clear(&mut self) {
+        for x in 0..self.width() {
+            for y in 0..self.height() {
+                // Every coordinate is within bounds.
+                let _ = self.set_pixel(x, y, false);
+            }
+        }
+    }
+}
+
+// Validate that `(x, y)` is within a `width` x `height` area.
+pub(crate) fn check_bounds(x: u8, y: u8, width: u8, height: u8) -> Result<(), ValidationError> {
+    if x >= width {
+        return Err(ValidationError::ValueTooLarge {
+            name: "x",
+            value: x,
+            limit: width,
+            inclusive: false,
+        });
+    }
+
+    if y >= height {
+        return Err(ValidationError::ValueTooLarge {
+            name: "y",
+            value: y,
+            limit: height,
+            inclusive: false,
+        });
+    }
+
+    Ok(())
+}
+
+/// A named rectangular area of a canvas, see [`Panel`](struct.Panel.html).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Region<'a> {
+    name: &'a str,
+    x: u8,
+    y: u8,
+    width: u8,
+    height: u8,
+}
+
+impl<'a> Region<'a> {
+    /// Create a `Region`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the region.
+    /// * `x`, `y` - The top left pixel of the region.
+    /// * `width`, `height` - The size of the region, in pixels.
+    pub const fn new(name: &'a str, x: u8, y: u8, width: u8, height: u8) -> Self {
+        Region {
+            name,
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Return the name.
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Return the top left pixel, in canvas coordinates.
+    pub fn origin(&self) -> (u8, u8) {
+        (self.x, self.y)
+    }
+
+    // Validate that the region fits within a `width` x `height` canvas.
+    fn check_fits(&self, width: u8, height: u8) -> Result<(), ValidationError> {
+        let right = self.x as u16 + self.width as u16;
+        if right > width as u16 {
+            return Err(ValidationError::ValueTooLarge {
+                name: "x",
+                value: right.min(u8::MAX as u16) as u8,
+                limit: width,
+                inclusive: true,
+            });
+        }
+
+        let bottom = self.y as u16 + self.height as u16;
+        if bottom > height as u16 {
+            return Err(ValidationError::ValueTooLarge {
+                name: "y",
+                value: bottom.min(u8::MAX as u16) as u8,
+                limit: height,
+                inclusive: true,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// A region of a canvas, drawn with coordinates relative to its top left pixel.
+pub struct RegionCanvas<'c, C: ?Sized> {
+    canvas: &'c mut C,
+    region: Region<'c>,
+}
+
+impl<'c, C: ?Sized> RegionCanvas<'c, C> {
+    /// Return the region.
+    pub fn region(&self) -> &Region<'c> {
+        &self.region
+    }
+}
+
+impl<C: Canvas + ?Sized> RegionCanvas<'_, C> {
+    /// Set the pixels of the column `x` from the `bits`, bit `n` for the pixel at `y = n`.
+    ///
+    /// On a segment layout a column is a digit, and the bits are its segments, e.g. the
+    /// [`Segments`](../seven_segment/struct.Segments.html) of a digit of a
+    /// [`SevenSegment4`](../layouts/struct.SevenSegment4.html) layout.
+    ///
+    /// # Errors
+    ///
+    /// If the column is outside the region then [`ht16k33::ValidationError::ValueTooLarge`] is returned.
+    ///
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    pub fn set_column(&mut self, x: u8, bits: u16) -> Result<(), ValidationError> {
+        check_bounds(x, 0, self.width(), self.height())?;
+
+        for y in 0..self.height().min(u16::BITS as u8) {
+            self.set_pixel(x, y, bits & (1 << y) != 0)?;
+        }
+
+        Ok(())
+    }
+
+    /// Show the `text` from the left edge of the region, with the glyphs of the `display`, blanking the rest of
+    /// the region.
+    ///
+    /// Each cell of a glyph is drawn as a column, see [set_column()](struct.RegionCanvas.html#method.set_column),
+    /// and the text is cut at the right edge of the region.
+    pub fn draw_text<D>(&mut self, display: &D, text: &str)
+    where
+        D: ScrollDisplay,
+    {
+        let mut cells = [0; MAX_GLYPH_WIDTH];
+        let mut x = 0;
+
+        for c in text.chars() {
+            let count = display.glyph(c, &mut cells);
+            for cell in &cells[..count] {
+                if x < self.width() {
+                    // Every column is within bounds.
+                    let _ = self.set_column(x, *cell);
+                    x += 1;
+                }
+            }
+        }
+
+        for x in x..self.width() {
+            let _ = self.set_column(x, 0);
+        }
+    }
+}
+
+impl<C: Canvas + ?Sized> Canvas for RegionCanvas<'_, C> {
+    fn width(&self) -> u8 {
+        self.region.width
+    }
+
+    fn height(&self) -> u8 {
+        self.region.height
+    }
+
+    fn pixel(&self, x: u8, y: u8) -> Result<bool, ValidationError> {
+        check_bounds(x, y, self.width(), self.height())?;
+
+        self.canvas.pixel(self.region.x + x, self.region.y + y)
+    }
+
+    fn set_pixel(&mut self, x: u8, y: u8, enabled: bool) -> Result<(), ValidationError> {
+        check_bounds(x, y, self.width(), self.height())?;
+
+        self.canvas
+            .set_pixel(self.region.x + x, self.region.y + y, enabled)
+    }
+}
+
+/// A canvas split into named regions.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use ht16k33::{Address, HT16K33};
+/// use ht16k33::chain::{Canvas, DisplayChain, Panel, Region};
+/// use ht16k33::layouts::Matrix8x8;
+/// # fn main() -> Result<(), Error> {
+///
+/// const REGIONS: [Region; 2] = [
+///     Region::new("speed", 0, 0, 12, 8),
+///     Region::new("heading", 12, 0, 4, 8),
+/// ];
+///
+/// let devices = [
+///     HT16K33::new(I2cMock::new(), Address::new(0x70)).initialize()?,
+///     HT16K33::new(I2cMock::new(), Address::new(0x71)).initialize()?,
+/// ];
+///
+/// let mut panel = Panel::new(DisplayChain::new(devices, Matrix8x8), &REGIONS)?;
+///
+/// // The top left pixel of the heading, on the second backpack.
+/// panel.region("heading").unwrap().set_pixel(0, 0, true)?;
+/// assert!(panel.canvas().pixel(12, 0)?);
+///
+/// panel.canvas_mut().flush()?;
+///
+/// # Ok(())
+/// # }
+/// ```
+pub struct Panel<'a, C> {
+    canvas: C,
+    regions: &'a [Region<'a>],
+}
+
+impl<'a, C: Canvas> Panel<'a, C> {
+    /// Create a `Panel`.
+    ///
+    /// If a name is repeated, only the first region with that name can be found.
+    ///
+    /// # Errors
+    ///
+    /// If a region does not fit within the `canvas` then [`ht16k33::ValidationError::ValueTooLarge`] is returned.
+    ///
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    pub fn new(canvas: C, regions: &'a [Region<'a>]) -> Result<Self, ValidationError> {
+        for region in regions {
+            region.check_fits(canvas.width(), canvas.height())?;
+        }
+
+        Ok(Panel { canvas, regions })
+    }
+
+    /// Return the canvas, consuming the panel.
+    pub fn into_canvas(self) -> C {
+        self.canvas
+    }
+
+    /// Return the canvas.
+    pub fn canvas(&self) -> &C {
+        &self.canvas
+    }
+
+    /// Return the canvas for modification, e.g. to flush a chain.
+    pub fn canvas_mut(&mut self) -> &mut C {
+        &mut self.canvas
+    }
+
+    /// Return the regions.
+    pub fn regions(&self) -> &'a [Region<'a>] {
+        self.regions
+    }
+
+    /// Return the region with the given `name`, as a [`Canvas`](trait.Canvas.html).
+    pub fn region(&mut self, name: &str) -> Option<RegionCanvas<'_, C>> {
+        let region = *self.regions.iter().find(|region| region.name == name)?;
+
+        Some(RegionCanvas {
+            canvas: &mut self.canvas,
+            region,
+        })
+    }
+}
+
+/// Several HT16K33 drivers with the same layout, tiled left to right into one canvas.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use ht16k33::{Address, HT16K33};
+/// use ht16k33::chain::{Canvas, DisplayChain};
+/// use ht16k33::layouts::Matrix8x8;
+/// # fn main() -> Result<(), Error> {
+///
+/// // Four 8x8 backpacks, left to right.
+/// let devices = [
+///     HT16K33::new(I2cMock::new(), Address::new(0x70)).initialize()?,
+///     HT16K33::new(I2cMock::new(), Address::new(0x71)).initialize()?,
+///     HT16K33::new(I2cMock::new(), Address::new(0x72)).initialize()?,
+///     HT16K33::new(I2cMock::new(), Address::new(0x73)).initialize()?,
+/// ];
+///
+/// let mut chain = DisplayChain::new(devices, Matrix8x8);
+///
+/// assert_eq!(32, chain.width());
+///
+/// // Lit on the third backpack.
+/// chain.set_pixel(20, 4, true)?;
+/// chain.flush()?;
+///
+/// # Ok(())
+/// # }
+/// ```
+pub struct DisplayChain<I2C, L, const N: usize> {
+    devices: [HT16K33<I2C>; N],
+    layout: L,
+
+    // The device flushed by the next `poll()`.
+    next: usize,
+}
+
+impl<I2C, E, L, const N: usize> DisplayChain<I2C, L, N>
+where
+    I2C: Write<Error = E>,
+    L: Layout,
+{
+    /// Create a `DisplayChain`.
+    ///
+    /// # Arguments
+    ///
+    /// * `devices` - The initialized drivers, ordered left to right.
+    /// * `layout` - The layout of each device.
+    pub fn new(devices: [HT16K33<I2C>; N], layout: L) -> Self {
+        DisplayChain {
+            devices,
+            layout,
+            next: 0,
+        }
+    }
+
+    /// Return the drivers, consuming the chain.
+    pub fn into_devices(self) -> [HT16K33<I2C>; N] {
+        self.devices
+    }
+
+    /// Return the drivers, ordered left to right.
+    pub fn devices(&self) -> &[HT16K33<I2C>; N] {
+        &self.devices
+    }
+
+    /// Return the drivers for modification, e.g. to set the dimming of one device.
+    pub fn devices_mut(&mut self) -> &mut [HT16K33<I2C>; N] {
+        &mut self.devices
+    }
+
+    /// Return the layout of each device.
+    pub fn layout(&self) -> &L {
+        &self.layout
+    }
+
+    /// Split the chain into the named `regions`, addressed with [Panel::region()](struct.Panel.html#method.region).
+    ///
+    /// # Errors
+    ///
+    /// If a region does not fit within the chain then [`ht16k33::ValidationError::ValueTooLarge`] is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// use ht16k33::{Address, HT16K33};
+    /// use ht16k33::chain::{DisplayChain, Region};
+    /// use ht16k33::layouts::SevenSegment4;
+    /// use ht16k33::scroller::SegmentDisplay;
+    /// # fn main() -> Result<(), Error> {
+    ///
+    /// // Two 4-digit backpacks, the speed on the first 5 digits and the gear on the last.
+    /// const REGIONS: [Region; 2] = [
+    ///     Region::new("speed", 0, 0, 5, 8),
+    ///     Region::new("gear", 7, 0, 1, 8),
+    /// ];
+    ///
+    /// let devices = [
+    ///     HT16K33::new(I2cMock::new(), Address::new(0x70)).initialize()?,
+    ///     HT16K33::new(I2cMock::new(), Address::new(0x71)).initialize()?,
+    /// ];
+    ///
+    /// let mut panel = DisplayChain::new(devices, SevenSegment4).into_panel(&REGIONS)?;
+    ///
+    /// panel.region("speed").unwrap().draw_text(&SegmentDisplay, "  120");
+    /// panel.region("gear").unwrap().draw_text(&SegmentDisplay, "4");
+    /// panel.canvas_mut().flush()?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    pub fn into_panel<'a>(
+        self,
+        regions: &'a [Region<'a>],
+    ) -> Result<Panel<'a, Self>, ValidationError> {
+        Panel::new(self, regions)
+    }
+
+    /// Write the changed rows of every device.
+    ///
+    /// Devices without changes are skipped, see [HT16K33::flush()](../struct.HT16K33.html#method.flush).
+    pub fn flush(&mut self) -> Result<(), E> {
+        for device in self.devices.iter_mut() {
+            device.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the changed rows of the next device, returning whether every device has been flushed since the
+    /// previous pass.
+    ///
+    /// Unlike [flush()](struct.DisplayChain.html#method.flush), each call writes to at most one device, so a
+    /// superloop calling `poll()` on every iteration never spends longer than one device's write on the bus.
+    /// A device that fails to write is retried by the next call.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// use ht16k33::i2c_mock::I2cMock;
+    /// use ht16k33::chain::{Canvas, DisplayChain};
+    /// use ht16k33::layouts::Matrix8x8;
+    /// use ht16k33::{Address, HT16K33};
+    /// # fn main() -> Result<(), Error> {
+    ///
+    /// let devices = [
+    ///     HT16K33::new(I2cMock::new(), Address::new(0x70)).initialize()?,
+    ///     HT16K33::new(I2cMock::new(), Address::new(0x71)).initialize()?,
+    /// ];
+    /// let mut chain = DisplayChain::new(devices, Matrix8x8);
+    ///
+    /// chain.set_pixel(12, 0, true)?;
+    ///
+    /// // One device per call.
+    /// assert!(!chain.poll()?);
+    /// assert!(chain.poll()?);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn poll(&mut self) -> Result<bool, E> {
+        if N == 0 {
+            return Ok(true);
+        }
+
+        self.devices[self.next].flush()?;
+        self.next = (self.next + 1) % N;
+
+        Ok(self.next == 0)
+    }
+
+    /// Write the whole display buffer of every device.
+    pub fn flush_full(&mut self) -> Result<(), E> {
+        for device in self.devices.iter_mut() {
+            device.flush_full()?;
+        }
+
+        Ok(())
+    }
+
+    // Return the device index and device-local coordinates of the pixel.
+    fn locate(&self, x: u8, y: u8) -> Result<(usize, u8, u8), ValidationError> {
+        check_bounds(x, y, self.width(), self.height())?;
+
+        let tile_width = self.layout.width();
+        Ok(((x / tile_width) as usize, x % tile_width, y))
+    }
+}
+
+impl<I2C, E, L, const N: usize> Canvas for DisplayChain<I2C, L, N>
+where
+    I2C: Write<Error = E>,
+    L: Layout,
+{
+    fn width(&self) -> u8 {
+        (N as u8).saturating_mul(self.layout.width())
+    }
+
+    fn height(&self) -> u8 {
+        self.layout.height()
+    }
+
+    fn pixel(&self, x: u8, y: u8) -> Result<bool, ValidationError> {
+        let (index, x, y) = self.locate(x, y)?;
+
+        Ok(match self.layout.location(x, y) {
+            Some(location) => self.devices[index].display_buffer()[location.row_as_index()]
+                .contains(location.common),
+            None => false,
+        })
+    }
+
+    fn set_pixel(&mut self, x: u8, y: u8, enabled: bool) -> Result<(), ValidationError> {
+        let (index, x, y) = self.locate(x, y)?;
+
+        if let Some(location) = self.layout.location(x, y) {
+            self.devices[index].update_display_buffer(location, enabled);
+        }
+
+        Ok(())
+    }
+
+    fn clear(&mut self) {
+        for device in self.devices.iter_mut() {
+            device.clear_display_buffer();
+        }
+    }
+}
+
+/// A single device drawn through its layout, see [MixedChain::canvas()](struct.MixedChain.html#method.canvas).
+pub struct DeviceCanvas<'c, I2C> {
+    device: &'c mut HT16K33<I2C>,
+    layout: &'c dyn Layout,
+}
+
+impl<'c, I2C> DeviceCanvas<'c, I2C> {
+    /// Return the driver, e.g. to write raw display buffer rows.
+    pub fn device(&mut self) -> &mut HT16K33<I2C> {
+        self.device
+    }
+
+    /// Return the layout.
+    pub fn layout(&self) -> &'c dyn Layout {
+        self.layout
+    }
+}
+
+impl<I2C> Canvas for DeviceCanvas<'_, I2C> {
+    fn width(&self) -> u8 {
+        self.layout.width()
+    }
+
+    fn height(&self) -> u8 {
+        self.layout.height()
+    }
+
+    fn pixel(&self, x: u8, y: u8) -> Result<bool, ValidationError> {
+        check_bounds(x, y, self.width(), self.height())?;
+
+        Ok(match self.layout.location(x, y) {
+            Some(location) => {
+                self.device.display_buffer()[location.row_as_index()].contains(location.common)
+            }
+            None => false,
+        })
+    }
+
+    fn set_pixel(&mut self, x: u8, y: u8, enabled: bool) -> Result<(), ValidationError> {
+        check_bounds(x, y, self.width(), self.height())?;
+
+        if let Some(location) = self.layout.location(x, y) {
+            self.device.update_display_buffer(location, enabled);
+        }
+
+        Ok(())
+    }
+}
+
+/// Several HT16K33 drivers with different layouts, each addressed by name.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use ht16k33::{Address, HT16K33};
+/// use ht16k33::chain::{Canvas, MixedChain};
+/// use ht16k33::layouts::{Matrix8x8, SevenSegment4};
+/// # fn main() -> Result<(), Error> {
+///
+/// let devices = [
+///     HT16K33::new(I2cMock::new(), Address::new(0x70)).initialize()?,
+///     HT16K33::new(I2cMock::new(), Address::new(0x71)).initialize()?,
+/// ];
+///
+/// let mut panel = MixedChain::new(devices, [("speed", &SevenSegment4), ("radar", &Matrix8x8)]);
+///
+/// // The decimal point of the second digit.
+/// panel.canvas("speed").unwrap().set_pixel(1, 7, true)?;
+/// panel.canvas("radar").unwrap().set_pixel(4, 4, true)?;
+/// panel.flush()?;
+///
+/// # Ok(())
+/// # }
+/// ```
+pub struct MixedChain<'a, I2C, const N: usize> {
+    devices: [HT16K33<I2C>; N],
+    members: [(&'a str, &'a dyn Layout); N],
+}
+
+impl<'a, I2C, E, const N: usize> MixedChain<'a, I2C, N>
+where
+    I2C: Write<Error = E>,
+{
+    /// Create a `MixedChain`.
+    ///
+    /// # Arguments
+    ///
+    /// * `devices` - The initialized drivers.
+    /// * `members` - The name and layout of each driver, in the same order. If a name is repeated, only the
+    ///   first device with that name can be found by name.
+    pub fn new(devices: [HT16K33<I2C>; N], members: [(&'a str, &'a dyn Layout); N]) -> Self {
+        MixedChain { devices, members }
+    }
+
+    /// Return the drivers, consuming the chain.
+    pub fn into_devices(self) -> [HT16K33<I2C>; N] {
+        self.devices
+    }
+
+    /// Return the drivers.
+    pub fn devices(&self) -> &[HT16K33<I2C>; N] {
+        &self.devices
+    }
+
+    /// Return the drivers for modification.
+    pub fn devices_mut(&mut self) -> &mut [HT16K33<I2C>; N] {
+        &mut self.devices
+    }
+
+    /// Return the device names, in order.
+    pub fn names(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.members.iter().map(|(name, _)| *name)
+    }
+
+    /// Return the index of the device with the given `name`.
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.members.iter().position(|(member, _)| *member == name)
+    }
+
+    /// Return the driver with the given `name`.
+    pub fn device(&self, name: &str) -> Option<&HT16K33<I2C>> {
+        self.position(name).map(|index| &self.devices[index])
+    }
+
+    /// Return the driver with the given `name` for modification.
+    pub fn device_mut(&mut self, name: &str) -> Option<&mut HT16K33<I2C>> {
+        let index = self.position(name)?;
+        Some(&mut self.devices[index])
+    }
+
+    /// Return the layout of the device with the given `name`.
+    pub fn layout(&self, name: &str) -> Option<&'a dyn Layout> {
+        self.position(name).map(|index| self.members[index].1)
+    }
+
+    /// Return the device with the given `name` as a [`Canvas`](trait.Canvas.html), through its layout.
+    pub fn canvas(&mut self, name: &str) -> Option<DeviceCanvas<'_, I2C>> {
+        let index = self.position(name)?;
+
+        Some(DeviceCanvas {
+            device: &mut self.devices[index],
+            layout: self.members[index].1,
+        })
+    }
+
+    /// Clear the display buffer of every device.
+    pub fn clear(&mut self) {
+        for device in self.devices.iter_mut() {
+            device.clear_display_buffer();
+        }
+    }
+
+    /// Write the changed rows of every device.
+    ///
+    /// Devices without changes are skipped, see [HT16K33::flush()](../struct.HT16K33.html#method.flush).
+    pub fn flush(&mut self) -> Result<(), E> {
+        for device in self.devices.iter_mut() {
+            device.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the whole display buffer of every device.
+    pub fn flush_full(&mut self) -> Result<(), E> {
+        for device in self.devices.iter_mut() {
+            device.flush_full()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c_mock::{I2cMock, SharedI2cMock};
+    use crate::layouts::{Matrix8x8, Raw, SevenSegment4};
+    use crate::scroller::SegmentDisplay;
+    use crate::seven_segment::{Segments, DIGIT_ROWS};
+    use crate::types::Address;
+    use core::cell::RefCell;
+
+    fn chain() -> DisplayChain<I2cMock, Matrix8x8, 3> {
+        DisplayChain::new(
+            [
+                HT16K33::new(I2cMock::new(), Address::new(0x70))
+                    .initialize()
+                    .unwrap(),
+                HT16K33::new(I2cMock::new(), Address::new(0x71))
+                    .initialize()
+                    .unwrap(),
+                HT16K33::new(I2cMock::new(), Address::new(0x72))
+                    .initialize()
+                    .unwrap(),
+            ],
+            Matrix8x8,
+        )
+    }
+
+    #[test]
+    fn size() {
+        let chain = chain();
+
+        assert_eq!(24, chain.width());
+        assert_eq!(8, chain.height());
+    }
+
+    #[test]
+    fn set_pixel() {
+        let mut chain = chain();
+
+        chain.set_pixel(9, 2, true).unwrap();
+        assert!(chain.pixel(9, 2).unwrap());
+        assert!(!chain.pixel(1, 2).unwrap());
+
+        // The second device, local pixel (1, 2).
+        let location = Matrix8x8.location(1, 2).unwrap();
+        assert!(
+            chain.devices()[1].display_buffer()[location.row_as_index()].contains(location.common)
+        );
+        assert_eq!(
+            0,
+            chain.devices()[0].display_buffer()[location.row_as_index()].bits()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_pixel_out_of_bounds() {
+        chain().set_pixel(24, 0, true).unwrap();
+    }
+
+    #[test]
+    fn clear() {
+        let mut chain = chain();
+
+        chain.set_pixel(0, 0, true).unwrap();
+        chain.set_pixel(23, 7, true).unwrap();
+        chain.clear();
+
+        for x in 0..chain.width() {
+            for y in 0..chain.height() {
+                assert!(!chain.pixel(x, y).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn flush() {
+        // Both devices on the same bus.
+        let bus = RefCell::new(I2cMock::new());
+        let mut chain = DisplayChain::new(
+            [
+                HT16K33::new(SharedI2cMock::new(&bus), Address::new(0x70))
+                    .initialize()
+                    .unwrap(),
+                HT16K33::new(SharedI2cMock::new(&bus), Address::new(0x71))
+                    .initialize()
+                    .unwrap(),
+            ],
+            Raw,
+        );
+
+        chain.set_pixel(17, 3, true).unwrap();
+        chain.flush().unwrap();
+
+        let bus = bus.into_inner();
+        assert_eq!([0; 16], bus.device(Address::new(0x70)).data_values);
+        assert_eq!(0b0000_1000, bus.device(Address::new(0x71)).data_values[1]);
+    }
+
+    #[test]
+    fn poll() {
+        let mut chain = chain();
+
+        chain.set_pixel(20, 3, true).unwrap();
+
+        assert!(!chain.poll().unwrap());
+        assert!(!chain.poll().unwrap());
+        assert!(chain.poll().unwrap());
+
+        let [first, _, third] = chain.into_devices();
+        assert_eq!(
+            [0; 16],
+            first.destroy().device(Address::new(0x70)).data_values
+        );
+        assert_eq!(
+            0b0000_1000,
+            third.destroy().device(Address::new(0x72)).data_values[6]
+        );
+    }
+
+    fn panel() -> MixedChain<'static, I2cMock, 2> {
+        MixedChain::new(
+            [
+                HT16K33::new(I2cMock::new(), Address::new(0x70))
+                    .initialize()
+                    .unwrap(),
+                HT16K33::new(I2cMock::new(), Address::new(0x71))
+                    .initialize()
+                    .unwrap(),
+            ],
+            [("digits", &SevenSegment4), ("matrix", &Matrix8x8)],
+        )
+    }
+
+    #[test]
+    fn mixed_names() {
+        let panel = panel();
+
+        assert_eq!(Some(1), panel.position("matrix"));
+        assert_eq!(None, panel.position("missing"));
+        assert_eq!(Some(4), panel.layout("digits").map(|layout| layout.width()));
+
+        let mut names = panel.names();
+        assert_eq!(Some("digits"), names.next());
+        assert_eq!(Some("matrix"), names.next());
+        assert_eq!(None, names.next());
+    }
+
+    #[test]
+    fn mixed_canvas() {
+        let mut panel = panel();
+
+        panel
+            .canvas("digits")
+            .unwrap()
+            .set_pixel(3, 0, true)
+            .unwrap();
+        panel
+            .canvas("matrix")
+            .unwrap()
+            .set_pixel(0, 1, true)
+            .unwrap();
+        assert!(panel.canvas("digits").unwrap().pixel(3, 0).unwrap());
+        assert!(panel
+            .canvas("matrix")
+            .unwrap()
+            .set_pixel(8, 0, true)
+            .is_err());
+
+        panel.flush().unwrap();
+
+        let [digits, matrix] = panel.into_devices();
+        assert_eq!(
+            0b0000_0001,
+            digits.destroy().device(Address::new(0x70)).data_values[DIGIT_ROWS[3]]
+        );
+        assert_eq!(
+            0b1000_0000,
+            matrix.destroy().device(Address::new(0x71)).data_values[2]
+        );
+    }
+
+    const REGIONS: [Region; 2] = [
+        Region::new("left", 0, 0, 4, 8),
+        Region::new("center", 4, 2, 8, 4),
+    ];
+
+    #[test]
+    fn panel_region() {
+        let mut panel = Panel::new(chain(), &REGIONS).unwrap();
+
+        let mut center = panel.region("center").unwrap();
+        assert_eq!((8, 4), (center.width(), center.height()));
+        center.set_pixel(7, 3, true).unwrap();
+        assert!(center.set_pixel(8, 0, true).is_err());
+        center.clear();
+        center.set_pixel(0, 0, true).unwrap();
+
+        assert!(panel.region("missing").is_none());
+        assert!(panel.canvas().pixel(4, 2).unwrap());
+        assert!(!panel.canvas().pixel(11, 5).unwrap());
+
+        // Clearing a region leaves the rest of the canvas.
+        panel.canvas_mut().set_pixel(0, 0, true).unwrap();
+        panel.region("center").unwrap().clear();
+        assert!(panel.canvas().pixel(0, 0).unwrap());
+        assert!(!panel.canvas().pixel(4, 2).unwrap());
+    }
+
+    #[test]
+    fn panel_region_text() {
+        const REGIONS: [Region; 2] = [
+            Region::new("speed", 0, 0, 3, 8),
+            Region::new("gear", 3, 0, 1, 8),
+        ];
+
+        let devices = [
+            HT16K33::new(I2cMock::new(), Address::new(0x70))
+                .initialize()
+                .unwrap(),
+            HT16K33::new(I2cMock::new(), Address::new(0x71))
+                .initialize()
+                .unwrap(),
+        ];
+        let mut panel = DisplayChain::new(devices, SevenSegment4)
+            .into_panel(&REGIONS)
+            .unwrap();
+
+        panel
+            .region("gear")
+            .unwrap()
+            .set_column(0, Segments::DIGIT_5.bits() as u16)
+            .unwrap();
+        // Cut at the right edge, the gear is left unchanged.
+        panel
+            .region("speed")
+            .unwrap()
+            .draw_text(&SegmentDisplay, "1234");
+
+        let [first, _] = panel.into_canvas().into_devices();
+        let buffer = first.display_buffer();
+        assert_eq!(Segments::DIGIT_1.bits(), buffer[DIGIT_ROWS[0]].bits());
+        assert_eq!(Segments::DIGIT_3.bits(), buffer[DIGIT_ROWS[2]].bits());
+        assert_eq!(Segments::DIGIT_5.bits(), buffer[DIGIT_ROWS[3]].bits());
+    }
+
+    #[test]
+    fn panel_region_too_large() {
+        const REGIONS: [Region; 1] = [Region::new("wide", 20, 0, 7, 8)];
+
+        assert!(Panel::new(chain(), &REGIONS).is_err());
+    }
+}
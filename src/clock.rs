@@ -0,0 +1,667 @@
+//! # clock
+//!
+//! Clock widgets for the 4-digit 7-segment backpacks, see [`seven_segment`](../seven_segment/index.html).
+//!
+//! A [`TimeSetFlow`](struct.TimeSetFlow.html) implements the classic way of setting a clock with two keys:
+//! hold the set key to start editing, press the up key to advance the blinking field, and press the set key to
+//! move from the hours to the minutes and then confirm the time.
+//!
+//! A [`ClockFace`](struct.ClockFace.html) shows a time of day or a stopwatch `Duration`, with a 12 or 24-hour
+//! format, optional leading zero and a blinking colon.
+use crate::content::elapsed;
+use crate::errors::ValidationError;
+use crate::hal::blocking::i2c::Write;
+use crate::keypad::KeyEvent;
+use crate::seven_segment::{Segments, SevenSegment};
+use crate::types::BlinkRate;
+
+use core::time::Duration;
+
+/// The default time the set key is held to start editing, in milliseconds.
+pub const DEFAULT_HOLD: u32 = 2000;
+
+/// The default blink period of the edited field, in milliseconds.
+pub const DEFAULT_BLINK: u32 = 500;
+
+/// A time of day, on a 24-hour clock.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Time {
+    /// The hours, `0..=23`.
+    pub hours: u8,
+    /// The minutes, `0..=59`.
+    pub minutes: u8,
+}
+
+/// The field of a [`TimeSetFlow`](struct.TimeSetFlow.html) being edited.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Field {
+    /// The hours, shown on the left half of the display.
+    Hours,
+    /// The minutes, shown on the right half of the display.
+    Minutes,
+}
+
+/// The hour format of a [`ClockFace`](struct.ClockFace.html).
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum HourFormat {
+    /// Hours `0` to `23`.
+    ///
+    /// *This is the default.*
+    #[default]
+    H24,
+    /// Hours `1` to `12`.
+    H12,
+}
+
+/// How the colon of a [`ClockFace`](struct.ClockFace.html) blinks.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum ColonBlink {
+    /// Always on.
+    ///
+    /// *This is the default.*
+    #[default]
+    Steady,
+    /// On during the first half of each period, in milliseconds, of the time passed to
+    /// [render_time()](struct.ClockFace.html#method.render_time).
+    Tick(u32),
+    /// Always on in the display buffer, with the whole display blinking at the chip's blink rate, written by
+    /// [write_blink()](struct.ClockFace.html#method.write_blink).
+    Chip(BlinkRate),
+}
+
+/// Shows a time of day or a stopwatch on a 4-digit 7-segment display, see the [`clock`](index.html) module.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use ht16k33::HT16K33;
+/// use ht16k33::clock::{ClockFace, ColonBlink, HourFormat, Time};
+/// use ht16k33::seven_segment::{Segments, SevenSegment};
+/// use core::time::Duration;
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// let mut display = SevenSegment::new(HT16K33::new(i2c, address).initialize()?);
+///
+/// let mut face = ClockFace::new();
+/// face.set_format(HourFormat::H12);
+/// face.set_colon_blink(ColonBlink::Tick(1000));
+///
+/// // Shown as ` 1:05`.
+/// face.render_time(&mut display, Time { hours: 13, minutes: 5 }, 0)?;
+/// assert_eq!(Segments::DIGIT_1, display.segments(1)?);
+///
+/// // A stopwatch at 2 minutes 30 seconds, shown as ` 2:30`.
+/// face.render_duration(&mut display, Duration::from_secs(150), 0)?;
+/// display.write_display_buffer()?;
+///
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ClockFace {
+    format: HourFormat,
+    leading_zero: bool,
+    colon_blink: ColonBlink,
+}
+
+impl ClockFace {
+    /// Create a `ClockFace` with 24-hour format, no leading zero and a steady colon.
+    pub fn new() -> Self {
+        ClockFace::default()
+    }
+
+    /// Set the hour format.
+    pub fn set_format(&mut self, format: HourFormat) {
+        self.format = format;
+    }
+
+    /// Set whether the left field is shown with a leading zero, e.g. `09:30` rather than ` 9:30`.
+    pub fn set_leading_zero(&mut self, leading_zero: bool) {
+        self.leading_zero = leading_zero;
+    }
+
+    /// Set how the colon blinks.
+    pub fn set_colon_blink(&mut self, colon_blink: ColonBlink) {
+        self.colon_blink = colon_blink;
+    }
+
+    /// Show the time of day as `HH:MM` on the `display`.
+    ///
+    /// # Arguments
+    ///
+    /// * `display` - The display to render into, which must then be written to be displayed.
+    /// * `time` - The time of day.
+    /// * `now` - The current time in milliseconds, wrapping on overflow, for [`ColonBlink::Tick`].
+    ///
+    /// # Errors
+    ///
+    /// If the hours or minutes are out of range then [`ht16k33::ValidationError::OutOfRange`] is returned and
+    /// the display buffer is left unchanged.
+    ///
+    /// [`ColonBlink::Tick`]: enum.ColonBlink.html#variant.Tick
+    /// [`ht16k33::ValidationError::OutOfRange`]: ../enum.ValidationError.html#variant.OutOfRange
+    pub fn render_time<I2C, E>(
+        &self,
+        display: &mut SevenSegment<I2C>,
+        time: Time,
+        now: u32,
+    ) -> Result<(), ValidationError>
+    where
+        I2C: Write<Error = E>,
+    {
+        check_range("hours", time.hours, 23)?;
+        check_range("minutes", time.minutes, 59)?;
+
+        let hours = match (self.format, time.hours % 12) {
+            (HourFormat::H24, _) => time.hours,
+            (HourFormat::H12, 0) => 12,
+            (HourFormat::H12, hours) => hours,
+        };
+
+        display_fields(display, hours, time.minutes, self.leading_zero, None)?;
+        display.set_colon(self.colon(now));
+
+        Ok(())
+    }
+
+    /// Show a stopwatch `duration` on the `display`, as `MM:SS` below an hour and as `HH:MM` from then on.
+    ///
+    /// # Arguments
+    ///
+    /// * `display` - The display to render into, which must then be written to be displayed.
+    /// * `duration` - The elapsed time.
+    /// * `now` - The current time in milliseconds, wrapping on overflow, for [`ColonBlink::Tick`].
+    ///
+    /// # Errors
+    ///
+    /// If the `duration` is 100 hours or more then [`ht16k33::ValidationError::Overflow`] is returned and the
+    /// display buffer is left unchanged.
+    ///
+    /// [`ColonBlink::Tick`]: enum.ColonBlink.html#variant.Tick
+    /// [`ht16k33::ValidationError::Overflow`]: ../enum.ValidationError.html#variant.Overflow
+    pub fn render_duration<I2C, E>(
+        &self,
+        display: &mut SevenSegment<I2C>,
+        duration: Duration,
+        now: u32,
+    ) -> Result<(), ValidationError>
+    where
+        I2C: Write<Error = E>,
+    {
+        let seconds = duration.as_secs();
+        let (left, right) = match seconds {
+            0..=3599 => (seconds / 60, seconds % 60),
+            3600..=359_999 => (seconds / 3600, seconds / 60 % 60),
+            _ => {
+                return Err(ValidationError::Overflow {
+                    name: "duration",
+                    digits: 4,
+                })
+            }
+        };
+
+        display_fields(display, left as u8, right as u8, self.leading_zero, None)?;
+        display.set_colon(self.colon(now));
+
+        Ok(())
+    }
+
+    /// Write the blink setting of the [`ColonBlink`](enum.ColonBlink.html) to the chip, turning the display on.
+    ///
+    /// Only [`ColonBlink::Chip`](enum.ColonBlink.html#variant.Chip) blinks the display, the other modes turn
+    /// blinking off.
+    pub fn write_blink<I2C, E>(&self, display: &mut SevenSegment<I2C>) -> Result<(), E>
+    where
+        I2C: Write<Error = E>,
+    {
+        let blink = match self.colon_blink {
+            ColonBlink::Chip(blink) => blink,
+            _ => BlinkRate::Off,
+        };
+
+        display.driver_mut().set_display_blink(true, blink)
+    }
+
+    // Return whether the colon is on at `now`.
+    fn colon(&self, now: u32) -> bool {
+        match self.colon_blink {
+            ColonBlink::Tick(period) => now % period.max(1) < period / 2,
+            _ => true,
+        }
+    }
+}
+
+/// Sets a time with two keys, see the [`clock`](index.html) module.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use ht16k33::HT16K33;
+/// use ht16k33::clock::{Time, TimeSetFlow};
+/// use ht16k33::keypad::KeyEvent;
+/// use ht16k33::seven_segment::SevenSegment;
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// let mut display = SevenSegment::new(HT16K33::new(i2c, address).initialize()?);
+///
+/// // Key 0 sets, key 1 advances the field.
+/// let mut flow = TimeSetFlow::new(0, 1);
+/// flow.set_time(Time { hours: 9, minutes: 30 });
+///
+/// // Hold the set key to start editing the hours.
+/// flow.key_event(KeyEvent::Pressed(0), 0);
+/// flow.tick(2000);
+/// assert!(flow.is_editing());
+///
+/// flow.key_event(KeyEvent::Pressed(1), 2500);
+/// flow.key_event(KeyEvent::Pressed(0), 3000);
+/// flow.key_event(KeyEvent::Pressed(1), 3500);
+///
+/// // Confirm, the final time is returned to the application.
+/// let time = flow.key_event(KeyEvent::Pressed(0), 4000);
+/// assert_eq!(Some(Time { hours: 10, minutes: 31 }), time);
+///
+/// flow.render(&mut display, 4000);
+/// display.write_display_buffer()?;
+///
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TimeSetFlow {
+    set_key: u8,
+    up_key: u8,
+    hold: u32,
+    blink: u32,
+
+    time: Time,
+
+    // The edited field, or `None` when not editing.
+    field: Option<Field>,
+
+    // When the set key was pressed, while it is held outside of editing.
+    held_since: Option<u32>,
+
+    // When the edited field last changed, so it is shown right after a change.
+    changed_at: u32,
+}
+
+impl TimeSetFlow {
+    /// Create a `TimeSetFlow` with the default hold time and blink period, showing `00:00`.
+    ///
+    /// # Arguments
+    ///
+    /// * `set_key` - The key held to start editing, and pressed to move to the next field.
+    /// * `up_key` - The key pressed to advance the edited field.
+    pub fn new(set_key: u8, up_key: u8) -> Self {
+        TimeSetFlow {
+            set_key,
+            up_key,
+            hold: DEFAULT_HOLD,
+            blink: DEFAULT_BLINK,
+            time: Time::default(),
+            field: None,
+            held_since: None,
+            changed_at: 0,
+        }
+    }
+
+    /// Set how long the set key is held to start editing, in milliseconds.
+    pub fn set_hold(&mut self, hold: u32) {
+        self.hold = hold;
+    }
+
+    /// Set the blink period of the edited field, in milliseconds.
+    pub fn set_blink(&mut self, blink: u32) {
+        self.blink = blink.max(1);
+    }
+
+    /// Return the shown time.
+    pub fn time(&self) -> Time {
+        self.time
+    }
+
+    /// Set the shown time, e.g. from the RTC while not editing.
+    ///
+    /// Hours and minutes out of range wrap around.
+    pub fn set_time(&mut self, time: Time) {
+        self.time = Time {
+            hours: time.hours % 24,
+            minutes: time.minutes % 60,
+        };
+    }
+
+    /// Return the edited field, or `None` when not editing.
+    pub fn field(&self) -> Option<Field> {
+        self.field
+    }
+
+    /// Return whether the time is being edited.
+    pub fn is_editing(&self) -> bool {
+        self.field.is_some()
+    }
+
+    /// Stop editing without confirming the time.
+    pub fn cancel(&mut self) {
+        self.field = None;
+        self.held_since = None;
+    }
+
+    /// Handle a key event, returning the time once it is confirmed.
+    ///
+    /// While editing, presses of the up key advance the edited field, wrapping around, and presses of the set
+    /// key move from the hours to the minutes, then confirm the time. Other events are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The key event.
+    /// * `now` - The current time in milliseconds, wrapping on overflow.
+    pub fn key_event(&mut self, event: KeyEvent, now: u32) -> Option<Time> {
+        match (self.field, event) {
+            (None, KeyEvent::Pressed(key)) if key == self.set_key => {
+                self.held_since = Some(now);
+            }
+            (None, KeyEvent::Released(key)) if key == self.set_key => {
+                self.held_since = None;
+            }
+            (Some(Field::Hours), KeyEvent::Pressed(key)) if key == self.up_key => {
+                self.time.hours = (self.time.hours + 1) % 24;
+                self.changed_at = now;
+            }
+            (Some(Field::Minutes), KeyEvent::Pressed(key)) if key == self.up_key => {
+                self.time.minutes = (self.time.minutes + 1) % 60;
+                self.changed_at = now;
+            }
+            (Some(Field::Hours), KeyEvent::Pressed(key)) if key == self.set_key => {
+                self.field = Some(Field::Minutes);
+                self.changed_at = now;
+            }
+            (Some(Field::Minutes), KeyEvent::Pressed(key)) if key == self.set_key => {
+                self.field = None;
+                return Some(self.time);
+            }
+            _ => {}
+        }
+
+        None
+    }
+
+    /// Start editing the hours once the set key has been held long enough.
+    ///
+    /// Call this regularly, e.g. from the same timer tick that polls the keypad.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time in milliseconds, wrapping on overflow.
+    pub fn tick(&mut self, now: u32) {
+        if let Some(since) = self.held_since {
+            if elapsed(now, since, self.hold) {
+                self.held_since = None;
+                self.field = Some(Field::Hours);
+                self.changed_at = now;
+            }
+        }
+    }
+
+    /// Show the time as `HH:MM` on the `display`, with the edited field blinking.
+    ///
+    /// The hours are shown without a leading zero, and the colon is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `display` - The display to render into, which must then be written to be displayed.
+    /// * `now` - The current time in milliseconds, wrapping on overflow.
+    pub fn render<I2C, E>(&self, display: &mut SevenSegment<I2C>, now: u32)
+    where
+        I2C: Write<Error = E>,
+    {
+        // Shown during the first half of each blink period after a change, hidden during the second.
+        let hidden = self.field.filter(|_| {
+            let phase = now.wrapping_sub(self.changed_at) % self.blink;
+            phase >= self.blink / 2
+        });
+
+        // The hours and minutes are always in range, so the digits cannot overflow.
+        let _ = display_fields(display, self.time.hours, self.time.minutes, false, hidden);
+        display.set_colon(true);
+    }
+}
+
+// Fail if the `value` is larger than `max`.
+fn check_range(name: &'static str, value: u8, max: u8) -> Result<(), ValidationError> {
+    if value > max {
+        return Err(ValidationError::OutOfRange {
+            name,
+            value,
+            min: 0,
+            max,
+        });
+    }
+
+    Ok(())
+}
+
+// Show two 2-digit fields, the left one with an optional leading zero, with the `hidden` field blank.
+fn display_fields<I2C, E>(
+    display: &mut SevenSegment<I2C>,
+    left: u8,
+    right: u8,
+    leading_zero: bool,
+    hidden: Option<Field>,
+) -> Result<(), ValidationError>
+where
+    I2C: Write<Error = E>,
+{
+    let tens = if left >= 10 || leading_zero {
+        Segments::from_hex_digit(left / 10)?
+    } else {
+        Segments::empty()
+    };
+    let digits = [
+        tens,
+        Segments::from_hex_digit(left % 10)?,
+        Segments::from_hex_digit(right / 10)?,
+        Segments::from_hex_digit(right % 10)?,
+    ];
+
+    for (index, &segments) in digits.iter().enumerate() {
+        let field = if index < 2 {
+            Field::Hours
+        } else {
+            Field::Minutes
+        };
+
+        if hidden == Some(field) {
+            display.set_segments(index as u8, Segments::empty())?;
+        } else {
+            display.set_segments(index as u8, segments)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c_mock::I2cMock;
+    use crate::seven_segment::Indicators;
+    use crate::types::Address;
+    use crate::HT16K33;
+
+    fn display() -> SevenSegment<I2cMock> {
+        SevenSegment::new(
+            HT16K33::new(I2cMock::new(), Address::DEFAULT)
+                .initialize()
+                .unwrap(),
+        )
+    }
+
+    fn editing(hours: u8, minutes: u8) -> TimeSetFlow {
+        let mut flow = TimeSetFlow::new(0, 1);
+        flow.set_time(Time { hours, minutes });
+        flow.key_event(KeyEvent::Pressed(0), 0);
+        flow.tick(DEFAULT_HOLD);
+
+        flow
+    }
+
+    #[test]
+    fn hold_to_enter() {
+        let mut flow = TimeSetFlow::new(0, 1);
+
+        // Released too early.
+        flow.key_event(KeyEvent::Pressed(0), 0);
+        flow.tick(1000);
+        flow.key_event(KeyEvent::Released(0), 1500);
+        flow.tick(3000);
+        assert!(!flow.is_editing());
+
+        // The up key does nothing outside of editing.
+        flow.key_event(KeyEvent::Pressed(1), 3000);
+        assert_eq!(Time::default(), flow.time());
+
+        flow.key_event(KeyEvent::Pressed(0), 4000);
+        flow.tick(4000 + DEFAULT_HOLD);
+        assert_eq!(Some(Field::Hours), flow.field());
+
+        // Releasing the held key keeps editing.
+        flow.key_event(KeyEvent::Released(0), 6500);
+        assert_eq!(Some(Field::Hours), flow.field());
+    }
+
+    #[test]
+    fn increments_wrap() {
+        let mut flow = editing(23, 59);
+
+        assert_eq!(None, flow.key_event(KeyEvent::Pressed(1), 0));
+        assert_eq!(0, flow.time().hours);
+
+        flow.key_event(KeyEvent::Pressed(0), 0);
+        assert_eq!(Some(Field::Minutes), flow.field());
+
+        flow.key_event(KeyEvent::Pressed(1), 0);
+        assert_eq!(
+            Some(Time {
+                hours: 0,
+                minutes: 0
+            }),
+            flow.key_event(KeyEvent::Pressed(0), 0)
+        );
+        assert!(!flow.is_editing());
+    }
+
+    #[test]
+    fn cancel() {
+        let mut flow = editing(12, 0);
+
+        flow.cancel();
+        assert!(!flow.is_editing());
+        assert_eq!(None, flow.key_event(KeyEvent::Pressed(0), 0));
+    }
+
+    #[test]
+    fn render_time() {
+        let mut display = display();
+        let mut face = ClockFace::new();
+
+        face.render_time(
+            &mut display,
+            Time {
+                hours: 0,
+                minutes: 7,
+            },
+            0,
+        )
+        .unwrap();
+        assert_eq!(Segments::empty(), display.segments(0).unwrap());
+        assert_eq!(Segments::DIGIT_0, display.segments(1).unwrap());
+        assert_eq!(Segments::DIGIT_7, display.segments(3).unwrap());
+
+        face.set_format(HourFormat::H12);
+        face.set_leading_zero(true);
+        face.render_time(
+            &mut display,
+            Time {
+                hours: 0,
+                minutes: 7,
+            },
+            0,
+        )
+        .unwrap();
+        assert_eq!(Segments::DIGIT_1, display.segments(0).unwrap());
+        assert_eq!(Segments::DIGIT_2, display.segments(1).unwrap());
+
+        assert!(face
+            .render_time(
+                &mut display,
+                Time {
+                    hours: 24,
+                    minutes: 0
+                },
+                0
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn render_duration() {
+        let mut display = display();
+        let face = ClockFace::new();
+
+        face.render_duration(&mut display, Duration::from_secs(3599), 0)
+            .unwrap();
+        assert_eq!(Segments::DIGIT_5, display.segments(0).unwrap());
+        assert_eq!(Segments::DIGIT_9, display.segments(3).unwrap());
+
+        // From an hour, the hours and minutes.
+        face.render_duration(&mut display, Duration::from_secs(3 * 3600 + 25 * 60), 0)
+            .unwrap();
+        assert_eq!(Segments::DIGIT_3, display.segments(1).unwrap());
+        assert_eq!(Segments::DIGIT_2, display.segments(2).unwrap());
+
+        assert!(face
+            .render_duration(&mut display, Duration::from_secs(100 * 3600), 0)
+            .is_err());
+    }
+
+    #[test]
+    fn colon_tick() {
+        let mut display = display();
+        let mut face = ClockFace::new();
+        face.set_colon_blink(ColonBlink::Tick(1000));
+
+        face.render_time(&mut display, Time::default(), 1200)
+            .unwrap();
+        assert!(display.indicators().contains(Indicators::COLON));
+
+        face.render_time(&mut display, Time::default(), 1700)
+            .unwrap();
+        assert!(!display.indicators().contains(Indicators::COLON));
+    }
+
+    #[test]
+    fn render_blinks_field() {
+        let mut display = display();
+        let flow = editing(9, 5);
+
+        flow.render(&mut display, DEFAULT_HOLD);
+        assert_eq!(Segments::empty(), display.segments(0).unwrap());
+        assert_eq!(Segments::DIGIT_9, display.segments(1).unwrap());
+        assert_eq!(Segments::DIGIT_0, display.segments(2).unwrap());
+        assert_eq!(Segments::DIGIT_5, display.segments(3).unwrap());
+
+        // The hours are hidden during the second half of the blink period.
+        flow.render(&mut display, DEFAULT_HOLD + DEFAULT_BLINK / 2);
+        assert_eq!(Segments::empty(), display.segments(1).unwrap());
+        assert_eq!(Segments::DIGIT_5, display.segments(3).unwrap());
+    }
+}
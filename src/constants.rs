@@ -1,5 +1,64 @@
+use core::mem::size_of;
+
+use crate::types::{DisplayData, DisplayDataAddress, Frame};
+
 /// The number of ROWS available (28-pin).
 pub const ROWS_SIZE: usize = 16;
 
 /// The number of COMMONS available.
 pub const COMMONS_SIZE: usize = 8;
+
+/// The number of bytes of key RAM, 13 keys on each of the 3 key scan lines.
+pub const KEYS_SIZE: usize = 6;
+
+// The address of the key RAM.
+pub(crate) const KEY_DATA_ADDRESS: u8 = 0x40;
+
+// The address of the INT flag register.
+pub(crate) const INT_FLAG_ADDRESS: u8 = 0x60;
+
+// The number of keys on each key scan line, `K1` to `K13`.
+pub(crate) const KEYS_PER_LINE: u8 = 13;
+
+// The time for the oscillator to start, before the display RAM is written.
+pub(crate) const OSCILLATOR_STARTUP_MS: u16 = 1;
+
+// The time for the chips to accept I2C transfers after power-on.
+pub(crate) const POWER_UP_MS: u16 = 1;
+
+/// Return the number of bytes of display RAM of `devices` chips, e.g. to size static storage for a chain.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::{display_ram_size, ROWS_SIZE};
+///
+/// static FRAMES: [u8; display_ram_size(4)] = [0; display_ram_size(4)];
+///
+/// assert_eq!(4 * ROWS_SIZE, display_ram_size(4));
+/// ```
+pub const fn display_ram_size(devices: usize) -> usize {
+    devices * ROWS_SIZE
+}
+
+/// Return the number of bytes of an I2C write of `rows` rows of display RAM, including the address byte.
+pub const fn display_write_size(rows: usize) -> usize {
+    rows + 1
+}
+
+/// The number of bytes of an I2C write of the whole display RAM, see
+/// [write_i2c_frame()](struct.HT16K33.html#method.write_i2c_frame).
+pub const I2C_FRAME_SIZE: usize = display_write_size(ROWS_SIZE);
+
+// Compile-time checks that the geometry and the buffer types agree, so changing one without the others fails
+// to build.
+const _: () = assert!(size_of::<DisplayData>() * 8 == COMMONS_SIZE);
+const _: () = assert!(size_of::<[DisplayData; ROWS_SIZE]>() == display_ram_size(1));
+const _: () = assert!(size_of::<Frame>() == display_ram_size(1));
+const _: () = assert!(Frame::ENCODED_SIZE == display_ram_size(1) + 1);
+const _: () = assert!(ROWS_SIZE <= DisplayDataAddress::all().bits() as usize + 1);
+const _: () = assert!(KEYS_SIZE == 3 * 2);
+const _: () = assert!(display_write_size(ROWS_SIZE) <= u8::MAX as usize);
+
+// The 16x8 matrix layout must fit in the display RAM.
+const _: () = assert!(8 * 2 <= ROWS_SIZE);
@@ -0,0 +1,169 @@
+use crate::content::{elapsed, ContentSource};
+use crate::types::Frame;
+
+/// Shows an alert over a content source for a while, then restores the source.
+///
+/// The source keeps running while the alert is shown, so e.g. a [`Compositor`](struct.Compositor.html)
+/// resumes with its current layers rather than where it was interrupted. To alert with text, render it into a
+/// frame first.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::content::{Alert, ContentSource, Still};
+/// use ht16k33::{DisplayData, Frame, ROWS_SIZE};
+///
+/// let clock = Frame::from_rows([DisplayData::COMMON_0; ROWS_SIZE]);
+/// let warning = Frame::from_rows([DisplayData::all(); ROWS_SIZE]);
+///
+/// let mut content = Alert::new(Still(clock));
+/// assert_eq!(Some(clock), content.next_frame(0));
+///
+/// content.show_alert(warning, 3000, 100);
+/// assert_eq!(Some(warning), content.next_frame(100));
+/// assert_eq!(None, content.next_frame(2000));
+///
+/// // Restored once the alert expires.
+/// assert_eq!(Some(clock), content.next_frame(3100));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Alert<S> {
+    source: S,
+
+    // The latest frame from the source, restored when the alert ends.
+    frame: Frame,
+
+    // The alert frame, when it started and how long it is shown.
+    alert: Option<(Frame, u32, u32)>,
+
+    // Whether the current frame still has to be returned.
+    pending: bool,
+}
+
+impl<S> Alert<S>
+where
+    S: ContentSource,
+{
+    /// Create an `Alert` showing the given content `source` until an alert is shown.
+    pub fn new(source: S) -> Self {
+        Alert {
+            source,
+            frame: Frame::empty(),
+            alert: None,
+            pending: false,
+        }
+    }
+
+    /// Return the content source.
+    pub fn source_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+
+    /// Return the content source, consuming the alert.
+    pub fn into_source(self) -> S {
+        self.source
+    }
+
+    /// Show the `frame` instead of the content source, replacing any current alert.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The alert to show.
+    /// * `duration` - How long the alert is shown, in milliseconds.
+    /// * `now` - The current time in milliseconds, wrapping on overflow.
+    pub fn show_alert(&mut self, frame: Frame, duration: u32, now: u32) {
+        self.alert = Some((frame, now, duration));
+        self.pending = true;
+    }
+
+    /// Restore the content source before the alert expires.
+    pub fn cancel(&mut self) {
+        if self.alert.take().is_some() {
+            self.pending = true;
+        }
+    }
+
+    /// Return whether an alert is shown at `now`.
+    pub fn is_active(&self, now: u32) -> bool {
+        match self.alert {
+            Some((_, start, duration)) => !elapsed(now, start, duration),
+            None => false,
+        }
+    }
+}
+
+impl<S> ContentSource for Alert<S>
+where
+    S: ContentSource,
+{
+    fn next_frame(&mut self, now: u32) -> Option<Frame> {
+        if let Some(frame) = self.source.next_frame(now) {
+            self.frame = frame;
+            self.pending |= self.alert.is_none();
+        }
+
+        if self.alert.is_some() && !self.is_active(now) {
+            self.alert = None;
+            self.pending = true;
+        }
+
+        if !self.pending {
+            return None;
+        }
+
+        self.pending = false;
+        Some(match self.alert {
+            Some((frame, _, _)) => frame,
+            None => self.frame,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::ROWS_SIZE;
+    use crate::content::Animation;
+    use crate::types::DisplayData;
+
+    const FRAMES: [Frame; 2] = [
+        Frame::from_rows([DisplayData::COMMON_0; ROWS_SIZE]),
+        Frame::from_rows([DisplayData::COMMON_1; ROWS_SIZE]),
+    ];
+    const WARNING: Frame = Frame::from_rows([DisplayData::COMMON_7; ROWS_SIZE]);
+
+    #[test]
+    fn source_keeps_running() {
+        let mut content = Alert::new(Animation::new(&FRAMES, 100));
+
+        assert_eq!(Some(FRAMES[0]), content.next_frame(0));
+
+        content.show_alert(WARNING, 100, 50);
+        assert!(content.is_active(50));
+        assert_eq!(Some(WARNING), content.next_frame(50));
+
+        // The animation advances underneath the alert.
+        assert_eq!(None, content.next_frame(100));
+        assert_eq!(None, content.next_frame(149));
+
+        assert_eq!(Some(FRAMES[1]), content.next_frame(150));
+        assert!(!content.is_active(150));
+    }
+
+    #[test]
+    fn cancel() {
+        let mut content = Alert::new(Animation::new(&FRAMES, 1000));
+
+        content.next_frame(0);
+        content.show_alert(WARNING, 5000, 0);
+        assert_eq!(Some(WARNING), content.next_frame(10));
+
+        content.cancel();
+        assert_eq!(Some(FRAMES[0]), content.next_frame(20));
+        assert_eq!(None, content.next_frame(30));
+
+        // Cancelling without an alert changes nothing.
+        content.cancel();
+        assert_eq!(None, content.next_frame(40));
+    }
+}
@@ -0,0 +1,413 @@
+use crate::constants::ROWS_SIZE;
+use crate::content::{elapsed, ContentSource};
+use crate::errors::ValidationError;
+use crate::hal::blocking::i2c::Write;
+use crate::types::{DisplayData, Frame};
+use crate::HT16K33;
+
+/// How an [`Animation`](struct.Animation.html) continues after its last frame.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum LoopMode {
+    /// Restart from the first frame.
+    #[default]
+    Loop,
+    /// Stop, keeping the last frame shown.
+    Once,
+    /// Play the frames backwards to the first frame, then forwards again.
+    PingPong,
+}
+
+/// A fixed-capacity store of up to `N` frames, e.g. a boot animation, without a heap.
+///
+/// Frames are added as raw display RAM, [`ROWS_SIZE`] bytes each, and played by an
+/// [`Animation`](struct.Animation.html) over [frames()](struct.FrameStore.html#method.frames).
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::content::{Animation, FrameStore};
+/// use ht16k33::ROWS_SIZE;
+/// # use ht16k33::ValidationError;
+/// # fn main() -> Result<(), ValidationError> {
+///
+/// let mut store: FrameStore<4> = FrameStore::new();
+/// store.push_bytes(&[0x00; ROWS_SIZE])?;
+/// store.push_bytes(&[0xFF; ROWS_SIZE])?;
+///
+/// let animation = Animation::new(store.frames(), 100);
+///
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`ROWS_SIZE`]: ../constant.ROWS_SIZE.html
+#[derive(Clone, Debug)]
+pub struct FrameStore<const N: usize> {
+    frames: [Frame; N],
+    len: usize,
+}
+
+impl<const N: usize> Default for FrameStore<N> {
+    fn default() -> Self {
+        FrameStore::new()
+    }
+}
+
+impl<const N: usize> FrameStore<N> {
+    /// Create an empty `FrameStore`.
+    pub const fn new() -> Self {
+        FrameStore {
+            frames: [Frame::empty(); N],
+            len: 0,
+        }
+    }
+
+    /// Return the stored frames, in the order they were added.
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames[..self.len]
+    }
+
+    /// Return the number of stored frames.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return whether no frames are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove all frames.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Add a frame.
+    ///
+    /// # Errors
+    ///
+    /// If the store is full then [`ht16k33::ValidationError::ValueTooLarge`] is returned.
+    ///
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    pub fn push(&mut self, frame: Frame) -> Result<(), ValidationError> {
+        if self.len == N {
+            return Err(ValidationError::ValueTooLarge {
+                name: "frame count",
+                value: (N + 1).min(u8::MAX as usize) as u8,
+                limit: N.min(u8::MAX as usize) as u8,
+                inclusive: true,
+            });
+        }
+
+        self.frames[self.len] = frame;
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Add a frame from its raw display RAM rows.
+    ///
+    /// # Errors
+    ///
+    /// If the store is full then [`ht16k33::ValidationError::ValueTooLarge`] is returned.
+    ///
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    pub fn push_bytes(&mut self, bytes: &[u8; ROWS_SIZE]) -> Result<(), ValidationError> {
+        let mut rows = [DisplayData::COMMON_NONE; ROWS_SIZE];
+        for (row, byte) in rows.iter_mut().zip(bytes.iter()) {
+            *row = DisplayData::from_bits_truncate(*byte);
+        }
+
+        self.push(Frame::from_rows(rows))
+    }
+}
+
+/// Plays a sequence of frames at a fixed frame duration, looping forever unless another
+/// [`LoopMode`](enum.LoopMode.html) is set.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::content::{Animation, ContentSource};
+/// use ht16k33::{DisplayData, Frame, ROWS_SIZE};
+///
+/// let frames = [
+///     Frame::empty(),
+///     Frame::from_rows([DisplayData::all(); ROWS_SIZE]),
+/// ];
+///
+/// let mut animation = Animation::new(&frames, 100);
+///
+/// assert_eq!(Some(frames[0]), animation.next_frame(0));
+/// assert_eq!(None, animation.next_frame(50));
+/// assert_eq!(Some(frames[1]), animation.next_frame(100));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Animation<'a> {
+    frames: &'a [Frame],
+    frame_duration: u32,
+    loop_mode: LoopMode,
+    index: usize,
+    last: Option<u32>,
+
+    // Whether a ping-pong animation is playing backwards.
+    reverse: bool,
+    finished: bool,
+}
+
+impl<'a> Animation<'a> {
+    /// Create an `Animation`.
+    ///
+    /// # Arguments
+    ///
+    /// * `frames` - The frames to play, in order.
+    /// * `frame_duration` - How long each frame is shown, in milliseconds.
+    pub fn new(frames: &'a [Frame], frame_duration: u32) -> Self {
+        Animation {
+            frames,
+            frame_duration,
+            loop_mode: LoopMode::Loop,
+            index: 0,
+            last: None,
+            reverse: false,
+            finished: false,
+        }
+    }
+
+    /// Set how the animation continues after its last frame.
+    pub fn set_loop_mode(&mut self, loop_mode: LoopMode) {
+        self.loop_mode = loop_mode;
+    }
+
+    /// Restart from the first frame.
+    pub fn reset(&mut self) {
+        self.index = 0;
+        self.last = None;
+        self.reverse = false;
+        self.finished = false;
+    }
+
+    /// Return the index of the current frame.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Return whether a [`LoopMode::Once`](enum.LoopMode.html#variant.Once) animation has shown its last frame
+    /// for the frame duration.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Write the next frame to the `driver`, returning whether a frame was written.
+    ///
+    /// Call this regularly, e.g. from a timer tick, to play the animation without a
+    /// [`Pipeline`](../pipeline/struct.Pipeline.html).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// use ht16k33::content::{Animation, LoopMode};
+    /// use ht16k33::{DisplayData, Frame, HT16K33, ROWS_SIZE};
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    ///
+    /// let frames = [Frame::empty(), Frame::from_rows([DisplayData::all(); ROWS_SIZE])];
+    /// let mut boot = Animation::new(&frames, 100);
+    /// boot.set_loop_mode(LoopMode::Once);
+    ///
+    /// let mut now = 0;
+    /// while !boot.is_finished() {
+    ///     boot.tick(&mut ht16k33, now)?;
+    ///     now += 10;
+    /// }
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn tick<I2C, E>(&mut self, driver: &mut HT16K33<I2C>, now: u32) -> Result<bool, E>
+    where
+        I2C: Write<Error = E>,
+    {
+        match self.next_frame(now) {
+            Some(frame) => {
+                driver.set_frame(&frame);
+                driver.flush()?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    // Move to the next frame, returning `false` if the animation has finished.
+    fn advance(&mut self) -> bool {
+        let last = self.frames.len() - 1;
+
+        match self.loop_mode {
+            LoopMode::Loop => self.index = (self.index + 1) % self.frames.len(),
+            LoopMode::Once if self.index < last => self.index += 1,
+            LoopMode::Once => {
+                self.finished = true;
+                return false;
+            }
+            LoopMode::PingPong if last == 0 => {}
+            LoopMode::PingPong => {
+                if self.index == last {
+                    self.reverse = true;
+                } else if self.index == 0 {
+                    self.reverse = false;
+                }
+
+                if self.reverse {
+                    self.index -= 1;
+                } else {
+                    self.index += 1;
+                }
+            }
+        }
+
+        true
+    }
+}
+
+impl<'a> ContentSource for Animation<'a> {
+    fn next_frame(&mut self, now: u32) -> Option<Frame> {
+        if self.frames.is_empty() {
+            return None;
+        }
+
+        let show = match self.last {
+            None => true,
+            Some(_) if self.finished => false,
+            Some(last) if elapsed(now, last, self.frame_duration) => self.advance(),
+            Some(_) => false,
+        };
+
+        if !show {
+            return None;
+        }
+
+        self.last = Some(now);
+        Some(self.frames[self.index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let mut animation = Animation::new(&[], 100);
+
+        assert_eq!(None, animation.next_frame(0));
+    }
+
+    #[test]
+    fn loops() {
+        let frames = [
+            Frame::empty(),
+            Frame::from_rows([DisplayData::COMMON_0; ROWS_SIZE]),
+            Frame::from_rows([DisplayData::COMMON_1; ROWS_SIZE]),
+        ];
+        let mut animation = Animation::new(&frames, 10);
+
+        for (now, index) in [(0, 0), (10, 1), (20, 2), (30, 0)].iter() {
+            assert_eq!(Some(frames[*index]), animation.next_frame(*now));
+        }
+    }
+
+    #[test]
+    fn wrapping_time() {
+        let frames = [
+            Frame::empty(),
+            Frame::from_rows([DisplayData::COMMON_0; ROWS_SIZE]),
+        ];
+        let mut animation = Animation::new(&frames, 10);
+
+        assert_eq!(Some(frames[0]), animation.next_frame(u32::MAX - 4));
+        assert_eq!(None, animation.next_frame(u32::MAX));
+        assert_eq!(Some(frames[1]), animation.next_frame(5));
+    }
+
+    #[test]
+    fn reset() {
+        let frames = [
+            Frame::empty(),
+            Frame::from_rows([DisplayData::COMMON_0; ROWS_SIZE]),
+        ];
+        let mut animation = Animation::new(&frames, 10);
+
+        animation.next_frame(0);
+        animation.next_frame(10);
+        assert_eq!(1, animation.index());
+
+        animation.reset();
+        assert_eq!(Some(frames[0]), animation.next_frame(20));
+    }
+
+    fn frames() -> [Frame; 3] {
+        [
+            Frame::empty(),
+            Frame::from_rows([DisplayData::COMMON_0; ROWS_SIZE]),
+            Frame::from_rows([DisplayData::COMMON_1; ROWS_SIZE]),
+        ]
+    }
+
+    #[test]
+    fn once() {
+        let frames = frames();
+        let mut animation = Animation::new(&frames, 10);
+        animation.set_loop_mode(LoopMode::Once);
+
+        for (now, index) in [(0, 0), (10, 1), (20, 2)].iter() {
+            assert_eq!(Some(frames[*index]), animation.next_frame(*now));
+        }
+        assert!(!animation.is_finished());
+
+        assert_eq!(None, animation.next_frame(30));
+        assert!(animation.is_finished());
+        assert_eq!(None, animation.next_frame(40));
+        assert_eq!(2, animation.index());
+
+        animation.reset();
+        assert_eq!(Some(frames[0]), animation.next_frame(50));
+    }
+
+    #[test]
+    fn ping_pong() {
+        let frames = frames();
+        let mut animation = Animation::new(&frames, 10);
+        animation.set_loop_mode(LoopMode::PingPong);
+
+        for (now, index) in [(0, 0), (10, 1), (20, 2), (30, 1), (40, 0), (50, 1)].iter() {
+            assert_eq!(Some(frames[*index]), animation.next_frame(*now));
+        }
+
+        // A single frame is held.
+        let mut animation = Animation::new(&frames[..1], 10);
+        animation.set_loop_mode(LoopMode::PingPong);
+        animation.next_frame(0);
+        assert_eq!(Some(frames[0]), animation.next_frame(10));
+    }
+
+    #[test]
+    fn frame_store() {
+        let mut store: FrameStore<2> = FrameStore::new();
+        assert!(store.is_empty());
+
+        store.push_bytes(&[0x01; ROWS_SIZE]).unwrap();
+        store.push(Frame::empty()).unwrap();
+        assert!(store.push(Frame::empty()).is_err());
+
+        assert_eq!(2, store.len());
+        assert_eq!(DisplayData::COMMON_0, store.frames()[0].rows()[15]);
+
+        store.clear();
+        assert!(store.frames().is_empty());
+    }
+}
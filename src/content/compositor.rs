@@ -0,0 +1,236 @@
+use crate::content::ContentSource;
+use crate::types::Frame;
+
+/// How a [`Layer`](struct.Layer.html) is merged with the layers below it.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Blend {
+    /// Add the enabled LEDs of the layer, the disabled LEDs are transparent.
+    #[default]
+    Or,
+    /// Replace the layers below with the layer, e.g. for an alert.
+    Replace,
+}
+
+/// A content source in a [`Compositor`](struct.Compositor.html).
+pub struct Layer<'a> {
+    source: &'a mut dyn ContentSource,
+    blend: Blend,
+    enabled: bool,
+
+    // The last frame of the source, kept while the source returns `None`.
+    frame: Frame,
+}
+
+impl<'a> Layer<'a> {
+    /// Create an enabled `Layer`.
+    pub fn new(source: &'a mut dyn ContentSource, blend: Blend) -> Self {
+        Layer {
+            source,
+            blend,
+            enabled: true,
+            frame: Frame::empty(),
+        }
+    }
+
+    /// Return the blend mode.
+    pub fn blend(&self) -> Blend {
+        self.blend
+    }
+
+    /// Return whether the layer is shown.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Return the last frame of the source.
+    pub fn frame(&self) -> &Frame {
+        &self.frame
+    }
+}
+
+/// Merges layers of content into a single frame, from the bottom layer up.
+///
+/// Each layer keeps its last frame, so toggling a layer or changing its blend mode only re-composes the frame,
+/// without re-rendering the other layers. Disabled layers are not polled.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::content::{Blend, Compositor, ContentSource, Layer, Still};
+/// use ht16k33::{DisplayData, Frame, ROWS_SIZE};
+///
+/// let mut background = Still(Frame::from_rows([DisplayData::COMMON_0; ROWS_SIZE]));
+/// let mut text = Still(Frame::from_rows([DisplayData::COMMON_1; ROWS_SIZE]));
+/// let mut alert = Still(Frame::from_rows([DisplayData::all(); ROWS_SIZE]));
+///
+/// let mut compositor = Compositor::new([
+///     Layer::new(&mut background, Blend::Or),
+///     Layer::new(&mut text, Blend::Or),
+///     Layer::new(&mut alert, Blend::Replace),
+/// ]);
+/// compositor.set_enabled(2, false);
+///
+/// let frame = compositor.next_frame(0).unwrap();
+/// assert_eq!(DisplayData::COMMON_0 | DisplayData::COMMON_1, frame.rows()[0]);
+///
+/// compositor.set_enabled(2, true);
+/// assert_eq!(DisplayData::all(), compositor.next_frame(10).unwrap().rows()[0]);
+/// ```
+pub struct Compositor<'a, const N: usize> {
+    layers: [Layer<'a>; N],
+
+    // Whether the frame must be composed again, even if no source has a new frame.
+    dirty: bool,
+}
+
+impl<'a, const N: usize> Compositor<'a, N> {
+    /// Create a `Compositor` from the `layers`, ordered from the bottom up.
+    pub fn new(layers: [Layer<'a>; N]) -> Self {
+        Compositor {
+            layers,
+            dirty: true,
+        }
+    }
+
+    /// Return the layers, ordered from the bottom up.
+    pub fn layers(&self) -> &[Layer<'a>; N] {
+        &self.layers
+    }
+
+    /// Show or hide the layer at `index`.
+    ///
+    /// Layers outside the compositor are ignored.
+    pub fn set_enabled(&mut self, index: usize, enabled: bool) {
+        if let Some(layer) = self.layers.get_mut(index) {
+            self.dirty |= layer.enabled != enabled;
+            layer.enabled = enabled;
+        }
+    }
+
+    /// Set the blend mode of the layer at `index`.
+    ///
+    /// Layers outside the compositor are ignored.
+    pub fn set_blend(&mut self, index: usize, blend: Blend) {
+        if let Some(layer) = self.layers.get_mut(index) {
+            self.dirty |= layer.blend != blend;
+            layer.blend = blend;
+        }
+    }
+
+    // Merge the enabled layers.
+    fn compose(&self) -> Frame {
+        let mut frame = Frame::empty();
+
+        for layer in self.layers.iter().filter(|layer| layer.enabled) {
+            match layer.blend {
+                Blend::Or => {
+                    for (row, value) in frame.rows_mut().iter_mut().zip(layer.frame.rows()) {
+                        *row |= *value;
+                    }
+                }
+                Blend::Replace => frame = layer.frame,
+            }
+        }
+
+        frame
+    }
+}
+
+impl<const N: usize> ContentSource for Compositor<'_, N> {
+    fn next_frame(&mut self, now: u32) -> Option<Frame> {
+        for layer in self.layers.iter_mut().filter(|layer| layer.enabled) {
+            if let Some(frame) = layer.source.next_frame(now) {
+                self.dirty |= frame != layer.frame;
+                layer.frame = frame;
+            }
+        }
+
+        if !self.dirty {
+            return None;
+        }
+
+        self.dirty = false;
+        Some(self.compose())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::Still;
+    use crate::types::DisplayData;
+
+    fn still(value: DisplayData) -> Still {
+        Still(Frame::from_rows([value; crate::constants::ROWS_SIZE]))
+    }
+
+    #[test]
+    fn unchanged() {
+        let mut background = still(DisplayData::COMMON_0);
+        let mut compositor = Compositor::new([Layer::new(&mut background, Blend::Or)]);
+
+        assert!(compositor.next_frame(0).is_some());
+        assert_eq!(None, compositor.next_frame(10));
+
+        // Toggling re-composes, setting the same value does not.
+        compositor.set_enabled(0, true);
+        assert_eq!(None, compositor.next_frame(20));
+        compositor.set_enabled(0, false);
+        assert_eq!(Some(Frame::empty()), compositor.next_frame(30));
+    }
+
+    #[test]
+    fn blend() {
+        let mut background = still(DisplayData::COMMON_0);
+        let mut alert = still(DisplayData::COMMON_7);
+        let mut text = still(DisplayData::COMMON_1);
+        let mut compositor = Compositor::new([
+            Layer::new(&mut background, Blend::Or),
+            Layer::new(&mut alert, Blend::Replace),
+            Layer::new(&mut text, Blend::Or),
+        ]);
+
+        let frame = compositor.next_frame(0).unwrap();
+        assert_eq!(
+            DisplayData::COMMON_7 | DisplayData::COMMON_1,
+            frame.rows()[0]
+        );
+
+        compositor.set_blend(1, Blend::Or);
+        let frame = compositor.next_frame(10).unwrap();
+        assert_eq!(
+            DisplayData::COMMON_0 | DisplayData::COMMON_7 | DisplayData::COMMON_1,
+            frame.rows()[0]
+        );
+    }
+
+    #[test]
+    fn keeps_last_frame() {
+        let mut calls = 0;
+        let mut blink = |_now: u32| {
+            calls += 1;
+            if calls == 1 {
+                Some(Frame::from_rows(
+                    [DisplayData::COMMON_3; crate::constants::ROWS_SIZE],
+                ))
+            } else {
+                None
+            }
+        };
+        let mut background = still(DisplayData::COMMON_0);
+        let mut compositor = Compositor::new([
+            Layer::new(&mut background, Blend::Or),
+            Layer::new(&mut blink, Blend::Or),
+        ]);
+
+        compositor.next_frame(0);
+        compositor.set_enabled(0, false);
+
+        let frame = compositor.next_frame(10).unwrap();
+        assert_eq!(DisplayData::COMMON_3, frame.rows()[0]);
+        assert_eq!(
+            DisplayData::COMMON_3,
+            compositor.layers()[1].frame().rows()[0]
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//! # content
+//!
+//! Sources of frames for a [`Pipeline`](../pipeline/struct.Pipeline.html).
+//!
+//! Local widgets and remote receivers implement the same [`ContentSource`](trait.ContentSource.html) trait,
+//! so a sign fed over UART or BLE plugs into the same pipeline as local animations.
+mod alert;
+mod animation;
+mod compositor;
+mod multiplexer;
+mod raw_frame_decoder;
+mod scroll;
+
+pub use self::alert::Alert;
+pub use self::animation::{Animation, FrameStore, LoopMode};
+pub use self::compositor::{Blend, Compositor, Layer};
+pub use self::multiplexer::{Multiplexer, Slot};
+pub use self::raw_frame_decoder::RawFrameDecoder;
+pub use self::scroll::Scroll;
+
+use crate::types::Frame;
+
+/// A source of frames.
+///
+/// Closures of the form `FnMut(u32) -> Option<Frame>` are also content sources.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::content::ContentSource;
+/// use ht16k33::{DisplayData, Frame, ROWS_SIZE};
+///
+/// // Blink the whole display every second.
+/// let mut blink = |now: u32| {
+///     let on = (now / 500) % 2 == 0;
+///     Some(Frame::from_rows([DisplayData::from_bits_truncate(if on { 0xFF } else { 0 }); ROWS_SIZE]))
+/// };
+///
+/// assert_eq!(Some(Frame::empty()), blink.next_frame(500));
+/// ```
+pub trait ContentSource {
+    /// Return the frame to show at time `now`, or `None` to keep showing the previous frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time in milliseconds, wrapping on overflow.
+    fn next_frame(&mut self, now: u32) -> Option<Frame>;
+}
+
+impl<F> ContentSource for F
+where
+    F: FnMut(u32) -> Option<Frame>,
+{
+    fn next_frame(&mut self, now: u32) -> Option<Frame> {
+        self(now)
+    }
+}
+
+/// A source that always shows the same frame.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Still(pub Frame);
+
+impl ContentSource for Still {
+    fn next_frame(&mut self, _now: u32) -> Option<Frame> {
+        Some(self.0)
+    }
+}
+
+// Return whether `interval` milliseconds have elapsed since `since`, tolerating wrap-around.
+pub(crate) fn elapsed(now: u32, since: u32, interval: u32) -> bool {
+    now.wrapping_sub(since) >= interval
+}
@@ -0,0 +1,164 @@
+use crate::content::{elapsed, ContentSource};
+use crate::types::Frame;
+
+/// A content source given the display for a while by a [`Multiplexer`](struct.Multiplexer.html).
+pub struct Slot<'a> {
+    source: &'a mut dyn ContentSource,
+    duration: u32,
+
+    // The last frame of the source, shown when it gets the display back.
+    frame: Frame,
+}
+
+impl<'a> Slot<'a> {
+    /// Create a `Slot` showing the `source` for `duration` milliseconds at a time.
+    pub fn new(source: &'a mut dyn ContentSource, duration: u32) -> Self {
+        Slot {
+            source,
+            duration,
+            frame: Frame::empty(),
+        }
+    }
+
+    /// Return how long the source is shown at a time, in milliseconds.
+    pub fn duration(&self) -> u32 {
+        self.duration
+    }
+}
+
+/// Shows several content sources in turn, each for its own duration.
+///
+/// Only the source being shown is polled. When a source gets the display, its last frame is shown right away,
+/// even if it has no new frame, so the previous source never lingers on the display.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::content::{ContentSource, Multiplexer, Slot, Still};
+/// use ht16k33::{DisplayData, Frame, ROWS_SIZE};
+///
+/// let clock_frame = Frame::from_rows([DisplayData::COMMON_0; ROWS_SIZE]);
+/// let temperature_frame = Frame::from_rows([DisplayData::COMMON_1; ROWS_SIZE]);
+/// let mut clock = Still(clock_frame);
+/// let mut temperature = Still(temperature_frame);
+///
+/// let mut multiplexer = Multiplexer::new([Slot::new(&mut clock, 5000), Slot::new(&mut temperature, 2000)]);
+///
+/// assert_eq!(Some(clock_frame), multiplexer.next_frame(0));
+/// assert_eq!(Some(temperature_frame), multiplexer.next_frame(5000));
+/// assert_eq!(Some(clock_frame), multiplexer.next_frame(7000));
+/// ```
+pub struct Multiplexer<'a, const N: usize> {
+    slots: [Slot<'a>; N],
+    index: usize,
+
+    // When the current slot got the display, `None` until the first frame.
+    since: Option<u32>,
+}
+
+impl<'a, const N: usize> Multiplexer<'a, N> {
+    /// Create a `Multiplexer` showing the `slots` in order, starting with the first.
+    pub fn new(slots: [Slot<'a>; N]) -> Self {
+        Multiplexer {
+            slots,
+            index: 0,
+            since: None,
+        }
+    }
+
+    /// Return the slots.
+    pub fn slots(&self) -> &[Slot<'a>; N] {
+        &self.slots
+    }
+
+    /// Return the index of the slot being shown.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Show the slot at `index` from the next frame, for its full duration.
+    ///
+    /// Slots outside the multiplexer are ignored.
+    pub fn show(&mut self, index: usize) {
+        if index < N {
+            self.index = index;
+            self.since = None;
+        }
+    }
+}
+
+impl<const N: usize> ContentSource for Multiplexer<'_, N> {
+    fn next_frame(&mut self, now: u32) -> Option<Frame> {
+        if N == 0 {
+            return None;
+        }
+
+        let handoff = match self.since {
+            None => true,
+            Some(since) if elapsed(now, since, self.slots[self.index].duration) => {
+                self.index = (self.index + 1) % N;
+                true
+            }
+            Some(_) => false,
+        };
+
+        if handoff {
+            self.since = Some(now);
+        }
+
+        let slot = &mut self.slots[self.index];
+        match slot.source.next_frame(now) {
+            Some(frame) => {
+                slot.frame = frame;
+                Some(frame)
+            }
+            None if handoff => Some(slot.frame),
+            None => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::ROWS_SIZE;
+    use crate::content::Animation;
+    use crate::types::DisplayData;
+
+    const FRAMES: [Frame; 2] = [
+        Frame::from_rows([DisplayData::COMMON_0; ROWS_SIZE]),
+        Frame::from_rows([DisplayData::COMMON_1; ROWS_SIZE]),
+    ];
+
+    #[test]
+    fn handoff() {
+        let mut first = Animation::new(&FRAMES[..1], 10_000);
+        let mut second = Animation::new(&FRAMES[1..], 10_000);
+        let mut multiplexer =
+            Multiplexer::new([Slot::new(&mut first, 100), Slot::new(&mut second, 50)]);
+
+        assert_eq!(Some(FRAMES[0]), multiplexer.next_frame(0));
+        assert_eq!(None, multiplexer.next_frame(99));
+        assert_eq!(Some(FRAMES[1]), multiplexer.next_frame(100));
+        assert_eq!(1, multiplexer.index());
+
+        // The animation has no new frame, its last frame is shown again.
+        assert_eq!(Some(FRAMES[0]), multiplexer.next_frame(150));
+        assert_eq!(0, multiplexer.index());
+    }
+
+    #[test]
+    fn show() {
+        let mut first = Animation::new(&FRAMES[..1], 10_000);
+        let mut second = Animation::new(&FRAMES[1..], 10_000);
+        let mut multiplexer =
+            Multiplexer::new([Slot::new(&mut first, 100), Slot::new(&mut second, 50)]);
+
+        multiplexer.next_frame(0);
+        multiplexer.show(1);
+        multiplexer.show(2);
+        assert_eq!(Some(FRAMES[1]), multiplexer.next_frame(10));
+        assert_eq!(None, multiplexer.next_frame(59));
+        assert_eq!(Some(FRAMES[0]), multiplexer.next_frame(60));
+    }
+}
@@ -0,0 +1,128 @@
+use crate::constants::ROWS_SIZE;
+use crate::content::{elapsed, ContentSource};
+use crate::types::{DisplayData, Frame};
+
+/// Decodes raw frames received over a byte stream, e.g. a UART or BLE serial link.
+///
+/// Each frame is sent as [`ROWS_SIZE`] bytes of display RAM, and frames are delimited by idle gaps on the
+/// link: a partially received frame is discarded when no byte arrives for the configured timeout. This keeps
+/// the receiver synchronized without any framing overhead.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::content::{ContentSource, RawFrameDecoder};
+/// use ht16k33::ROWS_SIZE;
+///
+/// let mut decoder = RawFrameDecoder::new(20);
+///
+/// for (index, byte) in [0xFFu8; ROWS_SIZE].iter().enumerate() {
+///     decoder.push(*byte, index as u32);
+/// }
+///
+/// let frame = decoder.next_frame(ROWS_SIZE as u32).unwrap();
+/// assert_eq!(0xFF, frame.rows()[0].bits());
+/// ```
+///
+/// [`ROWS_SIZE`]: ../constant.ROWS_SIZE.html
+#[derive(Clone, Debug)]
+pub struct RawFrameDecoder {
+    timeout: u32,
+    partial: [DisplayData; ROWS_SIZE],
+    received: usize,
+    last: Option<u32>,
+    complete: Option<Frame>,
+}
+
+impl RawFrameDecoder {
+    /// Create a `RawFrameDecoder`.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The idle time, in milliseconds, after which a partial frame is discarded.
+    pub fn new(timeout: u32) -> Self {
+        RawFrameDecoder {
+            timeout,
+            partial: [DisplayData::empty(); ROWS_SIZE],
+            received: 0,
+            last: None,
+            complete: None,
+        }
+    }
+
+    /// Feed a received `byte`, received at time `now`.
+    ///
+    /// Returns `true` when the byte completes a frame.
+    pub fn push(&mut self, byte: u8, now: u32) -> bool {
+        if let Some(last) = self.last {
+            if elapsed(now, last, self.timeout) {
+                self.received = 0;
+            }
+        }
+        self.last = Some(now);
+
+        self.partial[self.received] = DisplayData::from_bits_truncate(byte);
+        self.received += 1;
+
+        if self.received == ROWS_SIZE {
+            self.received = 0;
+            self.complete = Some(Frame::from_rows(self.partial));
+            return true;
+        }
+
+        false
+    }
+
+    /// Feed several received `bytes`, all received at time `now`.
+    pub fn extend(&mut self, bytes: &[u8], now: u32) {
+        for byte in bytes.iter() {
+            self.push(*byte, now);
+        }
+    }
+}
+
+impl ContentSource for RawFrameDecoder {
+    /// Return the most recently completed frame, if it has not been returned yet.
+    fn next_frame(&mut self, _now: u32) -> Option<Frame> {
+        self.complete.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complete_frame() {
+        let mut decoder = RawFrameDecoder::new(10);
+
+        let mut bytes = [0u8; ROWS_SIZE];
+        bytes[3] = 0b0000_1000;
+
+        for (index, byte) in bytes.iter().enumerate() {
+            let complete = decoder.push(*byte, 0);
+            assert_eq!(index == ROWS_SIZE - 1, complete);
+        }
+
+        let frame = decoder.next_frame(0).unwrap();
+        assert_eq!(0b0000_1000, frame.rows()[3].bits());
+
+        // Only returned once.
+        assert_eq!(None, decoder.next_frame(0));
+    }
+
+    #[test]
+    fn idle_gap_discards_partial_frame() {
+        let mut decoder = RawFrameDecoder::new(10);
+
+        decoder.extend(&[0xAA; 4], 0);
+
+        decoder.extend(&[0x55; ROWS_SIZE], 20);
+
+        let frame = decoder.next_frame(20).unwrap();
+        assert_eq!(
+            Frame::from_rows([DisplayData::from_bits_truncate(0x55); ROWS_SIZE]),
+            frame
+        );
+    }
+}
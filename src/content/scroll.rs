@@ -0,0 +1,129 @@
+use crate::constants::ROWS_SIZE;
+use crate::content::{elapsed, ContentSource};
+use crate::types::{DisplayData, Frame};
+
+/// Scrolls a strip of row values through the display RAM, one row per step, wrapping around.
+///
+/// Each row value of the strip is shown in one display RAM row, so the strip moves across whichever axis of
+/// the physical display the rows are wired to.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::content::{ContentSource, Scroll};
+/// use ht16k33::DisplayData;
+///
+/// let strip = [DisplayData::all(), DisplayData::empty(), DisplayData::empty()];
+/// let mut scroll = Scroll::new(&strip, 100);
+///
+/// let frame = scroll.next_frame(0).unwrap();
+/// assert_eq!(DisplayData::all(), frame.rows()[0]);
+///
+/// let frame = scroll.next_frame(100).unwrap();
+/// assert_eq!(DisplayData::all(), frame.rows()[2]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Scroll<'a> {
+    strip: &'a [DisplayData],
+    step_duration: u32,
+    offset: usize,
+    last: Option<u32>,
+}
+
+impl<'a> Scroll<'a> {
+    /// Create a `Scroll`.
+    ///
+    /// # Arguments
+    ///
+    /// * `strip` - The row values to scroll through.
+    /// * `step_duration` - How long each position is shown, in milliseconds.
+    pub fn new(strip: &'a [DisplayData], step_duration: u32) -> Self {
+        Scroll {
+            strip,
+            step_duration,
+            offset: 0,
+            last: None,
+        }
+    }
+
+    /// Return the index of the strip value shown in the first row.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    fn render(&self) -> Frame {
+        let mut rows = [DisplayData::empty(); ROWS_SIZE];
+
+        for (index, row) in rows.iter_mut().enumerate() {
+            *row = self.strip[(self.offset + index) % self.strip.len()];
+        }
+
+        Frame::from_rows(rows)
+    }
+}
+
+impl<'a> ContentSource for Scroll<'a> {
+    fn next_frame(&mut self, now: u32) -> Option<Frame> {
+        if self.strip.is_empty() {
+            return None;
+        }
+
+        match self.last {
+            None => {}
+            Some(last) if elapsed(now, last, self.step_duration) => {
+                self.offset = (self.offset + 1) % self.strip.len();
+            }
+            Some(_) => return None,
+        }
+
+        self.last = Some(now);
+        Some(self.render())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let mut scroll = Scroll::new(&[], 100);
+
+        assert_eq!(None, scroll.next_frame(0));
+    }
+
+    #[test]
+    fn short_strip_repeats() {
+        let strip = [DisplayData::COMMON_0, DisplayData::COMMON_1];
+        let mut scroll = Scroll::new(&strip, 10);
+
+        let frame = scroll.next_frame(0).unwrap();
+        for (index, row) in frame.rows().iter().enumerate() {
+            assert_eq!(strip[index % 2], *row);
+        }
+    }
+
+    #[test]
+    fn steps() {
+        let mut strip = [DisplayData::empty(); ROWS_SIZE + 4];
+        strip[0] = DisplayData::all();
+        let mut scroll = Scroll::new(&strip, 10);
+
+        scroll.next_frame(0);
+        assert_eq!(None, scroll.next_frame(5));
+
+        // The lit value moves from row 0 to the end of the strip, which is past the last row.
+        let frame = scroll.next_frame(10).unwrap();
+        assert_eq!(1, scroll.offset());
+        assert_eq!(Frame::empty(), frame);
+
+        for now in 2..ROWS_SIZE + 4 {
+            scroll.next_frame(now as u32 * 10);
+        }
+
+        // Wrapped around.
+        let frame = scroll.next_frame((ROWS_SIZE + 4) as u32 * 10).unwrap();
+        assert_eq!(0, scroll.offset());
+        assert_eq!(DisplayData::all(), frame.rows()[0]);
+    }
+}
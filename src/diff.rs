@@ -0,0 +1,204 @@
+//! # diff
+//!
+//! Compare display frames row by row, e.g. to assert in a test that only one digit changed.
+//!
+//! The driver uses the same comparison to find the rows to write on a
+//! [flush()](../struct.HT16K33.html#method.flush).
+use crate::types::DisplayData;
+
+use core::fmt;
+
+/// Return the rows that differ between the frames `a` and `b`, with the bits that changed.
+///
+/// Rows past the end of the shorter frame are not compared.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::diff::diff_frames;
+/// use ht16k33::{DisplayData, ROWS_SIZE};
+///
+/// let before = [DisplayData::empty(); ROWS_SIZE];
+/// let mut after = before;
+/// after[2] = DisplayData::COMMON_0 | DisplayData::COMMON_3;
+///
+/// let mut diff = diff_frames(&before, &after);
+/// assert_eq!("row 2: on 0, 3", format!("{}", diff));
+///
+/// // Only digit 2 changed.
+/// assert_eq!(Some((2, DisplayData::COMMON_0 | DisplayData::COMMON_3)), diff.next());
+/// assert_eq!(None, diff.next());
+/// ```
+pub fn diff_frames<'a>(a: &'a [DisplayData], b: &'a [DisplayData]) -> Diff<'a> {
+    let len = a.len().min(b.len());
+
+    Diff {
+        a: &a[..len],
+        b: &b[..len],
+        front: 0,
+        back: len,
+    }
+}
+
+/// The changed rows of two frames, see [diff_frames()](fn.diff_frames.html).
+///
+/// Iterates over the remaining changed rows, as the row index and the changed bits. The `Display` implementation
+/// is a human-readable summary of the remaining changes, one line per row, listing the commons turned on and off.
+#[derive(Clone, Debug)]
+pub struct Diff<'a> {
+    a: &'a [DisplayData],
+    b: &'a [DisplayData],
+    front: usize,
+    back: usize,
+}
+
+impl<'a> Diff<'a> {
+    /// Return the remaining changed rows, bit `n` for row `n`.
+    ///
+    /// Only the 16 rows of the display RAM fit, changes from row 16 on are left out.
+    pub fn rows(&self) -> u16 {
+        self.clone()
+            .filter(|(row, _)| *row < u16::BITS as usize)
+            .fold(0, |rows, (row, _)| rows | (1 << row))
+    }
+
+    /// Return whether no rows remain changed.
+    pub fn is_empty(&self) -> bool {
+        self.clone().next().is_none()
+    }
+
+    fn changed(&self, row: usize) -> DisplayData {
+        self.a[row] ^ self.b[row]
+    }
+}
+
+impl<'a> Iterator for Diff<'a> {
+    type Item = (usize, DisplayData);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.front < self.back {
+            let row = self.front;
+            self.front += 1;
+
+            let changed = self.changed(row);
+            if !changed.is_empty() {
+                return Some((row, changed));
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a> DoubleEndedIterator for Diff<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while self.front < self.back {
+            self.back -= 1;
+            let row = self.back;
+
+            let changed = self.changed(row);
+            if !changed.is_empty() {
+                return Some((row, changed));
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a> fmt::Display for Diff<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut changes = self.clone().peekable();
+        if changes.peek().is_none() {
+            return write!(f, "no changes");
+        }
+
+        for (index, (row, changed)) in changes.enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "row {}:", row)?;
+
+            let on = changed & self.b[row];
+            let off = changed & self.a[row];
+            if !on.is_empty() {
+                write!(f, " on")?;
+                write_commons(f, on)?;
+            }
+            if !off.is_empty() {
+                let separator = if on.is_empty() { "" } else { ";" };
+                write!(f, "{} off", separator)?;
+                write_commons(f, off)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Write the commons set in `commons`, comma separated.
+fn write_commons(f: &mut fmt::Formatter, commons: DisplayData) -> fmt::Result {
+    let mut first = true;
+    for common in 0..8 {
+        if commons.bits() & (1 << common) == 0 {
+            continue;
+        }
+        if first {
+            write!(f, " {}", common)?;
+        } else {
+            write!(f, ", {}", common)?;
+        }
+        first = false;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::ROWS_SIZE;
+    use std::string::ToString;
+
+    #[test]
+    fn diff_frames() {
+        let a = [DisplayData::empty(); ROWS_SIZE];
+        let mut b = a;
+        b[1] = DisplayData::COMMON_7;
+        b[9] = DisplayData::COMMON_1;
+
+        let diff = super::diff_frames(&a, &b);
+        assert_eq!((1 << 1) | (1 << 9), diff.rows());
+        assert_eq!(Some((9, DisplayData::COMMON_1)), diff.clone().next_back());
+        assert_eq!(2, diff.count());
+
+        assert!(super::diff_frames(&a, &a).is_empty());
+    }
+
+    #[test]
+    fn rows_past_display_ram() {
+        let a = [DisplayData::empty(); 17];
+        let mut b = a;
+        b[2] = DisplayData::COMMON_0;
+        b[16] = DisplayData::COMMON_0;
+
+        let diff = super::diff_frames(&a, &b);
+        assert_eq!(1 << 2, diff.rows());
+        assert_eq!(Some((16, DisplayData::COMMON_0)), diff.clone().next_back());
+    }
+
+    #[test]
+    fn summary() {
+        let mut a = [DisplayData::empty(); ROWS_SIZE];
+        let mut b = a;
+        a[0] = DisplayData::COMMON_2;
+        b[0] = DisplayData::COMMON_4 | DisplayData::COMMON_5;
+        a[3] = DisplayData::COMMON_1;
+
+        assert_eq!(
+            "row 0: on 4, 5; off 2\nrow 3: off 1",
+            super::diff_frames(&a, &b).to_string()
+        );
+        assert_eq!("no changes", super::diff_frames(&a, &a).to_string());
+    }
+}
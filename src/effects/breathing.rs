@@ -0,0 +1,245 @@
+use crate::effects::{Effect, Output};
+use crate::hal::blocking::i2c::Write;
+use crate::types::Dimming;
+use crate::HT16K33;
+
+/// Non-blocking breathing effect, sweeping the dimming up and down between two levels, e.g. for a status
+/// indicator.
+///
+/// Start breathing with [start()](struct.Breathing.html#method.start), then call
+/// [tick()](struct.Breathing.html#method.tick) regularly to step the driver dimming. Each period rises from
+/// `min` to `max` and falls back to `min`. The breathing can be [paused](struct.Breathing.html#method.pause) and
+/// [resumed](struct.Breathing.html#method.resume) at the same level, and
+/// [stop()](struct.Breathing.html#method.stop) restores the dimming from before the start. As an
+/// [`Effect`](trait.Effect.html), the breathing sets the output dimming instead.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use ht16k33::effects::Breathing;
+/// use ht16k33::{Dimming, HT16K33};
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+///
+/// let mut breathing = Breathing::new(Dimming::BRIGHTNESS_MIN, Dimming::BRIGHTNESS_MAX, 2000);
+/// breathing.start(*ht16k33.dimming(), 0);
+///
+/// for now in (0..4000).step_by(20) {
+///     breathing.tick(&mut ht16k33, now)?;
+/// }
+///
+/// breathing.stop(&mut ht16k33)?;
+/// assert_eq!(Dimming::BRIGHTNESS_MAX, *ht16k33.dimming());
+///
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Breathing {
+    min: Dimming,
+    max: Dimming,
+    period: u32,
+
+    // The dimming from before the start, restored on stop.
+    restore: Option<Dimming>,
+
+    // When the current period started, if running.
+    start: Option<u32>,
+
+    // The time into the period, if paused.
+    paused: Option<u32>,
+}
+
+impl Breathing {
+    /// Create a stopped `Breathing`.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - The dimmest level.
+    /// * `max` - The brightest level.
+    /// * `period` - The length of a rise and fall, in milliseconds.
+    pub fn new(min: Dimming, max: Dimming, period: u32) -> Self {
+        Breathing {
+            min,
+            max,
+            period,
+            restore: None,
+            start: None,
+            paused: None,
+        }
+    }
+
+    /// Start breathing from the `min` level.
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - The dimming to restore on [stop()](struct.Breathing.html#method.stop), usually the current
+    ///   dimming.
+    /// * `now` - The current time in milliseconds, wrapping on overflow.
+    pub fn start(&mut self, current: Dimming, now: u32) {
+        self.restore = Some(current);
+        self.start = Some(now);
+        self.paused = None;
+    }
+
+    /// Hold the current level until [resume()](struct.Breathing.html#method.resume).
+    pub fn pause(&mut self, now: u32) {
+        if let Some(start) = self.start.take() {
+            self.paused = Some(self.phase(now, start));
+        }
+    }
+
+    /// Continue breathing from the level it was paused at.
+    pub fn resume(&mut self, now: u32) {
+        if let Some(phase) = self.paused.take() {
+            self.start = Some(now.wrapping_sub(phase));
+        }
+    }
+
+    /// Return whether the breathing is started and not paused.
+    pub fn is_running(&self) -> bool {
+        self.start.is_some()
+    }
+
+    /// Return whether the breathing is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused.is_some()
+    }
+
+    /// Return the dimming at `now`, if started and not stopped.
+    pub fn level(&self, now: u32) -> Option<Dimming> {
+        let phase = match (self.start, self.paused) {
+            (Some(start), _) => self.phase(now, start),
+            (None, Some(phase)) => phase,
+            (None, None) => return None,
+        };
+
+        let min = self.min.bits() as u32;
+        let max = self.max.bits() as u32;
+        let half = self.period / 2;
+        if half == 0 {
+            return Some(self.max);
+        }
+
+        // Rise during the first half of the period, and fall during the second.
+        let rise = if phase < half {
+            phase
+        } else {
+            self.period - phase
+        };
+        let level = min + (max.saturating_sub(min) * rise.min(half) + half / 2) / half;
+
+        Some(Dimming::from_bits_truncate(level as u8))
+    }
+
+    /// Set the `driver` dimming to the level at `now` if it changed.
+    pub fn tick<I2C, E>(&mut self, driver: &mut HT16K33<I2C>, now: u32) -> Result<(), E>
+    where
+        I2C: Write<Error = E>,
+    {
+        if let Some(level) = self.level(now) {
+            if *driver.dimming() != level {
+                driver.set_dimming(level)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stop breathing, and set the `driver` dimming back to the dimming from before the start.
+    pub fn stop<I2C, E>(&mut self, driver: &mut HT16K33<I2C>) -> Result<(), E>
+    where
+        I2C: Write<Error = E>,
+    {
+        self.start = None;
+        self.paused = None;
+
+        if let Some(restore) = self.restore.take() {
+            if *driver.dimming() != restore {
+                driver.set_dimming(restore)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Return the time into the period at `now`.
+    fn phase(&self, now: u32, start: u32) -> u32 {
+        match self.period {
+            0 => 0,
+            period => now.wrapping_sub(start) % period,
+        }
+    }
+}
+
+impl Effect for Breathing {
+    fn apply(&mut self, output: &mut Output, now: u32) {
+        if let Some(level) = self.level(now) {
+            output.dimming = level;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c_mock::I2cMock;
+    use crate::types::Address;
+
+    #[test]
+    fn level() {
+        let mut breathing = Breathing::new(Dimming::BRIGHTNESS_MIN, Dimming::BRIGHTNESS_MAX, 3000);
+        assert_eq!(None, breathing.level(0));
+
+        breathing.start(Dimming::BRIGHTNESS_MAX, 1000);
+        assert_eq!(Some(Dimming::BRIGHTNESS_MIN), breathing.level(1000));
+        assert_eq!(Some(Dimming::BRIGHTNESS_9_16), breathing.level(1750));
+        assert_eq!(Some(Dimming::BRIGHTNESS_MAX), breathing.level(2500));
+        assert_eq!(Some(Dimming::BRIGHTNESS_9_16), breathing.level(3250));
+        assert_eq!(Some(Dimming::BRIGHTNESS_MIN), breathing.level(4000));
+    }
+
+    #[test]
+    fn pause_and_resume() {
+        let mut breathing =
+            Breathing::new(Dimming::BRIGHTNESS_4_16, Dimming::BRIGHTNESS_12_16, 1000);
+        breathing.start(Dimming::BRIGHTNESS_MAX, 0);
+
+        breathing.pause(250);
+        assert!(breathing.is_paused());
+        assert_eq!(Some(Dimming::BRIGHTNESS_8_16), breathing.level(5000));
+
+        // Continues from the paused level.
+        breathing.resume(5000);
+        assert!(breathing.is_running());
+        assert_eq!(Some(Dimming::BRIGHTNESS_8_16), breathing.level(5000));
+        assert_eq!(Some(Dimming::BRIGHTNESS_12_16), breathing.level(5250));
+    }
+
+    #[test]
+    fn tick_and_stop() {
+        let mut driver = HT16K33::new(I2cMock::new(), Address::DEFAULT)
+            .initialize()
+            .unwrap();
+
+        let mut breathing = Breathing::new(Dimming::BRIGHTNESS_MIN, Dimming::BRIGHTNESS_4_16, 80);
+        breathing.start(*driver.dimming(), 0);
+
+        for now in 0..80 {
+            breathing.tick(&mut driver, now).unwrap();
+        }
+        breathing.stop(&mut driver).unwrap();
+        assert!(!breathing.is_running());
+
+        // One write per level change, after the initialization: down to `min`, up 3 levels, down 3, and the
+        // restore.
+        let i2c = driver.destroy();
+        assert_eq!(4 + 1 + 3 + 3 + 1, i2c.transactions().len());
+        assert_eq!(Dimming::BRIGHTNESS_MAX, i2c.dimming());
+    }
+}
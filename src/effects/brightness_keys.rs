@@ -0,0 +1,211 @@
+use crate::content::elapsed;
+use crate::effects::{Effect, Output};
+use crate::keypad::KeyEvent;
+use crate::layouts::Layout;
+use crate::types::Dimming;
+
+/// The number of brightness levels of [`BrightnessKeys`](struct.BrightnessKeys.html).
+pub const BRIGHTNESS_LEVELS: u8 = 8;
+
+// The dimming of each level, roughly following a 2.2 gamma curve so the steps look evenly spaced.
+const GAMMA: [u8; BRIGHTNESS_LEVELS as usize] = [0, 1, 2, 3, 5, 7, 11, 15];
+
+/// Raises and lowers the display brightness with two keypad keys, briefly showing the level as a bar.
+///
+/// Feed the [`KeyEvent`](../keypad/enum.KeyEvent.html)s from a [`Keypad`](../keypad/struct.Keypad.html) to
+/// [key_event()](struct.BrightnessKeys.html#method.key_event). The brightness steps through
+/// [`BRIGHTNESS_LEVELS`] gamma-corrected levels. As an [`Effect`](trait.Effect.html), it limits the output
+/// dimming to the selected level, and replaces the frame with a bar filling the layout from the left in
+/// proportion to the level for a while after each change.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::effects::{BrightnessKeys, Effect, Output};
+/// use ht16k33::keypad::KeyEvent;
+/// use ht16k33::layouts::Matrix8x8;
+/// use ht16k33::Dimming;
+///
+/// // Keys 0 and 1 lower and raise the brightness, the bar is shown for 1 second.
+/// let mut brightness = BrightnessKeys::new(Matrix8x8, 1, 0, 1000);
+///
+/// assert!(brightness.key_event(KeyEvent::Pressed(0), 0));
+///
+/// let mut output = Output::default();
+/// brightness.apply(&mut output, 100);
+/// assert_eq!(Dimming::BRIGHTNESS_12_16, output.dimming);
+/// ```
+///
+/// [`BRIGHTNESS_LEVELS`]: constant.BRIGHTNESS_LEVELS.html
+#[derive(Clone, Debug)]
+pub struct BrightnessKeys<L> {
+    layout: L,
+    up: u8,
+    down: u8,
+    level: u8,
+
+    // How long the bar is shown, and when it was last started.
+    duration: u32,
+    shown: Option<u32>,
+}
+
+impl<L> BrightnessKeys<L>
+where
+    L: Layout,
+{
+    /// Create a `BrightnessKeys` at the highest level.
+    ///
+    /// # Arguments
+    ///
+    /// * `layout` - The layout the level bar is drawn on.
+    /// * `up` - The key raising the brightness.
+    /// * `down` - The key lowering the brightness.
+    /// * `duration` - How long the level bar is shown after a change, in milliseconds.
+    pub fn new(layout: L, up: u8, down: u8, duration: u32) -> Self {
+        BrightnessKeys {
+            layout,
+            up,
+            down,
+            level: BRIGHTNESS_LEVELS - 1,
+            duration,
+            shown: None,
+        }
+    }
+
+    /// Return the brightness level, from `0` (dimmest) to `BRIGHTNESS_LEVELS - 1`.
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
+    /// Set the brightness level, limited to `BRIGHTNESS_LEVELS - 1`, without showing the level bar.
+    pub fn set_level(&mut self, level: u8) {
+        self.level = level.min(BRIGHTNESS_LEVELS - 1);
+    }
+
+    /// Return the dimming of the brightness level.
+    pub fn dimming(&self) -> Dimming {
+        Dimming::from_bits_truncate(GAMMA[self.level as usize])
+    }
+
+    /// Handle a key event, returning whether the brightness level changed.
+    ///
+    /// Presses of the up and down keys step the level and show the level bar, even when the level is already
+    /// at its limit. Other events are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The key event.
+    /// * `now` - The current time in milliseconds, wrapping on overflow.
+    pub fn key_event(&mut self, event: KeyEvent, now: u32) -> bool {
+        let level = match event {
+            KeyEvent::Pressed(key) if key == self.up => (self.level + 1).min(BRIGHTNESS_LEVELS - 1),
+            KeyEvent::Pressed(key) if key == self.down => self.level.saturating_sub(1),
+            _ => return false,
+        };
+
+        self.shown = Some(now);
+
+        let changed = level != self.level;
+        self.level = level;
+
+        changed
+    }
+
+    /// Return whether the level bar is shown at time `now`.
+    pub fn is_showing(&self, now: u32) -> bool {
+        match self.shown {
+            Some(start) => !elapsed(now, start, self.duration),
+            None => false,
+        }
+    }
+}
+
+impl<L> Effect for BrightnessKeys<L>
+where
+    L: Layout,
+{
+    /// Limit the dimming to the selected level, and draw the level bar while it is shown.
+    fn apply(&mut self, output: &mut Output, now: u32) {
+        let dimming = self.dimming();
+        if output.dimming.bits() > dimming.bits() {
+            output.dimming = dimming;
+        }
+
+        if !self.is_showing(now) {
+            self.shown = None;
+            return;
+        }
+
+        let columns =
+            (self.level as u16 + 1) * self.layout.width() as u16 / BRIGHTNESS_LEVELS as u16;
+
+        output.frame.clear();
+        for x in 0..columns as u8 {
+            for y in 0..self.layout.height() {
+                if let Some(location) = self.layout.location(x, y) {
+                    output.frame.set_led(location, true);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layouts::Raw;
+    use crate::types::{DisplayData, LedLocation};
+
+    #[test]
+    fn steps_within_limits() {
+        let mut brightness = BrightnessKeys::new(Raw, 1, 0, 1000);
+
+        assert!(!brightness.key_event(KeyEvent::Pressed(1), 0));
+        assert_eq!(Dimming::BRIGHTNESS_MAX, brightness.dimming());
+
+        for _ in 0..BRIGHTNESS_LEVELS {
+            brightness.key_event(KeyEvent::Pressed(0), 0);
+        }
+        assert_eq!(0, brightness.level());
+        assert_eq!(Dimming::BRIGHTNESS_MIN, brightness.dimming());
+
+        assert!(brightness.key_event(KeyEvent::Pressed(1), 0));
+        assert_eq!(1, brightness.level());
+
+        // Releases and other keys are ignored.
+        assert!(!brightness.key_event(KeyEvent::Released(1), 0));
+        assert!(!brightness.key_event(KeyEvent::Pressed(2), 0));
+        assert_eq!(1, brightness.level());
+    }
+
+    #[test]
+    fn level_bar() {
+        let mut brightness = BrightnessKeys::new(Raw, 1, 0, 1000);
+        let location = LedLocation::new(15, 0).unwrap();
+
+        let mut frame = crate::types::Frame::empty();
+        frame.set_led(location, true);
+
+        // Not shown before a key is pressed.
+        let mut output = Output::new(frame);
+        brightness.apply(&mut output, 0);
+        assert_eq!(frame, output.frame);
+
+        // Half of the levels light half of the columns.
+        brightness.set_level(BRIGHTNESS_LEVELS / 2);
+        brightness.key_event(KeyEvent::Pressed(0), 100);
+
+        let mut output = Output::new(frame);
+        brightness.apply(&mut output, 500);
+        assert_eq!(DisplayData::all(), output.frame.rows()[0]);
+        assert_eq!(DisplayData::all(), output.frame.rows()[7]);
+        assert_eq!(DisplayData::empty(), output.frame.rows()[8]);
+        assert_eq!(Dimming::from_bits_truncate(3), output.dimming);
+
+        // The frame is restored once the bar expires.
+        let mut output = Output::new(frame);
+        brightness.apply(&mut output, 1100);
+        assert_eq!(frame, output.frame);
+        assert!(!brightness.is_showing(1100));
+    }
+}
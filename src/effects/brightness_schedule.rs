@@ -0,0 +1,137 @@
+use crate::effects::{Effect, Output};
+use crate::types::Dimming;
+
+/// Milliseconds in one day.
+const DAY_MS: u32 = 24 * 60 * 60 * 1000;
+
+/// Night-dimming effect that lowers the display brightness during a daily time window.
+///
+/// The effect reads `now` as milliseconds since local midnight, wrapping once per day, so the caller's clock
+/// must be aligned to midnight for the window to match the wall clock.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::effects::{BrightnessSchedule, Effect, Output};
+/// use ht16k33::Dimming;
+///
+/// const HOUR_MS: u32 = 60 * 60 * 1000;
+///
+/// // Dim from 22:00 until 07:00.
+/// let mut schedule = BrightnessSchedule::new(Dimming::BRIGHTNESS_2_16, 22 * HOUR_MS, 7 * HOUR_MS);
+///
+/// let mut output = Output::default();
+/// schedule.apply(&mut output, 23 * HOUR_MS);
+/// assert_eq!(Dimming::BRIGHTNESS_2_16, output.dimming);
+///
+/// let mut output = Output::default();
+/// schedule.apply(&mut output, 12 * HOUR_MS);
+/// assert_eq!(Dimming::BRIGHTNESS_MAX, output.dimming);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct BrightnessSchedule {
+    night: Dimming,
+    start: u32,
+    end: u32,
+}
+
+impl Default for BrightnessSchedule {
+    /// Never dims.
+    fn default() -> BrightnessSchedule {
+        BrightnessSchedule::new(Dimming::BRIGHTNESS_MAX, 0, 0)
+    }
+}
+
+impl BrightnessSchedule {
+    /// Create a `BrightnessSchedule`.
+    ///
+    /// The window may wrap past midnight, e.g. a `start` of 22:00 and `end` of 07:00. An empty window (equal
+    /// `start` and `end`) never dims.
+    ///
+    /// # Arguments
+    ///
+    /// * `night` - The maximum dimming during the window.
+    /// * `start` - The start of the window, in milliseconds since midnight.
+    /// * `end` - The end of the window (exclusive), in milliseconds since midnight.
+    pub fn new(night: Dimming, start: u32, end: u32) -> Self {
+        BrightnessSchedule {
+            night,
+            start: start % DAY_MS,
+            end: end % DAY_MS,
+        }
+    }
+
+    /// Return whether the given time of day is within the window.
+    pub fn is_night(&self, now: u32) -> bool {
+        let time = now % DAY_MS;
+
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+impl Effect for BrightnessSchedule {
+    /// Limit the dimming to the night level during the window, brighter output is never raised.
+    fn apply(&mut self, output: &mut Output, now: u32) {
+        if self.is_night(now) && output.dimming.bits() > self.night.bits() {
+            output.dimming = self.night;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR_MS: u32 = 60 * 60 * 1000;
+
+    #[test]
+    fn default_never_dims() {
+        let schedule = BrightnessSchedule::default();
+
+        for hour in 0..24 {
+            assert!(!schedule.is_night(hour * HOUR_MS));
+        }
+    }
+
+    #[test]
+    fn window() {
+        let schedule = BrightnessSchedule::new(Dimming::BRIGHTNESS_MIN, 8 * HOUR_MS, 10 * HOUR_MS);
+
+        assert!(!schedule.is_night(7 * HOUR_MS));
+        assert!(schedule.is_night(8 * HOUR_MS));
+        assert!(schedule.is_night(9 * HOUR_MS));
+        assert!(!schedule.is_night(10 * HOUR_MS));
+
+        // Wraps once per day.
+        assert!(schedule.is_night(DAY_MS + 9 * HOUR_MS));
+    }
+
+    #[test]
+    fn window_past_midnight() {
+        let schedule = BrightnessSchedule::new(Dimming::BRIGHTNESS_MIN, 22 * HOUR_MS, 7 * HOUR_MS);
+
+        assert!(schedule.is_night(23 * HOUR_MS));
+        assert!(schedule.is_night(0));
+        assert!(schedule.is_night(6 * HOUR_MS));
+        assert!(!schedule.is_night(7 * HOUR_MS));
+        assert!(!schedule.is_night(21 * HOUR_MS));
+    }
+
+    #[test]
+    fn never_brightens() {
+        let mut schedule =
+            BrightnessSchedule::new(Dimming::BRIGHTNESS_8_16, 22 * HOUR_MS, 7 * HOUR_MS);
+
+        let mut output = Output {
+            dimming: Dimming::BRIGHTNESS_2_16,
+            ..Output::default()
+        };
+        schedule.apply(&mut output, 23 * HOUR_MS);
+
+        assert_eq!(Dimming::BRIGHTNESS_2_16, output.dimming);
+    }
+}
@@ -0,0 +1,190 @@
+use crate::constants::{COMMONS_SIZE, ROWS_SIZE};
+use crate::effects::{Effect, Output};
+use crate::types::{DisplayData, Frame};
+
+/// Fade-out trail effect for matrix layouts.
+///
+/// LEDs that are turned off keep glowing for a configurable number of frames. The HT16K33 can only dim the
+/// whole display, so the trail is faded by temporal dithering: while an LED decays it is lit on a
+/// shrinking share of the frames, which reads as a dimming trail at normal refresh rates. This is useful
+/// for motion trails on scopes and meters.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::effects::Decay;
+/// use ht16k33::{Frame, LedLocation};
+/// # use ht16k33::ValidationError;
+/// # fn main() -> Result<(), ValidationError> {
+///
+/// let mut decay = Decay::new(4);
+/// let location = LedLocation::new(0, 0)?;
+///
+/// let mut frame = Frame::empty();
+/// frame.set_led(location, true);
+/// decay.apply(&mut frame);
+///
+/// // The LED was turned off, but the first frame of the trail is still lit.
+/// let mut frame = Frame::empty();
+/// decay.apply(&mut frame);
+/// assert!(frame.led(location));
+///
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Decay {
+    // Number of frames an LED persists after being turned off.
+    length: u8,
+
+    // Remaining trail frames for each LED, indexed by row and then common.
+    remaining: [[u8; COMMONS_SIZE]; ROWS_SIZE],
+
+    // Frame counter used to offset the dithering pattern.
+    phase: u8,
+}
+
+impl Decay {
+    /// Create a `Decay` effect.
+    ///
+    /// # Arguments
+    ///
+    /// * `length` - Number of frames an LED persists after being turned off, `0` disables the trail.
+    pub fn new(length: u8) -> Self {
+        Decay {
+            length,
+            remaining: [[0; COMMONS_SIZE]; ROWS_SIZE],
+            phase: 0,
+        }
+    }
+
+    /// Return the trail length, in frames.
+    pub fn length(&self) -> u8 {
+        self.length
+    }
+
+    /// Forget all trails, e.g. when switching content.
+    pub fn reset(&mut self) {
+        self.remaining = [[0; COMMONS_SIZE]; ROWS_SIZE];
+        self.phase = 0;
+    }
+
+    /// Apply the trails to the given `frame`, and record its enabled LEDs as the start of new trails.
+    ///
+    /// Call this once per displayed frame.
+    pub fn apply(&mut self, frame: &mut Frame) {
+        if self.length == 0 {
+            return;
+        }
+
+        for (row, data) in frame.rows_mut().iter_mut().enumerate() {
+            for common in 0..COMMONS_SIZE {
+                let flag = DisplayData::from_bits_truncate(1 << common);
+                let remaining = &mut self.remaining[row][common];
+
+                if data.contains(flag) {
+                    *remaining = self.length;
+                    continue;
+                }
+
+                if *remaining == 0 {
+                    continue;
+                }
+
+                // Offset the dithering threshold per LED so a trail doesn't blink in lockstep.
+                let threshold = (self.phase as usize + row + common) % self.length as usize;
+                if *remaining as usize > threshold {
+                    data.insert(flag);
+                }
+
+                *remaining -= 1;
+            }
+        }
+
+        self.phase = self.phase.wrapping_add(1);
+    }
+}
+
+impl Effect for Decay {
+    fn apply(&mut self, output: &mut Output, _now: u32) {
+        Decay::apply(self, &mut output.frame);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LedLocation;
+
+    #[test]
+    fn disabled() {
+        let mut decay = Decay::new(0);
+        let location = LedLocation::new(2, 3).unwrap();
+
+        let mut frame = Frame::empty();
+        frame.set_led(location, true);
+        decay.apply(&mut frame);
+
+        let mut frame = Frame::empty();
+        decay.apply(&mut frame);
+        assert_eq!(frame, Frame::empty());
+    }
+
+    #[test]
+    fn trail_expires() {
+        let length = 4;
+        let mut decay = Decay::new(length);
+        let location = LedLocation::new(2, 3).unwrap();
+
+        let mut frame = Frame::empty();
+        frame.set_led(location, true);
+        decay.apply(&mut frame);
+
+        let mut lit = 0;
+        for _ in 0..length {
+            let mut frame = Frame::empty();
+            decay.apply(&mut frame);
+            if frame.led(location) {
+                lit += 1;
+            }
+        }
+
+        // The trail fades, it is lit for some but not all of its frames.
+        assert!(lit > 0 && lit < length, "trail was lit for {} frames", lit);
+
+        for _ in 0..(length * 2) {
+            let mut frame = Frame::empty();
+            decay.apply(&mut frame);
+            assert!(!frame.led(location), "trail should have expired");
+        }
+    }
+
+    #[test]
+    fn enabled_leds_pass_through() {
+        let mut decay = Decay::new(8);
+        let location = LedLocation::new(15, 7).unwrap();
+
+        for _ in 0..20 {
+            let mut frame = Frame::empty();
+            frame.set_led(location, true);
+            decay.apply(&mut frame);
+            assert!(frame.led(location));
+        }
+    }
+
+    #[test]
+    fn reset() {
+        let mut decay = Decay::new(8);
+        let location = LedLocation::new(0, 0).unwrap();
+
+        let mut frame = Frame::empty();
+        frame.set_led(location, true);
+        decay.apply(&mut frame);
+
+        decay.reset();
+
+        let mut frame = Frame::empty();
+        decay.apply(&mut frame);
+        assert_eq!(frame, Frame::empty());
+    }
+}
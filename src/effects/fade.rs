@@ -0,0 +1,190 @@
+use crate::content::elapsed;
+use crate::effects::{Effect, Output};
+use crate::hal::blocking::i2c::Write;
+use crate::types::Dimming;
+use crate::HT16K33;
+
+/// Non-blocking dimming fade.
+///
+/// Start a fade with [fade_to()](struct.Fade.html#method.fade_to), then call
+/// [tick()](struct.Fade.html#method.tick) regularly to step the driver dimming through the levels in between.
+/// As an [`Effect`](trait.Effect.html), the fade sets the output dimming instead.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use ht16k33::effects::Fade;
+/// use ht16k33::{Dimming, HT16K33};
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+///
+/// let mut fade = Fade::default();
+/// fade.fade_to(*ht16k33.dimming(), Dimming::BRIGHTNESS_MIN, 300, 0);
+///
+/// let mut now = 0;
+/// while fade.tick(&mut ht16k33, now)? {
+///     now += 20;
+/// }
+///
+/// assert_eq!(Dimming::BRIGHTNESS_MIN, *ht16k33.dimming());
+///
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Fade {
+    from: Dimming,
+    to: Dimming,
+    start: u32,
+    duration: u32,
+
+    // Whether a fade was started, an idle fade leaves the dimming unchanged.
+    started: bool,
+}
+
+impl Fade {
+    /// Start fading.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The dimming to fade from, usually the current dimming.
+    /// * `to` - The dimming to fade to.
+    /// * `duration` - The length of the fade, in milliseconds.
+    /// * `now` - The current time in milliseconds, wrapping on overflow.
+    pub fn fade_to(&mut self, from: Dimming, to: Dimming, duration: u32, now: u32) {
+        *self = Fade {
+            from,
+            to,
+            start: now,
+            duration,
+            started: true,
+        };
+    }
+
+    /// Return the dimming the fade ends at, if one was started.
+    pub fn target(&self) -> Option<Dimming> {
+        if self.started {
+            Some(self.to)
+        } else {
+            None
+        }
+    }
+
+    /// Return whether the fade is still in progress at `now`.
+    pub fn is_fading(&self, now: u32) -> bool {
+        self.started && !elapsed(now, self.start, self.duration)
+    }
+
+    /// Return the dimming at `now`, if a fade was started.
+    pub fn level(&self, now: u32) -> Option<Dimming> {
+        if !self.started {
+            return None;
+        }
+
+        if !self.is_fading(now) {
+            return Some(self.to);
+        }
+
+        let from = self.from.bits() as i64;
+        let to = self.to.bits() as i64;
+        let progress = now.wrapping_sub(self.start) as i64;
+
+        let level = from + (to - from) * progress / self.duration as i64;
+        Some(Dimming::from_bits_truncate(level as u8))
+    }
+
+    /// Set the `driver` dimming to the level at `now` if it changed, returning whether the fade is still in
+    /// progress.
+    pub fn tick<I2C, E>(&mut self, driver: &mut HT16K33<I2C>, now: u32) -> Result<bool, E>
+    where
+        I2C: Write<Error = E>,
+    {
+        if let Some(level) = self.level(now) {
+            if *driver.dimming() != level {
+                driver.set_dimming(level)?;
+            }
+        }
+
+        Ok(self.is_fading(now))
+    }
+}
+
+impl Effect for Fade {
+    fn apply(&mut self, output: &mut Output, now: u32) {
+        if let Some(level) = self.level(now) {
+            output.dimming = level;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c_mock::I2cMock;
+    use crate::types::Address;
+
+    #[test]
+    fn idle() {
+        let fade = Fade::default();
+
+        assert_eq!(None, fade.level(0));
+        assert!(!fade.is_fading(0));
+
+        let mut output = Output::new(Default::default());
+        output.dimming = Dimming::BRIGHTNESS_3_16;
+        Fade::default().apply(&mut output, 0);
+        assert_eq!(Dimming::BRIGHTNESS_3_16, output.dimming);
+    }
+
+    #[test]
+    fn level() {
+        let mut fade = Fade::default();
+        fade.fade_to(Dimming::BRIGHTNESS_MAX, Dimming::BRIGHTNESS_MIN, 1500, 1000);
+
+        assert_eq!(Some(Dimming::BRIGHTNESS_MAX), fade.level(1000));
+        assert_eq!(Some(Dimming::BRIGHTNESS_9_16), fade.level(1750));
+        assert_eq!(Some(Dimming::BRIGHTNESS_MIN), fade.level(2500));
+        assert_eq!(Some(Dimming::BRIGHTNESS_MIN), fade.level(9000));
+        assert!(fade.is_fading(2499));
+        assert!(!fade.is_fading(2500));
+    }
+
+    #[test]
+    fn fade_up_wraps() {
+        let mut fade = Fade::default();
+        fade.fade_to(
+            Dimming::BRIGHTNESS_MIN,
+            Dimming::BRIGHTNESS_MAX,
+            150,
+            u32::MAX - 49,
+        );
+
+        assert_eq!(Some(Dimming::BRIGHTNESS_6_16), fade.level(0));
+    }
+
+    #[test]
+    fn tick() {
+        let mut driver = HT16K33::new(I2cMock::new(), Address::DEFAULT)
+            .initialize()
+            .unwrap();
+
+        let mut fade = Fade::default();
+        fade.fade_to(*driver.dimming(), Dimming::BRIGHTNESS_MIN, 150, 0);
+
+        let mut now = 0;
+        while fade.tick(&mut driver, now).unwrap() {
+            now += 10;
+        }
+        assert_eq!(150, now);
+
+        // One write per level, after the initialization.
+        let i2c = driver.destroy();
+        assert_eq!(4 + 15, i2c.transactions().len());
+        assert_eq!(Dimming::BRIGHTNESS_MIN, i2c.dimming());
+    }
+}
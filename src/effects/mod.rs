@@ -0,0 +1,170 @@
+//! # effects
+//!
+//! Post-processing effects applied to a rendered [`Frame`](../struct.Frame.html) before it is written to the
+//! display.
+//!
+//! Effects implement the [`Effect`](trait.Effect.html) trait and are composed with
+//! [`Effect::then()`](trait.Effect.html#method.then) into a single effect, which a
+//! [`Pipeline`](../pipeline/struct.Pipeline.html) applies to every rendered frame.
+mod breathing;
+mod brightness_keys;
+mod brightness_schedule;
+mod decay;
+mod fade;
+
+pub use self::breathing::Breathing;
+pub use self::brightness_keys::{BrightnessKeys, BRIGHTNESS_LEVELS};
+pub use self::brightness_schedule::BrightnessSchedule;
+pub use self::decay::Decay;
+pub use self::fade::Fade;
+
+use crate::types::{Dimming, Frame};
+
+/// The result of rendering, before it is written to the display.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Output {
+    /// The display RAM contents.
+    pub frame: Frame,
+    /// The display dimming.
+    pub dimming: Dimming,
+}
+
+impl Output {
+    /// Create an `Output` with the given `frame` at the default dimming.
+    pub fn new(frame: Frame) -> Self {
+        Output {
+            frame,
+            dimming: Dimming::default(),
+        }
+    }
+}
+
+/// A post-processing step applied to each rendered [`Output`](struct.Output.html).
+pub trait Effect {
+    /// Apply the effect to the `output` rendered at time `now`.
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - The rendered output to modify.
+    /// * `now` - The current time in milliseconds, wrapping on overflow.
+    fn apply(&mut self, output: &mut Output, now: u32);
+
+    /// Compose this effect with a `next` effect, which is applied after this one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ht16k33::effects::{BrightnessSchedule, Decay, Effect};
+    ///
+    /// let effect = Decay::new(4).then(BrightnessSchedule::default());
+    /// ```
+    fn then<N>(self, next: N) -> Chain<Self, N>
+    where
+        Self: Sized,
+        N: Effect,
+    {
+        Chain {
+            first: self,
+            second: next,
+        }
+    }
+}
+
+impl<T> Effect for &mut T
+where
+    T: Effect + ?Sized,
+{
+    fn apply(&mut self, output: &mut Output, now: u32) {
+        (**self).apply(output, now);
+    }
+}
+
+/// The effect that leaves the output unchanged.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Identity;
+
+impl Effect for Identity {
+    fn apply(&mut self, _output: &mut Output, _now: u32) {}
+}
+
+/// Two effects applied in sequence, created by [`Effect::then()`](trait.Effect.html#method.then).
+#[derive(Clone, Debug)]
+pub struct Chain<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> Chain<A, B> {
+    /// Return the composed effects.
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+
+    /// Return the first effect.
+    pub fn first_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Return the second effect.
+    pub fn second_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+}
+
+impl<A, B> Effect for Chain<A, B>
+where
+    A: Effect,
+    B: Effect,
+{
+    fn apply(&mut self, output: &mut Output, now: u32) {
+        self.first.apply(output, now);
+        self.second.apply(output, now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LedLocation;
+
+    struct Invert;
+
+    impl Effect for Invert {
+        fn apply(&mut self, output: &mut Output, _now: u32) {
+            for row in output.frame.rows_mut().iter_mut() {
+                *row = !*row;
+            }
+        }
+    }
+
+    struct Light(LedLocation);
+
+    impl Effect for Light {
+        fn apply(&mut self, output: &mut Output, _now: u32) {
+            output.frame.set_led(self.0, true);
+        }
+    }
+
+    #[test]
+    fn identity() {
+        let mut output = Output::default();
+        Identity.apply(&mut output, 0);
+
+        assert_eq!(output, Output::default());
+    }
+
+    #[test]
+    fn chain_order() {
+        let location = LedLocation::new(0, 0).unwrap();
+
+        // Light then invert leaves the LED off.
+        let mut output = Output::default();
+        Light(location).then(Invert).apply(&mut output, 0);
+        assert!(!output.frame.led(location));
+
+        // Invert then light leaves the LED on.
+        let mut output = Output::default();
+        Invert.then(Light(location)).apply(&mut output, 0);
+        assert!(output.frame.led(location));
+    }
+}
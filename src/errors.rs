@@ -14,6 +14,126 @@ pub enum ValidationError {
         /// Whether the limit is inclusive or not.
         inclusive: bool,
     },
+    /// The value is outside of the valid range.
+    OutOfRange {
+        /// Name of the value.
+        name: &'static str,
+        /// Value that failed validation.
+        value: u8,
+        /// The lowest valid value.
+        min: u8,
+        /// The highest valid value.
+        max: u8,
+    },
+    /// The value cannot be shown using the available digits.
+    Overflow {
+        /// Name of the value.
+        name: &'static str,
+        /// Number of digits available.
+        digits: u8,
+    },
+    /// The value is not a multiple of the required alignment.
+    Unaligned {
+        /// Name of the value.
+        name: &'static str,
+        /// Value that failed validation.
+        value: u8,
+        /// The value must be a multiple of the alignment.
+        alignment: u8,
+    },
+}
+
+/// An error of the driver, either from the I2C bus or from validating a value.
+///
+/// The driver methods return the bare I2C error `E`, and the helpers validating values return a
+/// [`ValidationError`]. Use `Error` to handle both in one place, e.g. with `?` after
+/// `.map_err(Error::I2c)`, and [kind()](enum.Error.html#method.kind) to handle the error regardless of the I2C
+/// implementation.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::i2c_mock::{I2cMock, I2cMockError};
+/// use ht16k33::{Dimming, Error, ErrorKind, HT16K33};
+/// # fn main() -> Result<(), I2cMockError> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// fn set_brightness(driver: &mut HT16K33<I2cMock>, value: u8) -> Result<(), Error<I2cMockError>> {
+///     let dimming = Dimming::from_u8(value)?;
+///     driver.set_dimming(dimming).map_err(Error::I2c)
+/// }
+///
+/// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+///
+/// let error = set_brightness(&mut ht16k33, 16).unwrap_err();
+/// assert_eq!(ErrorKind::Dimming, error.kind());
+///
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`ValidationError`]: enum.ValidationError.html
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error<E> {
+    /// The I2C bus failed.
+    I2c(E),
+    /// A value failed validation.
+    Validation(ValidationError),
+}
+
+/// The kind of an [`Error`](enum.Error.html), independent of the I2C implementation.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The I2C bus failed.
+    I2c,
+    /// The I2C address is not a valid HT16K33 address.
+    InvalidAddress,
+    /// The dimming is out of range.
+    Dimming,
+    /// A row, common, pixel or digit index is past the end of the display.
+    BufferIndex,
+    /// A value cannot be shown using the available digits.
+    Overflow,
+    /// Another value failed validation.
+    InvalidValue,
+}
+
+impl<E> Error<E> {
+    /// Return the kind of error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::I2c(_) => ErrorKind::I2c,
+            Error::Validation(error) => error.kind(),
+        }
+    }
+}
+
+impl<E> From<ValidationError> for Error<E> {
+    fn from(error: ValidationError) -> Self {
+        Error::Validation(error)
+    }
+}
+
+impl ValidationError {
+    /// Return the kind of [`Error`](enum.Error.html) of this validation error.
+    pub fn kind(&self) -> ErrorKind {
+        let name = match self {
+            ValidationError::Overflow { .. } => return ErrorKind::Overflow,
+            ValidationError::ValueTooLarge { name, .. } => name,
+            ValidationError::OutOfRange { name, .. } => name,
+            ValidationError::Unaligned { name, .. } => name,
+        };
+
+        match *name {
+            "address" => ErrorKind::InvalidAddress,
+            "dimming" | "brightness" => ErrorKind::Dimming,
+            "row" | "common" | "x" | "y" | "index" | "digit index" => ErrorKind::BufferIndex,
+            _ => ErrorKind::InvalidValue,
+        }
+    }
 }
 
 #[cfg(feature = "std")]
@@ -22,6 +142,31 @@ extern crate std;
 #[cfg(feature = "std")]
 impl std::error::Error for ValidationError {}
 
+#[cfg(feature = "std")]
+impl<E> std::error::Error for Error<E>
+where
+    E: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::I2c(error) => Some(error),
+            Error::Validation(error) => Some(error),
+        }
+    }
+}
+
+impl<E> fmt::Display for Error<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::I2c(error) => write!(f, "I2C error: {}", error),
+            Error::Validation(error) => write!(f, "{}", error),
+        }
+    }
+}
+
 impl fmt::Display for ValidationError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -35,6 +180,48 @@ impl fmt::Display for ValidationError {
                 "'{}' value [{}] must be less than (or equal: {}) [{}])",
                 name, value, limit, inclusive
             ),
+            ValidationError::OutOfRange {
+                name,
+                value,
+                min,
+                max,
+            } => write!(
+                f,
+                "'{}' value [{:#x}] must be within [{:#x}] and [{:#x}]",
+                name, value, min, max
+            ),
+            ValidationError::Overflow { name, digits } => {
+                write!(f, "'{}' value does not fit in [{}] digits", name, digits)
+            }
+            ValidationError::Unaligned {
+                name,
+                value,
+                alignment,
+            } => write!(
+                f,
+                "'{}' value [{}] must be a multiple of [{}]",
+                name, value, alignment
+            ),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Address, Dimming, LedLocation};
+
+    #[test]
+    fn kind() {
+        let error: Error<()> = Address::from_u8(0).unwrap_err().into();
+        assert_eq!(ErrorKind::InvalidAddress, error.kind());
+
+        let error: Error<()> = Dimming::from_u8(16).unwrap_err().into();
+        assert_eq!(ErrorKind::Dimming, error.kind());
+
+        let error: Error<()> = LedLocation::new(16, 0).unwrap_err().into();
+        assert_eq!(ErrorKind::BufferIndex, error.kind());
+
+        assert_eq!(ErrorKind::I2c, Error::I2c(()).kind());
+    }
+}
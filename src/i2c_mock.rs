@@ -4,26 +4,494 @@
 //! not have I2C support.
 use embedded_hal as hal;
 
+use core::cell::RefCell;
 use core::fmt;
 
-use crate::constants::ROWS_SIZE;
-use crate::types::DisplayDataAddress;
+use crate::constants::{INT_FLAG_ADDRESS, KEYS_PER_LINE, KEYS_SIZE, KEY_DATA_ADDRESS, ROWS_SIZE};
+use crate::errors::ValidationError;
+use crate::types::{Address, Dimming, Display, DisplayData, DisplayDataAddress, Frame, Oscillator};
 
-/// Mock error to satisfy the I2C trait.
+// The chip only uses the low nibble of a display data address, and wraps around within it.
+const DATA_ADDRESS_MASK: u8 = 0b0000_1111;
+
+/// The kinds of I2C failure that can be injected into an [`I2cMock`](struct.I2cMock.html).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ErrorKind {
+    /// The slave did not acknowledge the address or data.
+    Nack,
+    /// A bus error, e.g. a misplaced START or STOP condition.
+    Bus,
+    /// The master lost arbitration to another master.
+    ArbitrationLoss,
+    /// The key RAM was read during a key scan, see [`KeyScan`](struct.KeyScan.html).
+    KeyScan,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::Nack => write!(f, "no acknowledge"),
+            ErrorKind::Bus => write!(f, "bus error"),
+            ErrorKind::ArbitrationLoss => write!(f, "arbitration loss"),
+            ErrorKind::KeyScan => write!(f, "key RAM read during key scan"),
+        }
+    }
+}
+
+/// The maximum number of transactions kept in the [`I2cMock`](struct.I2cMock.html) log, older transactions
+/// are dropped.
+pub const LOG_SIZE: usize = 32;
+
+/// The maximum number of bytes kept per logged transaction, longer writes are truncated.
+pub const TRANSACTION_SIZE: usize = ROWS_SIZE * 2 + 1;
+
+/// The key scan timing emulated by an [`I2cMock`](struct.I2cMock.html), see
+/// [set_key_scan()](struct.I2cMock.html#method.set_key_scan).
+///
+/// Each key scan cycle starts with a scan of `window` milliseconds, and the key RAM is only updated from the
+/// pressed keys at the end of the scan.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct KeyScan {
+    /// The length of a key scan cycle, in milliseconds, a `period` of 0 is treated as 1.
+    pub period: u32,
+    /// The length of the scan at the start of each cycle, in milliseconds.
+    pub window: u32,
+    /// Whether reading the key RAM during the scan fails with [`ErrorKind::KeyScan`], as the datasheet
+    /// forbids it.
+    ///
+    /// [`ErrorKind::KeyScan`]: enum.ErrorKind.html#variant.KeyScan
+    pub strict: bool,
+}
+
+impl KeyScan {
+    /// A 20ms key scan cycle with a 1ms scan, reading during the scan is allowed.
+    pub const DEFAULT: KeyScan = KeyScan {
+        period: 20,
+        window: 1,
+        strict: false,
+    };
+
+    /// [`DEFAULT`](struct.KeyScan.html#associatedconstant.DEFAULT), but reading during the scan fails.
+    pub const STRICT: KeyScan = KeyScan {
+        strict: true,
+        ..KeyScan::DEFAULT
+    };
+
+    // Return the number of scans completed at `now`.
+    fn scans(self, now: u32) -> u32 {
+        match now.checked_sub(self.window) {
+            Some(since) => since / self.period.max(1) + 1,
+            None => 0,
+        }
+    }
+
+    // Return whether a scan is running at `now`.
+    fn is_scanning(self, now: u32) -> bool {
+        now % self.period.max(1) < self.window
+    }
+}
+
+/// The kind of a logged I2C transaction.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Operation {
+    /// A `write`.
+    Write,
+    /// A `write_read`, reading the given number of bytes.
+    WriteRead(usize),
+    /// A `read` of the given number of bytes, continuing from the current data address.
+    Read(usize),
+}
+
+/// A successful I2C transaction, recorded by an [`I2cMock`](struct.I2cMock.html).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Transaction {
+    operation: Operation,
+    address: u8,
+    bytes: [u8; TRANSACTION_SIZE],
+    len: usize,
+}
+
+impl Transaction {
+    const EMPTY: Transaction = Transaction {
+        operation: Operation::Write,
+        address: 0,
+        bytes: [0; TRANSACTION_SIZE],
+        len: 0,
+    };
+
+    fn new(operation: Operation, address: u8, bytes: &[u8]) -> Self {
+        let len = bytes.len().min(TRANSACTION_SIZE);
+
+        let mut transaction = Transaction {
+            operation,
+            address,
+            len,
+            ..Transaction::EMPTY
+        };
+        transaction.bytes[..len].copy_from_slice(&bytes[..len]);
+        transaction
+    }
+
+    /// Return the kind of transaction.
+    pub fn operation(&self) -> Operation {
+        self.operation
+    }
+
+    /// Return the slave address.
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    /// Return the first byte written, the command or display data address.
+    pub fn register(&self) -> u8 {
+        self.bytes[0]
+    }
+
+    /// Return the bytes written after the [register()](struct.Transaction.html#method.register).
+    pub fn payload(&self) -> &[u8] {
+        &self.bytes[self.len.min(1)..self.len]
+    }
+}
+
+/// An ASCII-art picture of the display RAM, see [I2cMock::picture()](struct.I2cMock.html#method.picture).
+///
+/// Each line is a row, with the commons `0` to `7` from left to right, `#` for an enabled LED and `.` for a
+/// disabled one. `Debug` shows the same picture, so failing assertions print what the display would show.
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct Picture<'a> {
+    rows: &'a [u8; ROWS_SIZE],
+}
+
+impl fmt::Display for Picture<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, row) in self.rows.iter().enumerate() {
+            write!(f, "{:2} ", index)?;
+            for common in 0..8 {
+                let led = if row & (1 << common) != 0 { '#' } else { '.' };
+                write!(f, "{}", led)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Picture<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f)?;
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Mock error to satisfy the I2C trait, returned for injected failures.
 #[derive(Debug)]
-pub struct I2cMockError;
+pub struct I2cMockError {
+    kind: ErrorKind,
+}
+
+impl I2cMockError {
+    /// Return the kind of failure.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
 
 #[cfg(feature = "std")]
 impl std::error::Error for I2cMockError {}
 
 impl fmt::Display for I2cMockError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "I2c MockError")
+        write!(f, "I2c MockError: {}", self.kind)
+    }
+}
+
+/// The number of HT16K33 devices on an [`I2cMock`](struct.I2cMock.html) bus, one per address `0x70` to `0x77`.
+pub const DEVICES_SIZE: usize = (Address::MAX - Address::MIN + 1) as usize;
+
+/// The state of one HT16K33 on an [`I2cMock`](struct.I2cMock.html) bus, see
+/// [device()](struct.I2cMock.html#method.device).
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Device {
+    /// Display RAM state.
+    pub data_values: [u8; ROWS_SIZE],
+
+    /// Key RAM state, set it to simulate pressed keys.
+    ///
+    /// With [set_key_scan()](struct.I2cMock.html#method.set_key_scan), these are the pressed keys, and the key
+    /// RAM read by the driver only follows them after a key scan.
+    pub key_values: [u8; KEYS_SIZE],
+
+    // The emulated key scan timing, and the key RAM as of the last scan.
+    key_scan: Option<KeyScan>,
+    scanned_keys: [u8; KEYS_SIZE],
+
+    // Set when a scan finds a pressed key, cleared by reading the last byte of the key RAM.
+    int_flag: bool,
+
+    // Command register states, starting at the power-on defaults.
+    oscillator: Oscillator,
+    display: Display,
+    dimming: Dimming,
+
+    // The data address the next read or write continues from, auto-incremented by each byte.
+    pointer: u8,
+}
+
+impl Device {
+    const POWER_ON: Device = Device {
+        data_values: [0; ROWS_SIZE],
+        key_values: [0; KEYS_SIZE],
+        key_scan: None,
+        scanned_keys: [0; KEYS_SIZE],
+        int_flag: false,
+        oscillator: Oscillator::OFF,
+        display: Display::OFF,
+        dimming: Dimming::BRIGHTNESS_MAX,
+        pointer: DisplayDataAddress::ROW_0.bits(),
+    };
+
+    /// Return an ASCII-art picture of the display RAM.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ht16k33::i2c_mock::I2cMock;
+    /// use ht16k33::Address;
+    ///
+    /// let mut i2c_mock = I2cMock::new();
+    /// i2c_mock.device_mut(Address::DEFAULT).data_values[1] = 0b0000_0101;
+    ///
+    /// let picture = i2c_mock.device(Address::DEFAULT).picture().to_string();
+    /// assert_eq!(" 1 #.#.....", picture.lines().nth(1).unwrap());
+    /// ```
+    pub fn picture(&self) -> Picture<'_> {
+        Picture {
+            rows: &self.data_values,
+        }
+    }
+
+    /// Return the display RAM as a [`Frame`](../struct.Frame.html).
+    pub fn frame(&self) -> Frame {
+        let mut frame = Frame::empty();
+        for (row, value) in frame.rows_mut().iter_mut().zip(self.data_values.iter()) {
+            *row = DisplayData::from_bits_truncate(*value);
+        }
+
+        frame
+    }
+
+    /// Press the key `K(col + 1)` on the key scan line `KS(row)`, see the [`keypad`](../keypad/index.html) key
+    /// numbering.
+    ///
+    /// Without [set_key_scan()](struct.I2cMock.html#method.set_key_scan), the key RAM and the INT flag are
+    /// updated right away, otherwise at the end of the next scan.
+    ///
+    /// # Errors
+    ///
+    /// If the `row` is larger than `2` or the `col` is larger than `12` then
+    /// [`ValidationError::ValueTooLarge`](../enum.ValidationError.html#variant.ValueTooLarge) is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// use ht16k33::i2c_mock::I2cMock;
+    /// use ht16k33::keypad::{KeyEvent, Keypad};
+    /// use ht16k33::{Address, HT16K33};
+    /// # fn main() -> Result<(), Error> {
+    ///
+    /// let mut i2c_mock = I2cMock::new();
+    /// i2c_mock.device_mut(Address::DEFAULT).press_key(1, 1)?;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c_mock, Address::DEFAULT).initialize()?;
+    /// assert!(ht16k33.read_int_flag()?);
+    ///
+    /// let mut keypad: Keypad<8> = Keypad::new(1);
+    /// keypad.poll(&mut ht16k33)?;
+    /// assert_eq!(Some(KeyEvent::Pressed(17)), keypad.next_event());
+    ///
+    /// // Reading the key RAM clears the INT flag.
+    /// assert!(!ht16k33.read_int_flag()?);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn press_key(&mut self, row: u8, col: u8) -> Result<(), ValidationError> {
+        self.set_key(row, col, true)
+    }
+
+    /// Release the key `K(col + 1)` on the key scan line `KS(row)`, see
+    /// [press_key()](struct.Device.html#method.press_key).
+    ///
+    /// # Errors
+    ///
+    /// If the `row` is larger than `2` or the `col` is larger than `12` then
+    /// [`ValidationError::ValueTooLarge`](../enum.ValidationError.html#variant.ValueTooLarge) is returned.
+    pub fn release_key(&mut self, row: u8, col: u8) -> Result<(), ValidationError> {
+        self.set_key(row, col, false)
+    }
+
+    /// Return the INT flag, set when a key scan finds a pressed key and cleared when the last byte of the key
+    /// RAM is read.
+    pub fn int_flag(&self) -> bool {
+        self.int_flag
+    }
+
+    fn set_key(&mut self, row: u8, col: u8, pressed: bool) -> Result<(), ValidationError> {
+        let lines = (KEYS_SIZE / 2) as u8;
+        if row >= lines {
+            return Err(ValidationError::ValueTooLarge {
+                name: "key scan line",
+                value: row,
+                limit: lines,
+                inclusive: false,
+            });
+        }
+        if col >= KEYS_PER_LINE {
+            return Err(ValidationError::ValueTooLarge {
+                name: "key",
+                value: col,
+                limit: KEYS_PER_LINE,
+                inclusive: false,
+            });
+        }
+
+        let index = row as usize * 2 + col as usize / 8;
+        let bit = 1 << (col % 8);
+        if pressed {
+            self.key_values[index] |= bit;
+        } else {
+            self.key_values[index] &= !bit;
+        }
+
+        if self.key_scan.is_none() {
+            self.int_flag = self.key_values.iter().any(|&keys| keys != 0);
+        }
+
+        Ok(())
+    }
+
+    // Update the key RAM if a key scan ends between `then` and `now`.
+    fn advance(&mut self, then: u32, now: u32) {
+        if let Some(key_scan) = self.key_scan {
+            if key_scan.scans(now) > key_scan.scans(then) {
+                self.scanned_keys = self.key_values;
+                self.int_flag = self.scanned_keys.iter().any(|&keys| keys != 0);
+            }
+        }
+    }
+
+    // Fail reading the key RAM at `pointer` during a strict key scan.
+    fn check_key_scan(&self, pointer: u8, now: u32) -> Result<(), I2cMockError> {
+        match self.key_scan {
+            Some(key_scan)
+                if key_scan.strict && pointer >= KEY_DATA_ADDRESS && key_scan.is_scanning(now) =>
+            {
+                Err(I2cMockError {
+                    kind: ErrorKind::KeyScan,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Return the last oscillator state written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// use ht16k33::i2c_mock::I2cMock;
+    /// use ht16k33::{Address, Oscillator, HT16K33};
+    /// # fn main() -> Result<(), Error> {
+    ///
+    /// let ht16k33 = HT16K33::new(I2cMock::new(), Address::new(0x71)).initialize()?;
+    ///
+    /// let i2c_mock = ht16k33.destroy();
+    /// assert_eq!(Oscillator::ON, i2c_mock.device(Address::new(0x71)).oscillator());
+    /// assert_eq!(Oscillator::OFF, i2c_mock.device(Address::new(0x70)).oscillator());
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn oscillator(&self) -> Oscillator {
+        self.oscillator
+    }
+
+    /// Return the last display state written, including the blink rate.
+    pub fn display(&self) -> Display {
+        self.display
+    }
+
+    /// Return the last dimming written.
+    pub fn dimming(&self) -> Dimming {
+        self.dimming
+    }
+
+    // Return the byte at the data address and advance it, wrapping around within the display or key RAM.
+    fn read_next(&mut self) -> u8 {
+        if self.pointer == INT_FLAG_ADDRESS {
+            return if self.int_flag { 0xFF } else { 0x00 };
+        }
+
+        if self.pointer >= KEY_DATA_ADDRESS {
+            let offset = (self.pointer - KEY_DATA_ADDRESS) as usize % KEYS_SIZE;
+            self.pointer = KEY_DATA_ADDRESS + ((offset + 1) % KEYS_SIZE) as u8;
+            if offset == KEYS_SIZE - 1 {
+                self.int_flag = false;
+            }
+            match self.key_scan {
+                Some(_) => self.scanned_keys[offset],
+                None => self.key_values[offset],
+            }
+        } else {
+            let offset = (self.pointer & DATA_ADDRESS_MASK) as usize;
+            self.pointer = (self.pointer + 1) & DATA_ADDRESS_MASK;
+            self.data_values[offset]
+        }
+    }
+
+    // Store a byte at the data address and advance it, the key RAM is read-only.
+    fn write_next(&mut self, value: u8) {
+        if self.pointer >= KEY_DATA_ADDRESS {
+            self.read_next();
+            return;
+        }
+
+        let offset = (self.pointer & DATA_ADDRESS_MASK) as usize;
+        self.data_values[offset] = value;
+        self.read_next();
+    }
+
+    // Store a command register write, other single byte writes are ignored.
+    fn write_command(&mut self, command: u8) {
+        // The command is in the high nibble, and its setting in the low nibble.
+        let setting = command & 0b0000_1111;
+
+        match command & 0b1111_0000 {
+            value if value == Oscillator::COMMAND.bits() => {
+                self.oscillator = Oscillator::from_bits_truncate(setting);
+            }
+            value if value == Display::COMMAND.bits() => {
+                self.display = Display::from_bits_truncate(setting);
+            }
+            value if value == Dimming::COMMAND.bits() => {
+                self.dimming = Dimming::from_bits_truncate(setting);
+            }
+            _ => {}
+        }
     }
 }
 
 /// The mock I2C state.
 ///
+/// The bus has a [`Device`](struct.Device.html) at each address `0x70` to `0x77`, each with its own RAM and
+/// command registers, so that several drivers can share the bus. Transactions with any other address fail with
+/// [`ErrorKind::Nack`](enum.ErrorKind.html#variant.Nack), like on a bus without a device at that address.
+///
 /// # Example
 ///
 /// ```
@@ -35,18 +503,280 @@ impl fmt::Display for I2cMockError {
 ///
 /// # }
 /// ```
+///
+/// With the `serde` feature, the RAM, command register and key scan states are serialized, the injected
+/// failures and the transaction log are not.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct I2cMock {
-    /// Display RAM state.
-    pub data_values: [u8; ROWS_SIZE],
+    // The devices at the addresses `0x70` to `0x77`, in order.
+    devices: [Device; DEVICES_SIZE],
+
+    // The emulated time in milliseconds, for the key scan.
+    now: u32,
+
+    // Failures to return from the next transactions, instead of performing them.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    write_failure: Option<(u32, ErrorKind)>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    write_read_failure: Option<ErrorKind>,
+
+    // The most recent successful transactions, oldest first.
+    #[cfg_attr(feature = "serde", serde(skip, default = "empty_log"))]
+    log: [Transaction; LOG_SIZE],
+    #[cfg_attr(feature = "serde", serde(skip))]
+    log_len: usize,
 }
 
 impl I2cMock {
     /// Create an I2cMock.
     pub fn new() -> Self {
         I2cMock {
-            data_values: [0; ROWS_SIZE],
+            devices: [Device::POWER_ON; DEVICES_SIZE],
+            now: 0,
+            write_failure: None,
+            write_read_failure: None,
+            log: [Transaction::EMPTY; LOG_SIZE],
+            log_len: 0,
         }
     }
+
+    /// Return the device at the `address`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// use ht16k33::i2c_mock::I2cMock;
+    /// use ht16k33::{Address, LedLocation, HT16K33};
+    /// # fn main() -> Result<(), Error> {
+    ///
+    /// let mut i2c_mock = I2cMock::new();
+    ///
+    /// let mut left = HT16K33::new(&mut i2c_mock, Address::new(0x70)).initialize()?;
+    /// left.set_led(LedLocation::new(0, 0)?, true)?;
+    ///
+    /// let mut right = HT16K33::new(&mut i2c_mock, Address::new(0x71)).initialize()?;
+    /// right.set_led(LedLocation::new(1, 0)?, true)?;
+    ///
+    /// assert_eq!(0b0000_0001, i2c_mock.device(Address::new(0x70)).data_values[0]);
+    /// assert_eq!(0b0000_0000, i2c_mock.device(Address::new(0x71)).data_values[0]);
+    /// assert_eq!(0b0000_0001, i2c_mock.device(Address::new(0x71)).data_values[1]);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn device(&self, address: Address) -> &Device {
+        &self.devices[(address.value() - Address::MIN) as usize]
+    }
+
+    /// Return the device at the `address` for modification, e.g. to set its key RAM.
+    pub fn device_mut(&mut self, address: Address) -> &mut Device {
+        &mut self.devices[(address.value() - Address::MIN) as usize]
+    }
+
+    /// Return an ASCII-art picture of the display RAM of the device at [`Address::DEFAULT`].
+    ///
+    /// [`Address::DEFAULT`]: ../struct.Address.html#associatedconstant.DEFAULT
+    pub fn picture(&self) -> Picture<'_> {
+        self.device(Address::DEFAULT).picture()
+    }
+
+    /// Return the most recent successful transactions, oldest first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// use ht16k33::i2c_mock::{I2cMock, Operation};
+    /// use ht16k33::{Address, Dimming, HT16K33};
+    /// # fn main() -> Result<(), Error> {
+    ///
+    /// let mut ht16k33 = HT16K33::new(I2cMock::new(), Address::new(0x70)).initialize()?;
+    /// ht16k33.set_dimming(Dimming::BRIGHTNESS_MIN)?;
+    ///
+    /// let i2c_mock = ht16k33.destroy();
+    /// let transaction = i2c_mock.transactions().last().unwrap();
+    ///
+    /// assert_eq!(Operation::Write, transaction.operation());
+    /// assert_eq!(0x70, transaction.address());
+    /// assert_eq!((Dimming::COMMAND | Dimming::BRIGHTNESS_MIN).bits(), transaction.register());
+    /// assert!(transaction.payload().is_empty());
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.log[..self.log_len]
+    }
+
+    /// Clear the transaction log.
+    pub fn clear_log(&mut self) {
+        self.log_len = 0;
+    }
+
+    // Append a transaction to the log, dropping the oldest when full.
+    fn record(&mut self, operation: Operation, address: u8, bytes: &[u8]) {
+        if self.log_len == LOG_SIZE {
+            self.log.copy_within(1.., 0);
+            self.log_len -= 1;
+        }
+
+        self.log[self.log_len] = Transaction::new(operation, address, bytes);
+        self.log_len += 1;
+    }
+
+    // Return the index of the device acknowledging the `address`.
+    fn route(address: u8) -> Result<usize, I2cMockError> {
+        match Address::from_u8(address) {
+            Ok(address) => Ok((address.value() - Address::MIN) as usize),
+            Err(_) => Err(I2cMockError {
+                kind: ErrorKind::Nack,
+            }),
+        }
+    }
+
+    /// Fail the next `write` with the given `kind` of error, without changing any state.
+    pub fn fail_next_write(&mut self, kind: ErrorKind) {
+        self.fail_write_after(0, kind);
+    }
+
+    /// Fail a `write` with the given `kind` of error after `successes` successful writes, without changing any
+    /// state.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// use ht16k33::i2c_mock::{ErrorKind, I2cMock};
+    /// use ht16k33::{Address, Dimming, HT16K33};
+    /// # fn main() -> Result<(), Error> {
+    ///
+    /// // Initialization writes 4 times, fail the write after it.
+    /// let mut i2c_mock = I2cMock::new();
+    /// i2c_mock.fail_write_after(4, ErrorKind::Nack);
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c_mock, Address::DEFAULT).initialize()?;
+    ///
+    /// let error = ht16k33.set_dimming(Dimming::BRIGHTNESS_MIN).unwrap_err();
+    /// assert_eq!(ErrorKind::Nack, error.kind());
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fail_write_after(&mut self, successes: u32, kind: ErrorKind) {
+        self.write_failure = Some((successes, kind));
+    }
+
+    /// Fail the next `write_read` with the given `kind` of error, without changing any state.
+    pub fn fail_next_write_read(&mut self, kind: ErrorKind) {
+        self.write_read_failure = Some(kind);
+    }
+
+    /// Emulate the key scan timing of every device, or read the
+    /// [`key_values`](struct.Device.html#structfield.key_values) directly with `None`.
+    ///
+    /// The emulated time starts at `0`, at the start of a scan, and only moves with
+    /// [advance()](struct.I2cMock.html#method.advance).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use embedded_hal::blocking::i2c::WriteRead;
+    /// use ht16k33::i2c_mock::{ErrorKind, I2cMock, KeyScan};
+    /// use ht16k33::Address;
+    ///
+    /// let mut i2c_mock = I2cMock::new();
+    /// i2c_mock.set_key_scan(Some(KeyScan::STRICT));
+    /// i2c_mock.device_mut(Address::DEFAULT).key_values[0] = 0b0000_0001;
+    ///
+    /// // Reading during the scan fails.
+    /// let mut read_buffer = [0u8; 6];
+    /// let error = i2c_mock.write_read(0x70, &[0x40], &mut read_buffer).unwrap_err();
+    /// assert_eq!(ErrorKind::KeyScan, error.kind());
+    ///
+    /// // The pressed key is in the key RAM after the scan.
+    /// i2c_mock.advance(1);
+    /// i2c_mock.write_read(0x70, &[0x40], &mut read_buffer).unwrap();
+    /// assert_eq!(0b0000_0001, read_buffer[0]);
+    /// ```
+    pub fn set_key_scan(&mut self, key_scan: Option<KeyScan>) {
+        for device in self.devices.iter_mut() {
+            device.key_scan = key_scan;
+            device.scanned_keys = [0; KEYS_SIZE];
+        }
+        self.now = 0;
+    }
+
+    /// Move the emulated key scan time forward by `ms` milliseconds, updating the key RAM if a scan ends.
+    pub fn advance(&mut self, ms: u32) {
+        let now = self.now.saturating_add(ms);
+
+        for device in self.devices.iter_mut() {
+            device.advance(self.now, now);
+        }
+
+        self.now = now;
+    }
+
+    /// Press a key of the device at [`Address::DEFAULT`], see
+    /// [Device::press_key()](struct.Device.html#method.press_key).
+    ///
+    /// # Errors
+    ///
+    /// If the `row` is larger than `2` or the `col` is larger than `12` then
+    /// [`ValidationError::ValueTooLarge`](../enum.ValidationError.html#variant.ValueTooLarge) is returned.
+    ///
+    /// [`Address::DEFAULT`]: ../struct.Address.html#associatedconstant.DEFAULT
+    pub fn press_key(&mut self, row: u8, col: u8) -> Result<(), ValidationError> {
+        self.device_mut(Address::DEFAULT).press_key(row, col)
+    }
+
+    /// Release a key of the device at [`Address::DEFAULT`], see
+    /// [Device::release_key()](struct.Device.html#method.release_key).
+    ///
+    /// # Errors
+    ///
+    /// If the `row` is larger than `2` or the `col` is larger than `12` then
+    /// [`ValidationError::ValueTooLarge`](../enum.ValidationError.html#variant.ValueTooLarge) is returned.
+    ///
+    /// [`Address::DEFAULT`]: ../struct.Address.html#associatedconstant.DEFAULT
+    pub fn release_key(&mut self, row: u8, col: u8) -> Result<(), ValidationError> {
+        self.device_mut(Address::DEFAULT).release_key(row, col)
+    }
+
+    /// Return the INT flag of the device at [`Address::DEFAULT`].
+    ///
+    /// [`Address::DEFAULT`]: ../struct.Address.html#associatedconstant.DEFAULT
+    pub fn int_flag(&self) -> bool {
+        self.device(Address::DEFAULT).int_flag()
+    }
+
+    /// Return the last oscillator state written to the device at [`Address::DEFAULT`].
+    ///
+    /// [`Address::DEFAULT`]: ../struct.Address.html#associatedconstant.DEFAULT
+    pub fn oscillator(&self) -> Oscillator {
+        self.device(Address::DEFAULT).oscillator()
+    }
+
+    /// Return the last display state written to the device at [`Address::DEFAULT`], including the blink rate.
+    ///
+    /// [`Address::DEFAULT`]: ../struct.Address.html#associatedconstant.DEFAULT
+    pub fn display(&self) -> Display {
+        self.device(Address::DEFAULT).display()
+    }
+
+    /// Return the last dimming written to the device at [`Address::DEFAULT`].
+    ///
+    /// [`Address::DEFAULT`]: ../struct.Address.html#associatedconstant.DEFAULT
+    pub fn dimming(&self) -> Dimming {
+        self.device(Address::DEFAULT).dimming()
+    }
+}
+
+impl Default for I2cMock {
+    fn default() -> Self {
+        I2cMock::new()
+    }
 }
 
 impl hal::blocking::i2c::WriteRead for I2cMock {
@@ -56,7 +786,7 @@ impl hal::blocking::i2c::WriteRead for I2cMock {
     ///
     /// # Arguments
     ///
-    /// * `_address` - The slave address. Ignored.
+    /// * `address` - The slave address, selecting the device.
     /// * `bytes` - The command/address instructions to be written.
     /// * `buffer` - The read results.
     ///
@@ -69,25 +799,79 @@ impl hal::blocking::i2c::WriteRead for I2cMock {
     /// let mut i2c_mock = I2cMock::new();
     ///
     /// let mut read_buffer = [0u8; 16];
-    /// i2c_mock.write_read(0, &[ht16k33::DisplayDataAddress::ROW_0.bits()], &mut read_buffer);
+    /// i2c_mock.write_read(0x70, &[ht16k33::DisplayDataAddress::ROW_0.bits()], &mut read_buffer);
     ///
     /// # }
     /// ```
     fn write_read(
         &mut self,
-        _address: u8,
+        address: u8,
         bytes: &[u8],
         buffer: &mut [u8],
     ) -> Result<(), Self::Error> {
-        // The `bytes` have the `data_address` command + index to start reading from,
-        // need to clear the command to extract the starting index.
-        let mut data_offset = (bytes[0] ^ DisplayDataAddress::ROW_0.bits()) as usize;
+        if let Some(kind) = self.write_read_failure.take() {
+            return Err(I2cMockError { kind });
+        }
+        let index = I2cMock::route(address)?;
+        self.devices[index].check_key_scan(bytes[0], self.now)?;
+
+        self.record(Operation::WriteRead(buffer.len()), address, bytes);
+
+        // The `bytes` have the data address to start reading from.
+        let device = &mut self.devices[index];
+        device.pointer = bytes[0];
 
         for value in buffer.iter_mut() {
-            *value = self.data_values[data_offset];
+            *value = device.read_next();
+        }
+
+        Ok(())
+    }
+}
+
+impl hal::blocking::i2c::Read for I2cMock {
+    type Error = I2cMockError;
+
+    /// `read` implementation, continuing from the data address of the previous transaction with the device like
+    /// the chip.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The slave address, selecting the device.
+    /// * `buffer` - The read results.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use embedded_hal::blocking::i2c::{Read, Write};
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::Address;
+    /// # fn main() {
+    /// let mut i2c_mock = I2cMock::new();
+    /// i2c_mock.device_mut(Address::DEFAULT).data_values[2] = 0xAB;
+    ///
+    /// // Set the data address, then read from it.
+    /// i2c_mock.write(0x70, &[ht16k33::DisplayDataAddress::ROW_2.bits()]);
+    ///
+    /// let mut read_buffer = [0u8; 1];
+    /// i2c_mock.read(0x70, &mut read_buffer);
+    /// assert_eq!([0xAB], read_buffer);
+    ///
+    /// # }
+    /// ```
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        if let Some(kind) = self.write_read_failure.take() {
+            return Err(I2cMockError { kind });
+        }
+        let index = I2cMock::route(address)?;
+        let pointer = self.devices[index].pointer;
+        self.devices[index].check_key_scan(pointer, self.now)?;
+
+        self.record(Operation::Read(buffer.len()), address, &[pointer]);
 
-            // The HT16K33 supports auto-increment and wrap-around, emulate that.
-            data_offset = (data_offset + 1) % self.data_values.len();
+        let device = &mut self.devices[index];
+        for value in buffer.iter_mut() {
+            *value = device.read_next();
         }
 
         Ok(())
@@ -101,7 +885,7 @@ impl hal::blocking::i2c::Write for I2cMock {
     ///
     /// # Arguments
     ///
-    /// * `_address` - The slave address. Ignored.
+    /// * `address` - The slave address, selecting the device.
     /// * `bytes` - The command/address instructions to be written.
     ///
     /// # Examples
@@ -116,44 +900,375 @@ impl hal::blocking::i2c::Write for I2cMock {
     /// // starting at the data address which auto-increments and then wraps.
     /// let write_buffer = [ht16k33::DisplayDataAddress::ROW_0.bits(), 0u8, 0u8];
     ///
-    /// i2c_mock.write(0, &write_buffer);
+    /// i2c_mock.write(0x70, &write_buffer);
     ///
     /// # }
     /// ```
-    fn write(&mut self, _address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
-        // "Command-only" writes are length 1 and write-only, and cannot be read back,
-        // discard them for simplicity.
-        if bytes.len() == 1 {
-            return Ok(());
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        match self.write_failure {
+            Some((0, kind)) => {
+                self.write_failure = None;
+                return Err(I2cMockError { kind });
+            }
+            Some((successes, kind)) => self.write_failure = Some((successes - 1, kind)),
+            None => {}
         }
+        let index = I2cMock::route(address)?;
 
-        // Other writes have data, store them.
-        let mut data_offset = (bytes[0] ^ DisplayDataAddress::ROW_0.bits()) as usize;
-        let data = &bytes[1..];
+        self.record(Operation::Write, address, bytes);
 
-        for value in data.iter() {
-            self.data_values[data_offset] = *value;
+        // Any other byte is a command, like on the chip the bytes following it are not stored, so that a
+        // malformed data address does not silently write the display RAM.
+        let device = &mut self.devices[index];
+        if !is_data_address(bytes[0]) {
+            device.write_command(bytes[0]);
+            return Ok(());
+        }
 
-            // The HT16K33 supports auto-increment and wrap-around, emulate that.
-            data_offset = (data_offset + 1) % self.data_values.len();
+        // Data addresses without data only set where the next read starts.
+        device.pointer = bytes[0];
+        for value in bytes[1..].iter() {
+            device.write_next(*value);
         }
 
         Ok(())
     }
 }
 
+// Return an empty transaction log.
+#[cfg(feature = "serde")]
+fn empty_log() -> [Transaction; LOG_SIZE] {
+    [Transaction::EMPTY; LOG_SIZE]
+}
+
+// Return whether the `byte` is a display or key RAM address, rather than a command.
+fn is_data_address(byte: u8) -> bool {
+    byte & !DATA_ADDRESS_MASK == DisplayDataAddress::ROW_0.bits()
+        || (KEY_DATA_ADDRESS..KEY_DATA_ADDRESS + KEYS_SIZE as u8).contains(&byte)
+        || byte == INT_FLAG_ADDRESS
+}
+
+/// Lend the mock to a driver, so that it can still be inspected if the driver is consumed by an error.
+impl hal::blocking::i2c::WriteRead for &mut I2cMock {
+    type Error = I2cMockError;
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        (**self).write_read(address, bytes, buffer)
+    }
+}
+
+/// Lend the mock to a driver, so that it can still be inspected if the driver is consumed by an error.
+impl hal::blocking::i2c::Read for &mut I2cMock {
+    type Error = I2cMockError;
+
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        (**self).read(address, buffer)
+    }
+}
+
+/// Lend the mock to a driver, so that it can still be inspected if the driver is consumed by an error.
+impl hal::blocking::i2c::Write for &mut I2cMock {
+    type Error = I2cMockError;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        (**self).write(address, bytes)
+    }
+}
+
+/// A handle to an [`I2cMock`](struct.I2cMock.html) shared between several drivers, e.g. a chain of displays at
+/// different addresses on the same bus.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// use ht16k33::i2c_mock::{I2cMock, SharedI2cMock};
+/// use ht16k33::{Address, Dimming, HT16K33};
+/// use std::cell::RefCell;
+/// # fn main() -> Result<(), Error> {
+///
+/// let bus = RefCell::new(I2cMock::new());
+///
+/// let mut left = HT16K33::new(SharedI2cMock::new(&bus), Address::new(0x70)).initialize()?;
+/// let mut right = HT16K33::new(SharedI2cMock::new(&bus), Address::new(0x71)).initialize()?;
+/// right.set_dimming(Dimming::BRIGHTNESS_MIN)?;
+///
+/// assert_eq!(Dimming::BRIGHTNESS_MAX, bus.borrow().device(Address::new(0x70)).dimming());
+/// assert_eq!(Dimming::BRIGHTNESS_MIN, bus.borrow().device(Address::new(0x71)).dimming());
+///
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy)]
+pub struct SharedI2cMock<'a> {
+    bus: &'a RefCell<I2cMock>,
+}
+
+impl<'a> SharedI2cMock<'a> {
+    /// Create a handle to the shared `bus`.
+    pub fn new(bus: &'a RefCell<I2cMock>) -> Self {
+        SharedI2cMock { bus }
+    }
+}
+
+impl hal::blocking::i2c::WriteRead for SharedI2cMock<'_> {
+    type Error = I2cMockError;
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.bus.borrow_mut().write_read(address, bytes, buffer)
+    }
+}
+
+impl hal::blocking::i2c::Read for SharedI2cMock<'_> {
+    type Error = I2cMockError;
+
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.bus.borrow_mut().read(address, buffer)
+    }
+}
+
+impl hal::blocking::i2c::Write for SharedI2cMock<'_> {
+    type Error = I2cMockError;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.bus.borrow_mut().write(address, bytes)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use hal::blocking::i2c::{Write, WriteRead};
+    use hal::blocking::i2c::{Read, Write, WriteRead};
 
-    const ADDRESS: u8 = 0;
+    const ADDRESS: u8 = 0x70;
 
     #[test]
     fn new() {
         let _i2c_mock = I2cMock::new();
     }
 
+    #[test]
+    fn devices() {
+        let mut i2c_mock = I2cMock::new();
+
+        let write_buffer = [super::DisplayDataAddress::ROW_0.bits(), 1u8];
+        i2c_mock.write(0x77, &write_buffer).unwrap();
+        i2c_mock
+            .write(0x71, &[(Display::COMMAND | Display::ON).bits()])
+            .unwrap();
+
+        assert_eq!(1, i2c_mock.device(Address::new(0x77)).data_values[0]);
+        assert_eq!(0, i2c_mock.device(Address::DEFAULT).data_values[0]);
+        assert_eq!(Display::ON, i2c_mock.device(Address::new(0x71)).display());
+        assert_eq!(Display::OFF, i2c_mock.display());
+
+        // No device acknowledges other addresses, and nothing is logged.
+        let error = i2c_mock.write(0x20, &write_buffer).unwrap_err();
+        assert_eq!(ErrorKind::Nack, error.kind());
+        let mut read_buffer = [0u8; 1];
+        assert!(i2c_mock
+            .write_read(0x69, &[0x00], &mut read_buffer)
+            .is_err());
+        assert_eq!(2, i2c_mock.transactions().len());
+    }
+
+    #[test]
+    fn write_commands() {
+        let mut i2c_mock = I2cMock::new();
+
+        assert_eq!(Oscillator::OFF, i2c_mock.oscillator());
+        assert_eq!(Display::OFF, i2c_mock.display());
+        assert_eq!(Dimming::BRIGHTNESS_MAX, i2c_mock.dimming());
+
+        i2c_mock
+            .write(ADDRESS, &[(Oscillator::COMMAND | Oscillator::ON).bits()])
+            .unwrap();
+        i2c_mock
+            .write(ADDRESS, &[(Display::COMMAND | Display::TWO_HZ).bits()])
+            .unwrap();
+        i2c_mock
+            .write(
+                ADDRESS,
+                &[(Dimming::COMMAND | Dimming::BRIGHTNESS_3_16).bits()],
+            )
+            .unwrap();
+
+        assert_eq!(Oscillator::ON, i2c_mock.oscillator());
+        assert_eq!(Display::TWO_HZ, i2c_mock.display());
+        assert_eq!(Dimming::BRIGHTNESS_3_16, i2c_mock.dimming());
+
+        // Display data is unchanged.
+        assert_eq!(
+            [0; ROWS_SIZE],
+            i2c_mock.device(Address::DEFAULT).data_values
+        );
+    }
+
+    #[test]
+    fn write_driver_commands() {
+        let mut ht16k33 = crate::HT16K33::new(I2cMock::new(), Address::new(ADDRESS))
+            .initialize()
+            .unwrap();
+
+        ht16k33.set_display(Display::HALF_HZ).unwrap();
+        ht16k33.set_dimming(Dimming::BRIGHTNESS_MIN).unwrap();
+
+        let i2c_mock = ht16k33.destroy();
+        assert_eq!(Oscillator::ON, i2c_mock.oscillator());
+        assert_eq!(Display::HALF_HZ, i2c_mock.display());
+        assert_eq!(Dimming::BRIGHTNESS_MIN, i2c_mock.dimming());
+    }
+
+    #[test]
+    fn fail_next_write() {
+        let mut i2c_mock = I2cMock::new();
+        i2c_mock.fail_next_write(ErrorKind::ArbitrationLoss);
+
+        let write_buffer = [super::DisplayDataAddress::ROW_0.bits(), 1u8];
+        let error = i2c_mock.write(ADDRESS, &write_buffer).unwrap_err();
+        assert_eq!(ErrorKind::ArbitrationLoss, error.kind());
+        assert_eq!(
+            0,
+            i2c_mock.device(Address::DEFAULT).data_values[0],
+            "failed writes are discarded"
+        );
+
+        // Only the next write fails.
+        i2c_mock.write(ADDRESS, &write_buffer).unwrap();
+        assert_eq!(1, i2c_mock.device(Address::DEFAULT).data_values[0]);
+    }
+
+    #[test]
+    fn fail_next_write_read() {
+        let mut i2c_mock = I2cMock::new();
+        i2c_mock.device_mut(Address::DEFAULT).data_values[0] = 1;
+        i2c_mock.fail_next_write_read(ErrorKind::Bus);
+
+        let mut read_buffer = [0u8; 1];
+        let error = i2c_mock
+            .write_read(
+                ADDRESS,
+                &[super::DisplayDataAddress::ROW_0.bits()],
+                &mut read_buffer,
+            )
+            .unwrap_err();
+        assert_eq!(ErrorKind::Bus, error.kind());
+        assert_eq!(0, read_buffer[0]);
+
+        i2c_mock
+            .write_read(
+                ADDRESS,
+                &[super::DisplayDataAddress::ROW_0.bits()],
+                &mut read_buffer,
+            )
+            .unwrap();
+        assert_eq!(1, read_buffer[0]);
+    }
+
+    #[test]
+    fn fail_write_after() {
+        let mut i2c_mock = I2cMock::new();
+        i2c_mock.fail_write_after(2, ErrorKind::Nack);
+
+        let command = [(Display::COMMAND | Display::ON).bits()];
+        i2c_mock.write(ADDRESS, &command).unwrap();
+        i2c_mock.write(ADDRESS, &command).unwrap();
+        assert!(i2c_mock.write(ADDRESS, &command).is_err());
+        i2c_mock.write(ADDRESS, &command).unwrap();
+    }
+
+    #[test]
+    fn flush_retries_after_failure() {
+        let mut i2c_mock = I2cMock::new();
+
+        // Fail the first write after initialization.
+        i2c_mock.fail_write_after(4, ErrorKind::Nack);
+        let mut ht16k33 = crate::HT16K33::new(i2c_mock, Address::new(ADDRESS))
+            .initialize()
+            .unwrap();
+
+        ht16k33.display_buffer_mut()[3] = crate::DisplayData::all();
+        assert!(ht16k33.flush().is_err());
+
+        // The failed rows are still pending.
+        ht16k33.flush().unwrap();
+
+        let i2c_mock = ht16k33.destroy();
+        assert_eq!(0xff, i2c_mock.device(Address::DEFAULT).data_values[3]);
+    }
+
+    #[test]
+    fn transactions() {
+        let mut i2c_mock = I2cMock::new();
+        assert!(i2c_mock.transactions().is_empty());
+
+        let command = [(Display::COMMAND | Display::ON).bits()];
+        i2c_mock.write(0x70, &command).unwrap();
+
+        let write_buffer = [super::DisplayDataAddress::ROW_2.bits(), 1u8, 2u8];
+        i2c_mock.write(0x71, &write_buffer).unwrap();
+
+        let mut read_buffer = [0u8; 4];
+        i2c_mock
+            .write_read(
+                0x72,
+                &[super::DisplayDataAddress::ROW_0.bits()],
+                &mut read_buffer,
+            )
+            .unwrap();
+
+        // Failed transactions are not logged.
+        i2c_mock.fail_next_write(ErrorKind::Nack);
+        assert!(i2c_mock.write(0x70, &command).is_err());
+
+        let transactions = i2c_mock.transactions();
+        assert_eq!(3, transactions.len());
+
+        assert_eq!(Operation::Write, transactions[0].operation());
+        assert_eq!(0x70, transactions[0].address());
+        assert_eq!(command[0], transactions[0].register());
+        assert!(transactions[0].payload().is_empty());
+
+        assert_eq!(0x71, transactions[1].address());
+        assert_eq!(write_buffer[0], transactions[1].register());
+        assert_eq!(&[1, 2], transactions[1].payload());
+
+        assert_eq!(Operation::WriteRead(4), transactions[2].operation());
+        assert_eq!(0x72, transactions[2].address());
+
+        i2c_mock.clear_log();
+        assert!(i2c_mock.transactions().is_empty());
+    }
+
+    #[test]
+    fn transactions_drop_oldest() {
+        let mut i2c_mock = I2cMock::new();
+
+        for value in 0..(LOG_SIZE + 2) {
+            i2c_mock
+                .write(
+                    ADDRESS,
+                    &[super::DisplayDataAddress::ROW_0.bits(), value as u8],
+                )
+                .unwrap();
+        }
+
+        let transactions = i2c_mock.transactions();
+        assert_eq!(LOG_SIZE, transactions.len());
+        assert_eq!(&[2], transactions[0].payload());
+        assert_eq!(&[LOG_SIZE as u8 + 1], transactions[LOG_SIZE - 1].payload());
+    }
+
     #[test]
     fn write() {
         let mut i2c_mock = I2cMock::new();
@@ -161,17 +1276,21 @@ mod tests {
         let write_buffer = [super::DisplayDataAddress::ROW_0.bits(), 1u8, 1u8];
         i2c_mock.write(ADDRESS, &write_buffer).unwrap();
 
-        for value in 0..i2c_mock.data_values.len() {
+        for value in 0..i2c_mock.device(Address::DEFAULT).data_values.len() {
             match value {
                 0 | 1 => assert_eq!(
-                    i2c_mock.data_values[value], 1,
+                    i2c_mock.device(Address::DEFAULT).data_values[value],
+                    1,
                     "index [{}] should be 1, found [{}]",
-                    value, i2c_mock.data_values[value]
+                    value,
+                    i2c_mock.device(Address::DEFAULT).data_values[value]
                 ),
                 _ => assert_eq!(
-                    i2c_mock.data_values[value], 0,
+                    i2c_mock.device(Address::DEFAULT).data_values[value],
+                    0,
                     "index [{}] should be 0, found [{}]",
-                    value, i2c_mock.data_values[value]
+                    value,
+                    i2c_mock.device(Address::DEFAULT).data_values[value]
                 ),
             }
         }
@@ -185,17 +1304,21 @@ mod tests {
         let write_buffer = [super::DisplayDataAddress::ROW_0.bits() | offset, 1u8, 1u8];
         i2c_mock.write(ADDRESS, &write_buffer).unwrap();
 
-        for value in 0..i2c_mock.data_values.len() {
+        for value in 0..i2c_mock.device(Address::DEFAULT).data_values.len() {
             match value {
                 4 | 5 => assert_eq!(
-                    i2c_mock.data_values[value], 1,
+                    i2c_mock.device(Address::DEFAULT).data_values[value],
+                    1,
                     "index [{}] should be 1, found [{}]",
-                    value, i2c_mock.data_values[value]
+                    value,
+                    i2c_mock.device(Address::DEFAULT).data_values[value]
                 ),
                 _ => assert_eq!(
-                    i2c_mock.data_values[value], 0,
+                    i2c_mock.device(Address::DEFAULT).data_values[value],
+                    0,
                     "index [{}] should be 0, found [{}]",
-                    value, i2c_mock.data_values[value]
+                    value,
+                    i2c_mock.device(Address::DEFAULT).data_values[value]
                 ),
             }
         }
@@ -215,22 +1338,118 @@ mod tests {
 
         i2c_mock.write(ADDRESS, &write_buffer).unwrap();
 
-        for value in 0..i2c_mock.data_values.len() {
+        for value in 0..i2c_mock.device(Address::DEFAULT).data_values.len() {
             match value {
                 0 | 1 => assert_eq!(
-                    i2c_mock.data_values[value], 2,
+                    i2c_mock.device(Address::DEFAULT).data_values[value],
+                    2,
                     "index [{}] should be 2, found [{}]",
-                    value, i2c_mock.data_values[value]
+                    value,
+                    i2c_mock.device(Address::DEFAULT).data_values[value]
                 ),
                 _ => assert_eq!(
-                    i2c_mock.data_values[value], 1,
+                    i2c_mock.device(Address::DEFAULT).data_values[value],
+                    1,
                     "index [{}] should be 1, found [{}]",
-                    value, i2c_mock.data_values[value]
+                    value,
+                    i2c_mock.device(Address::DEFAULT).data_values[value]
                 ),
             }
         }
     }
 
+    #[test]
+    fn key_scan() {
+        let mut i2c_mock = I2cMock::new();
+        i2c_mock.set_key_scan(Some(KeyScan::STRICT));
+        i2c_mock.advance(5);
+        i2c_mock.device_mut(Address::DEFAULT).key_values[1] = 0b0000_0010;
+
+        let ht16k33 = crate::HT16K33::new(&mut i2c_mock, Address::DEFAULT);
+        let mut ht16k33 = ht16k33.initialize().unwrap();
+
+        // The key RAM only changes after the next scan.
+        assert_eq!([0; KEYS_SIZE], ht16k33.read_keys().unwrap());
+
+        let (i2c_mock, parked) = ht16k33.release();
+        i2c_mock.advance(15);
+        let mut ht16k33 = parked.attach(i2c_mock);
+
+        let error = ht16k33.read_keys().unwrap_err();
+        assert_eq!(ErrorKind::KeyScan, error.kind());
+
+        let (i2c_mock, parked) = ht16k33.release();
+        i2c_mock.advance(1);
+        let mut ht16k33 = parked.attach(i2c_mock);
+
+        assert_eq!(0b0000_0010, ht16k33.read_keys().unwrap()[1]);
+    }
+
+    #[test]
+    fn key_scan_zero_period() {
+        let mut i2c_mock = I2cMock::new();
+        i2c_mock.set_key_scan(Some(KeyScan {
+            period: 0,
+            ..KeyScan::DEFAULT
+        }));
+        i2c_mock.press_key(0, 0).unwrap();
+
+        // Scans every millisecond instead of dividing by zero.
+        i2c_mock.advance(1);
+        assert!(i2c_mock.int_flag());
+    }
+
+    #[test]
+    fn press_and_release_key() {
+        let mut i2c_mock = I2cMock::new();
+        i2c_mock.press_key(2, 12).unwrap();
+        assert!(i2c_mock.int_flag());
+        assert_eq!(0b0001_0000, i2c_mock.device(Address::DEFAULT).key_values[5]);
+
+        assert!(i2c_mock.press_key(3, 0).is_err());
+        assert!(i2c_mock.press_key(0, 13).is_err());
+
+        let ht16k33 = crate::HT16K33::new(&mut i2c_mock, Address::DEFAULT);
+        let mut ht16k33 = ht16k33.initialize().unwrap();
+
+        assert!(ht16k33.read_int_flag().unwrap());
+        ht16k33.read_keys().unwrap();
+        assert!(!ht16k33.read_int_flag().unwrap());
+
+        let (i2c_mock, parked) = ht16k33.release();
+        i2c_mock.release_key(2, 12).unwrap();
+        assert!(!i2c_mock.int_flag());
+        let mut ht16k33 = parked.attach(i2c_mock);
+
+        assert_eq!([0; KEYS_SIZE], ht16k33.read_keys().unwrap());
+    }
+
+    #[test]
+    fn int_flag_after_key_scan() {
+        let mut i2c_mock = I2cMock::new();
+        i2c_mock.set_key_scan(Some(KeyScan::DEFAULT));
+        i2c_mock.press_key(0, 0).unwrap();
+
+        // The INT flag is only set by the next scan.
+        assert!(!i2c_mock.int_flag());
+        i2c_mock.advance(20);
+        assert!(i2c_mock.int_flag());
+    }
+
+    #[test]
+    fn write_malformed_address() {
+        let mut i2c_mock = I2cMock::new();
+
+        // An address past the display RAM is a command byte, its data is not stored.
+        let write_buffer = [super::DisplayDataAddress::ROW_0.bits() | 0x10, 1u8, 1u8];
+        i2c_mock.write(ADDRESS, &write_buffer).unwrap();
+
+        assert_eq!(
+            [0; super::ROWS_SIZE],
+            i2c_mock.device(Address::DEFAULT).data_values
+        );
+    }
+
     #[test]
     fn write_with_wraparound_and_offset() {
         let mut i2c_mock = I2cMock::new();
@@ -247,17 +1466,21 @@ mod tests {
 
         i2c_mock.write(ADDRESS, &write_buffer).unwrap();
 
-        for value in 0..i2c_mock.data_values.len() {
+        for value in 0..i2c_mock.device(Address::DEFAULT).data_values.len() {
             match value {
                 4 | 5 => assert_eq!(
-                    i2c_mock.data_values[value], 2,
+                    i2c_mock.device(Address::DEFAULT).data_values[value],
+                    2,
                     "index [{}] should be 2, found [{}]",
-                    value, i2c_mock.data_values[value]
+                    value,
+                    i2c_mock.device(Address::DEFAULT).data_values[value]
                 ),
                 _ => assert_eq!(
-                    i2c_mock.data_values[value], 1,
+                    i2c_mock.device(Address::DEFAULT).data_values[value],
+                    1,
                     "index [{}] should be 1, found [{}]",
-                    value, i2c_mock.data_values[value]
+                    value,
+                    i2c_mock.device(Address::DEFAULT).data_values[value]
                 ),
             }
         }
@@ -267,8 +1490,8 @@ mod tests {
     fn write_read() {
         let mut i2c_mock = I2cMock::new();
 
-        i2c_mock.data_values[0] = 1;
-        i2c_mock.data_values[1] = 1;
+        i2c_mock.device_mut(Address::DEFAULT).data_values[0] = 1;
+        i2c_mock.device_mut(Address::DEFAULT).data_values[1] = 1;
 
         let mut read_buffer = [0u8; super::ROWS_SIZE];
         i2c_mock
@@ -279,17 +1502,17 @@ mod tests {
             )
             .unwrap();
 
-        for value in 0..read_buffer.len() {
+        for (value, read_value) in read_buffer.iter().enumerate() {
             match value {
                 0 | 1 => assert_eq!(
-                    read_buffer[value], 1,
+                    *read_value, 1,
                     "index [{}] should be 1, found [{}]",
-                    value, read_buffer[value]
+                    value, *read_value
                 ),
                 _ => assert_eq!(
-                    read_buffer[value], 0,
+                    *read_value, 0,
                     "index [{}] should be 0, found [{}]",
-                    value, read_buffer[value]
+                    value, *read_value
                 ),
             }
         }
@@ -299,8 +1522,8 @@ mod tests {
     fn write_read_offset() {
         let mut i2c_mock = I2cMock::new();
 
-        i2c_mock.data_values[2] = 1;
-        i2c_mock.data_values[3] = 1;
+        i2c_mock.device_mut(Address::DEFAULT).data_values[2] = 1;
+        i2c_mock.device_mut(Address::DEFAULT).data_values[3] = 1;
 
         let mut read_buffer = [0u8; 4];
 
@@ -313,17 +1536,17 @@ mod tests {
             )
             .unwrap();
 
-        for value in 0..read_buffer.len() {
+        for (value, read_value) in read_buffer.iter().enumerate() {
             match value {
                 0 | 1 => assert_eq!(
-                    read_buffer[value], 1,
+                    *read_value, 1,
                     "index [{}] should be 1, found [{}]",
-                    value, read_buffer[value]
+                    value, *read_value
                 ),
                 _ => assert_eq!(
-                    read_buffer[value], 0,
+                    *read_value, 0,
                     "index [{}] should be 0, found [{}]",
-                    value, read_buffer[value]
+                    value, *read_value
                 ),
             }
         }
@@ -333,8 +1556,8 @@ mod tests {
     fn write_read_wraparound() {
         let mut i2c_mock = I2cMock::new();
 
-        i2c_mock.data_values[2] = 1;
-        i2c_mock.data_values[3] = 1;
+        i2c_mock.device_mut(Address::DEFAULT).data_values[2] = 1;
+        i2c_mock.device_mut(Address::DEFAULT).data_values[3] = 1;
 
         let mut read_buffer = [0u8; super::ROWS_SIZE + 4];
 
@@ -346,17 +1569,17 @@ mod tests {
             )
             .unwrap();
 
-        for value in 0..read_buffer.len() {
+        for (value, read_value) in read_buffer.iter().enumerate() {
             match value {
                 2 | 3 | 18 | 19 => assert_eq!(
-                    read_buffer[value], 1,
+                    *read_value, 1,
                     "index [{}] should be 1, found [{}]",
-                    value, read_buffer[value]
+                    value, *read_value
                 ),
                 _ => assert_eq!(
-                    read_buffer[value], 0,
+                    *read_value, 0,
                     "index [{}] should be 0, found [{}]",
-                    value, read_buffer[value]
+                    value, *read_value
                 ),
             }
         }
@@ -366,8 +1589,8 @@ mod tests {
     fn write_read_wraparound_and_offset() {
         let mut i2c_mock = I2cMock::new();
 
-        i2c_mock.data_values[0] = 1;
-        i2c_mock.data_values[1] = 1;
+        i2c_mock.device_mut(Address::DEFAULT).data_values[0] = 1;
+        i2c_mock.device_mut(Address::DEFAULT).data_values[1] = 1;
 
         let mut read_buffer = [0u8; super::ROWS_SIZE];
 
@@ -380,21 +1603,88 @@ mod tests {
             )
             .unwrap();
 
-        for value in 0..read_buffer.len() {
+        for (value, read_value) in read_buffer.iter().enumerate() {
             match value {
                 // The indexes will be 12/13 b/c the data values are at 1/2, but the read is offset
                 // by 4, so the read buffer will wraparound to load those values.
                 12 | 13 => assert_eq!(
-                    read_buffer[value], 1,
+                    *read_value, 1,
                     "index [{}] should be 1, found [{}]",
-                    value, read_buffer[value]
+                    value, *read_value
                 ),
                 _ => assert_eq!(
-                    read_buffer[value], 0,
+                    *read_value, 0,
                     "index [{}] should be 0, found [{}]",
-                    value, read_buffer[value]
+                    value, *read_value
                 ),
             }
         }
     }
+
+    #[test]
+    fn read_continues() {
+        let mut i2c_mock = I2cMock::new();
+        for (index, value) in i2c_mock
+            .device_mut(Address::DEFAULT)
+            .data_values
+            .iter_mut()
+            .enumerate()
+        {
+            *value = index as u8;
+        }
+
+        // Reads start at the power-on data address, and continue across transactions.
+        let mut buffer = [0u8; 2];
+        i2c_mock.read(ADDRESS, &mut buffer).unwrap();
+        assert_eq!([0, 1], buffer);
+
+        i2c_mock
+            .write_read(ADDRESS, &[DisplayDataAddress::ROW_14.bits()], &mut buffer)
+            .unwrap();
+        i2c_mock.read(ADDRESS, &mut buffer).unwrap();
+        assert_eq!([0, 1], buffer);
+
+        // After a data write, reads continue after the written data.
+        i2c_mock.write(ADDRESS, &[5, 0xAA]).unwrap();
+        i2c_mock.read(ADDRESS, &mut buffer).unwrap();
+        assert_eq!([6, 7], buffer);
+
+        assert_eq!(
+            Operation::Read(2),
+            i2c_mock.transactions().last().unwrap().operation()
+        );
+    }
+
+    #[test]
+    fn read_keys() {
+        let mut i2c_mock = I2cMock::new();
+        i2c_mock.device_mut(Address::DEFAULT).key_values = [1, 2, 3, 4, 5, 6];
+
+        let mut buffer = [0u8; 4];
+        i2c_mock.write(ADDRESS, &[KEY_DATA_ADDRESS + 4]).unwrap();
+        i2c_mock.read(ADDRESS, &mut buffer).unwrap();
+        assert_eq!([5, 6, 1, 2], buffer);
+    }
+
+    #[test]
+    fn picture() {
+        extern crate std;
+        use std::format;
+
+        let mut i2c_mock = I2cMock::new();
+        i2c_mock.device_mut(Address::DEFAULT).data_values[0] = 0b1000_0001;
+        i2c_mock.device_mut(Address::DEFAULT).data_values[15] = 0xFF;
+
+        let picture = format!("{}", i2c_mock.picture());
+        let mut lines = picture.lines();
+        assert_eq!(Some(" 0 #......#"), lines.next());
+        assert_eq!(Some(" 1 ........"), lines.next());
+        assert_eq!(Some("15 ########"), lines.nth(13));
+        assert_eq!(None, lines.next());
+
+        assert_eq!(
+            format!("\n{}", picture),
+            format!("{:?}", i2c_mock.picture())
+        );
+    }
 }
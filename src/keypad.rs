@@ -0,0 +1,414 @@
+//! # keypad
+//!
+//! Press and release events from the HT16K33 key scan.
+//!
+//! A [`Keypad`](struct.Keypad.html) compares successive reads of the key RAM, see
+//! [read_keys()](../struct.HT16K33.html#method.read_keys), and queues a [`KeyEvent`](enum.KeyEvent.html) for each
+//! key that changed once the reads have been stable for a number of samples.
+//!
+//! Keys are numbered by their bit in the key RAM: key `Kn` (`n` = 1..=13) on scan line `KSm` (`m` = 0..=2) is
+//! key `16 * m + n - 1`.
+//!
+//! Instead of reading the key RAM on a timer, [wait_for_keys()](struct.Keypad.html#method.wait_for_keys) blocks
+//! on the INT pin of the chip, and only uses the bus once a key is pressed.
+use crate::constants::KEYS_SIZE;
+use crate::hal::blocking::delay::DelayMs;
+use crate::hal::blocking::i2c::{Write, WriteRead};
+use crate::hal::digital::v2::InputPin;
+use crate::HT16K33;
+
+/// The default number of identical samples before a change is reported.
+pub const DEFAULT_SAMPLES: u8 = 3;
+
+/// The command making the ROW15/INT pin an active-low INT output, for
+/// [write_command()](../struct.HT16K33.html#method.write_command).
+pub const INT_ACTIVE_LOW_COMMAND: u8 = 0xA1;
+
+// The first and the longest wait between checks of an idle INT pin, in milliseconds.
+const MIN_BACKOFF_MS: u16 = 1;
+const MAX_BACKOFF_MS: u16 = 32;
+
+// The wait between reads of the key RAM while keys are held or debounced, about one key scan.
+const KEY_POLL_MS: u16 = 20;
+
+/// An error of [wait_for_keys()](struct.Keypad.html#method.wait_for_keys).
+#[derive(Debug)]
+pub enum WaitError<E, P> {
+    /// The I2C bus failed.
+    I2c(E),
+    /// Reading the INT pin failed.
+    Pin(P),
+}
+
+/// A change of a key.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum KeyEvent {
+    /// The key was pressed.
+    Pressed(u8),
+    /// The key was released.
+    Released(u8),
+}
+
+/// Debounces the key RAM into a queue of up to `Q` key events.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use ht16k33::HT16K33;
+/// use ht16k33::keypad::{KeyEvent, Keypad};
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// // Key K2 on scan line KS1 is held down.
+/// i2c.device_mut(address).key_values[2] = 0b0000_0010;
+///
+/// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+/// let mut keypad: Keypad<8> = Keypad::new(2);
+///
+/// // Called from e.g. a timer tick.
+/// keypad.poll(&mut ht16k33)?;
+/// keypad.poll(&mut ht16k33)?;
+///
+/// assert_eq!(Some(KeyEvent::Pressed(17)), keypad.next_event());
+/// assert_eq!(None, keypad.next_event());
+///
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Keypad<const Q: usize> {
+    samples: u8,
+
+    // The debounced keys, and the latest read with the number of times it was read in a row.
+    stable: [u8; KEYS_SIZE],
+    candidate: [u8; KEYS_SIZE],
+    count: u8,
+
+    // A ring buffer of events, oldest first.
+    events: [KeyEvent; Q],
+    head: usize,
+    pending: usize,
+    overflowed: bool,
+}
+
+impl<const Q: usize> Default for Keypad<Q> {
+    fn default() -> Self {
+        Keypad::new(DEFAULT_SAMPLES)
+    }
+}
+
+impl<const Q: usize> Keypad<Q> {
+    /// Create a `Keypad` with all keys released.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - The number of identical reads before a change is reported, at least 1.
+    pub fn new(samples: u8) -> Self {
+        Keypad {
+            samples: samples.max(1),
+            stable: [0; KEYS_SIZE],
+            candidate: [0; KEYS_SIZE],
+            count: 0,
+            events: [KeyEvent::Released(0); Q],
+            head: 0,
+            pending: 0,
+            overflowed: false,
+        }
+    }
+
+    /// Return whether the `key` is pressed, after debouncing.
+    pub fn is_pressed(&self, key: u8) -> bool {
+        match self.stable.get(key as usize / 8) {
+            Some(value) => value & (1 << (key % 8)) != 0,
+            None => false,
+        }
+    }
+
+    /// Return the number of queued events.
+    pub fn pending(&self) -> usize {
+        self.pending
+    }
+
+    /// Return whether events were dropped because the queue was full, clearing the flag.
+    pub fn take_overflow(&mut self) -> bool {
+        core::mem::take(&mut self.overflowed)
+    }
+
+    /// Return the oldest queued event.
+    pub fn next_event(&mut self) -> Option<KeyEvent> {
+        if self.pending == 0 {
+            return None;
+        }
+
+        let event = self.events[self.head];
+        self.head = (self.head + 1) % Q;
+        self.pending -= 1;
+
+        Some(event)
+    }
+
+    /// Add a read of the key RAM, queueing the changes once it has been read `samples` times in a row.
+    pub fn update(&mut self, keys: [u8; KEYS_SIZE]) {
+        if keys == self.candidate {
+            self.count = self.count.saturating_add(1);
+        } else {
+            self.candidate = keys;
+            self.count = 1;
+        }
+
+        if self.count < self.samples || self.candidate == self.stable {
+            return;
+        }
+
+        let (stable, candidate) = (self.stable, self.candidate);
+        for (index, (old, new)) in stable.iter().zip(candidate.iter()).enumerate() {
+            let changed = old ^ new;
+            for bit in (0..8).filter(|bit| changed & (1 << bit) != 0) {
+                let key = (index * 8 + bit) as u8;
+                if new & (1 << bit) != 0 {
+                    self.push(KeyEvent::Pressed(key));
+                } else {
+                    self.push(KeyEvent::Released(key));
+                }
+            }
+        }
+
+        self.stable = candidate;
+    }
+
+    /// Read the key RAM from the `driver` and [update()](struct.Keypad.html#method.update) the keypad.
+    pub fn poll<I2C, E>(&mut self, driver: &mut HT16K33<I2C>) -> Result<(), E>
+    where
+        I2C: Write<Error = E> + WriteRead<Error = E>,
+    {
+        let keys = driver.read_keys()?;
+        self.update(keys);
+
+        Ok(())
+    }
+
+    /// Wait until a key event is available, or until `timeout_ms` milliseconds have passed.
+    ///
+    /// The ROW15/INT pin of the chip must be configured as an active-low INT output, see
+    /// [`INT_ACTIVE_LOW_COMMAND`](constant.INT_ACTIVE_LOW_COMMAND.html). While the pin is idle, it is checked
+    /// with a growing backoff, from 1 ms up to 32 ms, without any I2C access. Once it signals a pressed key, the
+    /// key RAM is read at about the key scan rate, and keeps being read while keys are held, because the chip
+    /// does not signal releases.
+    ///
+    /// Returns the oldest queued event, or `None` on timeout.
+    ///
+    /// # Errors
+    ///
+    /// If reading the key RAM fails then [`WaitError::I2c`] is returned, and if reading the pin fails then
+    /// [`WaitError::Pin`] is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// use embedded_hal::blocking::delay::DelayMs;
+    /// use embedded_hal::digital::v2::InputPin;
+    /// use ht16k33::keypad::{KeyEvent, Keypad, INT_ACTIVE_LOW_COMMAND};
+    /// use ht16k33::HT16K33;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    /// # struct Delay;
+    /// # impl DelayMs<u16> for Delay {
+    /// #     fn delay_ms(&mut self, _ms: u16) {}
+    /// # }
+    /// # let mut delay = Delay;
+    /// # struct IntPin;
+    /// # impl InputPin for IntPin {
+    /// #     type Error = core::convert::Infallible;
+    /// #     fn is_high(&self) -> Result<bool, Self::Error> { Ok(false) }
+    /// #     fn is_low(&self) -> Result<bool, Self::Error> { Ok(true) }
+    /// # }
+    /// # let mut int_pin = IntPin;
+    /// # i2c.press_key(0, 4)?;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    /// ht16k33.write_command(INT_ACTIVE_LOW_COMMAND)?;
+    ///
+    /// let mut keypad: Keypad<8> = Keypad::default();
+    /// let event = keypad
+    ///     .wait_for_keys(&mut ht16k33, &mut int_pin, 1000, &mut delay)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(Some(KeyEvent::Pressed(4)), event);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`WaitError::I2c`]: enum.WaitError.html#variant.I2c
+    /// [`WaitError::Pin`]: enum.WaitError.html#variant.Pin
+    pub fn wait_for_keys<I2C, E, P, D>(
+        &mut self,
+        driver: &mut HT16K33<I2C>,
+        int_pin: &mut P,
+        timeout_ms: u32,
+        delay: &mut D,
+    ) -> Result<Option<KeyEvent>, WaitError<E, P::Error>>
+    where
+        I2C: Write<Error = E> + WriteRead<Error = E>,
+        P: InputPin,
+        D: DelayMs<u16>,
+    {
+        let mut waited = 0u32;
+        let mut backoff = MIN_BACKOFF_MS;
+
+        loop {
+            if let Some(event) = self.next_event() {
+                return Ok(Some(event));
+            }
+
+            let busy = self.stable != [0; KEYS_SIZE] || self.candidate != self.stable;
+            let wait = if busy || int_pin.is_low().map_err(WaitError::Pin)? {
+                self.poll(driver).map_err(WaitError::I2c)?;
+                if self.pending > 0 {
+                    continue;
+                }
+
+                backoff = MIN_BACKOFF_MS;
+                KEY_POLL_MS
+            } else {
+                let wait = backoff;
+                backoff = (backoff * 2).min(MAX_BACKOFF_MS);
+                wait
+            };
+
+            if waited >= timeout_ms {
+                return Ok(None);
+            }
+
+            let wait = (wait as u32).min(timeout_ms - waited);
+            delay.delay_ms(wait as u16);
+            waited += wait;
+        }
+    }
+
+    // Queue an event, dropping it if the queue is full.
+    fn push(&mut self, event: KeyEvent) {
+        if self.pending == Q {
+            self.overflowed = true;
+            return;
+        }
+
+        self.events[(self.head + self.pending) % Q] = event;
+        self.pending += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c_mock::I2cMock;
+    use crate::types::Address;
+    use core::convert::Infallible;
+
+    struct Delay {
+        total: u32,
+    }
+
+    impl DelayMs<u16> for Delay {
+        fn delay_ms(&mut self, ms: u16) {
+            self.total += ms as u32;
+        }
+    }
+
+    // An INT pin stuck at the given level.
+    struct Pin {
+        low: bool,
+    }
+
+    impl InputPin for Pin {
+        type Error = Infallible;
+
+        fn is_high(&self) -> Result<bool, Infallible> {
+            Ok(!self.low)
+        }
+
+        fn is_low(&self) -> Result<bool, Infallible> {
+            Ok(self.low)
+        }
+    }
+
+    #[test]
+    fn debounce() {
+        let mut keypad: Keypad<4> = Keypad::new(3);
+
+        // A bounce is ignored.
+        keypad.update([1, 0, 0, 0, 0, 0]);
+        keypad.update([0, 0, 0, 0, 0, 0]);
+        keypad.update([1, 0, 0, 0, 0, 0]);
+        keypad.update([1, 0, 0, 0, 0, 0]);
+        assert_eq!(None, keypad.next_event());
+        assert!(!keypad.is_pressed(0));
+
+        keypad.update([1, 0, 0, 0, 0, 0]);
+        assert_eq!(Some(KeyEvent::Pressed(0)), keypad.next_event());
+        assert!(keypad.is_pressed(0));
+
+        // Held keys are reported once.
+        keypad.update([1, 0, 0, 0, 0, 0]);
+        assert_eq!(None, keypad.next_event());
+
+        for _ in 0..3 {
+            keypad.update([0, 0, 0, 0, 0, 0b0001_0000]);
+        }
+        assert_eq!(Some(KeyEvent::Released(0)), keypad.next_event());
+        assert_eq!(Some(KeyEvent::Pressed(44)), keypad.next_event());
+        assert_eq!(None, keypad.next_event());
+    }
+
+    #[test]
+    fn overflow() {
+        let mut keypad: Keypad<2> = Keypad::new(1);
+
+        keypad.update([0b0000_0111, 0, 0, 0, 0, 0]);
+        assert_eq!(2, keypad.pending());
+        assert!(keypad.take_overflow());
+        assert!(!keypad.take_overflow());
+
+        // The queue wraps around.
+        assert_eq!(Some(KeyEvent::Pressed(0)), keypad.next_event());
+        keypad.update([0, 0, 0, 0, 0, 0]);
+        assert_eq!(Some(KeyEvent::Pressed(1)), keypad.next_event());
+        assert_eq!(Some(KeyEvent::Released(0)), keypad.next_event());
+        assert_eq!(None, keypad.next_event());
+    }
+
+    #[test]
+    fn wait_for_keys() {
+        let mut i2c = I2cMock::new();
+        i2c.press_key(1, 2).unwrap();
+        let mut driver = HT16K33::new(i2c, Address::DEFAULT).into_state();
+        let mut keypad: Keypad<4> = Keypad::new(2);
+        let mut delay = Delay { total: 0 };
+
+        // The INT pin is idle, nothing is read.
+        let mut pin = Pin { low: false };
+        let event = keypad.wait_for_keys(&mut driver, &mut pin, 100, &mut delay);
+        assert_eq!(None, event.unwrap());
+        assert_eq!(100, delay.total);
+        assert!(driver.i2c.int_flag());
+
+        // A pressed key is debounced over a key scan.
+        pin.low = true;
+        delay.total = 0;
+        let event = keypad.wait_for_keys(&mut driver, &mut pin, 100, &mut delay);
+        assert_eq!(Some(KeyEvent::Pressed(18)), event.unwrap());
+        assert_eq!(KEY_POLL_MS as u32, delay.total);
+
+        // The release is found by polling, without the INT pin.
+        pin.low = false;
+        driver.i2c.release_key(1, 2).unwrap();
+        let event = keypad.wait_for_keys(&mut driver, &mut pin, 100, &mut delay);
+        assert_eq!(Some(KeyEvent::Released(18)), event.unwrap());
+    }
+}
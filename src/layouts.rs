@@ -0,0 +1,306 @@
+//! # layouts
+//!
+//! Coordinate mappers from `(x, y)` pixel positions to the [`LedLocation`](../struct.LedLocation.html) wired to
+//! that pixel on a particular board.
+//!
+//! The origin `(0, 0)` is the top-left pixel, `x` increases to the right and `y` increases downwards.
+//!
+//! | Board                                     | Layout                                                                                         |
+//! |-------------------------------------------|------------------------------------------------------------------------------------------------|
+//! | Adafruit 8x8 mini and 1.2" matrix         | [`Matrix8x8`](struct.Matrix8x8.html)                                                           |
+//! | Adafruit bicolor 8x8 matrix               | [`Bicolor8x8Green`](struct.Bicolor8x8Green.html), [`Bicolor8x8Red`](struct.Bicolor8x8Red.html) |
+//! | Adafruit 16x8 matrix                      | [`Matrix16x8`](struct.Matrix16x8.html)                                                         |
+//! | Adafruit quad alphanumeric                | [`Alphanumeric4`](struct.Alphanumeric4.html)                                                   |
+//! | Adafruit 0.56" and 1.2" 4-digit 7-segment | [`SevenSegment4`](struct.SevenSegment4.html)                                                   |
+use crate::constants::{COMMONS_SIZE, ROWS_SIZE};
+use crate::seven_segment::{DIGITS_SIZE, DIGIT_ROWS};
+use crate::types::LedLocation;
+
+/// Maps pixel coordinates to LED locations for a particular board.
+pub trait Layout {
+    /// Return the width, in pixels.
+    fn width(&self) -> u8;
+
+    /// Return the height, in pixels.
+    fn height(&self) -> u8;
+
+    /// Return the LED location of the pixel at `(x, y)`, or `None` if it is outside the layout.
+    fn location(&self, x: u8, y: u8) -> Option<LedLocation>;
+}
+
+impl<T> Layout for &T
+where
+    T: Layout + ?Sized,
+{
+    fn width(&self) -> u8 {
+        (**self).width()
+    }
+
+    fn height(&self) -> u8 {
+        (**self).height()
+    }
+
+    fn location(&self, x: u8, y: u8) -> Option<LedLocation> {
+        (**self).location(x, y)
+    }
+}
+
+/// The display RAM as-is: each column is a row address, and each pixel in the column is a common.
+///
+/// The layout is [`ROWS_SIZE`] pixels wide and [`COMMONS_SIZE`] pixels high.
+///
+/// [`ROWS_SIZE`]: ../constant.ROWS_SIZE.html
+/// [`COMMONS_SIZE`]: ../constant.COMMONS_SIZE.html
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Raw;
+
+impl Layout for Raw {
+    fn width(&self) -> u8 {
+        ROWS_SIZE as u8
+    }
+
+    fn height(&self) -> u8 {
+        COMMONS_SIZE as u8
+    }
+
+    fn location(&self, x: u8, y: u8) -> Option<LedLocation> {
+        LedLocation::new(x, y).ok()
+    }
+}
+
+/// The Adafruit 8x8 LED matrix backpacks (mini and 1.2").
+///
+/// Each matrix row is wired to an even row address, and the columns are wired to the commons rotated by one,
+/// so column 0 is common 7 and column 1 is common 0.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Matrix8x8;
+
+impl Layout for Matrix8x8 {
+    fn width(&self) -> u8 {
+        8
+    }
+
+    fn height(&self) -> u8 {
+        8
+    }
+
+    fn location(&self, x: u8, y: u8) -> Option<LedLocation> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+
+        LedLocation::new(y * 2, (x + 7) % 8).ok()
+    }
+}
+
+/// One color of the Adafruit bicolor 8x8 LED matrix backpack, the green LEDs.
+///
+/// Each matrix row is a pair of row addresses, green then red, and column `x` is common `x`, see
+/// [`bicolor`](../bicolor/index.html) to set both colors of a pixel at once.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Bicolor8x8Green;
+
+impl Layout for Bicolor8x8Green {
+    fn width(&self) -> u8 {
+        8
+    }
+
+    fn height(&self) -> u8 {
+        8
+    }
+
+    fn location(&self, x: u8, y: u8) -> Option<LedLocation> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+
+        LedLocation::new(y * 2, x).ok()
+    }
+}
+
+/// One color of the Adafruit bicolor 8x8 LED matrix backpack, the red LEDs, see
+/// [`Bicolor8x8Green`](struct.Bicolor8x8Green.html).
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Bicolor8x8Red;
+
+impl Layout for Bicolor8x8Red {
+    fn width(&self) -> u8 {
+        8
+    }
+
+    fn height(&self) -> u8 {
+        8
+    }
+
+    fn location(&self, x: u8, y: u8) -> Option<LedLocation> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+
+        LedLocation::new(y * 2 + 1, x).ok()
+    }
+}
+
+/// The Adafruit 16x8 LED matrix backpacks, mounted landscape.
+///
+/// Each matrix row is a pair of row addresses, the left 8 columns then the right 8 columns, and within a row
+/// address column `x` is common `x % 8`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Matrix16x8;
+
+impl Layout for Matrix16x8 {
+    fn width(&self) -> u8 {
+        16
+    }
+
+    fn height(&self) -> u8 {
+        8
+    }
+
+    fn location(&self, x: u8, y: u8) -> Option<LedLocation> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+
+        LedLocation::new(y * 2 + x / 8, x % 8).ok()
+    }
+}
+
+/// The Adafruit quad alphanumeric backpacks, addressed by segment.
+///
+/// Each column is a 14-segment digit from left to right, and each pixel in the column is a segment of the
+/// digit, from `A` (`y = 0`) through `G1`, `G2`, `H` to `N` (`y = 13`), and the decimal point (`y = 14`). Each
+/// digit is a pair of row addresses, with the segments from `H` in the second.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Alphanumeric4;
+
+impl Layout for Alphanumeric4 {
+    fn width(&self) -> u8 {
+        4
+    }
+
+    fn height(&self) -> u8 {
+        15
+    }
+
+    fn location(&self, x: u8, y: u8) -> Option<LedLocation> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+
+        LedLocation::new(x * 2 + y / 8, y % 8).ok()
+    }
+}
+
+/// The Adafruit 4-digit 7-segment backpacks (0.56" and 1.2"), addressed by segment.
+///
+/// Each column is a digit from left to right, and each pixel in the column is a segment of the digit, from
+/// `A` (`y = 0`) to the decimal point (`y = 7`), see [`Segments`](../seven_segment/struct.Segments.html).
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SevenSegment4;
+
+impl Layout for SevenSegment4 {
+    fn width(&self) -> u8 {
+        DIGITS_SIZE as u8
+    }
+
+    fn height(&self) -> u8 {
+        COMMONS_SIZE as u8
+    }
+
+    fn location(&self, x: u8, y: u8) -> Option<LedLocation> {
+        if x >= self.width() {
+            return None;
+        }
+
+        LedLocation::new(DIGIT_ROWS[x as usize] as u8, y).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{DisplayData, DisplayDataAddress};
+
+    #[test]
+    fn raw() {
+        let location = Raw.location(15, 7).unwrap();
+        assert_eq!(DisplayDataAddress::ROW_15, location.row);
+        assert_eq!(DisplayData::COMMON_7, location.common);
+
+        assert_eq!(None, Raw.location(16, 0));
+        assert_eq!(None, Raw.location(0, 8));
+    }
+
+    #[test]
+    fn matrix_8x8() {
+        let location = Matrix8x8.location(0, 0).unwrap();
+        assert_eq!(DisplayDataAddress::ROW_0, location.row);
+        assert_eq!(DisplayData::COMMON_7, location.common);
+
+        let location = Matrix8x8.location(1, 3).unwrap();
+        assert_eq!(DisplayDataAddress::ROW_6, location.row);
+        assert_eq!(DisplayData::COMMON_0, location.common);
+
+        assert_eq!(None, Matrix8x8.location(8, 0));
+        assert_eq!(None, Matrix8x8.location(0, 8));
+    }
+
+    #[test]
+    fn seven_segment_4() {
+        let location = SevenSegment4.location(2, 7).unwrap();
+        assert_eq!(DisplayDataAddress::ROW_6, location.row);
+        assert_eq!(DisplayData::COMMON_7, location.common);
+
+        assert_eq!(None, SevenSegment4.location(4, 0));
+        assert_eq!(None, SevenSegment4.location(0, 8));
+    }
+
+    #[test]
+    fn bicolor_8x8() {
+        let location = Bicolor8x8Green.location(1, 2).unwrap();
+        assert_eq!(DisplayDataAddress::ROW_4, location.row);
+        assert_eq!(DisplayData::COMMON_1, location.common);
+
+        let location = Bicolor8x8Red.location(1, 2).unwrap();
+        assert_eq!(DisplayDataAddress::ROW_5, location.row);
+        assert_eq!(DisplayData::COMMON_1, location.common);
+
+        assert_eq!(None, Bicolor8x8Red.location(8, 0));
+    }
+
+    #[test]
+    fn matrix_16x8() {
+        let location = Matrix16x8.location(9, 3).unwrap();
+        assert_eq!(DisplayDataAddress::ROW_7, location.row);
+        assert_eq!(DisplayData::COMMON_1, location.common);
+
+        assert_eq!(None, Matrix16x8.location(16, 0));
+        assert_eq!(None, Matrix16x8.location(0, 8));
+    }
+
+    #[test]
+    fn alphanumeric_4() {
+        let location = Alphanumeric4.location(1, 14).unwrap();
+        assert_eq!(DisplayDataAddress::ROW_3, location.row);
+        assert_eq!(DisplayData::COMMON_6, location.common);
+
+        assert_eq!(None, Alphanumeric4.location(4, 0));
+        assert_eq!(None, Alphanumeric4.location(0, 15));
+    }
+
+    #[test]
+    fn matrix_8x8_is_one_to_one() {
+        let mut seen = [[false; COMMONS_SIZE]; ROWS_SIZE];
+
+        for x in 0..8 {
+            for y in 0..8 {
+                let location = Matrix8x8.location(x, y).unwrap();
+                let common = location.common.bits().trailing_zeros() as usize;
+
+                assert!(!seen[location.row_as_index()][common]);
+                seen[location.row_as_index()][common] = true;
+            }
+        }
+    }
+}
@@ -2,15 +2,15 @@
 //!
 //! `ht16k33` is a driver for the [Holtek HT16K33 RAM Mapping 16\*8 LED Controller Driver with keyscan](http://www.holtek.com/productdetail/-/vg/HT16K33) chip.
 //!
-//! Currently, only the 28-pin SOP package type is supported.
+//! All of the 20/24/28-pin SOP package types are supported, see `Package`.
 //!
 //! # Features
 //!
 //! - [x] Uses the [`embedded-hal`](https://crates.io/crates/embedded-hal) hardware abstraction.
 //! - [x] Supports `no_std` for embedded devices.
-//! - [ ] Supports all 20/24/28-pin SOP package types.
+//! - [x] Supports all 20/24/28-pin SOP package types.
 //! - [x] Displays all 128 LEDs.
-//! - [ ] Reads keyscan.
+//! - [x] Reads keyscan.
 //! - [ ] Manages interrupts.
 //! - [ ] Manages slave devices.
 //!
@@ -24,17 +24,17 @@
 //! ```!ignore
 //! # use failure::Error;
 //! use linux_embedded_hal::I2cdev;
-//! use ht16k33::HT16K33;
+//! use ht16k33::{Address, HT16K33};
 //! # fn main() -> Result<(), Error>{
 //!
 //! // The I2C device address.
-//! let address = 112u8;
+//! let address = Address::new(0x70);
 //!
 //! // Create an I2C device.
 //! let mut i2c = I2cdev::new("/path/to/i2c/device")?;
-//! i2c.set_slave_address(address as u16)?;
+//! i2c.set_slave_address(address.value() as u16)?;
 //!
-//! let mut ht16k33 = HT16K33::new(i2c, address);
+//! let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
 //!
 //! # Ok(())
 //! # }
@@ -48,6 +48,12 @@
 //! htk16k33 = { version = "*", default-features = false }
 //! ```
 //!
+//! ## Shared I2C bus
+//!
+//! The driver works with any device implementing the `embedded-hal` I2C traits, including bus proxies that
+//! share one bus between several peripherals. To lend the bus out for a while instead, use
+//! [release()](struct.HT16K33.html#method.release) and [attach()](struct.HT16K33.html#method.attach).
+//!
 //! ## All platforms, using I2C simulation
 //!
 //! Not all platforms have I2C support. The provided `ht16k33::i2c_mock` implements the
@@ -56,17 +62,18 @@
 //! ```
 //! # use failure::Error;
 //! use ht16k33::i2c_mock::I2cMock;
-//! use ht16k33::HT16K33;
-//! # fn main() {
+//! use ht16k33::{Address, HT16K33};
+//! # fn main() -> Result<(), Error> {
 //!
 //! // The I2C device address.
-//! let address = 112u8;
+//! let address = Address::new(0x70);
 //!
 //! // Create a mock I2C device.
 //! let mut i2c = I2cMock::new();
 //!
-//! let mut ht16k33 = HT16K33::new(i2c, address);
+//! let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
 //!
+//! # Ok(())
 //! # }
 //! ```
 #![cfg_attr(not(feature = "std"), no_std)]
@@ -75,22 +82,101 @@
 use embedded_hal as hal;
 
 #[cfg(feature = "serde")]
-use serde;
+#[macro_use]
+extern crate serde;
 
 mod constants;
 mod errors;
 mod types;
 
+pub mod bargraph;
+pub mod bicolor;
+pub mod binding;
+pub mod builder;
+pub mod chain;
+pub mod clock;
+pub mod content;
+pub mod diff;
+pub mod effects;
 pub mod i2c_mock;
-
-pub use errors::ValidationError;
-pub use types::{Dimming, Display, DisplayData, DisplayDataAddress, LedLocation, Oscillator};
-
-pub use constants::{COMMONS_SIZE, ROWS_SIZE};
+pub mod keypad;
+pub mod layouts;
+pub mod matrix16x8;
+pub mod panic_screen;
+pub mod pipeline;
+pub mod progress;
+pub mod protocol;
+#[cfg(feature = "std")]
+pub mod recorder;
+pub mod refresh;
+pub mod retry;
+pub mod scroller;
+pub mod self_test;
+pub mod seven_segment;
+#[cfg(feature = "simulator")]
+pub mod simulator;
+pub mod sixteen_segment;
+pub mod splash;
+pub mod split;
+#[cfg(feature = "stats")]
+pub mod stats;
+pub mod test_mode;
+#[cfg(feature = "text_buffer")]
+pub mod text_buffer;
+pub mod units;
+pub mod watchdog;
+
+pub use errors::{Error, ErrorKind, ValidationError};
+pub use types::{
+    Address, AddressSet, BlinkRate, Color, DeviceState, Dimming, Display, DisplayData,
+    DisplayDataAddress, Flip, FlushStrategy, Frame, LedLocation, Oscillator, Package, Rotation,
+    WriteMode,
+};
+
+pub use constants::{
+    display_ram_size, display_write_size, COMMONS_SIZE, I2C_FRAME_SIZE, KEYS_SIZE, ROWS_SIZE,
+};
+use core::fmt;
+use core::marker::PhantomData;
+use hal::blocking::delay::DelayMs;
 use hal::blocking::i2c::{Write, WriteRead};
 
+#[cfg(feature = "stats")]
+type DriverStats = stats::Stats;
+#[cfg(not(feature = "stats"))]
+type DriverStats = ();
+
+#[cfg(feature = "stats")]
+const DRIVER_STATS: DriverStats = stats::Stats::EMPTY;
+#[cfg(not(feature = "stats"))]
+const DRIVER_STATS: DriverStats = ();
+
+/// Marks an [`HT16K33`] that has not been initialized, see [initialize()](struct.HT16K33.html#method.initialize).
+///
+/// [`HT16K33`]: struct.HT16K33.html
+pub struct Uninitialized;
+
+/// Marks an initialized [`HT16K33`], which can write to the chip.
+///
+/// [`HT16K33`]: struct.HT16K33.html
+pub struct Initialized;
+
+/// Marks an [`HT16K33`] in standby, see [power_off()](struct.HT16K33.html#method.power_off).
+///
+/// The driver keeps the display setting to restore, while the chip's display and oscillator are off.
+///
+/// [`HT16K33`]: struct.HT16K33.html
+pub struct Standby;
+
 /// The HT16K33 state and configuration.
-pub struct HT16K33<I2C> {
+///
+/// The `STATE` is [`Uninitialized`] when created, and [`Initialized`] once
+/// [initialize()](struct.HT16K33.html#method.initialize) has configured the chip. Methods that write to the
+/// chip only exist on an initialized driver.
+///
+/// [`Uninitialized`]: struct.Uninitialized.html
+/// [`Initialized`]: struct.Initialized.html
+pub struct HT16K33<I2C, STATE = Initialized> {
     i2c: I2C,
 
     // Device I2C address.
@@ -100,54 +186,87 @@ pub struct HT16K33<I2C> {
     // the current values if it has not been written recently.
     buffer: [DisplayData; ROWS_SIZE],
 
+    // The values last written to the device, used to only write changed rows.
+    // Not valid until the whole buffer has been written or read.
+    shadow_buffer: [DisplayData; ROWS_SIZE],
+    shadow_valid: bool,
+
+    // How changed rows are grouped into writes.
+    flush_strategy: FlushStrategy,
+
+    // Whether `set_led()` writes the changed row right away.
+    write_mode: WriteMode,
+
+    // The orientation of an 8x8 matrix, applied when the buffer is written.
+    rotation: Rotation,
+    flip: Flip,
+
+    // The package variant, limiting the rows written to the chip.
+    package: Package,
+
+    // The LEDs wired on the board, as written to the chip. The others are always written as off.
+    active_mask: Frame,
+
+    // Counters of the display writes, empty without the `stats` feature.
+    stats: DriverStats,
+
     // The following values are write-only registers and cannot
     // be queried from the device. We need to track their state
     // here and synchronize them with the device.
     oscillator_state: Oscillator,
     display_state: Display,
     dimming_state: Dimming,
+
+    state: PhantomData<STATE>,
 }
 
-impl<I2C, E> HT16K33<I2C>
+impl<I2C, E> HT16K33<I2C, Uninitialized>
 where
-    I2C: Write<Error = E> + WriteRead<Error = E>,
+    I2C: Write<Error = E>,
 {
-    /// Create an HT16K33 driver.
+    /// Create an uninitialized HT16K33 driver.
     ///
     /// # Arguments
     ///
     /// * `i2c` - The I2C device to communicate with the HT16K33 chip.
+    /// * `address` - The I2C address of the HT16K33 chip.
     ///
     /// # Examples
     ///
     /// ```
     /// use ht16k33::i2c_mock::I2cMock;
-    /// use ht16k33::HT16K33;
+    /// use ht16k33::{Address, HT16K33};
     /// # fn main() {
     ///
     /// // Create an I2C device.
     /// let mut i2c = I2cMock::new();
     ///
     /// // The I2C device address.
-    /// let address = 0u8;
+    /// let address = Address::DEFAULT;
     ///
     /// let mut ht16k33 = HT16K33::new(i2c, address);
     ///
     /// # }
     /// ```
-    pub fn new(i2c: I2C, address: u8) -> Self {
-        // Configure the initial values to match the power-on defaults.
-        HT16K33 {
-            address,
-            i2c,
-            buffer: [DisplayData::empty(); ROWS_SIZE],
-            oscillator_state: Oscillator::OFF,
-            display_state: Display::OFF,
-            dimming_state: Dimming::BRIGHTNESS_MAX,
-        }
+    pub fn new(i2c: I2C, address: Address) -> Self {
+        HT16K33::new_const(address).attach(i2c)
+    }
+
+    /// Create a [`Builder`](builder/struct.Builder.html) for the initial configuration of an HT16K33 driver,
+    /// written in one step by [build_and_init()](builder/struct.Builder.html#method.build_and_init).
+    ///
+    /// # Arguments
+    ///
+    /// * `i2c` - The I2C device to communicate with the HT16K33 chip.
+    /// * `address` - The I2C address of the HT16K33 chip.
+    pub fn builder(i2c: I2C, address: Address) -> builder::Builder<I2C> {
+        builder::Builder::new(HT16K33::new(i2c, address))
     }
 
-    /// Initialize the HT16K33.
+    /// Initialize the HT16K33, returning the initialized driver.
+    ///
+    /// The display is turned off and cleared before anything else is written, so if the initialization is
+    /// interrupted, e.g. by a brownout, the display is left blank.
     ///
     /// # Examples
     ///
@@ -157,27 +276,278 @@ where
     /// # use ht16k33::HT16K33;
     /// # fn main() -> Result<(), Error> {
     /// # let mut i2c = I2cMock::new();
-    /// # let address = 0u8;
+    /// # let address = ht16k33::Address::DEFAULT;
     ///
-    /// let mut ht16k33 = HT16K33::new(i2c, address);
-    /// ht16k33.initialize()?;
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
     ///
     /// # Ok(())
     /// # }
     /// ```
-    pub fn initialize(&mut self) -> Result<(), E> {
+    pub fn initialize(self) -> Result<HT16K33<I2C, Initialized>, E> {
+        let mut driver = self.into_state();
+
+        // Turn off the display and clear it first, so that an interrupted initialization never shows the
+        // previous contents of the display RAM.
+        driver.set_display(Display::OFF)?;
+        driver.clear_display_buffer();
+        driver.flush_full()?;
+
+        // Set all other values to match their defaults.
+        driver.set_dimming(Dimming::BRIGHTNESS_MAX)?;
+
         // Enable the oscillator so we can use the device.
-        self.set_oscillator(Oscillator::ON)?;
+        driver.set_oscillator(Oscillator::ON)?;
 
-        // Set all values to match their defaults.
-        self.set_display(Display::OFF)?;
-        self.set_dimming(Dimming::BRIGHTNESS_MAX)?;
+        Ok(driver)
+    }
 
-        // And clear the display.
-        self.clear_display_buffer();
-        self.write_display_buffer()?;
+    /// Initialize the HT16K33 with the full power-up sequence, returning the driver with the display on.
+    ///
+    /// The oscillator is enabled first, and `delay` waits for it to start before the display RAM is cleared
+    /// and the display is turned on at full brightness.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// use embedded_hal::blocking::delay::DelayMs;
+    /// use ht16k33::HT16K33;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// # struct Delay;
+    /// # impl DelayMs<u16> for Delay {
+    /// #     fn delay_ms(&mut self, _ms: u16) {}
+    /// # }
+    /// # let mut delay = Delay;
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize_with_delay(&mut delay)?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn initialize_with_delay<D>(self, delay: &mut D) -> Result<HT16K33<I2C, Initialized>, E>
+    where
+        D: DelayMs<u16>,
+    {
+        let mut driver = self.into_state();
+
+        driver.set_display(Display::OFF)?;
+        driver.set_oscillator(Oscillator::ON)?;
+        delay.delay_ms(constants::OSCILLATOR_STARTUP_MS);
+
+        driver.clear_display_buffer();
+        driver.flush_full()?;
+        driver.set_dimming(Dimming::BRIGHTNESS_MAX)?;
+        driver.set_display(Display::ON)?;
+
+        Ok(driver)
+    }
+}
 
-        Ok(())
+impl<I2C, E> HT16K33<I2C, Uninitialized>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    /// Take over an HT16K33 that is already running, returning the initialized driver.
+    ///
+    /// *Requires an I2C device implementing `WriteRead`.*
+    ///
+    /// Unlike [initialize()](struct.HT16K33.html#method.initialize), nothing is written: the display buffer
+    /// is read back from the chip using [read_display_buffer()](struct.HT16K33.html#method.read_display_buffer),
+    /// so that the display keeps its contents, e.g. after a soft reboot of the MCU.
+    ///
+    /// The oscillator, display and dimming cannot be read from the chip, and keep their power-on defaults in
+    /// the driver until they are set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// use ht16k33::{Display, Oscillator, HT16K33};
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).resume()?;
+    /// ht16k33.set_oscillator(Oscillator::ON)?;
+    /// ht16k33.set_display(Display::ON)?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn resume(self) -> Result<HT16K33<I2C, Initialized>, E> {
+        let mut driver = self.into_state();
+        driver.read_display_buffer()?;
+
+        Ok(driver)
+    }
+
+    /// Return the addresses of the HT16K33 chips responding on the bus, e.g. to find the attached backpacks at
+    /// boot.
+    ///
+    /// *Requires an I2C device implementing `WriteRead`.*
+    ///
+    /// After `delay` waits for the chips to power up, each address from `0x70` to `0x77` is probed by reading
+    /// a byte of display RAM, which changes nothing on the chip. An address is found if the read succeeds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// use embedded_hal::blocking::delay::DelayMs;
+    /// use ht16k33::HT16K33;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new();
+    ///
+    /// # struct Delay;
+    /// # impl DelayMs<u16> for Delay {
+    /// #     fn delay_ms(&mut self, _ms: u16) {}
+    /// # }
+    /// # let mut delay = Delay;
+    /// for address in HT16K33::detect(&mut i2c, &mut delay).iter() {
+    ///     // ... create a driver for each display ...
+    /// }
+    ///
+    /// # }
+    /// ```
+    pub fn detect<D>(i2c: &mut I2C, delay: &mut D) -> AddressSet
+    where
+        D: DelayMs<u16>,
+    {
+        delay.delay_ms(constants::POWER_UP_MS);
+
+        let mut addresses = AddressSet::new();
+        for value in Address::MIN..=Address::MAX {
+            let mut read_buffer = [0u8; 1];
+            let probe =
+                i2c.write_read(value, &[DisplayDataAddress::ROW_0.bits()], &mut read_buffer);
+
+            if probe.is_ok() {
+                addresses.insert(Address::new(value));
+            }
+        }
+
+        addresses
+    }
+}
+
+impl<I2C, E> HT16K33<I2C, Standby>
+where
+    I2C: Write<Error = E>,
+{
+    /// Leave standby, restoring the dimming, display RAM and display setting from before
+    /// [power_off()](struct.HT16K33.html#method.power_off).
+    ///
+    /// The display is turned on last, so an interrupted restore never shows partial contents.
+    pub fn power_on(self) -> Result<HT16K33<I2C, Initialized>, E> {
+        let mut driver: HT16K33<I2C, Initialized> = self.into_state();
+        let display = driver.display_state;
+
+        driver.set_oscillator(Oscillator::ON)?;
+        driver.set_dimming(driver.dimming_state)?;
+        driver.flush_full()?;
+
+        // The chip's display is off, write the setting even if it matches the driver.
+        driver.display_state = Display::OFF;
+        driver.set_display(display)?;
+
+        Ok(driver)
+    }
+}
+
+impl HT16K33<(), Uninitialized> {
+    /// Create an uninitialized HT16K33 driver without an I2C device, in a `const` context.
+    ///
+    /// The driver can be placed in a `static`, e.g. an RTIC resource or a `StaticCell`, without lazy
+    /// initialization, and the I2C device is added later with [attach()](struct.HT16K33.html#method.attach).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// use ht16k33::i2c_mock::I2cMock;
+    /// use ht16k33::{Address, Uninitialized, HT16K33};
+    /// # fn main() -> Result<(), Error> {
+    ///
+    /// const DISPLAY: HT16K33<(), Uninitialized> = HT16K33::new_const(Address::DEFAULT);
+    ///
+    /// let i2c = I2cMock::new();
+    /// let mut ht16k33 = DISPLAY.attach(i2c).initialize()?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub const fn new_const(address: Address) -> Self {
+        // Configure the initial values to match the power-on defaults.
+        HT16K33 {
+            address: address.value(),
+            i2c: (),
+            buffer: [DisplayData::empty(); ROWS_SIZE],
+            shadow_buffer: [DisplayData::empty(); ROWS_SIZE],
+            shadow_valid: false,
+            flush_strategy: FlushStrategy::Span,
+            write_mode: WriteMode::WriteThrough,
+            rotation: Rotation::Deg0,
+            flip: Flip::empty(),
+            package: Package::Sop28,
+            active_mask: Frame::from_rows([DisplayData::all(); ROWS_SIZE]),
+            stats: DRIVER_STATS,
+            oscillator_state: Oscillator::OFF,
+            display_state: Display::OFF,
+            dimming_state: Dimming::BRIGHTNESS_MAX,
+            state: PhantomData,
+        }
+    }
+}
+
+impl<STATE> HT16K33<(), STATE> {
+    /// Attach an I2C device to a driver returned by [release()](struct.HT16K33.html#method.release).
+    pub fn attach<I2C>(self, i2c: I2C) -> HT16K33<I2C, STATE> {
+        HT16K33 {
+            i2c,
+            address: self.address,
+            buffer: self.buffer,
+            shadow_buffer: self.shadow_buffer,
+            shadow_valid: self.shadow_valid,
+            flush_strategy: self.flush_strategy,
+            write_mode: self.write_mode,
+            rotation: self.rotation,
+            flip: self.flip,
+            package: self.package,
+            active_mask: self.active_mask,
+            stats: self.stats,
+            oscillator_state: self.oscillator_state,
+            display_state: self.display_state,
+            dimming_state: self.dimming_state,
+            state: PhantomData,
+        }
+    }
+}
+
+impl<I2C, STATE> HT16K33<I2C, STATE> {
+    // Change the state marker, keeping all values.
+    fn into_state<NEXT>(self) -> HT16K33<I2C, NEXT> {
+        HT16K33 {
+            i2c: self.i2c,
+            address: self.address,
+            buffer: self.buffer,
+            shadow_buffer: self.shadow_buffer,
+            shadow_valid: self.shadow_valid,
+            flush_strategy: self.flush_strategy,
+            write_mode: self.write_mode,
+            rotation: self.rotation,
+            flip: self.flip,
+            package: self.package,
+            active_mask: self.active_mask,
+            stats: self.stats,
+            oscillator_state: self.oscillator_state,
+            display_state: self.display_state,
+            dimming_state: self.dimming_state,
+            state: PhantomData,
+        }
     }
 
     /// Return the given I2C device, making this device unusable.
@@ -190,7 +560,7 @@ where
     /// # use ht16k33::HT16K33;
     /// # fn main() {
     /// # let mut i2c = I2cMock::new();
-    /// # let address = 0u8;
+    /// # let address = ht16k33::Address::DEFAULT;
     ///
     /// let mut ht16k33 = HT16K33::new(i2c, address);
     /// i2c = ht16k33.destroy();
@@ -203,6 +573,111 @@ where
         self.i2c
     }
 
+    /// Return the I2C device and the driver without it, keeping the display buffer and state.
+    ///
+    /// Use this to lend the bus to other peripherals, then continue with
+    /// [attach()](struct.HT16K33.html#method.attach). For a bus shared by several drivers at once, pass each
+    /// driver a bus proxy instead, e.g. a `RefCell`-based wrapper implementing the `embedded-hal` I2C traits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    ///
+    /// let (i2c, parked) = ht16k33.release();
+    /// // ... use the I2C bus for other peripherals ...
+    /// let mut ht16k33 = parked.attach(i2c);
+    ///
+    /// ht16k33.flush()?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn release(self) -> (I2C, HT16K33<(), STATE>) {
+        let HT16K33 {
+            i2c,
+            address,
+            buffer,
+            shadow_buffer,
+            shadow_valid,
+            flush_strategy,
+            write_mode,
+            rotation,
+            flip,
+            package,
+            active_mask,
+            stats,
+            oscillator_state,
+            display_state,
+            dimming_state,
+            state,
+        } = self;
+
+        let parked = HT16K33 {
+            i2c: (),
+            address,
+            buffer,
+            shadow_buffer,
+            shadow_valid,
+            flush_strategy,
+            write_mode,
+            rotation,
+            flip,
+            package,
+            active_mask,
+            stats,
+            oscillator_state,
+            display_state,
+            dimming_state,
+            state,
+        };
+
+        (i2c, parked)
+    }
+
+    /// Retry transient I2C errors with the given `policy`, see the [retry](retry/index.html) module.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// use embedded_hal::blocking::delay::DelayMs;
+    /// use ht16k33::i2c_mock::{ErrorKind, I2cMock, I2cMockError};
+    /// use ht16k33::retry::RetryPolicy;
+    /// # use ht16k33::HT16K33;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// # struct Delay;
+    /// # impl DelayMs<u16> for Delay {
+    /// #     fn delay_ms(&mut self, _ms: u16) {}
+    /// # }
+    /// # let delay = Delay;
+    /// let policy = RetryPolicy::new(3, 2, delay, |error: &I2cMockError| error.kind() == ErrorKind::Nack);
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?.with_retry(policy);
+    /// ht16k33.flush()?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_retry<D, F>(
+        self,
+        policy: retry::RetryPolicy<D, F>,
+    ) -> HT16K33<retry::Retry<I2C, D, F>, STATE> {
+        let (i2c, parked) = self.release();
+
+        parked.attach(retry::Retry::new(i2c, policy))
+    }
+
     /// Return the current display buffer.
     ///
     /// # Examples
@@ -213,7 +688,7 @@ where
     /// # use ht16k33::HT16K33;
     /// # fn main() {
     /// # let mut i2c = I2cMock::new();
-    /// # let address = 0u8;
+    /// # let address = ht16k33::Address::DEFAULT;
     ///
     /// let mut ht16k33 = HT16K33::new(i2c, address);
     /// let &buffer = ht16k33.display_buffer();
@@ -224,6 +699,32 @@ where
         &self.buffer
     }
 
+    /// Return the current display buffer for modification.
+    ///
+    /// The buffer must be written using [write_display_buffer()](struct.HT16K33.html#method.write_display_buffer)
+    /// for the change to be displayed. LEDs outside the
+    /// [active_mask()](struct.HT16K33.html#method.active_mask) enabled through this reference stay in the buffer
+    /// until the next write, which clears them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// use ht16k33::DisplayData;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address);
+    /// ht16k33.display_buffer_mut()[0] = DisplayData::all();
+    ///
+    /// # }
+    /// ```
+    pub fn display_buffer_mut(&mut self) -> &mut [DisplayData; ROWS_SIZE] {
+        &mut self.buffer
+    }
+
     /// Return the current oscillator state.
     ///
     /// # Examples
@@ -234,7 +735,7 @@ where
     /// # use ht16k33::HT16K33;
     /// # fn main() {
     /// # let mut i2c = I2cMock::new();
-    /// # let address = 0u8;
+    /// # let address = ht16k33::Address::DEFAULT;
     ///
     /// let mut ht16k33 = HT16K33::new(i2c, address);
     /// let oscillator = ht16k33.oscillator();
@@ -255,7 +756,7 @@ where
     /// # use ht16k33::HT16K33;
     /// # fn main() {
     /// # let mut i2c = I2cMock::new();
-    /// # let address = 0u8;
+    /// # let address = ht16k33::Address::DEFAULT;
     ///
     /// let mut ht16k33 = HT16K33::new(i2c, address);
     /// let display = ht16k33.display();
@@ -276,7 +777,7 @@ where
     /// # use ht16k33::HT16K33;
     /// # fn main() {
     /// # let mut i2c = I2cMock::new();
-    /// # let address = 0u8;
+    /// # let address = ht16k33::Address::DEFAULT;
     ///
     /// let mut ht16k33 = HT16K33::new(i2c, address);
     /// let dimming = ht16k33.dimming();
@@ -287,108 +788,123 @@ where
         &self.dimming_state
     }
 
-    /// Enable/disable an LED address in the display buffer.
-    ///
-    /// The buffer must be written using [write_display_buffer()](struct.HT16K33.html#method.write_display_buffer)
-    /// for the change to be displayed.
-    ///
-    /// # Arguments
-    ///
-    /// * `location` - The LED location to update.
-    /// * `enabled` - Set the LED on (true) or off (false).
+    /// Return the cached oscillator, display and dimming settings, and a copy of the display buffer.
     ///
     /// # Examples
     ///
     /// ```
+    /// # use failure::Error;
     /// # use ht16k33::i2c_mock::I2cMock;
     /// # use ht16k33::HT16K33;
-    /// # use ht16k33::ValidationError;
-    /// use ht16k33::LedLocation;
-    /// # fn main() -> Result<(), ValidationError> {
+    /// use ht16k33::{BlinkRate, Dimming};
+    /// # fn main() -> Result<(), Error> {
     /// # let mut i2c = I2cMock::new();
-    /// # let address = 0u8;
+    /// # let address = ht16k33::Address::DEFAULT;
     ///
-    /// let mut ht16k33 = HT16K33::new(i2c, address);
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    /// ht16k33.set_dimming(Dimming::BRIGHTNESS_MIN)?;
     ///
-    /// let led_location = LedLocation::new(0, 0)?;
-    /// ht16k33.update_display_buffer(led_location, true);
+    /// let state = ht16k33.state();
+    /// assert_eq!(Dimming::BRIGHTNESS_MIN, state.dimming);
+    /// assert_eq!(BlinkRate::Off, state.blink_rate());
     ///
     /// # Ok(())
     /// # }
     /// ```
-    pub fn update_display_buffer(&mut self, location: LedLocation, enabled: bool) {
-        // TODO Validate `address` parameter.
+    pub fn state(&self) -> DeviceState {
+        DeviceState {
+            oscillator: self.oscillator_state,
+            display: self.display_state,
+            dimming: self.dimming_state,
+            buffer: Frame::from_rows(self.buffer),
+        }
+    }
 
-        // Turn on/off the specified LED.
-        self.buffer[location.row_as_index()].set(location.common, enabled);
+    /// Return how [flush()](struct.HT16K33.html#method.flush) groups changed rows into writes.
+    pub fn flush_strategy(&self) -> FlushStrategy {
+        self.flush_strategy
     }
 
-    /// Clear contents of the display buffer.
-    ///
-    /// The buffer must be written using [write_display_buffer()](struct.HT16K33.html#method.write_display_buffer)
-    /// for the change to be displayed.
+    /// Set how [flush()](struct.HT16K33.html#method.flush) groups changed rows into writes.
     ///
     /// # Examples
     ///
     /// ```
     /// # use ht16k33::i2c_mock::I2cMock;
     /// # use ht16k33::HT16K33;
+    /// use ht16k33::FlushStrategy;
     /// # fn main() {
     /// # let mut i2c = I2cMock::new();
-    /// # let address = 0u8;
+    /// # let address = ht16k33::Address::DEFAULT;
     ///
     /// let mut ht16k33 = HT16K33::new(i2c, address);
-    /// ht16k33.clear_display_buffer();
+    /// ht16k33.set_flush_strategy(FlushStrategy::COALESCE);
     ///
     /// # }
     /// ```
-    pub fn clear_display_buffer(&mut self) {
-        // TODO is there any advantage to iteration vs just assigning
-        // a new, empty `[0; ROWS_SIZE]` array?
-        for row in self.buffer.iter_mut() {
-            *row = DisplayData::COMMON_NONE;
-        }
+    pub fn set_flush_strategy(&mut self, flush_strategy: FlushStrategy) {
+        self.flush_strategy = flush_strategy;
     }
 
-    /// Control the oscillator.
-    ///
-    /// # Arguments
+    /// Return whether [set_led()](struct.HT16K33.html#method.set_led) writes to the chip.
+    pub fn write_mode(&self) -> WriteMode {
+        self.write_mode
+    }
+
+    /// Set whether [set_led()](struct.HT16K33.html#method.set_led) writes the changed row to the chip right
+    /// away, or only updates the display buffer until the next [flush()](struct.HT16K33.html#method.flush).
     ///
-    /// * `oscillator` - Set the oscillator On/Off.
+    /// Buffering avoids a write per LED when changing many LEDs at once.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use failure::Error;
     /// # use ht16k33::i2c_mock::I2cMock;
     /// # use ht16k33::HT16K33;
-    /// use ht16k33::Oscillator;
-    /// # fn main() -> Result<(), Error> {
+    /// use ht16k33::WriteMode;
+    /// # fn main() {
     /// # let mut i2c = I2cMock::new();
-    /// # let address = 0u8;
+    /// # let address = ht16k33::Address::DEFAULT;
     ///
     /// let mut ht16k33 = HT16K33::new(i2c, address);
-    /// ht16k33.set_oscillator(Oscillator::ON)?;
+    /// ht16k33.set_write_mode(WriteMode::Buffered);
     ///
-    /// # Ok(())
     /// # }
     /// ```
-    pub fn set_oscillator(&mut self, oscillator: Oscillator) -> Result<(), E> {
-        self.oscillator_state = oscillator;
-
-        self.i2c.write(
-            self.address,
-            &[(Oscillator::COMMAND | self.oscillator_state).bits()],
-        )?;
+    pub fn set_write_mode(&mut self, write_mode: WriteMode) {
+        self.write_mode = write_mode;
+    }
 
-        Ok(())
+    /// Return the counters of the display writes.
+    ///
+    /// *Requires the `stats` feature.*
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> &stats::Stats {
+        &self.stats
     }
 
-    /// Control the display.
+    /// Reset the counters of the display writes.
     ///
-    /// # Arguments
+    /// *Requires the `stats` feature.*
+    #[cfg(feature = "stats")]
+    pub fn reset_stats(&mut self) {
+        self.stats = stats::Stats::default();
+    }
+
+    /// Return the rotation of an 8x8 matrix backpack.
+    pub fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    /// Rotate the display buffer of an 8x8 matrix backpack clockwise when it is written, for a backpack
+    /// mounted rotated.
     ///
-    /// * `display` - Set the display On/Off.
+    /// The display buffer keeps the unrotated [`Matrix8x8`](layouts/struct.Matrix8x8.html) layout, including the
+    /// column 0 on common 7 wiring, and only the rows written to the chip are rotated. The odd rows, which
+    /// are not wired on the 8x8 backpacks, are written unchanged. Flips, see
+    /// [set_flip()](struct.HT16K33.html#method.set_flip), are applied before the rotation.
+    ///
+    /// The new rotation is shown on the next [flush()](struct.HT16K33.html#method.flush).
     ///
     /// # Examples
     ///
@@ -396,33 +912,182 @@ where
     /// # use failure::Error;
     /// # use ht16k33::i2c_mock::I2cMock;
     /// # use ht16k33::HT16K33;
-    /// use ht16k33::Display;
+    /// use ht16k33::layouts::{Layout, Matrix8x8};
+    /// use ht16k33::Rotation;
     /// # fn main() -> Result<(), Error> {
     /// # let mut i2c = I2cMock::new();
-    /// # let address = 0u8;
+    /// # let address = ht16k33::Address::DEFAULT;
     ///
-    /// let mut ht16k33 = HT16K33::new(i2c, address);
-    /// ht16k33.set_display(Display::HALF_HZ)?;
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    /// ht16k33.set_rotation(Rotation::Deg90);
+    ///
+    /// // The top-left pixel is shown top-right.
+    /// ht16k33.update_display_buffer(Matrix8x8.location(0, 0).unwrap(), true);
+    /// ht16k33.flush()?;
     ///
     /// # Ok(())
     /// # }
     /// ```
-    pub fn set_display(&mut self, display: Display) -> Result<(), E> {
-        self.display_state = display;
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.rotation = rotation;
+        self.mask_buffer();
+    }
 
-        self.i2c.write(
-            self.address,
-            &[(Display::COMMAND | self.display_state).bits()],
-        )?;
+    /// Return the mirroring of an 8x8 matrix backpack.
+    pub fn flip(&self) -> Flip {
+        self.flip
+    }
 
-        Ok(())
+    /// Mirror the display buffer of an 8x8 matrix backpack when it is written, before it is rotated, see
+    /// [set_rotation()](struct.HT16K33.html#method.set_rotation).
+    ///
+    /// The new mirroring is shown on the next [flush()](struct.HT16K33.html#method.flush).
+    pub fn set_flip(&mut self, flip: Flip) {
+        self.flip = flip;
+        self.mask_buffer();
     }
 
-    /// Control the display dimming.
+    /// Return the package variant of the chip.
+    pub fn package(&self) -> Package {
+        self.package
+    }
+
+    /// Set the package variant of the chip.
     ///
-    /// # Arguments
+    /// The display buffer keeps all [`ROWS_SIZE`](constant.ROWS_SIZE.html) rows, but only the rows wired to an
+    /// output of the package are written to the chip. Use [location()](struct.HT16K33.html#method.location) to
+    /// validate LED locations against the package.
     ///
-    /// * `dimming` - Set the dimming brightness.
+    /// # Examples
+    ///
+    /// ```
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// use ht16k33::Package;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address);
+    /// ht16k33.set_package(Package::Sop24);
+    ///
+    /// assert!(ht16k33.location(11, 0).is_ok());
+    /// assert!(ht16k33.location(12, 0).is_err());
+    ///
+    /// # }
+    /// ```
+    pub fn set_package(&mut self, package: Package) {
+        self.package = package;
+    }
+
+    /// Return the LEDs wired on the board, see [set_active_mask()](struct.HT16K33.html#method.set_active_mask).
+    pub fn active_mask(&self) -> Frame {
+        self.active_mask
+    }
+
+    /// Set the LEDs wired on the board, for custom boards only populating part of the matrix. By default all LEDs
+    /// are active.
+    ///
+    /// The `mask` uses the chip locations, before the [rotation()](struct.HT16K33.html#method.rotation). The LEDs
+    /// outside the mask are cleared from the display buffer, and every buffer update drops them, except the
+    /// changes made through [display_buffer_mut()](struct.HT16K33.html#method.display_buffer_mut), which are
+    /// cleared on the next write. The chip always gets them off, and
+    /// [flush_full()](struct.HT16K33.html#method.flush_full) skips the rows without active LEDs at either end of
+    /// the display RAM.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// # use ht16k33::ValidationError;
+    /// use ht16k33::{DisplayData, Frame, LedLocation, ROWS_SIZE};
+    /// # fn main() -> Result<(), ValidationError> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address);
+    ///
+    /// // Only the first 4 rows are populated.
+    /// let mut mask = [DisplayData::empty(); ROWS_SIZE];
+    /// mask[..4].copy_from_slice(&[DisplayData::all(); 4]);
+    /// ht16k33.set_active_mask(Frame::from_rows(mask));
+    ///
+    /// ht16k33.update_display_buffer(LedLocation::new(5, 0)?, true);
+    /// assert!(ht16k33.display_buffer()[5].is_empty());
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_active_mask(&mut self, mask: Frame) {
+        self.active_mask = mask;
+        self.mask_buffer();
+    }
+
+    /// Create an `LedLocation` with the given `row` and `common` values, validated against the package
+    /// variant, see [Package::location()](enum.Package.html#method.location).
+    ///
+    /// # Errors
+    ///
+    /// If the `row` or `common` does not exist on the package, then
+    /// [`ValidationError::ValueTooLarge`](enum.ValidationError.html#variant.ValueTooLarge) is returned.
+    pub fn location(&self, row: u8, common: u8) -> Result<LedLocation, ValidationError> {
+        self.package.location(row, common)
+    }
+
+    /// Write the whole display buffer into `frame` as the bytes of a single I2C write, the display data address
+    /// followed by the 16 rows as written to the chip.
+    ///
+    /// The frame is built in a buffer owned by the caller, e.g. a static DMA buffer, so a DMA-capable I2C
+    /// peripheral can send it as is. Call [mark_i2c_frame_written()](struct.HT16K33.html#method.mark_i2c_frame_written)
+    /// once the transfer is done, so that the next [flush()](struct.HT16K33.html#method.flush) only writes later
+    /// changes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// use ht16k33::{LedLocation, I2C_FRAME_SIZE};
+    /// # fn main() -> Result<(), ht16k33::ValidationError> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address);
+    /// ht16k33.update_display_buffer(LedLocation::new(1, 0)?, true);
+    ///
+    /// let mut frame = [0u8; I2C_FRAME_SIZE];
+    /// ht16k33.write_i2c_frame(&mut frame);
+    /// assert_eq!([0x00, 0x00, 0x01], frame[..3]);
+    ///
+    /// // ... send the frame with a DMA transfer ...
+    /// ht16k33.mark_i2c_frame_written(&frame);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn write_i2c_frame(&self, frame: &mut [u8; I2C_FRAME_SIZE]) {
+        let buffer = self.oriented_buffer();
+
+        frame[0] = DisplayDataAddress::ROW_0.bits();
+        for (value, row) in frame[1..].iter_mut().zip(buffer.iter()) {
+            *value = row.bits();
+        }
+    }
+
+    /// Record that the `frame` built by [write_i2c_frame()](struct.HT16K33.html#method.write_i2c_frame) was
+    /// written to the chip outside of the driver.
+    pub fn mark_i2c_frame_written(&mut self, frame: &[u8; I2C_FRAME_SIZE]) {
+        for (row, value) in self.shadow_buffer.iter_mut().zip(frame[1..].iter()) {
+            *row = DisplayData::from_bits_truncate(*value);
+        }
+        self.shadow_valid = true;
+    }
+
+    /// Return whether a [flush()](struct.HT16K33.html#method.flush) would write anything, i.e. the display buffer
+    /// changed since the last write or the chip contents are unknown.
+    ///
+    /// Use this to skip scheduling a flush, e.g. from a low-priority RTIC task, when there is nothing to write.
     ///
     /// # Examples
     ///
@@ -430,29 +1095,60 @@ where
     /// # use failure::Error;
     /// # use ht16k33::i2c_mock::I2cMock;
     /// # use ht16k33::HT16K33;
-    /// use ht16k33::Dimming;
+    /// use ht16k33::LedLocation;
     /// # fn main() -> Result<(), Error> {
     /// # let mut i2c = I2cMock::new();
-    /// # let address = 0u8;
+    /// # let address = ht16k33::Address::DEFAULT;
     ///
-    /// let mut ht16k33 = HT16K33::new(i2c, address);
-    /// ht16k33.set_dimming(Dimming::from_u8(4)?)?;
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    /// ht16k33.flush()?;
+    /// assert!(!ht16k33.is_dirty());
+    ///
+    /// ht16k33.update_display_buffer(LedLocation::new(3, 0)?, true);
+    /// assert!(ht16k33.is_dirty());
     ///
     /// # Ok(())
     /// # }
     /// ```
-    pub fn set_dimming(&mut self, dimming: Dimming) -> Result<(), E> {
-        self.dimming_state = dimming;
+    pub fn is_dirty(&self) -> bool {
+        if !self.shadow_valid {
+            return true;
+        }
 
-        self.i2c.write(
-            self.address,
-            &[(Dimming::COMMAND | self.dimming_state).bits()],
-        )?;
+        let buffer = self.oriented_buffer();
+        let rows = self.package.rows();
 
-        Ok(())
+        !diff::diff_frames(&self.shadow_buffer[..rows], &buffer).is_empty()
     }
 
-    /// Control an LED.
+    // Return the display buffer as written to the chip.
+    fn oriented_buffer(&self) -> [DisplayData; ROWS_SIZE] {
+        let mut buffer = types::orient(&self.buffer, self.rotation, self.flip, false);
+        for (row, mask) in buffer.iter_mut().zip(self.active_mask.rows().iter()) {
+            *row &= *mask;
+        }
+
+        buffer
+    }
+
+    // Clear the LEDs of the display buffer outside the active mask, see `set_active_mask()`.
+    fn mask_buffer(&mut self) {
+        let mask = types::orient(self.active_mask.rows(), self.rotation, self.flip, true);
+        for (row, mask) in self.buffer.iter_mut().zip(mask.iter()) {
+            *row &= *mask;
+        }
+    }
+
+    // Return whether the LED at the display buffer `location` is wired, see `set_active_mask()`.
+    fn is_active(&self, location: LedLocation) -> bool {
+        let location = types::orient_location(location, self.rotation, self.flip, false);
+        self.active_mask.led(location)
+    }
+
+    /// Enable/disable an LED address in the display buffer.
+    ///
+    /// The buffer must be written using [write_display_buffer()](struct.HT16K33.html#method.write_display_buffer)
+    /// for the change to be displayed.
     ///
     /// # Arguments
     ///
@@ -462,336 +1158,1940 @@ where
     /// # Examples
     ///
     /// ```
-    /// # use failure::Error;
     /// # use ht16k33::i2c_mock::I2cMock;
     /// # use ht16k33::HT16K33;
+    /// # use ht16k33::ValidationError;
     /// use ht16k33::LedLocation;
-    /// # fn main() -> Result<(), Error> {
+    /// # fn main() -> Result<(), ValidationError> {
     /// # let mut i2c = I2cMock::new();
-    /// # let address = 0u8;
+    /// # let address = ht16k33::Address::DEFAULT;
     ///
     /// let mut ht16k33 = HT16K33::new(i2c, address);
     ///
     /// let led_location = LedLocation::new(0, 0)?;
-    /// ht16k33.set_led(led_location, true)?;
+    /// ht16k33.update_display_buffer(led_location, true);
     ///
     /// # Ok(())
     /// # }
     /// ```
-    pub fn set_led(&mut self, location: LedLocation, enabled: bool) -> Result<(), E> {
+    pub fn update_display_buffer(&mut self, location: LedLocation, enabled: bool) {
         // TODO Validate `address` parameter.
-        self.update_display_buffer(location, enabled);
 
-        self.i2c.write(
-            self.address,
-            &[
-                location.row.bits(),
-                self.buffer[location.row_as_index()].bits(),
-            ],
-        )?;
+        if !self.is_active(location) {
+            return;
+        }
 
-        Ok(())
+        // Turn on/off the specified LED.
+        self.buffer[location.row_as_index()].set(location.common, enabled);
     }
 
-    /// Write the display buffer to the HT16K33 chip.
+    /// Clear contents of the display buffer.
+    ///
+    /// The buffer must be written using [write_display_buffer()](struct.HT16K33.html#method.write_display_buffer)
+    /// for the change to be displayed.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use failure::Error;
     /// # use ht16k33::i2c_mock::I2cMock;
     /// # use ht16k33::HT16K33;
-    /// # fn main() -> Result<(), Box<Error>> {
+    /// # fn main() {
     /// # let mut i2c = I2cMock::new();
-    /// # let address = 0u8;
+    /// # let address = ht16k33::Address::DEFAULT;
     ///
     /// let mut ht16k33 = HT16K33::new(i2c, address);
-    /// ht16k33.write_display_buffer();
+    /// ht16k33.clear_display_buffer();
     ///
-    /// # Ok(())
     /// # }
     /// ```
-    pub fn write_display_buffer(&mut self) -> Result<(), E> {
-        let mut write_buffer = [0u8; ROWS_SIZE + 1];
-        write_buffer[0] = DisplayDataAddress::ROW_0.bits();
-
-        for value in 0usize..self.buffer.len() {
-            write_buffer[value + 1] = self.buffer[value].bits();
+    pub fn clear_display_buffer(&mut self) {
+        // TODO is there any advantage to iteration vs just assigning
+        // a new, empty `[0; ROWS_SIZE]` array?
+        for row in self.buffer.iter_mut() {
+            *row = DisplayData::COMMON_NONE;
         }
+    }
 
-        self.i2c.write(self.address, &write_buffer)?;
+    /// Return a copy of the display buffer as a [`Frame`](struct.Frame.html).
+    ///
+    /// Unlike the buffer, frames have a stable binary format, see
+    /// [Frame::to_bytes()](struct.Frame.html#method.to_bytes).
+    pub fn frame(&self) -> Frame {
+        Frame::from_rows(self.buffer)
+    }
 
-        Ok(())
+    /// Replace the display buffer with the `frame`.
+    ///
+    /// The buffer must be written using [write_display_buffer()](struct.HT16K33.html#method.write_display_buffer)
+    /// for the change to be displayed.
+    pub fn set_frame(&mut self, frame: &Frame) {
+        self.buffer = *frame.rows();
+        self.mask_buffer();
     }
 
-    /// Read the display buffer from the HT16K33 chip.
+    /// Enable all LEDs in the display buffer.
+    ///
+    /// The buffer must be written using [write_display_buffer()](struct.HT16K33.html#method.write_display_buffer)
+    /// for the change to be displayed.
+    pub fn fill(&mut self) {
+        self.buffer = [DisplayData::all(); ROWS_SIZE];
+        self.mask_buffer();
+    }
+
+    /// Disable all LEDs of a row in the display buffer.
+    ///
+    /// The buffer must be written using [write_display_buffer()](struct.HT16K33.html#method.write_display_buffer)
+    /// for the change to be displayed.
+    pub fn clear_row(&mut self, row: DisplayDataAddress) {
+        self.buffer[row.bits() as usize] = DisplayData::COMMON_NONE;
+    }
+
+    /// Enable/disable many LEDs in the display buffer.
+    ///
+    /// Only the buffer is updated, so a whole image is written with a single
+    /// [write_display_buffer()](struct.HT16K33.html#method.write_display_buffer) instead of one write per LED.
     ///
     /// # Examples
     ///
     /// ```
+    /// # use failure::Error;
     /// # use ht16k33::i2c_mock::I2cMock;
     /// # use ht16k33::HT16K33;
-    /// # use std::error::Error;
-    /// # fn main() -> Result<(), Box<Error>> {
+    /// use ht16k33::LedLocation;
+    /// # fn main() -> Result<(), Error> {
     /// # let mut i2c = I2cMock::new();
-    /// # let address = 0u8;
+    /// # let address = ht16k33::Address::DEFAULT;
     ///
-    /// let mut ht16k33 = HT16K33::new(i2c, address);
-    /// ht16k33.read_display_buffer();
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    ///
+    /// // A diagonal line.
+    /// let pixels = (0..8).map(|index| (LedLocation::new(index, index).unwrap(), true));
+    /// ht16k33.set_pixels(pixels);
+    /// ht16k33.write_display_buffer()?;
     ///
     /// # Ok(())
     /// # }
     /// ```
-    pub fn read_display_buffer(&mut self) -> Result<(), E> {
-        let mut read_buffer = [0u8; ROWS_SIZE];
+    pub fn set_pixels<P>(&mut self, pixels: P)
+    where
+        P: IntoIterator<Item = (LedLocation, bool)>,
+    {
+        for (location, enabled) in pixels {
+            self.buffer[location.row_as_index()].set(location.common, enabled);
+        }
+        self.mask_buffer();
+    }
 
-        self.i2c.write_read(
-            self.address,
-            &[DisplayDataAddress::ROW_0.bits()],
-            &mut read_buffer,
-        )?;
+    /// Return every LED location with whether it is enabled in the display buffer, row by row.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address);
+    /// ht16k33.fill();
+    ///
+    /// assert_eq!(128, ht16k33.iter_leds().filter(|&(_, enabled)| enabled).count());
+    ///
+    /// # }
+    /// ```
+    pub fn iter_leds(&self) -> impl Iterator<Item = (LedLocation, bool)> + '_ {
+        (0..ROWS_SIZE as u8)
+            .flat_map(|row| (0..COMMONS_SIZE as u8).map(move |common| (row, common)))
+            .filter_map(move |(row, common)| {
+                let location = LedLocation::new(row, common).ok()?;
+                Some((
+                    location,
+                    self.buffer[row as usize].contains(location.common),
+                ))
+            })
+    }
 
-        for (index, value) in read_buffer.iter().enumerate() {
-            self.buffer[index] = DisplayData::from_bits_truncate(*value);
+    /// Write a description of the driver state to `out`, e.g. to include in a bug report.
+    ///
+    /// The dump lists the address, the cached register values, the flush and orientation settings, and the
+    /// display buffer and the rows last written to the chip as hex, one line each. With the `stats` feature, a
+    /// last line lists the [stats()](struct.HT16K33.html#method.stats) counters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// # fn main() {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let ht16k33 = HT16K33::new(i2c, address);
+    ///
+    /// let mut dump = String::new();
+    /// ht16k33.dump(&mut dump).unwrap();
+    ///
+    /// assert!(dump.starts_with("address: Address(0x70)\n"));
+    ///
+    /// # }
+    /// ```
+    pub fn dump<W>(&self, out: &mut W) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        writeln!(out, "address: {}", Address::new(self.address))?;
+        writeln!(out, "oscillator: {}", self.oscillator_state)?;
+        writeln!(out, "display: {}", self.display_state)?;
+        writeln!(out, "dimming: {}", self.dimming_state)?;
+        writeln!(out, "flush strategy: {:?}", self.flush_strategy)?;
+        writeln!(out, "write mode: {:?}", self.write_mode)?;
+        writeln!(out, "rotation: {:?}, flip: {:?}", self.rotation, self.flip)?;
+        writeln!(out, "package: {:?}", self.package)?;
+        writeln!(out, "buffer: {}", Frame::from_rows(self.buffer))?;
+
+        if self.shadow_valid {
+            writeln!(out, "written: {}", Frame::from_rows(self.shadow_buffer))?;
+        } else {
+            writeln!(out, "written: unknown")?;
         }
 
+        #[cfg(feature = "stats")]
+        writeln!(
+            out,
+            "stats: {} transfers, {} bytes, {} flushes, {}us total, {}us last, {}us max",
+            self.stats.transfers,
+            self.stats.bytes,
+            self.stats.flushes,
+            self.stats.total_us,
+            self.stats.last_us,
+            self.stats.max_us
+        )?;
+
         Ok(())
     }
 }
 
-#[cfg(test)]
-mod tests {
-    extern crate std;
-    use embedded_hal_mock as hal;
+impl<I2C, E> HT16K33<I2C, Initialized>
+where
+    I2C: Write<Error = E>,
+{
+    /// Control the oscillator.
+    ///
+    /// # Arguments
+    ///
+    /// * `oscillator` - Set the oscillator On/Off.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// use ht16k33::Oscillator;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    /// ht16k33.set_oscillator(Oscillator::ON)?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_oscillator(&mut self, oscillator: Oscillator) -> Result<(), E> {
+        self.oscillator_state = oscillator;
 
-    use self::hal::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
-    use super::*;
+        self.i2c.write(
+            self.address,
+            &[(Oscillator::COMMAND | self.oscillator_state).bits()],
+        )?;
 
-    use std::vec;
+        Ok(())
+    }
 
-    const ADDRESS: u8 = 0;
+    /// Control the display.
+    ///
+    /// Turning on a display that is off first writes any pending display buffer changes, see
+    /// [flush()](struct.HT16K33.html#method.flush). If the update is interrupted, e.g. by a brownout, the
+    /// display stays off rather than showing partially written contents.
+    ///
+    /// # Arguments
+    ///
+    /// * `display` - Set the display On/Off.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// use ht16k33::Display;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    /// ht16k33.set_display(Display::HALF_HZ)?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_display(&mut self, display: Display) -> Result<(), E> {
+        if !self.display_state.contains(Display::ON) && display.contains(Display::ON) {
+            self.flush()?;
+        }
+
+        self.display_state = display;
+
+        self.i2c.write(
+            self.address,
+            &[(Display::COMMAND | self.display_state).bits()],
+        )?;
+
+        Ok(())
+    }
+
+    /// Turn the display on or off, blinking at the given rate.
+    ///
+    /// This composes the [`Display`] setting with [`Display::new()`], see
+    /// [set_display()](struct.HT16K33.html#method.set_display).
+    ///
+    /// # Arguments
+    ///
+    /// * `on` - Whether the display is on.
+    /// * `blink` - The blink rate, ignored when the display is off.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// use ht16k33::{BlinkRate, Display};
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    /// ht16k33.set_display_blink(true, BlinkRate::Hz2)?;
+    ///
+    /// assert_eq!(Display::TWO_HZ, *ht16k33.display());
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`Display`]: struct.Display.html
+    /// [`Display::new()`]: struct.Display.html#method.new
+    pub fn set_display_blink(&mut self, on: bool, blink: BlinkRate) -> Result<(), E> {
+        self.set_display(Display::new(on, blink))
+    }
+
+    /// Write the `state` to the HT16K33 chip, e.g. after a resume from suspend or a reset of the chip.
+    ///
+    /// The whole state is written regardless of the driver's cached settings, as the chip may have lost them. Like
+    /// [power_on()](struct.HT16K33.html#method.power_on), the display is turned off first and turned on last, so
+    /// an interrupted restore never shows partial contents.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// use ht16k33::{Dimming, Display};
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    /// ht16k33.set_display(Display::ONE_HZ)?;
+    /// let state = ht16k33.state();
+    ///
+    /// // ... the chip is reset ...
+    /// ht16k33.restore_state(state)?;
+    ///
+    /// assert_eq!(Display::ONE_HZ, *ht16k33.display());
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn restore_state(&mut self, state: DeviceState) -> Result<(), E> {
+        self.i2c
+            .write(self.address, &[(Display::COMMAND | Display::OFF).bits()])?;
+        self.display_state = Display::OFF;
+
+        self.set_oscillator(state.oscillator)?;
+        self.set_dimming(state.dimming)?;
+        self.buffer = *state.buffer.rows();
+        self.flush_full()?;
+
+        self.set_display(state.display)
+    }
+
+    /// Enter standby to save power, turning off the display and then the oscillator.
+    ///
+    /// The display buffer, dimming and display setting are kept in the driver, and restored by
+    /// [power_on()](struct.HT16K33.html#method.power_on).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// use ht16k33::Display;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    /// ht16k33.set_display(Display::ONE_HZ)?;
+    ///
+    /// let standby = ht16k33.power_off()?;
+    /// // ... sleep ...
+    /// let ht16k33 = standby.power_on()?;
+    ///
+    /// assert_eq!(Display::ONE_HZ, *ht16k33.display());
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn power_off(mut self) -> Result<HT16K33<I2C, Standby>, E> {
+        // Keep the display setting in the driver, only the chip is turned off.
+        self.i2c
+            .write(self.address, &[(Display::COMMAND | Display::OFF).bits()])?;
+        self.set_oscillator(Oscillator::OFF)?;
+
+        Ok(self.into_state())
+    }
+
+    /// Write a raw command byte to the HT16K33 chip.
+    ///
+    /// *An escape hatch for undocumented commands or clone chips, prefer the typed setters.* The driver does not
+    /// decode the command, so its oscillator, display and dimming state are not updated, and are written again
+    /// by the typed setters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// use ht16k33::Dimming;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    /// ht16k33.write_command((Dimming::COMMAND | Dimming::BRIGHTNESS_MIN).bits())?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn write_command(&mut self, raw: u8) -> Result<(), E> {
+        self.i2c.write(self.address, &[raw])
+    }
+
+    /// Write the `data` bytes to the RAM of the HT16K33 chip, starting at the raw address `offset`.
+    ///
+    /// *An escape hatch for undocumented registers or clone chips, prefer the display buffer and
+    /// [flush()](struct.HT16K33.html#method.flush).* The display buffer is not updated, and the next
+    /// [flush()](struct.HT16K33.html#method.flush) writes the whole display buffer again.
+    ///
+    /// # Errors
+    ///
+    /// If `data` is longer than [`ROWS_SIZE`](constant.ROWS_SIZE.html) bytes then
+    /// [`Error::Validation`](enum.Error.html#variant.Validation) is returned, and nothing is written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    /// ht16k33.write_ram(0x02, &[0xFF, 0x81])?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn write_ram(&mut self, offset: u8, data: &[u8]) -> Result<(), Error<E>> {
+        if data.len() > ROWS_SIZE {
+            return Err(Error::Validation(ValidationError::ValueTooLarge {
+                name: "data length",
+                value: data.len().min(u8::MAX as usize) as u8,
+                limit: ROWS_SIZE as u8,
+                inclusive: true,
+            }));
+        }
+
+        let mut write_buffer = [0u8; display_write_size(ROWS_SIZE)];
+        write_buffer[0] = offset;
+        write_buffer[1..=data.len()].copy_from_slice(data);
+
+        self.i2c
+            .write(self.address, &write_buffer[..=data.len()])
+            .map_err(Error::I2c)?;
+
+        // The chip no longer matches the values last written.
+        self.shadow_valid = false;
+
+        Ok(())
+    }
+
+    /// Put the HT16K33 chip in its test mode, returning a guard that restores normal operation when dropped.
+    ///
+    /// *For factory diagnostics only.*
+    ///
+    /// # Safety
+    ///
+    /// The test mode is reserved by Holtek, and its effect on the chip is not documented. The caller must
+    /// ensure that the hardware tolerates it, e.g. on a test fixture. See [`test_mode`](test_mode/index.html).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    ///
+    /// let test_mode = unsafe { ht16k33.enter_test_mode()? };
+    /// // ... run the diagnostics ...
+    /// test_mode.exit()?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub unsafe fn enter_test_mode(&mut self) -> Result<test_mode::TestMode<'_, I2C, E>, E> {
+        self.write_command(test_mode::TEST_MODE_COMMAND)?;
+
+        Ok(test_mode::TestMode::new(self))
+    }
+
+    /// Control the display dimming.
+    ///
+    /// # Arguments
+    ///
+    /// * `dimming` - Set the dimming brightness.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// use ht16k33::Dimming;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    /// ht16k33.set_dimming(Dimming::from_u8(4)?)?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_dimming(&mut self, dimming: Dimming) -> Result<(), E> {
+        self.dimming_state = dimming;
+
+        self.i2c.write(
+            self.address,
+            &[(Dimming::COMMAND | self.dimming_state).bits()],
+        )?;
+
+        Ok(())
+    }
+
+    /// Set the display dimming to the perceived brightness, in percent.
+    ///
+    /// The percentage is mapped through a CIE 1931 lightness curve to the closest dimming step, see
+    /// [Dimming::from_percent()](struct.Dimming.html#method.from_percent). Use
+    /// [set_dimming()](struct.HT16K33.html#method.set_dimming) for exact control of the duty cycle.
+    ///
+    /// # Errors
+    ///
+    /// If the `percent` value is larger than `100` then [`Error::Validation`](enum.Error.html#variant.Validation)
+    /// is returned, and nothing is written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    /// ht16k33.set_brightness_percent(50)?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_brightness_percent(&mut self, percent: u8) -> Result<(), Error<E>> {
+        let dimming = Dimming::from_percent(percent)?;
+
+        self.set_dimming(dimming).map_err(Error::I2c)
+    }
+
+    /// Control an LED.
+    ///
+    /// With [`WriteMode::Buffered`](enum.WriteMode.html#variant.Buffered), only the display buffer is updated,
+    /// see [set_write_mode()](struct.HT16K33.html#method.set_write_mode).
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The LED location to update.
+    /// * `enabled` - Set the LED on (true) or off (false).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// use ht16k33::LedLocation;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    ///
+    /// let led_location = LedLocation::new(0, 0)?;
+    /// ht16k33.set_led(led_location, true)?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_led(&mut self, location: LedLocation, enabled: bool) -> Result<(), E> {
+        self.update_display_buffer(location, enabled);
+
+        if self.write_mode == WriteMode::Buffered || !self.is_active(location) {
+            return Ok(());
+        }
+
+        let location = types::orient_location(location, self.rotation, self.flip, false);
+        let row = location.row_as_index();
+        if row >= self.package.rows() {
+            // Not wired on this package.
+            return Ok(());
+        }
+
+        let value = self.oriented_buffer()[row];
+        self.i2c
+            .write(self.address, &[location.row.bits(), value.bits()])?;
+        self.shadow_buffer[row] = value;
+
+        Ok(())
+    }
+
+    /// Set a 16-bit row of LEDs, and write the change to the HT16K33 chip.
+    ///
+    /// The chip keeps the 16 row outputs of each common in two consecutive bytes of its RAM, so the low byte of
+    /// `value` goes to the `address` row and the high byte to the next one. The `address` is the first byte of a
+    /// common, an even address from `ROW_0` for common 0 up to `ROW_14` for common 7. The value is set in the
+    /// display buffer, so the [rotation()](struct.HT16K33.html#method.rotation) applies.
+    ///
+    /// The change is written with [flush()](struct.HT16K33.html#method.flush), which also writes any other
+    /// pending change of the display buffer.
+    ///
+    /// # Errors
+    ///
+    /// If `address` is odd then [`ht16k33::ValidationError::Unaligned`] is returned, and nothing is changed.
+    ///
+    /// [`ht16k33::ValidationError::Unaligned`]: enum.ValidationError.html#variant.Unaligned
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// use ht16k33::DisplayDataAddress;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    ///
+    /// // All 16 rows of common 1.
+    /// ht16k33.write_row(DisplayDataAddress::ROW_2, 0xFFFF)?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn write_row(&mut self, address: DisplayDataAddress, value: u16) -> Result<(), Error<E>> {
+        self.write_rows(address, &[value])
+    }
+
+    /// Set consecutive 16-bit rows of LEDs, starting at the `start` address, and write the changes to the
+    /// HT16K33 chip.
+    ///
+    /// Each value covers the two bytes of a common in the chip RAM, low byte first, see
+    /// [write_row()](struct.HT16K33.html#method.write_row). As there, the changes are written with
+    /// [flush()](struct.HT16K33.html#method.flush), along with any other pending change of the display buffer.
+    ///
+    /// # Errors
+    ///
+    /// If `start` is odd then [`ht16k33::ValidationError::Unaligned`] is returned. If the `values` do not fit
+    /// between `start` and the end of the RAM then [`ht16k33::ValidationError::ValueTooLarge`] is returned. In
+    /// both cases nothing is changed.
+    ///
+    /// [`ht16k33::ValidationError::Unaligned`]: enum.ValidationError.html#variant.Unaligned
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: enum.ValidationError.html#variant.ValueTooLarge
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// use ht16k33::DisplayDataAddress;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    ///
+    /// // A checkerboard.
+    /// let rows = [0x5555, 0xAAAA, 0x5555, 0xAAAA, 0x5555, 0xAAAA, 0x5555, 0xAAAA];
+    /// ht16k33.write_rows(DisplayDataAddress::ROW_0, &rows)?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn write_rows(
+        &mut self,
+        start: DisplayDataAddress,
+        values: &[u16],
+    ) -> Result<(), Error<E>> {
+        let first = start.bits() as usize;
+        // `is_multiple_of()` would need Rust 1.87.
+        #[allow(clippy::manual_is_multiple_of)]
+        if first % 2 != 0 {
+            return Err(Error::Validation(ValidationError::Unaligned {
+                name: "row",
+                value: start.bits(),
+                alignment: 2,
+            }));
+        }
+        if values.len() > ROWS_SIZE / 2 {
+            return Err(Error::Validation(ValidationError::ValueTooLarge {
+                name: "rows length",
+                value: values.len().min(u8::MAX as usize) as u8,
+                limit: (ROWS_SIZE / 2) as u8,
+                inclusive: true,
+            }));
+        }
+        if first + values.len() * 2 > ROWS_SIZE {
+            return Err(Error::Validation(ValidationError::ValueTooLarge {
+                name: "row",
+                value: start.bits(),
+                limit: (ROWS_SIZE - values.len() * 2) as u8,
+                inclusive: true,
+            }));
+        }
+
+        for (index, value) in values.iter().enumerate() {
+            let row = first + index * 2;
+            self.buffer[row] = DisplayData::from_bits_truncate(*value as u8);
+            self.buffer[row + 1] = DisplayData::from_bits_truncate((*value >> 8) as u8);
+        }
+
+        self.flush().map_err(Error::I2c)
+    }
+
+    /// Write the display buffer to the HT16K33 chip.
+    ///
+    /// Only the range of rows that changed since the last write is written, see [flush()](struct.HT16K33.html#method.flush).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    /// ht16k33.write_display_buffer()?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn write_display_buffer(&mut self) -> Result<(), E> {
+        self.flush()
+    }
+
+    /// Write the range of display buffer rows that changed since the last write to the HT16K33 chip.
+    ///
+    /// The driver keeps a copy of the values last written to the chip, and writes the changed rows using
+    /// auto-increment writes grouped by the [`FlushStrategy`], by default the smallest contiguous range of rows
+    /// covering all changes in a single write. Nothing is written if there are no changes. The whole buffer is
+    /// written if the chip contents are unknown, e.g. before the first write.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// use ht16k33::LedLocation;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    ///
+    /// // Only row 3 is written.
+    /// ht16k33.update_display_buffer(LedLocation::new(3, 0)?, true);
+    /// ht16k33.flush()?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`FlushStrategy`]: enum.FlushStrategy.html
+    pub fn flush(&mut self) -> Result<(), E> {
+        self.mask_buffer();
+        if !self.shadow_valid {
+            return self.flush_full();
+        }
+
+        let buffer = self.oriented_buffer();
+        let rows = self.package.rows();
+
+        let shadow_buffer = self.shadow_buffer;
+        let mut changed =
+            diff::diff_frames(&shadow_buffer[..rows], &buffer).map(|(index, _)| index);
+
+        let first = match changed.next() {
+            Some(first) => first,
+            None => return Ok(()),
+        };
+
+        let max_gap = match self.flush_strategy {
+            FlushStrategy::Span => {
+                let last = changed.next_back().unwrap_or(first);
+                return self.write_buffer_rows(&buffer, first, last);
+            }
+            FlushStrategy::Coalesce { max_gap } => max_gap,
+        };
+
+        // Extend the run while the next change is close enough, otherwise write it and start another.
+        let (mut start, mut end) = (first, first);
+        for index in changed {
+            if index - end - 1 > max_gap {
+                self.write_buffer_rows(&buffer, start, end)?;
+                start = index;
+            }
+            end = index;
+        }
+
+        self.write_buffer_rows(&buffer, start, end)
+    }
+
+    /// [flush()](struct.HT16K33.html#method.flush) the display buffer, adding the time spent to the
+    /// [stats()](struct.HT16K33.html#method.stats).
+    ///
+    /// *Requires the `stats` feature.*
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// use std::time::Instant;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    ///
+    /// let start = Instant::now();
+    /// let clock = || start.elapsed().as_micros() as u32;
+    ///
+    /// ht16k33.flush_timed(&clock)?;
+    /// assert_eq!(1, ht16k33.stats().flushes);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "stats")]
+    pub fn flush_timed<C>(&mut self, clock: &C) -> Result<(), E>
+    where
+        C: stats::Clock,
+    {
+        let start = clock.now_us();
+        let result = self.flush();
+        self.stats.record_flush(clock.now_us().wrapping_sub(start));
+
+        result
+    }
+
+    /// Write the whole display buffer to the HT16K33 chip, regardless of changes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    /// ht16k33.flush_full()?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn flush_full(&mut self) -> Result<(), E> {
+        self.mask_buffer();
+        let buffer = self.oriented_buffer();
+        let rows = self.package.rows();
+
+        // Skip the rows without active LEDs at either end, they are never shown.
+        let mask = &self.active_mask.rows()[..rows];
+        let first = mask.iter().position(|row| !row.is_empty());
+        let last = mask.iter().rposition(|row| !row.is_empty());
+        if let (Some(first), Some(last)) = (first, last) {
+            self.write_buffer_rows(&buffer, first, last)?;
+        }
+        // The skipped rows are always off in the buffer, whatever the chip holds.
+        self.shadow_buffer[..rows].copy_from_slice(&buffer[..rows]);
+        self.shadow_valid = true;
+
+        Ok(())
+    }
+
+    // Write the inclusive range of rows of the oriented `buffer` in a single auto-increment write.
+    fn write_buffer_rows(
+        &mut self,
+        buffer: &[DisplayData; ROWS_SIZE],
+        first: usize,
+        last: usize,
+    ) -> Result<(), E> {
+        let mut write_buffer = [0u8; display_write_size(ROWS_SIZE)];
+        write_buffer[0] = DisplayDataAddress::ROW_0.bits() | first as u8;
+
+        let rows = &buffer[first..=last];
+        for (value, row) in write_buffer[1..].iter_mut().zip(rows.iter()) {
+            *value = row.bits();
+        }
+
+        self.i2c.write(self.address, &write_buffer[..=rows.len()])?;
+
+        #[cfg(feature = "stats")]
+        self.stats.record_transfer(rows.len() + 1);
+
+        self.shadow_buffer[first..=last].copy_from_slice(rows);
+
+        Ok(())
+    }
+}
+
+// Reading the display and key RAM needs a bus with repeated-start reads, so these methods only exist when the
+// I2C device implements `WriteRead`, the rest of the driver also works on write-only buses.
+impl<I2C, E> HT16K33<I2C, Initialized>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    /// Read the display buffer from the HT16K33 chip.
+    ///
+    /// *Requires an I2C device implementing `WriteRead`.*
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    /// ht16k33.read_display_buffer()?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn read_display_buffer(&mut self) -> Result<(), E> {
+        let mut read_buffer = [0u8; ROWS_SIZE];
+
+        self.i2c.write_read(
+            self.address,
+            &[DisplayDataAddress::ROW_0.bits()],
+            &mut read_buffer,
+        )?;
+
+        for (index, value) in read_buffer.iter().enumerate() {
+            self.shadow_buffer[index] = DisplayData::from_bits_truncate(*value);
+        }
+
+        // The buffer now matches the device, apart from the LEDs outside the active mask.
+        self.buffer = types::orient(&self.shadow_buffer, self.rotation, self.flip, true);
+        self.mask_buffer();
+        self.shadow_valid = true;
+
+        Ok(())
+    }
+
+    /// Read the key RAM from the HT16K33 chip.
+    ///
+    /// Each pair of bytes is a key scan line, `KS0` to `KS2`, with keys `K1` to `K13` in the low 13 bits, see
+    /// [`keypad`](keypad/index.html) for press and release events.
+    ///
+    /// *Requires an I2C device implementing `WriteRead`.*
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    /// let keys = ht16k33.read_keys()?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn read_keys(&mut self) -> Result<[u8; KEYS_SIZE], E> {
+        let mut keys = [0u8; KEYS_SIZE];
+
+        self.i2c
+            .write_read(self.address, &[constants::KEY_DATA_ADDRESS], &mut keys)?;
+
+        Ok(keys)
+    }
+
+    /// Read the INT flag from the HT16K33 chip, set when the key scan found a pressed key, and cleared by
+    /// reading the key RAM with [read_keys()](struct.HT16K33.html#method.read_keys).
+    ///
+    /// *Requires an I2C device implementing `WriteRead`.*
+    pub fn read_int_flag(&mut self) -> Result<bool, E> {
+        let mut flag = [0u8; 1];
+
+        self.i2c
+            .write_read(self.address, &[constants::INT_FLAG_ADDRESS], &mut flag)?;
+
+        Ok(flag[0] != 0)
+    }
+
+    /// Read `buffer.len()` bytes of RAM from the HT16K33 chip, starting at the raw address `offset`.
+    ///
+    /// *An escape hatch for undocumented registers or clone chips, prefer
+    /// [read_display_buffer()](struct.HT16K33.html#method.read_display_buffer) and
+    /// [read_keys()](struct.HT16K33.html#method.read_keys).*
+    ///
+    /// *Requires an I2C device implementing `WriteRead`.*
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// # use ht16k33::HT16K33;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+    ///
+    /// let mut key_ram = [0u8; 6];
+    /// ht16k33.read_ram(0x40, &mut key_ram)?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn read_ram(&mut self, offset: u8, buffer: &mut [u8]) -> Result<(), E> {
+        self.i2c.write_read(self.address, &[offset], buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use embedded_hal_mock as hal;
+
+    use self::hal::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+    use super::*;
+    use crate::i2c_mock::I2cMock as ChipMock;
+
+    use std::vec;
+
+    const ADDRESS: u8 = 0x70;
+
+    // Skip the initialization writes, for testing the methods of an initialized driver.
+    fn initialized(i2c: I2cMock) -> HT16K33<I2cMock> {
+        HT16K33::new(i2c, Address::new(ADDRESS)).into_state()
+    }
+
+    // Initialize a driver of the emulated `chip`, for testing the effects on the chip state.
+    fn chip_driver(chip: &mut ChipMock) -> HT16K33<&mut ChipMock> {
+        HT16K33::new(chip, Address::new(ADDRESS))
+            .initialize()
+            .unwrap()
+    }
+
+    #[test]
+    fn new() {
+        let expectations = [];
+
+        let mut i2c = I2cMock::new(&expectations);
+        let ht16k33 = HT16K33::new(i2c, Address::new(ADDRESS));
+
+        i2c = ht16k33.destroy();
+        i2c.done();
+    }
+
+    #[test]
+    fn new_const() {
+        static PARKED: HT16K33<(), Uninitialized> = HT16K33::new_const(Address::DEFAULT);
+
+        assert_eq!(ADDRESS, PARKED.address);
+        assert_eq!(&Dimming::BRIGHTNESS_MAX, PARKED.dimming());
+        assert_eq!(&[DisplayData::empty(); ROWS_SIZE], PARKED.display_buffer());
+    }
+
+    #[test]
+    fn send() {
+        // RTIC shared resources must be `Send`.
+        fn assert_send<T: Send>() {}
+
+        assert_send::<HT16K33<ChipMock>>();
+        assert_send::<HT16K33<(), Standby>>();
+    }
+
+    #[test]
+    fn write_only_bus() {
+        // A bus without reads, counting the writes.
+        struct WriteOnly(usize);
+
+        impl Write for WriteOnly {
+            type Error = ();
+
+            fn write(&mut self, _address: u8, _bytes: &[u8]) -> Result<(), ()> {
+                self.0 += 1;
+                Ok(())
+            }
+        }
+
+        let mut ht16k33 = HT16K33::new(WriteOnly(0), Address::DEFAULT)
+            .initialize()
+            .unwrap();
+        ht16k33
+            .set_led(LedLocation::new(0, 0).unwrap(), true)
+            .unwrap();
+        ht16k33.flush().unwrap();
+
+        assert_eq!(5, ht16k33.destroy().0);
+    }
+
+    #[test]
+    fn initialize() {
+        let mut write_buffer = vec![super::DisplayDataAddress::ROW_0.bits()];
+        write_buffer.extend([0; super::ROWS_SIZE].iter().cloned());
+
+        let expectations = [
+            I2cTransaction::write(
+                ADDRESS,
+                vec![(super::Display::COMMAND | super::Display::OFF).bits()],
+            ),
+            I2cTransaction::write(ADDRESS, write_buffer),
+            I2cTransaction::write(
+                ADDRESS,
+                vec![(super::Dimming::COMMAND | Dimming::BRIGHTNESS_MAX).bits()],
+            ),
+            I2cTransaction::write(
+                ADDRESS,
+                vec![(super::Oscillator::COMMAND | super::Oscillator::ON).bits()],
+            ),
+        ];
+
+        let mut i2c = I2cMock::new(&expectations);
+        let ht16k33 = HT16K33::new(i2c, Address::new(ADDRESS))
+            .initialize()
+            .unwrap();
+
+        i2c = ht16k33.destroy();
+        i2c.done();
+    }
+
+    #[test]
+    fn initialize_interrupted() {
+        for successes in 0..4 {
+            let mut i2c = ChipMock::new();
+            i2c.device_mut(Address::DEFAULT).data_values = [0xff; ROWS_SIZE];
+            i2c.fail_write_after(successes, i2c_mock::ErrorKind::Nack);
+
+            assert!(HT16K33::new(&mut i2c, Address::new(ADDRESS))
+                .initialize()
+                .is_err());
+
+            // The display is never turned on, and is cleared before the oscillator is enabled.
+            assert_eq!(Display::OFF, i2c.display());
+            if i2c.oscillator() == Oscillator::ON {
+                assert_eq!([0; ROWS_SIZE], i2c.device(Address::DEFAULT).data_values);
+            }
+        }
+    }
+
+    #[test]
+    fn display_buffer() {
+        let expectations = [];
+
+        let mut i2c = I2cMock::new(&expectations);
+        let ht16k33 = HT16K33::new(i2c, Address::new(ADDRESS));
+
+        let &buffer = ht16k33.display_buffer();
+
+        // Ensure it's the expected size.
+        assert_eq!(buffer.len(), ROWS_SIZE);
+
+        for row in buffer.iter() {
+            // And because we just initialized this buffer, it should be all zeros.
+            assert_eq!(row.bits(), 0u8);
+        }
+
+        i2c = ht16k33.destroy();
+        i2c.done();
+    }
+
+    #[test]
+    fn oscillator() {
+        let expectations = [];
+
+        let mut i2c = I2cMock::new(&expectations);
+        let ht16k33 = HT16K33::new(i2c, Address::new(ADDRESS));
+
+        let &oscillator = ht16k33.oscillator();
+
+        assert_eq!(oscillator, Oscillator::OFF);
+
+        i2c = ht16k33.destroy();
+        i2c.done();
+    }
+
+    #[test]
+    fn display() {
+        let expectations = [];
+
+        let mut i2c = I2cMock::new(&expectations);
+        let ht16k33 = HT16K33::new(i2c, Address::new(ADDRESS));
+
+        let &display = ht16k33.display();
+
+        assert_eq!(display, Display::OFF);
+
+        i2c = ht16k33.destroy();
+        i2c.done();
+    }
+
+    #[test]
+    fn dimming() {
+        let expectations = [];
+
+        let mut i2c = I2cMock::new(&expectations);
+        let ht16k33 = HT16K33::new(i2c, Address::new(ADDRESS));
+
+        let &dimming = ht16k33.dimming();
+
+        assert_eq!(dimming, Dimming::BRIGHTNESS_MAX);
+
+        i2c = ht16k33.destroy();
+        i2c.done();
+    }
+
+    #[test]
+    fn update_display_buffer() {
+        let expectations = [];
+
+        let mut i2c = I2cMock::new(&expectations);
+        let mut ht16k33 = HT16K33::new(i2c, Address::new(ADDRESS));
+
+        let first_led = LedLocation::new(1, 4).unwrap();
+        let second_led = LedLocation::new(1, 5).unwrap();
+
+        // Turn on the LED.
+        ht16k33.update_display_buffer(first_led, true);
+        assert_eq!(ht16k33.display_buffer()[1].bits(), 0b0001_0000);
+
+        // Turn on another LED.
+        ht16k33.update_display_buffer(second_led, true);
+        assert_eq!(ht16k33.display_buffer()[1].bits(), 0b0011_0000);
+
+        // Turn off the first LED.
+        ht16k33.update_display_buffer(first_led, false);
+        assert_eq!(ht16k33.display_buffer()[1].bits(), 0b0010_0000);
+
+        i2c = ht16k33.destroy();
+        i2c.done();
+    }
+
+    #[test]
+    fn clear_display_buffer() {
+        let expectations = [];
+
+        let mut i2c = I2cMock::new(&expectations);
+        let mut ht16k33 = HT16K33::new(i2c, Address::new(ADDRESS));
+
+        let first_led = LedLocation::new(1, 4).unwrap();
+        let second_led = LedLocation::new(1, 5).unwrap();
+
+        // Turn on the LEDs.
+        ht16k33.update_display_buffer(first_led, true);
+        ht16k33.update_display_buffer(second_led, true);
+
+        // Clear the display buffer.
+        ht16k33.clear_display_buffer();
+
+        let &buffer = ht16k33.display_buffer();
+
+        // Ensure it's still the expected size.
+        assert_eq!(buffer.len(), ROWS_SIZE);
+
+        for row in buffer.iter() {
+            // We just cleared this buffer, it should be all zeros.
+            assert_eq!(row.bits(), 0u8);
+        }
+
+        i2c = ht16k33.destroy();
+        i2c.done();
+    }
+
+    #[test]
+    fn set_oscillator() {
+        let expectations = [I2cTransaction::write(
+            ADDRESS,
+            vec![(super::Oscillator::COMMAND | super::Oscillator::OFF).bits()],
+        )];
+
+        let mut i2c = I2cMock::new(&expectations);
+        let mut ht16k33 = initialized(i2c);
+
+        ht16k33.set_oscillator(super::Oscillator::OFF).unwrap();
+
+        i2c = ht16k33.destroy();
+        i2c.done();
+    }
+
+    #[test]
+    fn set_display() {
+        let expectations = [I2cTransaction::write(
+            ADDRESS,
+            vec![(super::Display::COMMAND | super::Display::OFF).bits()],
+        )];
+
+        let mut i2c = I2cMock::new(&expectations);
+        let mut ht16k33 = initialized(i2c);
+
+        ht16k33.set_display(super::Display::OFF).unwrap();
+
+        i2c = ht16k33.destroy();
+        i2c.done();
+    }
+
+    #[test]
+    fn set_display_on_flushes_first() {
+        let mut write_buffer = vec![super::DisplayDataAddress::ROW_0.bits()];
+        write_buffer.extend([0; super::ROWS_SIZE].iter().cloned());
+        write_buffer[1] = 0b0000_0001;
+
+        let expectations = [
+            I2cTransaction::write(ADDRESS, write_buffer),
+            I2cTransaction::write(
+                ADDRESS,
+                vec![(super::Display::COMMAND | super::Display::ON).bits()],
+            ),
+        ];
+
+        let mut i2c = I2cMock::new(&expectations);
+        let mut ht16k33 = initialized(i2c);
+
+        ht16k33.update_display_buffer(LedLocation::new(0, 0).unwrap(), true);
+        ht16k33.set_display(super::Display::ON).unwrap();
+
+        i2c = ht16k33.destroy();
+        i2c.done();
+    }
+
+    #[test]
+    fn set_display_on_interrupted() {
+        // Fail the display command, after the initialization and the data write.
+        let mut i2c = ChipMock::new();
+        i2c.fail_write_after(5, i2c_mock::ErrorKind::Nack);
+
+        let mut ht16k33 = chip_driver(&mut i2c);
+        ht16k33.update_display_buffer(LedLocation::new(0, 0).unwrap(), true);
+        assert!(ht16k33.set_display(Display::ON).is_err());
+
+        // The display stays off.
+        assert_eq!(Display::OFF, i2c.display());
+        assert_eq!(0b0000_0001, i2c.device(Address::DEFAULT).data_values[0]);
+    }
+
+    #[test]
+    fn set_dimming() {
+        let expectations = [I2cTransaction::write(
+            ADDRESS,
+            vec![(super::Dimming::COMMAND | Dimming::BRIGHTNESS_MAX).bits()],
+        )];
+
+        let mut i2c = I2cMock::new(&expectations);
+        let mut ht16k33 = initialized(i2c);
+
+        ht16k33.set_dimming(Dimming::BRIGHTNESS_MAX).unwrap();
+
+        i2c = ht16k33.destroy();
+        i2c.done();
+    }
+
+    #[test]
+    fn set_brightness_percent() {
+        let expectations = [I2cTransaction::write(
+            ADDRESS,
+            vec![(super::Dimming::COMMAND | super::Dimming::BRIGHTNESS_3_16).bits()],
+        )];
+
+        let mut i2c = I2cMock::new(&expectations);
+        let mut ht16k33 = initialized(i2c);
+
+        ht16k33.set_brightness_percent(50).unwrap();
+        assert_eq!(&Dimming::BRIGHTNESS_3_16, ht16k33.dimming());
+
+        let error = ht16k33.set_brightness_percent(101).unwrap_err();
+        assert_eq!(ErrorKind::Dimming, error.kind());
+
+        i2c = ht16k33.destroy();
+        i2c.done();
+    }
+
+    #[test]
+    fn write_ram() {
+        let mut write_buffer = vec![super::DisplayDataAddress::ROW_0.bits()];
+        write_buffer.extend([0; super::ROWS_SIZE].iter().cloned());
+
+        let expectations = [
+            I2cTransaction::write(ADDRESS, vec![0x02, 0xFF, 0x81]),
+            I2cTransaction::write(ADDRESS, write_buffer),
+        ];
+
+        let mut i2c = I2cMock::new(&expectations);
+        let mut ht16k33 = initialized(i2c);
+        ht16k33.shadow_valid = true;
+
+        ht16k33.write_ram(0x02, &[0xFF, 0x81]).unwrap();
+        assert!(ht16k33.write_ram(0x00, &[0; 17]).is_err());
+
+        // The whole buffer is written again.
+        ht16k33.flush().unwrap();
+
+        i2c = ht16k33.destroy();
+        i2c.done();
+    }
+
+    #[test]
+    fn set_led() {
+        let expectations = [I2cTransaction::write(ADDRESS, vec![1u8, 0b1000_0000])];
+
+        let mut i2c = I2cMock::new(&expectations);
+        let mut ht16k33 = initialized(i2c);
+
+        ht16k33
+            .set_led(LedLocation::new(1, 7).unwrap(), true)
+            .unwrap();
+
+        i2c = ht16k33.destroy();
+        i2c.done();
+    }
+
+    #[test]
+    fn set_led_buffered() {
+        let expectations = [];
+
+        let mut i2c = I2cMock::new(&expectations);
+        let mut ht16k33 = initialized(i2c);
+        ht16k33.set_write_mode(WriteMode::Buffered);
+
+        ht16k33
+            .set_led(LedLocation::new(1, 7).unwrap(), true)
+            .unwrap();
+
+        assert_eq!(0b1000_0000, ht16k33.display_buffer()[1].bits());
+
+        i2c = ht16k33.destroy();
+        i2c.done();
+    }
+
+    #[test]
+    fn write_i2c_frame() {
+        let expectations = [I2cTransaction::write(ADDRESS, vec![0u8, 0b0000_0010])];
+
+        let mut i2c = I2cMock::new(&expectations);
+        let mut ht16k33 = initialized(i2c);
+
+        ht16k33.update_display_buffer(LedLocation::new(15, 7).unwrap(), true);
+        let mut frame = [0xFF; I2C_FRAME_SIZE];
+        ht16k33.write_i2c_frame(&mut frame);
+        assert_eq!(0x00, frame[0]);
+        assert_eq!(0b1000_0000, frame[16]);
+        ht16k33.mark_i2c_frame_written(&frame);
+
+        // Only the later change is written.
+        ht16k33.update_display_buffer(LedLocation::new(0, 1).unwrap(), true);
+        ht16k33.flush().unwrap();
+
+        i2c = ht16k33.destroy();
+        i2c.done();
+    }
 
     #[test]
-    fn new() {
-        let expectations = [];
+    fn write_display_buffer() {
+        let mut write_buffer = vec![super::DisplayDataAddress::ROW_0.bits()];
+        write_buffer.extend([0; super::ROWS_SIZE].iter().cloned());
+
+        let expectations = [I2cTransaction::write(ADDRESS, write_buffer)];
 
         let mut i2c = I2cMock::new(&expectations);
-        let ht16k33 = HT16K33::new(i2c, ADDRESS);
+        let mut ht16k33 = initialized(i2c);
+
+        ht16k33.write_display_buffer().unwrap();
 
         i2c = ht16k33.destroy();
         i2c.done();
     }
 
     #[test]
-    fn initialize() {
+    fn flush_changed_rows() {
         let mut write_buffer = vec![super::DisplayDataAddress::ROW_0.bits()];
         write_buffer.extend([0; super::ROWS_SIZE].iter().cloned());
 
         let expectations = [
+            I2cTransaction::write(ADDRESS, write_buffer),
+            // Rows 2 through 5 are written, including the unchanged rows between them.
             I2cTransaction::write(
                 ADDRESS,
-                vec![(super::Oscillator::COMMAND | super::Oscillator::ON).bits()],
-            ),
-            I2cTransaction::write(
-                ADDRESS,
-                vec![(super::Display::COMMAND | super::Display::OFF).bits()],
-            ),
-            I2cTransaction::write(
-                ADDRESS,
-                vec![(super::Dimming::COMMAND | Dimming::BRIGHTNESS_MAX).bits()],
+                vec![
+                    super::DisplayDataAddress::ROW_2.bits(),
+                    0b0000_0001,
+                    0,
+                    0,
+                    0b1000_0000,
+                ],
             ),
-            I2cTransaction::write(ADDRESS, write_buffer),
         ];
 
         let mut i2c = I2cMock::new(&expectations);
-        let mut ht16k33 = HT16K33::new(i2c, ADDRESS);
+        let mut ht16k33 = initialized(i2c);
+
+        ht16k33.flush().unwrap();
+
+        ht16k33.update_display_buffer(LedLocation::new(2, 0).unwrap(), true);
+        ht16k33.update_display_buffer(LedLocation::new(5, 7).unwrap(), true);
+        ht16k33.flush().unwrap();
 
-        ht16k33.initialize().unwrap();
+        // Nothing changed, nothing is written.
+        ht16k33.flush().unwrap();
 
         i2c = ht16k33.destroy();
         i2c.done();
     }
 
     #[test]
-    fn display_buffer() {
-        let expectations = [];
+    fn flush_coalesce() {
+        let mut write_buffer = vec![super::DisplayDataAddress::ROW_0.bits()];
+        write_buffer.extend([0; super::ROWS_SIZE].iter().cloned());
 
-        let mut i2c = I2cMock::new(&expectations);
-        let ht16k33 = HT16K33::new(i2c, ADDRESS);
+        let expectations = [
+            I2cTransaction::write(ADDRESS, write_buffer),
+            // Rows 1 and 4 are close enough to merge, row 10 is written separately.
+            I2cTransaction::write(
+                ADDRESS,
+                vec![
+                    super::DisplayDataAddress::ROW_1.bits(),
+                    0b0000_0001,
+                    0,
+                    0,
+                    0b0000_0010,
+                ],
+            ),
+            I2cTransaction::write(ADDRESS, vec![10u8, 0b0000_0100]),
+        ];
 
-        let &buffer = ht16k33.display_buffer();
+        let mut i2c = I2cMock::new(&expectations);
+        let mut ht16k33 = initialized(i2c);
+        ht16k33.set_flush_strategy(FlushStrategy::COALESCE);
 
-        // Ensure it's the expected size.
-        assert_eq!(buffer.len(), ROWS_SIZE);
+        ht16k33.flush().unwrap();
 
-        for row in buffer.iter() {
-            // And because we just initialized this buffer, it should be all zeros.
-            assert_eq!(row.bits(), 0u8);
-        }
+        ht16k33.update_display_buffer(LedLocation::new(1, 0).unwrap(), true);
+        ht16k33.update_display_buffer(LedLocation::new(4, 1).unwrap(), true);
+        ht16k33.update_display_buffer(LedLocation::new(10, 2).unwrap(), true);
+        ht16k33.flush().unwrap();
 
         i2c = ht16k33.destroy();
         i2c.done();
     }
 
     #[test]
-    fn oscillator() {
-        let expectations = [];
+    fn flush_rotated() {
+        let mut write_buffer = vec![super::DisplayDataAddress::ROW_0.bits()];
+        write_buffer.extend([0; super::ROWS_SIZE].iter().cloned());
+
+        let expectations = [
+            I2cTransaction::write(ADDRESS, write_buffer),
+            // The top-left pixel, row 0 common 7, is rotated to the top-right, row 0 common 6.
+            I2cTransaction::write(
+                ADDRESS,
+                vec![super::DisplayDataAddress::ROW_0.bits(), 0b0100_0000],
+            ),
+            // Rotating back moves it again.
+            I2cTransaction::write(
+                ADDRESS,
+                vec![super::DisplayDataAddress::ROW_0.bits(), 0b1000_0000],
+            ),
+        ];
 
         let mut i2c = I2cMock::new(&expectations);
-        let ht16k33 = HT16K33::new(i2c, ADDRESS);
+        let mut ht16k33 = initialized(i2c);
 
-        let &oscillator = ht16k33.oscillator();
+        ht16k33.flush().unwrap();
 
-        assert_eq!(oscillator, Oscillator::OFF);
+        ht16k33.set_rotation(Rotation::Deg90);
+        ht16k33.update_display_buffer(LedLocation::new(0, 7).unwrap(), true);
+        ht16k33.flush().unwrap();
+
+        ht16k33.set_rotation(Rotation::Deg0);
+        ht16k33.flush().unwrap();
 
         i2c = ht16k33.destroy();
         i2c.done();
     }
 
     #[test]
-    fn display() {
-        let expectations = [];
-
-        let mut i2c = I2cMock::new(&expectations);
-        let ht16k33 = HT16K33::new(i2c, ADDRESS);
+    fn dump() {
+        let mut i2c = I2cMock::new(&[]);
+        let mut ht16k33 = initialized(i2c);
+        ht16k33.update_display_buffer(LedLocation::new(1, 0).unwrap(), true);
+
+        let mut dump = std::string::String::new();
+        ht16k33.dump(&mut dump).unwrap();
+
+        let lines: vec::Vec<&str> = dump.lines().collect();
+        assert_eq!(10 + cfg!(feature = "stats") as usize, lines.len());
+        assert_eq!(
+            lines[..10],
+            [
+                "address: Address(0x70)",
+                "oscillator: Oscillator::OFF",
+                "display: Display::OFF",
+                "dimming: Dimming::BRIGHTNESS_MAX",
+                "flush strategy: Span",
+                "write mode: WriteThrough",
+                "rotation: Deg0, flip: (empty)",
+                "package: Sop28",
+                "buffer: Frame(00 01 00 00 00 00 00 00 00 00 00 00 00 00 00 00)",
+                "written: unknown",
+            ]
+        );
 
-        let &display = ht16k33.display();
+        i2c = ht16k33.destroy();
+        i2c.done();
+    }
 
-        assert_eq!(display, Display::OFF);
+    #[test]
+    fn set_pixels() {
+        let mut i2c = I2cMock::new(&[]);
+        let mut ht16k33 = initialized(i2c);
+
+        ht16k33.fill();
+        ht16k33.clear_row(DisplayDataAddress::ROW_3);
+        ht16k33.set_pixels(vec![
+            (LedLocation::new(0, 1).unwrap(), false),
+            (LedLocation::new(3, 7).unwrap(), true),
+        ]);
+
+        assert_eq!(0b1111_1101, ht16k33.display_buffer()[0].bits());
+        assert_eq!(0b1000_0000, ht16k33.display_buffer()[3].bits());
+
+        let disabled: vec::Vec<_> = ht16k33
+            .iter_leds()
+            .filter(|&(_, enabled)| !enabled)
+            .map(|(location, _)| location)
+            .collect();
+        assert_eq!(8, disabled.len());
+        assert_eq!(LedLocation::new(0, 1).unwrap(), disabled[0]);
+        assert_eq!(LedLocation::new(3, 6).unwrap(), disabled[7]);
 
         i2c = ht16k33.destroy();
         i2c.done();
     }
 
     #[test]
-    fn dimming() {
-        let expectations = [];
+    #[cfg(feature = "stats")]
+    fn flush_timed() {
+        let mut write_buffer = vec![super::DisplayDataAddress::ROW_0.bits()];
+        write_buffer.extend([0; super::ROWS_SIZE].iter().cloned());
+
+        let expectations = [
+            I2cTransaction::write(ADDRESS, write_buffer),
+            I2cTransaction::write(ADDRESS, vec![2u8, 0b0000_0001]),
+        ];
 
         let mut i2c = I2cMock::new(&expectations);
-        let ht16k33 = HT16K33::new(i2c, ADDRESS);
+        let mut ht16k33 = initialized(i2c);
 
-        let &dimming = ht16k33.dimming();
+        // Each reading advances the clock by 50us.
+        let now = core::cell::Cell::new(0);
+        let clock = || {
+            now.set(now.get() + 50);
+            now.get()
+        };
 
-        assert_eq!(dimming, Dimming::BRIGHTNESS_MAX);
+        ht16k33.flush_timed(&clock).unwrap();
+        ht16k33.update_display_buffer(LedLocation::new(2, 0).unwrap(), true);
+        ht16k33.flush_timed(&clock).unwrap();
+
+        let stats = ht16k33.stats();
+        assert_eq!(2, stats.transfers);
+        assert_eq!(super::ROWS_SIZE as u32 + 1 + 2, stats.bytes);
+        assert_eq!(2, stats.flushes);
+        assert_eq!(100, stats.total_us);
+
+        ht16k33.reset_stats();
+        assert_eq!(0, ht16k33.stats().transfers);
 
         i2c = ht16k33.destroy();
         i2c.done();
     }
 
     #[test]
-    fn update_display_buffer() {
-        let expectations = [];
+    #[cfg(feature = "stats")]
+    fn dump_stats() {
+        let mut write_buffer = vec![super::DisplayDataAddress::ROW_0.bits()];
+        write_buffer.extend([0; super::ROWS_SIZE].iter().cloned());
 
-        let mut i2c = I2cMock::new(&expectations);
-        let mut ht16k33 = HT16K33::new(i2c, ADDRESS);
+        let expectations = [I2cTransaction::write(ADDRESS, write_buffer)];
 
-        let first_led = LedLocation::new(1, 4).unwrap();
-        let second_led = LedLocation::new(1, 5).unwrap();
+        let mut i2c = I2cMock::new(&expectations);
+        let mut ht16k33 = initialized(i2c);
 
-        // Turn on the LED.
-        ht16k33.update_display_buffer(first_led, true);
-        assert_eq!(ht16k33.display_buffer()[1].bits(), 0b0001_0000);
+        let clock = || 0;
+        ht16k33.flush_timed(&clock).unwrap();
 
-        // Turn on another LED.
-        ht16k33.update_display_buffer(second_led, true);
-        assert_eq!(ht16k33.display_buffer()[1].bits(), 0b0011_0000);
+        let mut dump = std::string::String::new();
+        ht16k33.dump(&mut dump).unwrap();
 
-        // Turn off the first LED.
-        ht16k33.update_display_buffer(first_led, false);
-        assert_eq!(ht16k33.display_buffer()[1].bits(), 0b0010_0000);
+        assert_eq!(
+            Some("stats: 1 transfers, 17 bytes, 1 flushes, 0us total, 0us last, 0us max"),
+            dump.lines().last()
+        );
 
         i2c = ht16k33.destroy();
         i2c.done();
     }
 
     #[test]
-    fn clear_display_buffer() {
-        let expectations = [];
+    fn flush_after_set_led() {
+        let mut write_buffer = vec![super::DisplayDataAddress::ROW_0.bits()];
+        write_buffer.extend([0; super::ROWS_SIZE].iter().cloned());
+
+        let expectations = [
+            I2cTransaction::write(ADDRESS, write_buffer),
+            I2cTransaction::write(ADDRESS, vec![1u8, 0b1000_0000]),
+        ];
 
         let mut i2c = I2cMock::new(&expectations);
-        let mut ht16k33 = HT16K33::new(i2c, ADDRESS);
+        let mut ht16k33 = initialized(i2c);
 
-        let first_led = LedLocation::new(1, 4).unwrap();
-        let second_led = LedLocation::new(1, 5).unwrap();
+        ht16k33.flush().unwrap();
+        ht16k33
+            .set_led(LedLocation::new(1, 7).unwrap(), true)
+            .unwrap();
 
-        // Turn on the LEDs.
-        ht16k33.update_display_buffer(first_led, true);
-        ht16k33.update_display_buffer(second_led, true);
+        // The LED was already written.
+        assert!(!ht16k33.is_dirty());
+        ht16k33.flush().unwrap();
 
-        // Clear the display buffer.
-        ht16k33.clear_display_buffer();
+        i2c = ht16k33.destroy();
+        i2c.done();
+    }
 
-        let &buffer = ht16k33.display_buffer();
+    #[test]
+    fn write_rows() {
+        let mut write_buffer = vec![super::DisplayDataAddress::ROW_0.bits()];
+        write_buffer.extend([0; super::ROWS_SIZE].iter().cloned());
 
-        // Ensure it's still the expected size.
-        assert_eq!(buffer.len(), ROWS_SIZE);
+        let expectations = [
+            I2cTransaction::write(ADDRESS, write_buffer),
+            I2cTransaction::write(
+                ADDRESS,
+                vec![super::DisplayDataAddress::ROW_2.bits(), 0x34, 0x12],
+            ),
+            // Only the low byte of the first value changed.
+            I2cTransaction::write(ADDRESS, vec![super::DisplayDataAddress::ROW_0.bits(), 0xFF]),
+        ];
 
-        for row in buffer.iter() {
-            // We just cleared this buffer, it should be all zeros.
-            assert_eq!(row.bits(), 0u8);
-        }
+        let mut i2c = I2cMock::new(&expectations);
+        let mut ht16k33 = initialized(i2c);
+        ht16k33.flush().unwrap();
+
+        ht16k33
+            .write_row(super::DisplayDataAddress::ROW_2, 0x1234)
+            .unwrap();
+        ht16k33
+            .write_rows(super::DisplayDataAddress::ROW_0, &[0x00FF, 0x1234])
+            .unwrap();
+
+        let error = ht16k33
+            .write_row(super::DisplayDataAddress::ROW_1, 0x0001)
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Validation(ValidationError::Unaligned { value: 1, .. })
+        ));
+        assert!(ht16k33
+            .write_rows(super::DisplayDataAddress::ROW_2, &[0; 8])
+            .is_err());
+        assert_eq!(0x12, ht16k33.display_buffer()[3].bits());
 
         i2c = ht16k33.destroy();
         i2c.done();
     }
 
     #[test]
-    fn set_oscillator() {
-        let expectations = [I2cTransaction::write(
-            ADDRESS,
-            vec![(super::Oscillator::COMMAND | super::Oscillator::OFF).bits()],
-        )];
+    fn active_mask() {
+        let expectations = [
+            // Only the rows with active LEDs are written.
+            I2cTransaction::write(
+                ADDRESS,
+                vec![super::DisplayDataAddress::ROW_1.bits(), 0, 0b0000_0001],
+            ),
+            I2cTransaction::write(ADDRESS, vec![2, 0b0000_0011]),
+        ];
 
         let mut i2c = I2cMock::new(&expectations);
-        let mut ht16k33 = HT16K33::new(i2c, ADDRESS);
+        let mut ht16k33 = initialized(i2c);
 
-        ht16k33.set_oscillator(super::Oscillator::OFF).unwrap();
+        let mut mask = [DisplayData::empty(); ROWS_SIZE];
+        mask[1] = DisplayData::all();
+        mask[2] = DisplayData::COMMON_0 | DisplayData::COMMON_1;
+        ht16k33.set_active_mask(Frame::from_rows(mask));
+
+        ht16k33.update_display_buffer(LedLocation::new(2, 0).unwrap(), true);
+        ht16k33.update_display_buffer(LedLocation::new(2, 7).unwrap(), true);
+        ht16k33.flush_full().unwrap();
+        assert!(!ht16k33.is_dirty());
+
+        // Masked LEDs are dropped without a write.
+        ht16k33
+            .set_led(LedLocation::new(9, 0).unwrap(), true)
+            .unwrap();
+        ht16k33
+            .set_led(LedLocation::new(2, 1).unwrap(), true)
+            .unwrap();
 
         i2c = ht16k33.destroy();
         i2c.done();
     }
 
     #[test]
-    fn set_display() {
-        let expectations = [I2cTransaction::write(
-            ADDRESS,
-            vec![(super::Display::COMMAND | super::Display::OFF).bits()],
-        )];
+    fn active_mask_buffer() {
+        let expectations = [];
 
         let mut i2c = I2cMock::new(&expectations);
-        let mut ht16k33 = HT16K33::new(i2c, ADDRESS);
+        let mut ht16k33 = initialized(i2c);
 
-        ht16k33.set_display(super::Display::OFF).unwrap();
+        let mut mask = [DisplayData::empty(); ROWS_SIZE];
+        mask[1] = DisplayData::COMMON_0;
+        ht16k33.set_active_mask(Frame::from_rows(mask));
+
+        let mut expected = [DisplayData::empty(); ROWS_SIZE];
+        expected[1] = DisplayData::COMMON_0;
+
+        ht16k33.fill();
+        assert_eq!(&expected, ht16k33.display_buffer());
+
+        ht16k33.set_frame(&Frame::from_rows([DisplayData::all(); ROWS_SIZE]));
+        assert_eq!(Frame::from_rows(expected), ht16k33.frame());
+
+        ht16k33.clear_display_buffer();
+        ht16k33.set_pixels(vec![
+            (LedLocation::new(1, 0).unwrap(), true),
+            (LedLocation::new(1, 1).unwrap(), true),
+            (LedLocation::new(5, 3).unwrap(), true),
+        ]);
+        assert_eq!(&expected, ht16k33.display_buffer());
+        assert_eq!(1, ht16k33.iter_leds().filter(|(_, on)| *on).count());
 
         i2c = ht16k33.destroy();
         i2c.done();
     }
 
     #[test]
-    fn set_dimming() {
-        let expectations = [I2cTransaction::write(
-            ADDRESS,
-            vec![(super::Dimming::COMMAND | Dimming::BRIGHTNESS_MAX).bits()],
-        )];
+    fn flush_full() {
+        let mut write_buffer = vec![super::DisplayDataAddress::ROW_0.bits()];
+        write_buffer.extend([0; super::ROWS_SIZE].iter().cloned());
+
+        let expectations = [
+            I2cTransaction::write(ADDRESS, write_buffer.clone()),
+            I2cTransaction::write(ADDRESS, write_buffer),
+        ];
 
         let mut i2c = I2cMock::new(&expectations);
-        let mut ht16k33 = HT16K33::new(i2c, ADDRESS);
+        let mut ht16k33 = initialized(i2c);
 
-        ht16k33.set_dimming(Dimming::BRIGHTNESS_MAX).unwrap();
+        ht16k33.flush_full().unwrap();
+        ht16k33.flush_full().unwrap();
 
         i2c = ht16k33.destroy();
         i2c.done();
     }
 
     #[test]
-    fn set_led() {
-        let expectations = [I2cTransaction::write(ADDRESS, vec![1u8, 0b1000_0000])];
+    fn flush_package() {
+        let mut write_buffer = vec![super::DisplayDataAddress::ROW_0.bits()];
+        write_buffer.extend([0; 8].iter().cloned());
+
+        let expectations = [
+            I2cTransaction::write(ADDRESS, write_buffer),
+            I2cTransaction::write(ADDRESS, vec![super::DisplayDataAddress::ROW_7.bits(), 1]),
+        ];
 
         let mut i2c = I2cMock::new(&expectations);
-        let mut ht16k33 = HT16K33::new(i2c, ADDRESS);
+        let mut ht16k33 = initialized(i2c);
+        ht16k33.set_package(Package::Sop20);
 
+        ht16k33.flush_full().unwrap();
+
+        // Row 8 is not wired on the 20-pin package, and is never written.
+        ht16k33.update_display_buffer(LedLocation::new(7, 0).unwrap(), true);
+        ht16k33.update_display_buffer(LedLocation::new(8, 0).unwrap(), true);
+        ht16k33.flush().unwrap();
         ht16k33
-            .set_led(LedLocation::new(1, 7).unwrap(), true)
+            .set_led(LedLocation::new(9, 0).unwrap(), true)
             .unwrap();
 
         i2c = ht16k33.destroy();
@@ -799,16 +3099,28 @@ mod tests {
     }
 
     #[test]
-    fn write_display_buffer() {
-        let mut write_buffer = vec![super::DisplayDataAddress::ROW_0.bits()];
-        write_buffer.extend([0; super::ROWS_SIZE].iter().cloned());
+    fn flush_after_read_display_buffer() {
+        let mut read_buffer = vec![0; super::ROWS_SIZE];
+        read_buffer[1] = 0b0000_0010;
 
-        let expectations = [I2cTransaction::write(ADDRESS, write_buffer)];
+        let expectations = [
+            I2cTransaction::write_read(
+                ADDRESS,
+                vec![super::DisplayDataAddress::ROW_0.bits()],
+                read_buffer,
+            ),
+            I2cTransaction::write(
+                ADDRESS,
+                vec![super::DisplayDataAddress::ROW_1.bits(), 0b0000_0011],
+            ),
+        ];
 
         let mut i2c = I2cMock::new(&expectations);
-        let mut ht16k33 = HT16K33::new(i2c, ADDRESS);
+        let mut ht16k33 = initialized(i2c);
 
-        ht16k33.write_display_buffer().unwrap();
+        ht16k33.read_display_buffer().unwrap();
+        ht16k33.update_display_buffer(LedLocation::new(1, 0).unwrap(), true);
+        ht16k33.flush().unwrap();
 
         i2c = ht16k33.destroy();
         i2c.done();
@@ -827,20 +3139,189 @@ mod tests {
         )];
 
         let mut i2c = I2cMock::new(&expectations);
-        let mut ht16k33 = HT16K33::new(i2c, ADDRESS);
+        let mut ht16k33 = initialized(i2c);
 
         ht16k33.read_display_buffer().unwrap();
 
         let &buffer = ht16k33.display_buffer();
 
-        for value in 0..buffer.len() {
+        for (value, row) in buffer.iter().enumerate() {
             match value {
-                1 | 15 => assert_eq!(buffer[value].bits(), 0b0000_0010),
-                _ => assert_eq!(buffer[value].bits(), 0),
+                1 | 15 => assert_eq!(row.bits(), 0b0000_0010),
+                _ => assert_eq!(row.bits(), 0),
             }
         }
 
         i2c = ht16k33.destroy();
         i2c.done();
     }
+
+    #[test]
+    fn resume_keeps_display_ram() {
+        let mut i2c = ChipMock::new();
+        i2c.device_mut(Address::DEFAULT).data_values[3] = 0b0101_0101;
+
+        let mut ht16k33 = HT16K33::new(&mut i2c, Address::new(ADDRESS))
+            .resume()
+            .unwrap();
+        assert_eq!(0b0101_0101, ht16k33.display_buffer()[3].bits());
+
+        // Only the changed row is written.
+        ht16k33.update_display_buffer(LedLocation::new(3, 1).unwrap(), true);
+        ht16k33.set_oscillator(Oscillator::ON).unwrap();
+        ht16k33.set_display(Display::ON).unwrap();
+        ht16k33.destroy();
+
+        assert_eq!(0b0101_0111, i2c.device(Address::DEFAULT).data_values[3]);
+        assert_eq!(Display::ON, i2c.display());
+
+        // The read, the changed row, the oscillator and the display.
+        assert_eq!(4, i2c.transactions().len());
+    }
+
+    #[test]
+    fn restore_state() {
+        let mut chip = ChipMock::new();
+        let mut ht16k33 = chip_driver(&mut chip);
+        ht16k33.update_display_buffer(LedLocation::new(2, 0).unwrap(), true);
+        ht16k33.set_dimming(Dimming::BRIGHTNESS_3_16).unwrap();
+        ht16k33.set_display(Display::TWO_HZ).unwrap();
+        let state = ht16k33.state();
+
+        // The chip lost its settings and RAM.
+        let mut chip = ChipMock::new();
+        let (_, parked) = ht16k33.release();
+        let mut ht16k33 = parked.attach(&mut chip);
+        ht16k33.restore_state(state).unwrap();
+        assert_eq!(state, ht16k33.state());
+
+        let chip = ht16k33.destroy();
+        assert_eq!(Oscillator::ON, chip.oscillator());
+        assert_eq!(Dimming::BRIGHTNESS_3_16, chip.dimming());
+        assert_eq!(Display::TWO_HZ, chip.display());
+        assert_eq!(0b0000_0001, chip.device(Address::DEFAULT).data_values[2]);
+
+        // Display off, oscillator, dimming, display RAM and display on.
+        assert_eq!(5, chip.transactions().len());
+    }
+
+    #[test]
+    fn release_and_attach() {
+        let mut bus = ChipMock::new();
+        let mut ht16k33 = chip_driver(&mut bus);
+        ht16k33.update_display_buffer(LedLocation::new(2, 2).unwrap(), true);
+
+        let (bus, parked) = ht16k33.release();
+        bus.clear_log();
+
+        // The pending change survives, and only it is written.
+        let mut ht16k33 = parked.attach(bus);
+        ht16k33.flush().unwrap();
+
+        let bus = ht16k33.destroy();
+        assert_eq!(1, bus.transactions().len());
+        assert_eq!(0b0000_0100, bus.device(Address::DEFAULT).data_values[2]);
+    }
+
+    #[test]
+    fn power_off_and_on() {
+        let mut i2c = ChipMock::new();
+        let mut ht16k33 = chip_driver(&mut i2c);
+        ht16k33.set_dimming(Dimming::BRIGHTNESS_3_16).unwrap();
+        ht16k33.set_display(Display::TWO_HZ).unwrap();
+        ht16k33.update_display_buffer(LedLocation::new(1, 1).unwrap(), true);
+        ht16k33.flush().unwrap();
+
+        let mut standby = ht16k33.power_off().unwrap();
+        assert_eq!(Display::TWO_HZ, *standby.display());
+
+        // Simulate display RAM lost during standby.
+        let (i2c, parked) = standby.release();
+        i2c.device_mut(Address::DEFAULT).data_values = [0; ROWS_SIZE];
+        i2c.write(
+            ADDRESS,
+            &[(Dimming::COMMAND | Dimming::BRIGHTNESS_MAX).bits()],
+        )
+        .unwrap();
+        assert_eq!(Oscillator::OFF, i2c.oscillator());
+        assert_eq!(Display::OFF, i2c.display());
+        standby = parked.attach(i2c);
+
+        let i2c = standby.power_on().unwrap().destroy();
+        assert_eq!(Oscillator::ON, i2c.oscillator());
+        assert_eq!(Display::TWO_HZ, i2c.display());
+        assert_eq!(Dimming::BRIGHTNESS_3_16, i2c.dimming());
+        assert_eq!(0b0000_0010, i2c.device(Address::DEFAULT).data_values[1]);
+    }
+
+    #[test]
+    fn initialize_with_delay() {
+        struct Delay {
+            total: u32,
+        }
+
+        impl DelayMs<u16> for Delay {
+            fn delay_ms(&mut self, ms: u16) {
+                self.total += ms as u32;
+            }
+        }
+
+        let mut i2c = ChipMock::new();
+        i2c.device_mut(Address::DEFAULT).data_values = [0xFF; ROWS_SIZE];
+        let mut delay = Delay { total: 0 };
+
+        let ht16k33 = HT16K33::new(i2c, Address::new(ADDRESS))
+            .initialize_with_delay(&mut delay)
+            .unwrap();
+        assert_eq!(Display::ON, *ht16k33.display());
+
+        let i2c = ht16k33.destroy();
+        assert_eq!(Oscillator::ON, i2c.oscillator());
+        assert_eq!(Display::ON, i2c.display());
+        assert_eq!([0; ROWS_SIZE], i2c.device(Address::DEFAULT).data_values);
+        assert!(delay.total >= constants::OSCILLATOR_STARTUP_MS as u32);
+    }
+
+    #[test]
+    fn detect() {
+        // A bus with chips at 0x71 and 0x75.
+        struct Bus;
+
+        impl Write for Bus {
+            type Error = ();
+
+            fn write(&mut self, _address: u8, _bytes: &[u8]) -> Result<(), ()> {
+                Ok(())
+            }
+        }
+
+        impl WriteRead for Bus {
+            type Error = ();
+
+            fn write_read(
+                &mut self,
+                address: u8,
+                _bytes: &[u8],
+                _buffer: &mut [u8],
+            ) -> Result<(), ()> {
+                match address {
+                    0x71 | 0x75 => Ok(()),
+                    _ => Err(()),
+                }
+            }
+        }
+
+        struct Delay;
+
+        impl DelayMs<u16> for Delay {
+            fn delay_ms(&mut self, _ms: u16) {}
+        }
+
+        let addresses = HT16K33::detect(&mut Bus, &mut Delay);
+
+        assert_eq!(
+            vec![Address::new(0x71), Address::new(0x75)],
+            addresses.iter().collect::<vec::Vec<_>>()
+        );
+    }
 }
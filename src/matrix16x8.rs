@@ -0,0 +1,210 @@
+//! # matrix16x8
+//!
+//! Support for the Adafruit 16x8 LED matrix backpack.
+//!
+//! The matrix is two 8x8 halves side by side, with each pixel row split over a pair of adjacent rows of the
+//! display RAM, see the [`Matrix16x8`](../layouts/struct.Matrix16x8.html) layout:
+//!
+//! | Row       | Contents                             |
+//! |-----------|--------------------------------------|
+//! | `2*y`     | Columns `0` to `7` of pixel row `y`  |
+//! | `2*y + 1` | Columns `8` to `15` of pixel row `y` |
+//!
+//! Within a row, the pixel column `x` is common `x % 8`.
+use crate::chain::{check_bounds, Canvas};
+use crate::errors::ValidationError;
+use crate::hal::blocking::i2c::Write;
+use crate::layouts::{Layout, Matrix16x8};
+use crate::types::LedLocation;
+use crate::HT16K33;
+
+/// The width of the matrix, in pixels.
+pub const MATRIX_WIDTH: u8 = 16;
+
+/// The height of the matrix, in pixels.
+pub const MATRIX_HEIGHT: u8 = 8;
+
+/// An Adafruit 16x8 LED matrix backpack, wrapping an initialized [`HT16K33`] driver.
+///
+/// The pixel helpers update the display buffer, which must be written using
+/// [write_display_buffer()](struct.LedMatrix16x8.html#method.write_display_buffer) for the change to be
+/// displayed. The matrix is also a [`Canvas`](../chain/trait.Canvas.html), e.g. to draw a
+/// [`Panel`](../chain/struct.Panel.html) on it.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use ht16k33::matrix16x8::LedMatrix16x8;
+/// use ht16k33::HT16K33;
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// let ht16k33 = HT16K33::new(i2c, address).initialize()?;
+///
+/// let mut matrix = LedMatrix16x8::new(ht16k33);
+/// matrix.set_pixel(12, 3, true)?;
+/// matrix.write_display_buffer()?;
+///
+/// assert!(matrix.pixel(12, 3)?);
+///
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`HT16K33`]: ../struct.HT16K33.html
+pub struct LedMatrix16x8<I2C> {
+    driver: HT16K33<I2C>,
+}
+
+impl<I2C, E> LedMatrix16x8<I2C>
+where
+    I2C: Write<Error = E>,
+{
+    /// Create a `LedMatrix16x8` from the given driver.
+    pub fn new(driver: HT16K33<I2C>) -> Self {
+        LedMatrix16x8 { driver }
+    }
+
+    /// Return the driver.
+    pub fn driver(&self) -> &HT16K33<I2C> {
+        &self.driver
+    }
+
+    /// Return the driver for modification, e.g. to change the dimming.
+    pub fn driver_mut(&mut self) -> &mut HT16K33<I2C> {
+        &mut self.driver
+    }
+
+    /// Return the driver, consuming the matrix.
+    pub fn into_driver(self) -> HT16K33<I2C> {
+        self.driver
+    }
+
+    /// Write the display buffer to the HT16K33 chip.
+    pub fn write_display_buffer(&mut self) -> Result<(), E> {
+        self.driver.write_display_buffer()
+    }
+
+    /// Turn off all pixels.
+    pub fn clear(&mut self) {
+        self.driver.clear_display_buffer();
+    }
+
+    /// Turn the pixel at (`x`, `y`) on or off.
+    ///
+    /// # Errors
+    ///
+    /// If `x` is not less than [`MATRIX_WIDTH`] or `y` is not less than [`MATRIX_HEIGHT`] then
+    /// [`ht16k33::ValidationError::ValueTooLarge`] is returned.
+    ///
+    /// [`MATRIX_WIDTH`]: constant.MATRIX_WIDTH.html
+    /// [`MATRIX_HEIGHT`]: constant.MATRIX_HEIGHT.html
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    pub fn set_pixel(&mut self, x: u8, y: u8, enabled: bool) -> Result<(), ValidationError> {
+        let location = location(x, y)?;
+        self.driver.update_display_buffer(location, enabled);
+
+        Ok(())
+    }
+
+    /// Return whether the pixel at (`x`, `y`) is on.
+    ///
+    /// # Errors
+    ///
+    /// If `x` is not less than [`MATRIX_WIDTH`] or `y` is not less than [`MATRIX_HEIGHT`] then
+    /// [`ht16k33::ValidationError::ValueTooLarge`] is returned.
+    ///
+    /// [`MATRIX_WIDTH`]: constant.MATRIX_WIDTH.html
+    /// [`MATRIX_HEIGHT`]: constant.MATRIX_HEIGHT.html
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    pub fn pixel(&self, x: u8, y: u8) -> Result<bool, ValidationError> {
+        let location = location(x, y)?;
+
+        Ok(self.driver.display_buffer()[location.row_as_index()].contains(location.common))
+    }
+}
+
+impl<I2C, E> Canvas for LedMatrix16x8<I2C>
+where
+    I2C: Write<Error = E>,
+{
+    fn width(&self) -> u8 {
+        MATRIX_WIDTH
+    }
+
+    fn height(&self) -> u8 {
+        MATRIX_HEIGHT
+    }
+
+    fn pixel(&self, x: u8, y: u8) -> Result<bool, ValidationError> {
+        LedMatrix16x8::pixel(self, x, y)
+    }
+
+    fn set_pixel(&mut self, x: u8, y: u8, enabled: bool) -> Result<(), ValidationError> {
+        LedMatrix16x8::set_pixel(self, x, y, enabled)
+    }
+
+    fn clear(&mut self) {
+        LedMatrix16x8::clear(self);
+    }
+}
+
+// Return the LED location of the pixel at (`x`, `y`).
+fn location(x: u8, y: u8) -> Result<LedLocation, ValidationError> {
+    check_bounds(x, y, MATRIX_WIDTH, MATRIX_HEIGHT)?;
+
+    // Every pixel within bounds is wired.
+    Ok(Matrix16x8.location(x, y).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c_mock::I2cMock;
+    use crate::types::Address;
+
+    fn matrix() -> LedMatrix16x8<I2cMock> {
+        LedMatrix16x8::new(
+            HT16K33::new(I2cMock::new(), Address::DEFAULT)
+                .initialize()
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn set_pixel() {
+        let mut matrix = matrix();
+
+        matrix.set_pixel(1, 0, true).unwrap();
+        matrix.set_pixel(9, 7, true).unwrap();
+
+        let buffer = matrix.driver().display_buffer();
+        assert_eq!(0b0000_0010, buffer[0].bits());
+        assert_eq!(0b0000_0010, buffer[15].bits());
+        assert!(matrix.pixel(9, 7).unwrap());
+        assert!(!matrix.pixel(1, 7).unwrap());
+    }
+
+    #[test]
+    fn set_pixel_out_of_bounds() {
+        let mut matrix = matrix();
+
+        assert!(matrix.set_pixel(16, 0, true).is_err());
+        assert!(matrix.set_pixel(0, 8, true).is_err());
+    }
+
+    #[test]
+    fn canvas() {
+        let mut matrix = matrix();
+
+        Canvas::set_pixel(&mut matrix, 15, 0, true).unwrap();
+        assert_eq!(16, Canvas::width(&matrix));
+        assert!(Canvas::pixel(&matrix, 15, 0).unwrap());
+
+        Canvas::clear(&mut matrix);
+        assert!(!Canvas::pixel(&matrix, 15, 0).unwrap());
+    }
+}
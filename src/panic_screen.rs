@@ -0,0 +1,108 @@
+//! # panic_screen
+//!
+//! Signal a crash on headless devices, e.g. from a panic handler.
+use crate::constants::ROWS_SIZE;
+use crate::hal::blocking::i2c::Write;
+use crate::seven_segment::{Segments, DIGIT_ROWS};
+use crate::types::{Address, Dimming, Display, DisplayDataAddress, Oscillator};
+
+/// The display RAM contents shown by [`show_panic_pattern()`].
+///
+/// Reads `Err` on a 4-digit 7-segment backpack, and is a distinctive pattern on other layouts.
+///
+/// [`show_panic_pattern()`]: fn.show_panic_pattern.html
+pub const PANIC_PATTERN: [u8; ROWS_SIZE] = panic_pattern();
+
+const fn panic_pattern() -> [u8; ROWS_SIZE] {
+    let r = Segments::E.bits() | Segments::G.bits();
+
+    let mut pattern = [0; ROWS_SIZE];
+    pattern[DIGIT_ROWS[0]] = Segments::DIGIT_E.bits();
+    pattern[DIGIT_ROWS[1]] = r;
+    pattern[DIGIT_ROWS[2]] = r;
+    pattern
+}
+
+/// Show the [`PANIC_PATTERN`], blinking at full brightness.
+///
+/// No driver is needed, the chip is configured from scratch so this can be called from a panic handler with
+/// a stolen or re-created I2C device. Every step is attempted even if an earlier one fails, and the first
+/// error is returned.
+///
+/// # Arguments
+///
+/// * `i2c` - The I2C device to communicate with the HT16K33 chip.
+/// * `address` - The device I2C address.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::i2c_mock::I2cMock;
+/// use ht16k33::panic_screen::{show_panic_pattern, PANIC_PATTERN};
+/// use ht16k33::Address;
+/// # fn main() {
+///
+/// let mut i2c = I2cMock::new();
+///
+/// // Best effort, there is nothing left to do on failure.
+/// let _ = show_panic_pattern(&mut i2c, Address::DEFAULT);
+///
+/// assert_eq!(PANIC_PATTERN, i2c.device(Address::DEFAULT).data_values);
+///
+/// # }
+/// ```
+///
+/// [`PANIC_PATTERN`]: constant.PANIC_PATTERN.html
+pub fn show_panic_pattern<I2C, E>(i2c: &mut I2C, address: Address) -> Result<(), E>
+where
+    I2C: Write<Error = E>,
+{
+    let mut data = [0u8; ROWS_SIZE + 1];
+    data[0] = DisplayDataAddress::ROW_0.bits();
+    data[1..].copy_from_slice(&PANIC_PATTERN);
+
+    let oscillator = [(Oscillator::COMMAND | Oscillator::ON).bits()];
+    let dimming = [(Dimming::COMMAND | Dimming::BRIGHTNESS_MAX).bits()];
+    let display = [(Display::COMMAND | Display::TWO_HZ).bits()];
+
+    let mut result = Ok(());
+    for bytes in [&oscillator[..], &dimming[..], &data[..], &display[..]].iter() {
+        let written = i2c.write(address.value(), bytes);
+        if result.is_ok() {
+            result = written;
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c_mock::{ErrorKind, I2cMock};
+
+    #[test]
+    fn show() {
+        let mut i2c = I2cMock::new();
+
+        show_panic_pattern(&mut i2c, Address::DEFAULT).unwrap();
+
+        assert_eq!(PANIC_PATTERN, i2c.device(Address::DEFAULT).data_values);
+        assert_eq!(Oscillator::ON, i2c.oscillator());
+        assert_eq!(Display::TWO_HZ, i2c.display());
+        assert_eq!(Dimming::BRIGHTNESS_MAX, i2c.dimming());
+    }
+
+    #[test]
+    fn best_effort() {
+        let mut i2c = I2cMock::new();
+        i2c.fail_next_write(ErrorKind::Bus);
+
+        let error = show_panic_pattern(&mut i2c, Address::DEFAULT).unwrap_err();
+        assert_eq!(ErrorKind::Bus, error.kind());
+
+        // The remaining steps were still written.
+        assert_eq!(PANIC_PATTERN, i2c.device(Address::DEFAULT).data_values);
+        assert_eq!(Display::TWO_HZ, i2c.display());
+    }
+}
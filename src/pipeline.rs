@@ -0,0 +1,370 @@
+//! # pipeline
+//!
+//! Drives rendering and post-processing [effects](../effects/index.html) for an
+//! [`HT16K33`](../struct.HT16K33.html).
+//!
+//! A [`Pipeline`](struct.Pipeline.html) takes the latest frame from its
+//! [content source](../content/trait.ContentSource.html), applies its composed effect, then writes the result
+//! to the display each time it is ticked. Effects such as trails and night-dimming therefore apply uniformly
+//! to any content source.
+use crate::constants::COMMONS_SIZE;
+use crate::content::{elapsed, ContentSource};
+use crate::effects::{Effect, Output};
+use crate::hal::blocking::i2c::Write;
+use crate::types::{Dimming, DisplayData, Frame};
+use crate::HT16K33;
+
+// 4x4 ordered dithering thresholds, so neighbouring LEDs switch at evenly spread times.
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// How a pipeline transitions between frames from its content source.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Interpolation {
+    /// Show each new frame immediately.
+    #[default]
+    None,
+    /// Dissolve from the previous frame to each new frame over the given duration, in milliseconds.
+    ///
+    /// Changed LEDs switch at dithered times spread over the duration, which smooths transitions for sources
+    /// that produce frames slower than the display refresh.
+    Dissolve(u32),
+}
+
+/// A content source followed by a composed effect.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use ht16k33::HT16K33;
+/// use ht16k33::effects::{BrightnessSchedule, Decay, Effect};
+/// use ht16k33::pipeline::Pipeline;
+/// use ht16k33::{Frame, LedLocation};
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+///
+/// // Sweep a single LED along the first row, leaving a trail.
+/// let source = |now: u32| {
+///     let mut frame = Frame::empty();
+///     frame.set_led(LedLocation::new(0, ((now / 100) % 8) as u8).unwrap(), true);
+///     Some(frame)
+/// };
+/// let effect = Decay::new(3).then(BrightnessSchedule::default());
+///
+/// let mut pipeline = Pipeline::new(source, effect);
+/// for now in (0..1000).step_by(50) {
+///     pipeline.tick(&mut ht16k33, now)?;
+/// }
+///
+/// # Ok(())
+/// # }
+/// ```
+pub struct Pipeline<S, F> {
+    source: S,
+    effect: F,
+
+    // The latest frame from the source, kept while the source has no new frame.
+    frame: Frame,
+
+    // The frame being transitioned from, and when the transition started.
+    previous: Frame,
+    changed_at: u32,
+    interpolation: Interpolation,
+
+    // The dimming before effects are applied.
+    dimming: Dimming,
+
+    output: Output,
+}
+
+impl<S, F> Pipeline<S, F>
+where
+    S: ContentSource,
+    F: Effect,
+{
+    /// Create a `Pipeline`.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source of frames.
+    /// * `effect` - The effect applied to each rendered frame.
+    pub fn new(source: S, effect: F) -> Self {
+        Pipeline {
+            source,
+            effect,
+            frame: Frame::empty(),
+            previous: Frame::empty(),
+            changed_at: 0,
+            interpolation: Interpolation::None,
+            dimming: Dimming::default(),
+            output: Output::default(),
+        }
+    }
+
+    /// Return the content source.
+    pub fn source_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+
+    /// Return the effect.
+    pub fn effect_mut(&mut self) -> &mut F {
+        &mut self.effect
+    }
+
+    /// Return the dimming before effects are applied.
+    pub fn dimming(&self) -> Dimming {
+        self.dimming
+    }
+
+    /// Set the dimming before effects are applied.
+    pub fn set_dimming(&mut self, dimming: Dimming) {
+        self.dimming = dimming;
+    }
+
+    /// Return the interpolation between frames.
+    pub fn interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    /// Set the interpolation between frames.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ht16k33::content::Animation;
+    /// use ht16k33::effects::Identity;
+    /// use ht16k33::pipeline::{Interpolation, Pipeline};
+    /// use ht16k33::{DisplayData, Frame, ROWS_SIZE};
+    ///
+    /// // A new frame every second, dissolving over half a second.
+    /// let frames = [Frame::empty(), Frame::from_rows([DisplayData::all(); ROWS_SIZE])];
+    /// let mut pipeline = Pipeline::new(Animation::new(&frames, 1000), Identity);
+    /// pipeline.set_interpolation(Interpolation::Dissolve(500));
+    ///
+    /// pipeline.render(0);
+    /// pipeline.render(1000);
+    ///
+    /// // Part way through the transition, some but not all LEDs are lit.
+    /// let frame = pipeline.render(1250).frame;
+    /// assert!(frame != frames[0] && frame != frames[1]);
+    ///
+    /// assert_eq!(frames[1], pipeline.render(1500).frame);
+    /// ```
+    pub fn set_interpolation(&mut self, interpolation: Interpolation) {
+        self.interpolation = interpolation;
+    }
+
+    /// Return the most recent output.
+    pub fn output(&self) -> &Output {
+        &self.output
+    }
+
+    /// Take the latest frame from the source and apply the effect.
+    ///
+    /// Effects are applied even when the source has no new frame, so time-based effects keep running.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time in milliseconds, wrapping on overflow.
+    pub fn render(&mut self, now: u32) -> &Output {
+        if let Some(frame) = self.source.next_frame(now) {
+            // Start the transition from what is currently shown, which may itself be part way through a
+            // transition.
+            self.previous = self.interpolate(now);
+            self.changed_at = now;
+            self.frame = frame;
+        }
+
+        self.output = Output {
+            frame: self.interpolate(now),
+            dimming: self.dimming,
+        };
+        self.effect.apply(&mut self.output, now);
+
+        &self.output
+    }
+
+    // Return the frame to show at time `now`, blending the previous and latest frames.
+    fn interpolate(&self, now: u32) -> Frame {
+        let duration = match self.interpolation {
+            Interpolation::Dissolve(duration) if !elapsed(now, self.changed_at, duration) => {
+                duration
+            }
+            _ => return self.frame,
+        };
+
+        // Progress through the transition, in 16ths.
+        let progress =
+            (u64::from(now.wrapping_sub(self.changed_at)) * 16 / u64::from(duration)) as u8;
+
+        let mut frame = self.previous;
+        for (row, (value, target)) in frame
+            .rows_mut()
+            .iter_mut()
+            .zip(self.frame.rows().iter())
+            .enumerate()
+        {
+            for common in 0..COMMONS_SIZE {
+                if BAYER_4X4[row % 4][common % 4] < progress {
+                    let flag = DisplayData::from_bits_truncate(1 << common);
+                    value.set(flag, target.contains(flag));
+                }
+            }
+        }
+
+        frame
+    }
+
+    /// Render, then write the output to the display.
+    ///
+    /// The dimming is only written when it changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `driver` - The display to write to.
+    /// * `now` - The current time in milliseconds, wrapping on overflow.
+    pub fn tick<I2C, E>(&mut self, driver: &mut HT16K33<I2C>, now: u32) -> Result<(), E>
+    where
+        I2C: Write<Error = E>,
+    {
+        let output = *self.render(now);
+
+        *driver.display_buffer_mut() = *output.frame.rows();
+        driver.write_display_buffer()?;
+
+        if *driver.dimming() != output.dimming {
+            driver.set_dimming(output.dimming)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::ROWS_SIZE;
+    use crate::content::Animation;
+    use crate::effects::{BrightnessSchedule, Decay, Identity};
+    use crate::i2c_mock::I2cMock;
+    use crate::types::Address;
+    use crate::types::{DisplayData, LedLocation};
+
+    #[test]
+    fn render() {
+        let location = LedLocation::new(3, 3).unwrap();
+
+        let mut pipeline = Pipeline::new(
+            |_now: u32| {
+                let mut frame = Frame::empty();
+                frame.set_led(location, true);
+                Some(frame)
+            },
+            Identity,
+        );
+
+        let output = pipeline.render(0);
+        assert!(output.frame.led(location));
+        assert_eq!(Dimming::BRIGHTNESS_MAX, output.dimming);
+    }
+
+    #[test]
+    fn effects_apply_to_rendered_frames() {
+        let location = LedLocation::new(3, 3).unwrap();
+
+        // Light the LED once, then turn it off.
+        let mut pipeline = Pipeline::new(
+            |now: u32| {
+                let mut frame = Frame::empty();
+                frame.set_led(location, now == 0);
+                Some(frame)
+            },
+            Decay::new(2).then(BrightnessSchedule::new(Dimming::BRIGHTNESS_MIN, 0, 1000)),
+        );
+
+        pipeline.render(0);
+
+        // The trail keeps the LED lit and the schedule dims.
+        let output = pipeline.render(1);
+        assert!(output.frame.led(location));
+        assert_eq!(Dimming::BRIGHTNESS_MIN, output.dimming);
+    }
+
+    #[test]
+    fn keeps_frame_without_new_content() {
+        let frames = [Frame::from_rows([DisplayData::COMMON_5; ROWS_SIZE])];
+
+        let mut pipeline = Pipeline::new(Animation::new(&frames, 100), Identity);
+
+        pipeline.render(0);
+        let output = pipeline.render(10);
+        assert_eq!(frames[0], output.frame);
+    }
+
+    #[test]
+    fn dissolve() {
+        let frames = [
+            Frame::empty(),
+            Frame::from_rows([DisplayData::all(); ROWS_SIZE]),
+        ];
+
+        let mut pipeline = Pipeline::new(Animation::new(&frames, 100), Identity);
+        pipeline.set_interpolation(Interpolation::Dissolve(80));
+
+        assert_eq!(frames[0], pipeline.render(0).frame);
+
+        // LEDs only ever switch on during the transition.
+        let mut lit = 0;
+        for now in (100..180).step_by(5) {
+            let frame = pipeline.render(now).frame;
+            let count: u32 = frame.rows().iter().map(|row| row.bits().count_ones()).sum();
+
+            assert!(count >= lit, "LEDs switched back off at {}", now);
+            lit = count;
+        }
+        assert!(lit > 0 && lit < 128);
+
+        assert_eq!(frames[1], pipeline.render(180).frame);
+    }
+
+    #[test]
+    fn no_interpolation() {
+        let frames = [
+            Frame::empty(),
+            Frame::from_rows([DisplayData::all(); ROWS_SIZE]),
+        ];
+
+        let mut pipeline = Pipeline::new(Animation::new(&frames, 100), Identity);
+
+        pipeline.render(0);
+        assert_eq!(frames[1], pipeline.render(100).frame);
+    }
+
+    #[test]
+    fn tick() {
+        let location = LedLocation::new(2, 1).unwrap();
+
+        let mut pipeline = Pipeline::new(
+            |_now: u32| {
+                let mut frame = Frame::empty();
+                frame.set_led(location, true);
+                Some(frame)
+            },
+            BrightnessSchedule::new(Dimming::BRIGHTNESS_4_16, 0, 1000),
+        );
+
+        let mut driver = HT16K33::new(I2cMock::new(), Address::DEFAULT)
+            .initialize()
+            .unwrap();
+        pipeline.tick(&mut driver, 0).unwrap();
+
+        assert_eq!(Dimming::BRIGHTNESS_4_16, *driver.dimming());
+
+        let i2c = driver.destroy();
+        assert_eq!(0b0000_0010, i2c.device(Address::DEFAULT).data_values[2]);
+    }
+}
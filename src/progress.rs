@@ -0,0 +1,179 @@
+//! # progress
+//!
+//! A minimal progress display, e.g. for firmware updates in a bootloader.
+//!
+//! The [`ProgressReporter`](enum.ProgressReporter.html) writes straight to the driver's display buffer, without
+//! the content source and pipeline machinery.
+use crate::bargraph::{bar_locations, BARS_SIZE};
+use crate::constants::ROWS_SIZE;
+use crate::hal::blocking::i2c::Write;
+use crate::layouts::Layout;
+use crate::seven_segment::{Segments, DIGIT_ROWS, HEX_DIGITS};
+use crate::types::DisplayData;
+use crate::HT16K33;
+
+/// Shows a percentage on a board, selected by its layout.
+///
+/// Percentages above 100 are shown as 100.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use ht16k33::HT16K33;
+/// use ht16k33::layouts::Matrix8x8;
+/// use ht16k33::progress::ProgressReporter;
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+///
+/// let progress = ProgressReporter::Matrix(&Matrix8x8);
+/// for percent in (0..=100).step_by(10) {
+///     progress.report(&mut ht16k33, percent)?;
+/// }
+///
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy)]
+pub enum ProgressReporter<'a> {
+    /// Fill the Adafruit 24-bar bargraph in green, see [`bargraph`](../bargraph/index.html).
+    Bargraph24,
+    /// Fill the columns of a matrix from left to right.
+    Matrix(&'a dyn Layout),
+    /// Show `P` and the percentage on a 4-digit 7-segment backpack, e.g. `P 42`, see
+    /// [`seven_segment`](../seven_segment/index.html).
+    SevenSegment,
+}
+
+impl ProgressReporter<'_> {
+    /// Render the `percent` into the display `buffer`, replacing its contents.
+    pub fn render(&self, percent: u8, buffer: &mut [DisplayData; ROWS_SIZE]) {
+        let percent = percent.min(100);
+
+        *buffer = [DisplayData::COMMON_NONE; ROWS_SIZE];
+
+        match *self {
+            ProgressReporter::Bargraph24 => {
+                for index in 0..scale(percent, BARS_SIZE) {
+                    if let Ok((green, _)) = bar_locations(index) {
+                        buffer[green.row_as_index()].insert(green.common);
+                    }
+                }
+            }
+            ProgressReporter::Matrix(layout) => {
+                for x in 0..scale(percent, layout.width()) {
+                    for y in 0..layout.height() {
+                        if let Some(location) = layout.location(x, y) {
+                            buffer[location.row_as_index()].insert(location.common);
+                        }
+                    }
+                }
+            }
+            ProgressReporter::SevenSegment => {
+                let p = Segments::A | Segments::B | Segments::E | Segments::F | Segments::G;
+                buffer[DIGIT_ROWS[0]] = DisplayData::from_bits_truncate(p.bits());
+
+                // Right-aligned, without leading zeros.
+                let mut value = percent;
+                for (index, row) in DIGIT_ROWS.iter().enumerate().skip(1).rev() {
+                    if value == 0 && index < DIGIT_ROWS.len() - 1 {
+                        break;
+                    }
+
+                    let digit = HEX_DIGITS[(value % 10) as usize];
+                    buffer[*row] = DisplayData::from_bits_truncate(digit.bits());
+                    value /= 10;
+                }
+            }
+        }
+    }
+
+    /// Render the `percent` into the `driver` display buffer, and write it.
+    pub fn report<I2C, E>(&self, driver: &mut HT16K33<I2C>, percent: u8) -> Result<(), E>
+    where
+        I2C: Write<Error = E>,
+    {
+        self.render(percent, driver.display_buffer_mut());
+        driver.flush()
+    }
+}
+
+// Scale a percentage to a count out of `size`, rounding to the nearest.
+fn scale(percent: u8, size: u8) -> u8 {
+    ((percent as u16 * size as u16 + 50) / 100) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layouts::Matrix8x8;
+
+    fn render(progress: ProgressReporter, percent: u8) -> [DisplayData; ROWS_SIZE] {
+        let mut buffer = [DisplayData::all(); ROWS_SIZE];
+        progress.render(percent, &mut buffer);
+        buffer
+    }
+
+    fn lit(buffer: &[DisplayData; ROWS_SIZE]) -> u32 {
+        buffer.iter().map(|row| row.bits().count_ones()).sum()
+    }
+
+    #[test]
+    fn bargraph() {
+        assert_eq!(0, lit(&render(ProgressReporter::Bargraph24, 0)));
+        assert_eq!(12, lit(&render(ProgressReporter::Bargraph24, 50)));
+        assert_eq!(24, lit(&render(ProgressReporter::Bargraph24, 100)));
+        assert_eq!(24, lit(&render(ProgressReporter::Bargraph24, 255)));
+
+        // Only green LEDs, on the odd rows.
+        let buffer = render(ProgressReporter::Bargraph24, 100);
+        for (row, value) in buffer.iter().enumerate().step_by(2) {
+            assert_eq!(0, value.bits(), "row {}", row);
+        }
+    }
+
+    #[test]
+    fn matrix() {
+        let progress = ProgressReporter::Matrix(&Matrix8x8);
+
+        assert_eq!(0, lit(&render(progress, 0)));
+        assert_eq!(8 * 3, lit(&render(progress, 40)));
+        assert_eq!(64, lit(&render(progress, 100)));
+
+        // The leftmost column is common 7.
+        let buffer = render(progress, 10);
+        assert_eq!(DisplayData::COMMON_7, buffer[0]);
+    }
+
+    #[test]
+    fn seven_segment() {
+        let digits = |percent| {
+            let buffer = render(ProgressReporter::SevenSegment, percent);
+            let mut digits = [0; 4];
+            for (digit, row) in digits.iter_mut().zip(DIGIT_ROWS.iter()) {
+                *digit = buffer[*row].bits();
+            }
+            digits
+        };
+
+        let p = 0b0111_0011;
+        assert_eq!(
+            [p, 0, Segments::DIGIT_4.bits(), Segments::DIGIT_2.bits()],
+            digits(42)
+        );
+        assert_eq!([p, 0, 0, Segments::DIGIT_0.bits()], digits(0));
+        assert_eq!(
+            [
+                p,
+                Segments::DIGIT_1.bits(),
+                Segments::DIGIT_0.bits(),
+                Segments::DIGIT_0.bits()
+            ],
+            digits(100)
+        );
+    }
+}
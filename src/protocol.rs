@@ -0,0 +1,488 @@
+//! # protocol
+//!
+//! A tiny framed protocol for driving a display remotely over any serial link, e.g. UART or BLE.
+//!
+//! Hosts build messages with [`encode()`](fn.encode.html), devices feed received bytes to a
+//! [`Decoder`](struct.Decoder.html), or to a [`RemoteSource`](struct.RemoteSource.html) to use the messages as
+//! a [content source](../content/trait.ContentSource.html).
+//!
+//! The payloads are plain bytes rather than postcard-encoded, so the protocol needs no serialization crate,
+//! and the format below is all a host in any language has to implement.
+//!
+//! # Wire format
+//!
+//! Each message is sent as:
+//!
+//! | Byte          | Contents                                                  |
+//! |---------------|-----------------------------------------------------------|
+//! | 0             | [`SYNC`](constant.SYNC.html)                              |
+//! | 1             | Command                                                   |
+//! | 2             | Payload length `N`                                        |
+//! | 3 ..= 2 + N   | Payload                                                   |
+//! | 3 + N         | Checksum, XOR of the command, length and payload bytes    |
+//!
+//! | Command | Message            | Payload                                    |
+//! |---------|--------------------|--------------------------------------------|
+//! | `0x01`  | Show frame         | [`ROWS_SIZE`](../constant.ROWS_SIZE.html) bytes of display RAM |
+//! | `0x02`  | Show text          | Up to [`TEXT_SIZE`](constant.TEXT_SIZE.html) bytes of UTF-8 |
+//! | `0x03`  | Set brightness     | One dimming value, `0`-`15`                |
+//!
+//! Corrupt messages are dropped, and the decoder resynchronizes on the next `SYNC` byte.
+use crate::constants::ROWS_SIZE;
+use crate::content::ContentSource;
+use crate::errors::ValidationError;
+use crate::types::{Dimming, DisplayData, Frame};
+
+use core::str;
+
+/// The first byte of every message.
+pub const SYNC: u8 = 0xA5;
+
+/// The maximum length of a text message, in bytes.
+pub const TEXT_SIZE: usize = 32;
+
+/// The maximum length of an encoded message, in bytes.
+pub const MESSAGE_SIZE: usize = 4 + TEXT_SIZE;
+
+const COMMAND_FRAME: u8 = 0x01;
+const COMMAND_TEXT: u8 = 0x02;
+const COMMAND_BRIGHTNESS: u8 = 0x03;
+
+/// The UTF-8 text of a "show text" message.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Text {
+    bytes: [u8; TEXT_SIZE],
+    len: u8,
+}
+
+impl Text {
+    /// Create a `Text` from the given string.
+    ///
+    /// # Errors
+    ///
+    /// If the text is longer than [`TEXT_SIZE`] bytes then [`ht16k33::ValidationError::ValueTooLarge`] is
+    /// returned.
+    ///
+    /// [`TEXT_SIZE`]: constant.TEXT_SIZE.html
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    pub fn new(text: &str) -> Result<Self, ValidationError> {
+        if text.len() > TEXT_SIZE {
+            return Err(ValidationError::ValueTooLarge {
+                name: "text length",
+                value: text.len().min(u8::MAX as usize) as u8,
+                limit: TEXT_SIZE as u8,
+                inclusive: true,
+            });
+        }
+
+        let mut bytes = [0; TEXT_SIZE];
+        bytes[..text.len()].copy_from_slice(text.as_bytes());
+
+        Ok(Text {
+            bytes,
+            len: text.len() as u8,
+        })
+    }
+
+    /// Return the text.
+    pub fn as_str(&self) -> &str {
+        // Only created from a `&str`, or validated when decoded.
+        str::from_utf8(&self.bytes[..self.len as usize]).unwrap_or("")
+    }
+}
+
+/// A decoded message.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Message {
+    /// Show the frame.
+    Frame(Frame),
+    /// Show the text.
+    Text(Text),
+    /// Set the display brightness.
+    Brightness(Dimming),
+}
+
+/// Encode a `message` into `buffer`, returning the number of bytes used.
+///
+/// # Errors
+///
+/// If the `buffer` is too small for the message then [`ht16k33::ValidationError::ValueTooLarge`] is returned.
+/// A buffer of [`MESSAGE_SIZE`] bytes fits every message.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::protocol::{encode, Message, Text, MESSAGE_SIZE};
+/// # use ht16k33::ValidationError;
+/// # fn main() -> Result<(), ValidationError> {
+///
+/// let mut buffer = [0u8; MESSAGE_SIZE];
+/// let len = encode(&Message::Text(Text::new("HI")?), &mut buffer)?;
+///
+/// // Send `&buffer[..len]` over the serial link.
+/// assert_eq!(6, len);
+///
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`MESSAGE_SIZE`]: constant.MESSAGE_SIZE.html
+/// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+pub fn encode(message: &Message, buffer: &mut [u8]) -> Result<usize, ValidationError> {
+    let mut payload = [0u8; TEXT_SIZE];
+
+    let (command, payload) = match message {
+        Message::Frame(frame) => {
+            for (value, row) in payload.iter_mut().zip(frame.rows().iter()) {
+                *value = row.bits();
+            }
+            (COMMAND_FRAME, &payload[..ROWS_SIZE])
+        }
+        Message::Text(text) => (COMMAND_TEXT, text.as_str().as_bytes()),
+        Message::Brightness(dimming) => {
+            payload[0] = dimming.bits();
+            (COMMAND_BRIGHTNESS, &payload[..1])
+        }
+    };
+
+    let len = payload.len() + 4;
+    if buffer.len() < len {
+        return Err(ValidationError::ValueTooLarge {
+            name: "message length",
+            value: len as u8,
+            limit: buffer.len().min(u8::MAX as usize) as u8,
+            inclusive: true,
+        });
+    }
+
+    buffer[0] = SYNC;
+    buffer[1] = command;
+    buffer[2] = payload.len() as u8;
+    buffer[3..len - 1].copy_from_slice(payload);
+    buffer[len - 1] = buffer[1..len - 1].iter().fold(0, |sum, byte| sum ^ byte);
+
+    Ok(len)
+}
+
+/// Decodes messages from a stream of received bytes.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::protocol::{encode, Decoder, Message, MESSAGE_SIZE};
+/// use ht16k33::Dimming;
+/// # use ht16k33::ValidationError;
+/// # fn main() -> Result<(), ValidationError> {
+///
+/// let mut buffer = [0u8; MESSAGE_SIZE];
+/// let len = encode(&Message::Brightness(Dimming::BRIGHTNESS_MIN), &mut buffer)?;
+///
+/// let mut decoder = Decoder::new();
+/// let mut received = None;
+/// for byte in buffer[..len].iter() {
+///     received = decoder.push(*byte);
+/// }
+///
+/// assert_eq!(Some(Message::Brightness(Dimming::BRIGHTNESS_MIN)), received);
+///
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Decoder {
+    buffer: [u8; MESSAGE_SIZE],
+    received: usize,
+    errors: u32,
+}
+
+impl Default for Decoder {
+    fn default() -> Decoder {
+        Decoder::new()
+    }
+}
+
+impl Decoder {
+    /// Create a `Decoder`.
+    pub fn new() -> Self {
+        Decoder {
+            buffer: [0; MESSAGE_SIZE],
+            received: 0,
+            errors: 0,
+        }
+    }
+
+    /// Return the number of corrupt messages dropped.
+    pub fn errors(&self) -> u32 {
+        self.errors
+    }
+
+    /// Feed a received `byte`, returning the message it completes, if any.
+    pub fn push(&mut self, byte: u8) -> Option<Message> {
+        if self.received == 0 && byte != SYNC {
+            // Waiting to synchronize.
+            return None;
+        }
+
+        self.buffer[self.received] = byte;
+        self.received += 1;
+
+        if self.received < 3 {
+            return None;
+        }
+
+        let len = self.buffer[2] as usize;
+        if len > TEXT_SIZE {
+            return self.drop_message();
+        }
+
+        if self.received < len + 4 {
+            return None;
+        }
+
+        self.received = 0;
+
+        let checksum = self.buffer[1..len + 3]
+            .iter()
+            .fold(0, |sum, byte| sum ^ byte);
+        if checksum != self.buffer[len + 3] {
+            return self.drop_message();
+        }
+
+        let payload = &self.buffer[3..len + 3];
+        let message = match (self.buffer[1], len) {
+            (COMMAND_FRAME, ROWS_SIZE) => {
+                let mut rows = [DisplayData::empty(); ROWS_SIZE];
+                for (row, value) in rows.iter_mut().zip(payload.iter()) {
+                    *row = DisplayData::from_bits_truncate(*value);
+                }
+                Message::Frame(Frame::from_rows(rows))
+            }
+            (COMMAND_TEXT, _) => match str::from_utf8(payload) {
+                Ok(text) => Message::Text(Text::new(text).ok()?),
+                Err(_) => return self.drop_message(),
+            },
+            (COMMAND_BRIGHTNESS, 1) => match Dimming::from_u8(payload[0]) {
+                Ok(dimming) => Message::Brightness(dimming),
+                Err(_) => return self.drop_message(),
+            },
+            _ => return self.drop_message(),
+        };
+
+        Some(message)
+    }
+
+    fn drop_message(&mut self) -> Option<Message> {
+        self.received = 0;
+        self.errors = self.errors.wrapping_add(1);
+        None
+    }
+}
+
+/// A content source fed by protocol messages.
+///
+/// Frames are shown as-is, text is rendered into a frame by the given renderer, and the most recent
+/// brightness is kept until taken with [take_dimming()](struct.RemoteSource.html#method.take_dimming).
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::content::ContentSource;
+/// use ht16k33::protocol::{encode, Message, RemoteSource, MESSAGE_SIZE};
+/// use ht16k33::{DisplayData, Frame, ROWS_SIZE};
+/// # use ht16k33::ValidationError;
+/// # fn main() -> Result<(), ValidationError> {
+///
+/// // This display can't show text, light it all up instead.
+/// let mut source = RemoteSource::new(|_text: &str, frame: &mut Frame| {
+///     *frame = Frame::from_rows([DisplayData::all(); ROWS_SIZE]);
+/// });
+///
+/// let mut buffer = [0u8; MESSAGE_SIZE];
+/// let len = encode(&Message::Frame(Frame::empty()), &mut buffer)?;
+/// source.extend(&buffer[..len]);
+///
+/// assert_eq!(Some(Frame::empty()), source.next_frame(0));
+///
+/// # Ok(())
+/// # }
+/// ```
+pub struct RemoteSource<R> {
+    decoder: Decoder,
+    render_text: R,
+    frame: Option<Frame>,
+    dimming: Option<Dimming>,
+}
+
+impl<R> RemoteSource<R>
+where
+    R: FnMut(&str, &mut Frame),
+{
+    /// Create a `RemoteSource`.
+    ///
+    /// # Arguments
+    ///
+    /// * `render_text` - Renders received text into an empty frame.
+    pub fn new(render_text: R) -> Self {
+        RemoteSource {
+            decoder: Decoder::new(),
+            render_text,
+            frame: None,
+            dimming: None,
+        }
+    }
+
+    /// Return the decoder.
+    pub fn decoder(&self) -> &Decoder {
+        &self.decoder
+    }
+
+    /// Feed a received `byte`.
+    pub fn push(&mut self, byte: u8) {
+        match self.decoder.push(byte) {
+            Some(Message::Frame(frame)) => self.frame = Some(frame),
+            Some(Message::Text(text)) => {
+                let mut frame = Frame::empty();
+                (self.render_text)(text.as_str(), &mut frame);
+                self.frame = Some(frame);
+            }
+            Some(Message::Brightness(dimming)) => self.dimming = Some(dimming),
+            None => {}
+        }
+    }
+
+    /// Feed several received `bytes`.
+    pub fn extend(&mut self, bytes: &[u8]) {
+        for byte in bytes.iter() {
+            self.push(*byte);
+        }
+    }
+
+    /// Return the most recently received brightness, if it has not been taken yet.
+    pub fn take_dimming(&mut self) -> Option<Dimming> {
+        self.dimming.take()
+    }
+}
+
+impl<R> ContentSource for RemoteSource<R>
+where
+    R: FnMut(&str, &mut Frame),
+{
+    fn next_frame(&mut self, _now: u32) -> Option<Frame> {
+        self.frame.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(message: Message) -> Option<Message> {
+        let mut buffer = [0u8; MESSAGE_SIZE];
+        let len = encode(&message, &mut buffer).unwrap();
+
+        let mut decoder = Decoder::new();
+        let mut received = None;
+        for byte in buffer[..len].iter() {
+            assert!(received.is_none(), "message completed early");
+            received = decoder.push(*byte);
+        }
+
+        received
+    }
+
+    #[test]
+    fn frame() {
+        let mut rows = [DisplayData::empty(); ROWS_SIZE];
+        rows[0] = DisplayData::COMMON_3;
+        rows[15] = DisplayData::all();
+        let message = Message::Frame(Frame::from_rows(rows));
+
+        assert_eq!(Some(message), round_trip(message));
+    }
+
+    #[test]
+    fn text() {
+        let message = Message::Text(Text::new("Hello, world!").unwrap());
+
+        assert_eq!(Some(message), round_trip(message));
+    }
+
+    #[test]
+    fn text_too_long() {
+        let text = "0123456789012345678901234567890123456789";
+
+        assert!(Text::new(&text[..TEXT_SIZE]).is_ok());
+        assert!(Text::new(&text[..TEXT_SIZE + 1]).is_err());
+    }
+
+    #[test]
+    fn brightness() {
+        let message = Message::Brightness(Dimming::BRIGHTNESS_7_16);
+
+        assert_eq!(Some(message), round_trip(message));
+    }
+
+    #[test]
+    fn buffer_too_small() {
+        let mut buffer = [0u8; ROWS_SIZE];
+
+        assert!(encode(&Message::Frame(Frame::empty()), &mut buffer).is_err());
+    }
+
+    #[test]
+    fn corrupt_message_is_dropped() {
+        let mut buffer = [0u8; MESSAGE_SIZE];
+        let len = encode(&Message::Brightness(Dimming::BRIGHTNESS_MAX), &mut buffer).unwrap();
+
+        let mut decoder = Decoder::new();
+
+        // Flip a payload bit, the checksum fails.
+        buffer[3] ^= 0b0000_0001;
+        for byte in buffer[..len].iter() {
+            assert_eq!(None, decoder.push(*byte));
+        }
+        assert_eq!(1, decoder.errors());
+
+        // Resynchronizes on the next message, skipping noise.
+        buffer[3] ^= 0b0000_0001;
+        assert_eq!(None, decoder.push(0x00));
+        let mut received = None;
+        for byte in buffer[..len].iter() {
+            received = decoder.push(*byte);
+        }
+        assert_eq!(Some(Message::Brightness(Dimming::BRIGHTNESS_MAX)), received);
+    }
+
+    #[test]
+    fn invalid_brightness_is_dropped() {
+        let mut decoder = Decoder::new();
+
+        let bytes = [SYNC, COMMAND_BRIGHTNESS, 1, 16, COMMAND_BRIGHTNESS ^ 1 ^ 16];
+        for byte in bytes.iter() {
+            assert_eq!(None, decoder.push(*byte));
+        }
+        assert_eq!(1, decoder.errors());
+    }
+
+    #[test]
+    fn remote_source() {
+        let mut source = RemoteSource::new(|text: &str, frame: &mut Frame| {
+            frame.rows_mut()[0] = DisplayData::from_bits_truncate(text.len() as u8);
+        });
+
+        let mut buffer = [0u8; MESSAGE_SIZE];
+
+        let len = encode(&Message::Text(Text::new("abc").unwrap()), &mut buffer).unwrap();
+        source.extend(&buffer[..len]);
+
+        let len = encode(&Message::Brightness(Dimming::BRIGHTNESS_MIN), &mut buffer).unwrap();
+        source.extend(&buffer[..len]);
+
+        let frame = source.next_frame(0).unwrap();
+        assert_eq!(3, frame.rows()[0].bits());
+        assert_eq!(None, source.next_frame(0));
+
+        assert_eq!(Some(Dimming::BRIGHTNESS_MIN), source.take_dimming());
+        assert_eq!(None, source.take_dimming());
+    }
+}
@@ -0,0 +1,203 @@
+//! # recorder
+//!
+//! A headless I2C device recording every frame written to the display RAM, to generate documentation examples
+//! and to check animation timing in regression tests.
+//!
+//! *Requires the `std` feature.*
+use crate::constants::ROWS_SIZE;
+use crate::hal::blocking::i2c::Write;
+use crate::layouts::Layout;
+use crate::types::{DisplayData, Frame};
+
+use core::convert::Infallible;
+use core::fmt;
+use std::vec::Vec;
+
+// The chip only uses the low nibble of a display data address, and wraps around within it.
+const DATA_ADDRESS_MASK: u8 = 0b0000_1111;
+
+/// A frame written to the display RAM, and the time it was written.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RecordedFrame {
+    /// The time of the write, in milliseconds.
+    pub time: u32,
+    /// The display RAM after the write.
+    pub frame: Frame,
+}
+
+/// A write-only I2C device recording the display RAM after each write, in place of a chip.
+///
+/// The time of each frame is set with [set_time()](struct.FrameRecorder.html#method.set_time), and the writes
+/// at the same time, e.g. the writes of a single flush, are merged into one frame. Command writes are ignored.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// use ht16k33::layouts::{Layout, Matrix8x8};
+/// use ht16k33::recorder::FrameRecorder;
+/// use ht16k33::{Address, HT16K33};
+/// # fn main() -> Result<(), Error> {
+///
+/// let mut recorder = FrameRecorder::new();
+/// let mut ht16k33 = HT16K33::new(&mut recorder, Address::DEFAULT).initialize()?;
+///
+/// for (time, x) in [(0, 0), (100, 1)] {
+///     ht16k33.clear_display_buffer();
+///     ht16k33.update_display_buffer(Matrix8x8.location(x, 0).unwrap(), true);
+///
+///     let (recorder, parked) = ht16k33.release();
+///     recorder.set_time(time);
+///     ht16k33 = parked.attach(recorder);
+///     ht16k33.flush()?;
+/// }
+///
+/// let recorder = ht16k33.destroy();
+/// assert_eq!(2, recorder.frames().len());
+/// assert_eq!(100, recorder.frames()[1].time);
+///
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FrameRecorder {
+    // The display RAM, as written so far.
+    rows: [DisplayData; ROWS_SIZE],
+
+    // The time of the next writes, in milliseconds.
+    time: u32,
+
+    frames: Vec<RecordedFrame>,
+}
+
+impl FrameRecorder {
+    /// Create a `FrameRecorder` at time `0`.
+    pub fn new() -> Self {
+        FrameRecorder::default()
+    }
+
+    /// Set the time of the next writes, in milliseconds.
+    pub fn set_time(&mut self, time: u32) {
+        self.time = time;
+    }
+
+    /// Return the recorded frames, oldest first.
+    pub fn frames(&self) -> &[RecordedFrame] {
+        &self.frames
+    }
+
+    /// Forget the recorded frames, keeping the display RAM.
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    /// Write the recorded frames as a sequence of text pictures of the given `layout`.
+    ///
+    /// Each frame starts with a `frame <index> @ <time>ms` line, followed by a line per row of the layout, with
+    /// `#` for an enabled pixel and `.` for a disabled one, and a blank line.
+    pub fn export<L, W>(&self, layout: &L, out: &mut W) -> fmt::Result
+    where
+        L: Layout,
+        W: fmt::Write,
+    {
+        for (index, recorded) in self.frames.iter().enumerate() {
+            writeln!(out, "frame {} @ {}ms", index, recorded.time)?;
+
+            for y in 0..layout.height() {
+                for x in 0..layout.width() {
+                    let enabled = layout
+                        .location(x, y)
+                        .is_some_and(|location| recorded.frame.led(location));
+                    write!(out, "{}", if enabled { '#' } else { '.' })?;
+                }
+                writeln!(out)?;
+            }
+
+            writeln!(out)?;
+        }
+
+        Ok(())
+    }
+
+    // Keep the display RAM as a frame at the current time, replacing a frame recorded at the same time.
+    fn record(&mut self) {
+        let recorded = RecordedFrame {
+            time: self.time,
+            frame: Frame::from_rows(self.rows),
+        };
+
+        match self.frames.last_mut() {
+            Some(last) if last.time == self.time => *last = recorded,
+            _ => self.frames.push(recorded),
+        }
+    }
+}
+
+impl Write for FrameRecorder {
+    type Error = Infallible;
+
+    fn write(&mut self, _address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        // Only display RAM writes with data change the frame.
+        if bytes.len() < 2 || bytes[0] & !DATA_ADDRESS_MASK != 0 {
+            return Ok(());
+        }
+
+        for (offset, value) in bytes[1..].iter().enumerate() {
+            let row = (bytes[0] as usize + offset) & DATA_ADDRESS_MASK as usize;
+            self.rows[row] = DisplayData::from_bits_truncate(*value);
+        }
+        self.record();
+
+        Ok(())
+    }
+}
+
+/// Lend the recorder to a driver, so that it can still be inspected after the driver is dropped.
+impl Write for &mut FrameRecorder {
+    type Error = Infallible;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        (**self).write(address, bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layouts::Raw;
+
+    #[test]
+    fn merge_same_time() {
+        let mut recorder = FrameRecorder::new();
+
+        recorder.write(0x70, &[0x00, 0x01]).unwrap();
+        recorder.write(0x70, &[0x02, 0x01]).unwrap();
+        recorder.set_time(50);
+        recorder.write(0x70, &[0x0F, 0x80, 0x80]).unwrap();
+
+        // Commands are not frames.
+        recorder.write(0x70, &[0x81]).unwrap();
+
+        let frames = recorder.frames();
+        assert_eq!(2, frames.len());
+        assert_eq!(0x01, frames[0].frame.rows()[2].bits());
+        assert_eq!(50, frames[1].time);
+        assert_eq!(0x80, frames[1].frame.rows()[15].bits());
+        assert_eq!(0x80, frames[1].frame.rows()[0].bits());
+    }
+
+    #[test]
+    fn export() {
+        let mut recorder = FrameRecorder::new();
+        recorder.write(0x70, &[0x01, 0b0000_0010]).unwrap();
+
+        let mut text = std::string::String::new();
+        recorder.export(&Raw, &mut text).unwrap();
+
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!("frame 0 @ 0ms", lines[0]);
+        assert_eq!("................", lines[1]);
+        assert_eq!(".#..............", lines[2]);
+        assert_eq!(10, lines.len());
+    }
+}
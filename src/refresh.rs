@@ -0,0 +1,172 @@
+//! # refresh
+//!
+//! Low-power refresh scheduling, e.g. for clocks.
+//!
+//! Changes accumulate in the driver's display buffer, and a
+//! [`RefreshCoalescer`](struct.RefreshCoalescer.html) flushes them at most once per interval, on a fixed
+//! cadence. Between refreshes the I2C bus is idle and the MCU can sleep until
+//! [next_refresh()](struct.RefreshCoalescer.html#method.next_refresh).
+use crate::hal::blocking::i2c::Write;
+use crate::HT16K33;
+
+/// The default refresh interval, in milliseconds.
+pub const DEFAULT_INTERVAL: u32 = 1000;
+
+/// Flushes the display at most once per interval, aligned to the first refresh.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use ht16k33::refresh::RefreshCoalescer;
+/// use ht16k33::{HT16K33, LedLocation};
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+/// let mut refresh = RefreshCoalescer::default();
+///
+/// // Called from e.g. an RTC tick interrupt.
+/// assert!(refresh.tick(&mut ht16k33, 0)?);
+///
+/// // Changes before the next refresh are only buffered.
+/// ht16k33.update_display_buffer(LedLocation::new(0, 0)?, true);
+/// assert!(!refresh.tick(&mut ht16k33, 500)?);
+/// assert_eq!(Some(1000), refresh.next_refresh());
+///
+/// assert!(refresh.tick(&mut ht16k33, 1000)?);
+///
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct RefreshCoalescer {
+    interval: u32,
+
+    // When the next refresh is due, `None` before the first refresh.
+    next_refresh: Option<u32>,
+}
+
+impl Default for RefreshCoalescer {
+    fn default() -> Self {
+        RefreshCoalescer::new(DEFAULT_INTERVAL)
+    }
+}
+
+impl RefreshCoalescer {
+    /// Create a `RefreshCoalescer`.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The minimum time between refreshes, in milliseconds.
+    pub fn new(interval: u32) -> Self {
+        RefreshCoalescer {
+            interval,
+            next_refresh: None,
+        }
+    }
+
+    /// Return the refresh interval, in milliseconds.
+    pub fn interval(&self) -> u32 {
+        self.interval
+    }
+
+    /// Return when the next refresh is due, or `None` if the next tick refreshes.
+    pub fn next_refresh(&self) -> Option<u32> {
+        self.next_refresh
+    }
+
+    /// Return whether a refresh is due at `now`.
+    pub fn is_due(&self, now: u32) -> bool {
+        match self.next_refresh {
+            // Due at or after `next_refresh`, tolerating wrap-around.
+            Some(next_refresh) => (now.wrapping_sub(next_refresh) as i32) >= 0,
+            None => true,
+        }
+    }
+
+    /// Flush the `driver` if a refresh is due at `now`, returning whether it was flushed.
+    ///
+    /// Call this from an external tick. Refreshes stay on the cadence of the first refresh, skipping any
+    /// missed intervals.
+    pub fn tick<I2C, E>(&mut self, driver: &mut HT16K33<I2C>, now: u32) -> Result<bool, E>
+    where
+        I2C: Write<Error = E>,
+    {
+        if !self.is_due(now) {
+            return Ok(false);
+        }
+
+        driver.flush()?;
+
+        let next_refresh = match self.next_refresh {
+            Some(due) if self.interval > 0 => {
+                let missed = now.wrapping_sub(due) / self.interval;
+                due.wrapping_add(self.interval.wrapping_mul(missed + 1))
+            }
+            _ => now.wrapping_add(self.interval),
+        };
+        self.next_refresh = Some(next_refresh);
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c_mock::I2cMock;
+    use crate::types::Address;
+    use crate::types::LedLocation;
+
+    fn driver() -> HT16K33<I2cMock> {
+        HT16K33::new(I2cMock::new(), Address::DEFAULT)
+            .initialize()
+            .unwrap()
+    }
+
+    #[test]
+    fn coalesce() {
+        let mut driver = driver();
+        let mut refresh = RefreshCoalescer::new(1000);
+
+        assert!(refresh.tick(&mut driver, 100).unwrap());
+
+        driver.update_display_buffer(LedLocation::new(0, 0).unwrap(), true);
+        driver.update_display_buffer(LedLocation::new(1, 0).unwrap(), true);
+        assert!(!refresh.tick(&mut driver, 600).unwrap());
+        assert!(!refresh.tick(&mut driver, 1099).unwrap());
+
+        assert!(refresh.tick(&mut driver, 1100).unwrap());
+        assert_eq!(Some(2100), refresh.next_refresh());
+
+        // The initialization, then both changes in a single write.
+        let i2c = driver.destroy();
+        assert_eq!(5, i2c.transactions().len());
+        assert_eq!(&[1, 1], i2c.transactions()[4].payload());
+    }
+
+    #[test]
+    fn skip_missed_intervals() {
+        let mut driver = driver();
+        let mut refresh = RefreshCoalescer::new(1000);
+
+        refresh.tick(&mut driver, 0).unwrap();
+
+        // Late by several intervals, the cadence is kept.
+        assert!(refresh.tick(&mut driver, 3500).unwrap());
+        assert_eq!(Some(4000), refresh.next_refresh());
+    }
+
+    #[test]
+    fn wraps() {
+        let mut driver = driver();
+        let mut refresh = RefreshCoalescer::new(1000);
+
+        refresh.tick(&mut driver, u32::MAX - 100).unwrap();
+        assert!(!refresh.tick(&mut driver, 500).unwrap());
+        assert!(refresh.tick(&mut driver, 899).unwrap());
+    }
+}
@@ -0,0 +1,215 @@
+//! # retry
+//!
+//! Retry transient I2C errors, e.g. sporadic NACKs on a long wire run to a backpack.
+//!
+//! A [`Retry`](struct.Retry.html) wraps the I2C device of a driver, see
+//! [with_retry()](../struct.HT16K33.html#method.with_retry), and repeats each transfer that fails with an error
+//! the [`RetryPolicy`](struct.RetryPolicy.html) considers transient, waiting between attempts. Other errors, and
+//! the last error once the retries are used up, are returned as is.
+use crate::hal::blocking::delay::DelayMs;
+use crate::hal::blocking::i2c::{Write, WriteRead};
+
+/// How many times, and how long apart, transient I2C errors are retried.
+///
+/// embedded-hal has no common error kinds, so the caller decides which errors are transient with the
+/// `is_transient` function.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// use embedded_hal::blocking::delay::DelayMs;
+/// use ht16k33::i2c_mock::{ErrorKind, I2cMock, I2cMockError};
+/// use ht16k33::retry::RetryPolicy;
+/// use ht16k33::HT16K33;
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// # struct Delay;
+/// # impl DelayMs<u16> for Delay {
+/// #     fn delay_ms(&mut self, _ms: u16) {}
+/// # }
+/// # let delay = Delay;
+/// let policy = RetryPolicy::new(3, 2, delay, |error: &I2cMockError| {
+///     matches!(error.kind(), ErrorKind::Nack | ErrorKind::ArbitrationLoss)
+/// });
+///
+/// let mut ht16k33 = HT16K33::new(i2c, address).with_retry(policy).initialize()?;
+///
+/// # Ok(())
+/// # }
+/// ```
+pub struct RetryPolicy<D, F> {
+    retries: u8,
+    delay_ms: u16,
+    delay: D,
+    is_transient: F,
+}
+
+impl<D, F> RetryPolicy<D, F> {
+    /// Create a `RetryPolicy`.
+    ///
+    /// # Arguments
+    ///
+    /// * `retries` - The number of attempts after the first one.
+    /// * `delay_ms` - The wait before each retry, in milliseconds.
+    /// * `delay` - The delay used to wait.
+    /// * `is_transient` - Return whether an error is worth retrying.
+    pub fn new(retries: u8, delay_ms: u16, delay: D, is_transient: F) -> Self {
+        RetryPolicy {
+            retries,
+            delay_ms,
+            delay,
+            is_transient,
+        }
+    }
+
+    /// Return the number of attempts after the first one.
+    pub fn retries(&self) -> u8 {
+        self.retries
+    }
+
+    /// Return the wait before each retry, in milliseconds.
+    pub fn delay_ms(&self) -> u16 {
+        self.delay_ms
+    }
+}
+
+/// An I2C device retrying transient errors with a [`RetryPolicy`](struct.RetryPolicy.html).
+pub struct Retry<I2C, D, F> {
+    i2c: I2C,
+    policy: RetryPolicy<D, F>,
+
+    // The number of retried transfers, to monitor the bus quality.
+    retried: u32,
+}
+
+impl<I2C, D, F> Retry<I2C, D, F> {
+    /// Wrap the `i2c` device.
+    pub fn new(i2c: I2C, policy: RetryPolicy<D, F>) -> Self {
+        Retry {
+            i2c,
+            policy,
+            retried: 0,
+        }
+    }
+
+    /// Return the number of retries so far, over all transfers.
+    pub fn retried(&self) -> u32 {
+        self.retried
+    }
+
+    /// Return the I2C device and the policy, consuming the wrapper.
+    pub fn into_inner(self) -> (I2C, RetryPolicy<D, F>) {
+        (self.i2c, self.policy)
+    }
+
+    // Run the `transfer` until it succeeds, fails with an error that is not transient, or the retries are used up.
+    fn attempt<E, T>(&mut self, mut transfer: T) -> Result<(), E>
+    where
+        D: DelayMs<u16>,
+        F: FnMut(&E) -> bool,
+        T: FnMut(&mut I2C) -> Result<(), E>,
+    {
+        let mut retries = self.policy.retries;
+        loop {
+            match transfer(&mut self.i2c) {
+                Err(error) if retries > 0 && (self.policy.is_transient)(&error) => {
+                    retries -= 1;
+                    self.retried = self.retried.saturating_add(1);
+                    self.policy.delay.delay_ms(self.policy.delay_ms);
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl<I2C, E, D, F> Write for Retry<I2C, D, F>
+where
+    I2C: Write<Error = E>,
+    D: DelayMs<u16>,
+    F: FnMut(&E) -> bool,
+{
+    type Error = E;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), E> {
+        self.attempt(|i2c| i2c.write(address, bytes))
+    }
+}
+
+impl<I2C, E, D, F> WriteRead for Retry<I2C, D, F>
+where
+    I2C: WriteRead<Error = E>,
+    D: DelayMs<u16>,
+    F: FnMut(&E) -> bool,
+{
+    type Error = E;
+
+    fn write_read(&mut self, address: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), E> {
+        self.attempt(|i2c| i2c.write_read(address, bytes, buffer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c_mock::{ErrorKind, I2cMock, I2cMockError};
+    use crate::types::Address;
+
+    struct Delay {
+        total: u32,
+    }
+
+    impl DelayMs<u16> for Delay {
+        fn delay_ms(&mut self, ms: u16) {
+            self.total += ms as u32;
+        }
+    }
+
+    fn is_nack(error: &I2cMockError) -> bool {
+        error.kind() == ErrorKind::Nack
+    }
+
+    #[test]
+    fn retry_transient() {
+        let mut i2c = I2cMock::new();
+        i2c.fail_next_write(ErrorKind::Nack);
+
+        let policy = RetryPolicy::new(2, 5, Delay { total: 0 }, is_nack);
+        let mut retry = Retry::new(i2c, policy);
+        retry.write(0x70, &[0x00, 0x01]).unwrap();
+
+        assert_eq!(1, retry.retried());
+        let (i2c, policy) = retry.into_inner();
+        assert_eq!(5, policy.delay.total);
+        assert_eq!(0x01, i2c.device(Address::DEFAULT).data_values[0]);
+    }
+
+    #[test]
+    fn other_errors_are_not_retried() {
+        let mut i2c = I2cMock::new();
+        i2c.fail_next_write(ErrorKind::Bus);
+
+        let policy = RetryPolicy::new(2, 5, Delay { total: 0 }, is_nack);
+        let mut retry = Retry::new(i2c, policy);
+
+        let error = retry.write(0x70, &[0x00, 0x01]).unwrap_err();
+        assert_eq!(ErrorKind::Bus, error.kind());
+        assert_eq!(0, retry.retried());
+    }
+
+    #[test]
+    fn retries_used_up() {
+        let mut i2c = I2cMock::new();
+        i2c.fail_next_write_read(ErrorKind::Nack);
+
+        let policy = RetryPolicy::new(0, 5, Delay { total: 0 }, is_nack);
+        let mut retry = Retry::new(i2c, policy);
+
+        let mut buffer = [0u8; 1];
+        assert!(retry.write_read(0x70, &[0x00], &mut buffer).is_err());
+        assert_eq!(0, retry.retried());
+    }
+}
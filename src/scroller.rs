@@ -0,0 +1,935 @@
+//! # scroller
+//!
+//! Marquee-style scrolling text for matrix, 7-segment and 16-segment displays.
+//!
+//! Text is turned into a strip of cells, one per matrix column or segment digit, and a
+//! [`Scroller`](struct.Scroller.html) moves a window the width of the display along the strip. The
+//! [`ScrollDisplay`](trait.ScrollDisplay.html) trait provides the glyphs of each display type and draws the
+//! visible cells into the display buffer.
+//!
+//! Characters can blink individually, e.g. the alarm digit of a clock while the others stay solid, see
+//! [set_blink_mask()](struct.Scroller.html#method.set_blink_mask).
+//!
+//! A [`MessageQueue`](struct.MessageQueue.html) feeds a scroller with prioritized messages, e.g. for status
+//! signs mixing routine and urgent notices.
+use crate::constants::ROWS_SIZE;
+use crate::layouts::Layout;
+use crate::seven_segment::{Segments, DIGITS_SIZE, DIGIT_ROWS};
+use crate::sixteen_segment;
+use crate::types::DisplayData;
+
+/// The maximum number of cells in a single glyph.
+pub const MAX_GLYPH_WIDTH: usize = 8;
+
+/// The default blink period of the blinking characters, in milliseconds.
+pub const DEFAULT_BLINK: u32 = 500;
+
+/// The number of characters of the text that can blink, one per bit of the
+/// [blink mask](struct.Scroller.html#method.set_blink_mask).
+pub const BLINK_MASK_SIZE: usize = 32;
+
+/// A display that text can be scrolled across.
+pub trait ScrollDisplay {
+    /// Return the number of cells visible at once.
+    fn width(&self) -> u8;
+
+    /// Write the cells of the character `c` into `cells`, returning the number of cells written.
+    ///
+    /// A cell is a matrix column or the segments of a digit, the bits used depend on the display.
+    fn glyph(&self, c: char, cells: &mut [u16; MAX_GLYPH_WIDTH]) -> usize;
+
+    /// Draw the `cell` at the visible position `slot`, replacing what was there.
+    fn draw(&self, slot: u8, cell: u16, buffer: &mut [DisplayData; ROWS_SIZE]);
+}
+
+/// A 4-digit 7-segment backpack, one character per digit.
+///
+/// Characters are shown using [Segments::from_char()](../seven_segment/struct.Segments.html#method.from_char),
+/// unsupported characters are blank.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SegmentDisplay;
+
+impl ScrollDisplay for SegmentDisplay {
+    fn width(&self) -> u8 {
+        DIGITS_SIZE as u8
+    }
+
+    fn glyph(&self, c: char, cells: &mut [u16; MAX_GLYPH_WIDTH]) -> usize {
+        cells[0] = Segments::from_char(c)
+            .unwrap_or_else(Segments::empty)
+            .bits() as u16;
+        1
+    }
+
+    fn draw(&self, slot: u8, cell: u16, buffer: &mut [DisplayData; ROWS_SIZE]) {
+        if let Some(row) = DIGIT_ROWS.get(slot as usize) {
+            buffer[*row] = DisplayData::from_bits_truncate(cell as u8);
+        }
+    }
+}
+
+/// A 16-segment display, one character per digit.
+///
+/// Characters are shown using
+/// [Segments::from_char()](../sixteen_segment/struct.Segments.html#method.from_char), unsupported characters are
+/// blank.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SixteenSegmentDisplay;
+
+impl ScrollDisplay for SixteenSegmentDisplay {
+    fn width(&self) -> u8 {
+        sixteen_segment::DIGITS_SIZE as u8
+    }
+
+    fn glyph(&self, c: char, cells: &mut [u16; MAX_GLYPH_WIDTH]) -> usize {
+        cells[0] = sixteen_segment::Segments::from_char(c)
+            .unwrap_or_else(sixteen_segment::Segments::empty)
+            .bits();
+        1
+    }
+
+    fn draw(&self, slot: u8, cell: u16, buffer: &mut [DisplayData; ROWS_SIZE]) {
+        let row = slot as usize * 2;
+        if row < ROWS_SIZE {
+            let [low, high] = sixteen_segment::Segments::from_bits_truncate(cell).to_rows();
+            buffer[row] = DisplayData::from_bits_truncate(low);
+            buffer[row + 1] = DisplayData::from_bits_truncate(high);
+        }
+    }
+}
+
+/// The height of the [`MatrixDisplay`](struct.MatrixDisplay.html) font, in pixels.
+pub const FONT_HEIGHT: u8 = 5;
+
+// A 3x5 font of digits, upper case letters and some punctuation. Each glyph is 3 columns, with the top pixel
+// in the least significant bit.
+const FONT: [(char, [u8; 3]); 41] = [
+    ('0', [0x1f, 0x11, 0x1f]),
+    ('1', [0x12, 0x1f, 0x10]),
+    ('2', [0x19, 0x15, 0x12]),
+    ('3', [0x11, 0x15, 0x0a]),
+    ('4', [0x07, 0x04, 0x1f]),
+    ('5', [0x17, 0x15, 0x09]),
+    ('6', [0x1e, 0x15, 0x1d]),
+    ('7', [0x01, 0x1d, 0x03]),
+    ('8', [0x1f, 0x15, 0x1f]),
+    ('9', [0x17, 0x15, 0x0f]),
+    ('A', [0x1e, 0x05, 0x1e]),
+    ('B', [0x1f, 0x15, 0x0a]),
+    ('C', [0x0e, 0x11, 0x11]),
+    ('D', [0x1f, 0x11, 0x0e]),
+    ('E', [0x1f, 0x15, 0x11]),
+    ('F', [0x1f, 0x05, 0x01]),
+    ('G', [0x0e, 0x11, 0x1d]),
+    ('H', [0x1f, 0x04, 0x1f]),
+    ('I', [0x11, 0x1f, 0x11]),
+    ('J', [0x08, 0x10, 0x0f]),
+    ('K', [0x1f, 0x04, 0x1b]),
+    ('L', [0x1f, 0x10, 0x10]),
+    ('M', [0x1f, 0x06, 0x1f]),
+    ('N', [0x1f, 0x01, 0x1e]),
+    ('O', [0x0e, 0x11, 0x0e]),
+    ('P', [0x1f, 0x05, 0x02]),
+    ('Q', [0x0e, 0x19, 0x16]),
+    ('R', [0x1f, 0x05, 0x1a]),
+    ('S', [0x12, 0x15, 0x09]),
+    ('T', [0x01, 0x1f, 0x01]),
+    ('U', [0x1f, 0x10, 0x1f]),
+    ('V', [0x0f, 0x10, 0x0f]),
+    ('W', [0x1f, 0x0c, 0x1f]),
+    ('X', [0x1b, 0x04, 0x1b]),
+    ('Y', [0x03, 0x1c, 0x03]),
+    ('Z', [0x19, 0x15, 0x13]),
+    ('-', [0x04, 0x04, 0x04]),
+    ('.', [0x00, 0x10, 0x00]),
+    (':', [0x00, 0x0a, 0x00]),
+    ('!', [0x00, 0x17, 0x00]),
+    ('?', [0x01, 0x15, 0x02]),
+];
+
+/// A matrix with the given layout, using a built-in 3x5 font with one blank column between characters.
+///
+/// Lower case letters are shown in upper case, and unsupported characters are blank.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct MatrixDisplay<L> {
+    layout: L,
+
+    // The row of the top pixel of the font.
+    top: u8,
+}
+
+impl<L> MatrixDisplay<L>
+where
+    L: Layout,
+{
+    /// Create a `MatrixDisplay`, with the text vertically centered.
+    pub fn new(layout: L) -> Self {
+        let top = layout.height().saturating_sub(FONT_HEIGHT) / 2;
+        MatrixDisplay { layout, top }
+    }
+
+    /// Return the layout.
+    pub fn layout(&self) -> &L {
+        &self.layout
+    }
+}
+
+impl<L> ScrollDisplay for MatrixDisplay<L>
+where
+    L: Layout,
+{
+    fn width(&self) -> u8 {
+        self.layout.width()
+    }
+
+    fn glyph(&self, c: char, cells: &mut [u16; MAX_GLYPH_WIDTH]) -> usize {
+        let c = c.to_ascii_uppercase();
+        let columns = FONT
+            .iter()
+            .find(|(glyph, _)| *glyph == c)
+            .map(|(_, columns)| *columns)
+            .unwrap_or([0; 3]);
+
+        for (cell, column) in cells.iter_mut().zip(columns.iter()) {
+            *cell = *column as u16;
+        }
+        cells[3] = 0;
+        4
+    }
+
+    fn draw(&self, slot: u8, cell: u16, buffer: &mut [DisplayData; ROWS_SIZE]) {
+        for y in 0..FONT_HEIGHT {
+            if let Some(location) = self.layout.location(slot, self.top + y) {
+                buffer[location.row_as_index()].set(location.common, cell & (1 << y) != 0);
+            }
+        }
+    }
+}
+
+/// The direction the text moves in.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Direction {
+    /// Text enters on the right and moves left.
+    #[default]
+    Left,
+    /// Text enters on the left and moves right.
+    Right,
+}
+
+/// Scrolls a string across a display.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use ht16k33::HT16K33;
+/// use ht16k33::scroller::{Scroller, SegmentDisplay};
+/// use ht16k33::seven_segment::Segments;
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+///
+/// let mut scroller = Scroller::new("HELLO", SegmentDisplay);
+/// scroller.set_wrap(true);
+///
+/// // The text enters from the right, one character per step, "HELL" is shown after 4 steps.
+/// for _ in 0..4 {
+///     scroller.step();
+/// }
+/// scroller.render(ht16k33.display_buffer_mut());
+/// ht16k33.write_display_buffer()?;
+///
+/// assert_eq!(Segments::DIGIT_E.bits(), ht16k33.display_buffer()[2].bits());
+///
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Scroller<'a, D> {
+    text: &'a str,
+    display: D,
+    direction: Direction,
+    wrap: bool,
+
+    // Blank cells before the text, or after it when scrolling right.
+    padding: u8,
+
+    // Number of steps taken.
+    offset: usize,
+
+    // The blinking characters, bit `n` for the character `n`, see `BLINK_MASK_SIZE`.
+    blink_mask: u32,
+    blink_period: u32,
+
+    // Whether the blinking characters are in their hidden phase.
+    blink_hidden: bool,
+}
+
+impl<'a, D> Scroller<'a, D>
+where
+    D: ScrollDisplay,
+{
+    /// Create a `Scroller` moving left without wrap-around, padded by the display width so the text enters
+    /// from the edge.
+    pub fn new(text: &'a str, display: D) -> Self {
+        let padding = display.width();
+
+        Scroller {
+            text,
+            display,
+            direction: Direction::Left,
+            wrap: false,
+            padding,
+            offset: 0,
+            blink_mask: 0,
+            blink_period: DEFAULT_BLINK,
+            blink_hidden: false,
+        }
+    }
+
+    /// Return the display.
+    pub fn display(&self) -> &D {
+        &self.display
+    }
+
+    /// Return the text.
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// Replace the text, and restart scrolling.
+    pub fn set_text(&mut self, text: &'a str) {
+        self.text = text;
+        self.reset();
+    }
+
+    /// Return the scroll direction.
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Set the scroll direction, and restart scrolling.
+    pub fn set_direction(&mut self, direction: Direction) {
+        self.direction = direction;
+        self.reset();
+    }
+
+    /// Return whether the text wraps around.
+    pub fn wrap(&self) -> bool {
+        self.wrap
+    }
+
+    /// Set whether the text wraps around, repeating after the padding.
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap;
+    }
+
+    /// Return the number of blank cells before the text.
+    pub fn padding(&self) -> u8 {
+        self.padding
+    }
+
+    /// Set the number of blank cells before the text, which also separate repetitions when wrapping around.
+    pub fn set_padding(&mut self, padding: u8) {
+        self.padding = padding;
+    }
+
+    /// Return the number of steps taken.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Restart scrolling.
+    pub fn reset(&mut self) {
+        self.offset = 0;
+    }
+
+    /// Continue scrolling from the given number of steps, e.g. to resume a previous text.
+    pub fn set_offset(&mut self, offset: usize) {
+        self.offset = offset;
+    }
+
+    /// Return the blinking characters, bit `n` for the character `n` of the text.
+    pub fn blink_mask(&self) -> u32 {
+        self.blink_mask
+    }
+
+    /// Set the blinking characters, bit `n` for the character `n` of the text.
+    ///
+    /// The blinking characters are blank during the second half of each blink period, toggled by
+    /// [tick()](struct.Scroller.html#method.tick), while the others stay solid. Unlike the chip's blink setting,
+    /// which blinks the whole display, this only affects the rendered characters, and both can be combined.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, if characters blink in a text longer than [`BLINK_MASK_SIZE`], as the characters past it
+    /// could not blink.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ht16k33::scroller::{Scroller, SegmentDisplay};
+    /// use ht16k33::seven_segment::Segments;
+    /// use ht16k33::{DisplayData, ROWS_SIZE};
+    ///
+    /// let mut scroller = Scroller::new("AL 7", SegmentDisplay);
+    /// scroller.set_padding(0);
+    /// scroller.set_blink_mask(1 << 3);
+    ///
+    /// let mut buffer = [DisplayData::empty(); ROWS_SIZE];
+    /// scroller.tick(250);
+    /// scroller.render(&mut buffer);
+    /// assert_eq!(0, buffer[8].bits());
+    ///
+    /// scroller.tick(500);
+    /// scroller.render(&mut buffer);
+    /// assert_eq!(Segments::DIGIT_7.bits(), buffer[8].bits());
+    /// ```
+    ///
+    /// [`BLINK_MASK_SIZE`]: constant.BLINK_MASK_SIZE.html
+    pub fn set_blink_mask(&mut self, blink_mask: u32) {
+        debug_assert!(
+            blink_mask == 0 || self.text.chars().count() <= BLINK_MASK_SIZE,
+            "only the first {} characters can blink",
+            BLINK_MASK_SIZE
+        );
+        self.blink_mask = blink_mask;
+    }
+
+    /// Return the blink period of the blinking characters, in milliseconds.
+    pub fn blink_period(&self) -> u32 {
+        self.blink_period
+    }
+
+    /// Set the blink period of the blinking characters, in milliseconds.
+    pub fn set_blink_period(&mut self, blink_period: u32) {
+        self.blink_period = blink_period;
+    }
+
+    /// Toggle the blinking characters at `now`, returning whether they changed and the text must be rendered.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time in milliseconds, wrapping on overflow.
+    pub fn tick(&mut self, now: u32) -> bool {
+        let period = self.blink_period.max(1);
+        let hidden = now % period >= period / 2;
+
+        let changed = hidden != self.blink_hidden;
+        self.blink_hidden = hidden;
+
+        changed && self.blink_mask != 0
+    }
+
+    /// Return the number of cells of the padded text.
+    pub fn length(&self) -> usize {
+        self.padding as usize + self.text_length()
+    }
+
+    /// Return whether the text has scrolled out of view, never when wrapping around.
+    pub fn is_finished(&self) -> bool {
+        !self.wrap && self.offset >= self.length()
+    }
+
+    /// Advance the text by one cell, a column on matrices or a character on 7-segment displays.
+    ///
+    /// Returns `false` without advancing once the text [is_finished()](struct.Scroller.html#method.is_finished).
+    pub fn step(&mut self) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+
+        self.offset += 1;
+        if self.wrap && self.length() > 0 {
+            self.offset %= self.length();
+        }
+
+        true
+    }
+
+    /// Draw the visible cells into the display `buffer`.
+    pub fn render(&self, buffer: &mut [DisplayData; ROWS_SIZE]) {
+        let length = self.length() as isize;
+        let width = self.display.width();
+
+        for slot in 0..width {
+            let index = match self.direction {
+                Direction::Left => self.offset as isize + slot as isize,
+                Direction::Right => length - width as isize - self.offset as isize + slot as isize,
+            };
+
+            let index = if self.wrap && length > 0 {
+                Some(index.rem_euclid(length) as usize)
+            } else if index >= 0 && index < length {
+                Some(index as usize)
+            } else {
+                None
+            };
+
+            let cell = index.map(|index| self.cell(index)).unwrap_or(0);
+            self.display.draw(slot, cell, buffer);
+        }
+    }
+
+    // Return the number of cells of the text, without padding.
+    fn text_length(&self) -> usize {
+        let mut cells = [0; MAX_GLYPH_WIDTH];
+        self.text
+            .chars()
+            .map(|c| self.display.glyph(c, &mut cells))
+            .sum()
+    }
+
+    // Return the cell at `index` of the padded text.
+    fn cell(&self, index: usize) -> u16 {
+        let index = match self.direction {
+            Direction::Left if index < self.padding as usize => return 0,
+            Direction::Left => index - self.padding as usize,
+            Direction::Right => index,
+        };
+
+        let mut cells = [0; MAX_GLYPH_WIDTH];
+        let mut start = 0;
+        for (position, c) in self.text.chars().enumerate() {
+            let width = self.display.glyph(c, &mut cells);
+            if index < start + width {
+                if self.blink_hidden
+                    && position < BLINK_MASK_SIZE
+                    && self.blink_mask & (1 << position) != 0
+                {
+                    return 0;
+                }
+                return cells[index - start];
+            }
+            start += width;
+        }
+
+        // Trailing padding when scrolling right.
+        0
+    }
+}
+
+/// A message for a [`MessageQueue`](struct.MessageQueue.html).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Message<'a> {
+    text: &'a str,
+    priority: u8,
+}
+
+impl<'a> Message<'a> {
+    /// Create a `Message`, higher `priority` messages preempt lower ones.
+    pub fn new(text: &'a str, priority: u8) -> Self {
+        Message { text, priority }
+    }
+
+    /// Return the text.
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// Return the priority.
+    pub fn priority(&self) -> u8 {
+        self.priority
+    }
+}
+
+// A queued message, with the scroll position to resume it at and its order of arrival.
+#[derive(Clone, Copy, Debug)]
+struct Slot<'a> {
+    message: Message<'a>,
+    offset: usize,
+    sequence: u32,
+}
+
+/// Up to `N` messages shown one at a time on a [`Scroller`](struct.Scroller.html), highest priority first.
+///
+/// Messages of the same priority are shown in the order they were pushed. A message preempted by a higher
+/// priority message resumes where it was interrupted. Each message is shown once, so the scroller should not
+/// wrap around.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::scroller::{Message, MessageQueue, Scroller, SegmentDisplay};
+///
+/// let mut scroller = Scroller::new("", SegmentDisplay);
+/// let mut queue: MessageQueue<4> = MessageQueue::new();
+///
+/// queue.push(Message::new("OPEN", 0)).unwrap();
+/// queue.step(&mut scroller);
+///
+/// // Shown right away, then "OPEN" resumes.
+/// queue.push(Message::new("FIRE", 9)).unwrap();
+/// queue.step(&mut scroller);
+/// assert_eq!("FIRE", scroller.text());
+///
+/// while queue.current().map(|message| message.priority()) == Some(9) {
+///     queue.step(&mut scroller);
+/// }
+/// assert_eq!("OPEN", scroller.text());
+///
+/// // Resumed after its first step, and advanced by the step that finished "FIRE".
+/// assert_eq!(2, scroller.offset());
+/// ```
+#[derive(Clone, Debug)]
+pub struct MessageQueue<'a, const N: usize> {
+    slots: [Option<Slot<'a>>; N],
+
+    // The slot of the message on the scroller.
+    current: Option<usize>,
+    sequence: u32,
+}
+
+impl<const N: usize> Default for MessageQueue<'_, N> {
+    fn default() -> Self {
+        MessageQueue::new()
+    }
+}
+
+impl<'a, const N: usize> MessageQueue<'a, N> {
+    /// Create an empty `MessageQueue`.
+    pub fn new() -> Self {
+        MessageQueue {
+            slots: [None; N],
+            current: None,
+            sequence: 0,
+        }
+    }
+
+    /// Return the number of queued messages, including the current one.
+    pub fn pending(&self) -> usize {
+        self.slots.iter().filter(|slot| slot.is_some()).count()
+    }
+
+    /// Return the message on the scroller.
+    pub fn current(&self) -> Option<&Message<'a>> {
+        self.current
+            .and_then(|index| self.slots[index].as_ref())
+            .map(|slot| &slot.message)
+    }
+
+    /// Queue a `message`, returning it if the queue is full.
+    ///
+    /// The message is shown from the next [step()](struct.MessageQueue.html#method.step) if it has a higher
+    /// priority than the current message.
+    pub fn push(&mut self, message: Message<'a>) -> Result<(), Message<'a>> {
+        let slot = match self.slots.iter_mut().find(|slot| slot.is_none()) {
+            Some(slot) => slot,
+            None => return Err(message),
+        };
+
+        *slot = Some(Slot {
+            message,
+            offset: 0,
+            sequence: self.sequence,
+        });
+        self.sequence = self.sequence.wrapping_add(1);
+
+        Ok(())
+    }
+
+    /// Remove all messages, the scroller keeps its text.
+    pub fn clear(&mut self) {
+        self.slots = [None; N];
+        self.current = None;
+    }
+
+    /// Show the highest priority message on the `scroller` and advance it by one cell.
+    ///
+    /// Finished messages are removed, and the next message is loaded. Returns whether a message is shown.
+    pub fn step<D>(&mut self, scroller: &mut Scroller<'a, D>) -> bool
+    where
+        D: ScrollDisplay,
+    {
+        self.select(scroller);
+
+        let index = match self.current {
+            Some(index) => index,
+            None => return false,
+        };
+
+        if scroller.step() {
+            return true;
+        }
+
+        self.slots[index] = None;
+        self.current = None;
+        self.select(scroller);
+
+        match self.current {
+            Some(_) => scroller.step(),
+            None => false,
+        }
+    }
+
+    // Load the highest priority message onto the `scroller`, saving the position of a preempted message.
+    fn select<D>(&mut self, scroller: &mut Scroller<'a, D>)
+    where
+        D: ScrollDisplay,
+    {
+        let sequence = self.sequence;
+        let next = self
+            .slots
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| slot.map(|slot| (index, slot)))
+            // Highest priority, then oldest, tolerating wrap-around of the sequence.
+            .max_by_key(|(_, slot)| (slot.message.priority, sequence.wrapping_sub(slot.sequence)))
+            .map(|(index, _)| index);
+
+        if next == self.current {
+            return;
+        }
+
+        if let Some(Some(slot)) = self.current.map(|index| &mut self.slots[index]) {
+            slot.offset = scroller.offset();
+        }
+
+        if let Some(Some(slot)) = next.map(|index| self.slots[index]) {
+            scroller.set_text(slot.message.text);
+            scroller.set_offset(slot.offset);
+        }
+
+        self.current = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layouts::Matrix8x8;
+
+    fn digits(scroller: &Scroller<SegmentDisplay>) -> [Option<char>; DIGITS_SIZE] {
+        let mut buffer = [DisplayData::COMMON_NONE; ROWS_SIZE];
+        scroller.render(&mut buffer);
+
+        let mut digits = [None; DIGITS_SIZE];
+        for (digit, row) in digits.iter_mut().zip(DIGIT_ROWS.iter()) {
+            if buffer[*row].bits() != 0 {
+                *digit = "0123456789ABCDEFHLOPr"
+                    .chars()
+                    .find(|c| Segments::from_char(*c).unwrap().bits() == buffer[*row].bits());
+            }
+        }
+        digits
+    }
+
+    #[test]
+    fn blink_mask() {
+        let mut scroller = Scroller::new("HELP", SegmentDisplay);
+        scroller.set_blink_mask(0b0100);
+        scroller.set_blink_period(1000);
+        for _ in 0..3 {
+            scroller.step();
+        }
+
+        // The blinking character moves with the text.
+        assert!(!scroller.tick(0));
+        assert_eq!([None, Some('H'), Some('E'), Some('L')], digits(&scroller));
+        assert!(scroller.tick(500));
+        assert_eq!([None, Some('H'), Some('E'), None], digits(&scroller));
+
+        scroller.step();
+        assert!(!scroller.tick(999));
+        assert_eq!([Some('H'), Some('E'), None, Some('P')], digits(&scroller));
+        assert!(scroller.tick(1000));
+        assert_eq!(
+            [Some('H'), Some('E'), Some('L'), Some('P')],
+            digits(&scroller)
+        );
+    }
+
+    #[test]
+    fn blink_sixteen_segment() {
+        use crate::sixteen_segment::Segments;
+
+        let mut scroller = Scroller::new("ALARM ON", SixteenSegmentDisplay);
+        scroller.set_padding(0);
+        scroller.set_blink_mask(0b1100_0000);
+
+        let mut buffer = [DisplayData::empty(); ROWS_SIZE];
+        let digit = |buffer: &[DisplayData; ROWS_SIZE], index: usize| {
+            Segments::from_rows([buffer[index * 2].bits(), buffer[index * 2 + 1].bits()])
+        };
+
+        scroller.tick(0);
+        scroller.render(&mut buffer);
+        assert_eq!(Segments::from_char('M'), Some(digit(&buffer, 4)));
+        assert_eq!(Segments::from_char('N'), Some(digit(&buffer, 7)));
+
+        scroller.tick(250);
+        scroller.render(&mut buffer);
+        assert_eq!(Segments::from_char('M'), Some(digit(&buffer, 4)));
+        assert!(digit(&buffer, 6).is_empty());
+        assert!(digit(&buffer, 7).is_empty());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "only the first 32 characters can blink")]
+    fn blink_long_text() {
+        let mut scroller = Scroller::new("THIS MESSAGE IS TOO LONG TO BLINK", SegmentDisplay);
+        scroller.set_blink_mask(1);
+    }
+
+    #[test]
+    fn scroll_left() {
+        let mut scroller = Scroller::new("HELP", SegmentDisplay);
+        assert_eq!(8, scroller.length());
+
+        assert_eq!([None; 4], digits(&scroller));
+
+        scroller.step();
+        assert_eq!([None, None, None, Some('H')], digits(&scroller));
+
+        for _ in 0..3 {
+            scroller.step();
+        }
+        assert_eq!(
+            [Some('H'), Some('E'), Some('L'), Some('P')],
+            digits(&scroller)
+        );
+
+        for _ in 0..4 {
+            assert!(scroller.step());
+        }
+        assert_eq!([None; 4], digits(&scroller));
+        assert!(scroller.is_finished());
+        assert!(!scroller.step());
+    }
+
+    #[test]
+    fn scroll_right() {
+        let mut scroller = Scroller::new("HELP", SegmentDisplay);
+        scroller.set_direction(Direction::Right);
+
+        assert_eq!([None; 4], digits(&scroller));
+
+        scroller.step();
+        assert_eq!([Some('P'), None, None, None], digits(&scroller));
+
+        scroller.step();
+        assert_eq!([Some('L'), Some('P'), None, None], digits(&scroller));
+    }
+
+    #[test]
+    fn wrap() {
+        let mut scroller = Scroller::new("10", SegmentDisplay);
+        scroller.set_padding(1);
+        scroller.set_wrap(true);
+
+        assert_eq!([None, Some('1'), Some('0'), None], digits(&scroller));
+
+        scroller.step();
+        assert_eq!([Some('1'), Some('0'), None, Some('1')], digits(&scroller));
+
+        for _ in 0..10 {
+            assert!(scroller.step());
+        }
+        assert!(!scroller.is_finished());
+        assert!(scroller.offset() < scroller.length());
+    }
+
+    #[test]
+    fn empty() {
+        let mut scroller = Scroller::new("", SegmentDisplay);
+        scroller.set_padding(0);
+        scroller.set_wrap(true);
+
+        scroller.step();
+        assert_eq!([None; 4], digits(&scroller));
+    }
+
+    #[test]
+    fn matrix() {
+        let display = MatrixDisplay::new(Matrix8x8);
+        let mut scroller = Scroller::new("1", display);
+        scroller.set_padding(0);
+
+        // 3 columns and a blank column.
+        assert_eq!(4, scroller.length());
+
+        let mut buffer = [DisplayData::all(); ROWS_SIZE];
+        scroller.render(&mut buffer);
+
+        // The top pixel of the font is row 1, and only the middle column of `1` is lit there.
+        let pixel = |buffer: &[DisplayData; ROWS_SIZE], x, y| {
+            let location = Matrix8x8.location(x, y).unwrap();
+            buffer[location.row_as_index()].contains(location.common)
+        };
+        assert!(!pixel(&buffer, 0, 1));
+        assert!(pixel(&buffer, 1, 1));
+        assert!(!pixel(&buffer, 2, 1));
+        assert!(pixel(&buffer, 0, 2));
+
+        // Rows outside the font are untouched.
+        assert!(pixel(&buffer, 0, 0));
+
+        scroller.step();
+        scroller.render(&mut buffer);
+        assert!(pixel(&buffer, 0, 1));
+    }
+
+    #[test]
+    fn matrix_lower_case() {
+        let display = MatrixDisplay::new(Matrix8x8);
+
+        let mut upper = [0; MAX_GLYPH_WIDTH];
+        let mut lower = [0; MAX_GLYPH_WIDTH];
+        display.glyph('A', &mut upper);
+        display.glyph('a', &mut lower);
+
+        assert_eq!(upper, lower);
+        assert_ne!([0; MAX_GLYPH_WIDTH], upper);
+    }
+
+    #[test]
+    fn message_queue_order() {
+        let mut scroller = Scroller::new("", SegmentDisplay);
+        let mut queue: MessageQueue<3> = MessageQueue::new();
+
+        queue.push(Message::new("A", 1)).unwrap();
+        queue.push(Message::new("B", 5)).unwrap();
+        queue.push(Message::new("C", 1)).unwrap();
+        assert_eq!(Err(Message::new("D", 9)), queue.push(Message::new("D", 9)));
+
+        let mut shown = [""; 3];
+        let mut count = 0;
+        while queue.step(&mut scroller) {
+            if shown[..count].last() != Some(&scroller.text()) {
+                shown[count] = scroller.text();
+                count += 1;
+            }
+        }
+
+        assert_eq!(["B", "A", "C"], shown);
+        assert_eq!(0, queue.pending());
+        assert!(queue.current().is_none());
+    }
+
+    #[test]
+    fn message_queue_preempt() {
+        let mut scroller = Scroller::new("", SegmentDisplay);
+        let mut queue: MessageQueue<2> = MessageQueue::new();
+
+        queue.push(Message::new("HELLO", 0)).unwrap();
+        for _ in 0..3 {
+            queue.step(&mut scroller);
+        }
+
+        queue.push(Message::new("E", 1)).unwrap();
+        queue.step(&mut scroller);
+        assert_eq!("E", scroller.text());
+        assert_eq!(1, scroller.offset());
+
+        // "E" is 5 cells with padding, the last step loads and advances "HELLO".
+        for _ in 0..5 {
+            queue.step(&mut scroller);
+        }
+        assert_eq!("HELLO", scroller.text());
+        assert_eq!(4, scroller.offset());
+    }
+}
@@ -0,0 +1,254 @@
+//! # self_test
+//!
+//! Check the wiring of a display with a single call, by showing test patterns and reading the display RAM back.
+use crate::constants::ROWS_SIZE;
+use crate::errors::Error;
+use crate::hal::blocking::delay::DelayMs;
+use crate::hal::blocking::i2c::{Write, WriteRead};
+use crate::types::{Dimming, Display, DisplayData, DisplayDataAddress};
+use crate::HT16K33;
+
+/// The result of a [self_test()](fn.self_test.html).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SelfTestReport {
+    /// The number of patterns written and read back.
+    pub patterns: u8,
+    /// The bits of each display RAM row that read back differently from a written pattern.
+    pub mismatches: [DisplayData; ROWS_SIZE],
+}
+
+impl SelfTestReport {
+    /// Return whether every pattern read back as written.
+    pub fn passed(&self) -> bool {
+        self.mismatches.iter().all(|row| row.is_empty())
+    }
+
+    /// Return the rows that read back differently from a written pattern, bit `n` for row `n`.
+    pub fn failed_rows(&self) -> u16 {
+        self.mismatches
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| !row.is_empty())
+            .fold(0, |rows, (index, _)| rows | (1 << index))
+    }
+}
+
+/// Show the test patterns on the display, and check that the display RAM reads back as written.
+///
+/// The patterns are shown in order, each for `step_duration` milliseconds:
+///
+/// 1. All LEDs on.
+/// 2. Each row of the [package](../struct.HT16K33.html#method.package) on, one at a time.
+/// 3. All LEDs on, through each of the 16 dimming levels.
+///
+/// The patterns are written to the display RAM as is, ignoring the rotation and flip of the driver. The dimming,
+/// display setting and display buffer are restored afterwards, also when the test fails with an error.
+///
+/// *Requires an I2C device implementing `WriteRead`.*
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use embedded_hal::blocking::delay::DelayMs;
+/// use ht16k33::self_test::self_test;
+/// use ht16k33::HT16K33;
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// # struct Delay;
+/// # impl DelayMs<u16> for Delay {
+/// #     fn delay_ms(&mut self, _ms: u16) {}
+/// # }
+/// # let mut delay = Delay;
+/// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+///
+/// let report = self_test(&mut ht16k33, 250, &mut delay)?;
+/// assert!(report.passed());
+///
+/// # Ok(())
+/// # }
+/// ```
+pub fn self_test<I2C, E, D>(
+    driver: &mut HT16K33<I2C>,
+    step_duration: u16,
+    delay: &mut D,
+) -> Result<SelfTestReport, Error<E>>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    D: DelayMs<u16>,
+{
+    let dimming = *driver.dimming();
+    let display = *driver.display();
+
+    let mut report = SelfTestReport {
+        patterns: 0,
+        mismatches: [DisplayData::empty(); ROWS_SIZE],
+    };
+
+    let result = show_patterns(driver, step_duration, delay, &mut report);
+
+    // Restore the settings even after a failure, so the test patterns are not left on the display.
+    let dimming_result = driver.set_dimming(dimming);
+    let display_result = driver.set_display(display);
+    let flush_result = driver.flush_full();
+
+    result?;
+    dimming_result
+        .and(display_result)
+        .and(flush_result)
+        .map_err(Error::I2c)?;
+
+    Ok(report)
+}
+
+// Show the test patterns in order, stopping at the first error.
+fn show_patterns<I2C, E, D>(
+    driver: &mut HT16K33<I2C>,
+    step_duration: u16,
+    delay: &mut D,
+    report: &mut SelfTestReport,
+) -> Result<(), Error<E>>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    D: DelayMs<u16>,
+{
+    driver.set_display(Display::ON).map_err(Error::I2c)?;
+
+    let rows = driver.package().rows();
+    let mut all_on = [0u8; ROWS_SIZE];
+    all_on[..rows].iter_mut().for_each(|row| *row = 0xFF);
+
+    check_pattern(driver, &all_on, report)?;
+    delay.delay_ms(step_duration);
+
+    for row in 0..rows {
+        let mut pattern = [0u8; ROWS_SIZE];
+        pattern[row] = 0xFF;
+
+        check_pattern(driver, &pattern, report)?;
+        delay.delay_ms(step_duration);
+    }
+
+    check_pattern(driver, &all_on, report)?;
+    for level in 0..16 {
+        driver
+            .set_dimming(Dimming::from_bits_truncate(level))
+            .map_err(Error::I2c)?;
+        delay.delay_ms(step_duration);
+    }
+
+    Ok(())
+}
+
+// Write the `pattern` to the display RAM, and record the bits that read back differently.
+fn check_pattern<I2C, E>(
+    driver: &mut HT16K33<I2C>,
+    pattern: &[u8; ROWS_SIZE],
+    report: &mut SelfTestReport,
+) -> Result<(), Error<E>>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    let address = DisplayDataAddress::ROW_0.bits();
+    driver.write_ram(address, pattern)?;
+
+    let mut read_back = [0u8; ROWS_SIZE];
+    driver
+        .read_ram(address, &mut read_back)
+        .map_err(Error::I2c)?;
+
+    for ((mismatch, written), read) in report
+        .mismatches
+        .iter_mut()
+        .zip(pattern.iter())
+        .zip(read_back.iter())
+    {
+        *mismatch |= DisplayData::from_bits_truncate(written ^ read);
+    }
+    report.patterns += 1;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c_mock::{ErrorKind, I2cMock};
+    use crate::types::{Address, LedLocation, Package};
+
+    struct Delay {
+        total: u32,
+    }
+
+    impl DelayMs<u16> for Delay {
+        fn delay_ms(&mut self, ms: u16) {
+            self.total += ms as u32;
+        }
+    }
+
+    #[test]
+    fn passed() {
+        let mut driver = HT16K33::new(I2cMock::new(), Address::DEFAULT)
+            .initialize()
+            .unwrap();
+        driver.set_package(Package::Sop20);
+        driver.update_display_buffer(LedLocation::new(1, 1).unwrap(), true);
+        let mut delay = Delay { total: 0 };
+
+        let report = self_test(&mut driver, 10, &mut delay).unwrap();
+
+        assert!(report.passed());
+        assert_eq!(0, report.failed_rows());
+        // All on, 8 rows, and all on again for the dimming sweep.
+        assert_eq!(10, report.patterns);
+        assert_eq!(10 * (1 + 8 + 16), delay.total);
+
+        let i2c = driver.destroy();
+        assert_eq!(Display::OFF, i2c.display());
+        assert_eq!(Dimming::BRIGHTNESS_MAX, i2c.dimming());
+        assert_eq!(0b0000_0010, i2c.device(Address::DEFAULT).data_values[1]);
+        assert_eq!(0, i2c.device(Address::DEFAULT).data_values[0]);
+    }
+
+    #[test]
+    fn restore_after_error() {
+        let mut i2c_mock = I2cMock::new();
+        // Initialization, the display, and the patterns write 16 times, fail during the dimming sweep.
+        i2c_mock.fail_write_after(20, ErrorKind::Nack);
+
+        let mut driver = HT16K33::new(i2c_mock, Address::DEFAULT)
+            .initialize()
+            .unwrap();
+        driver.set_package(Package::Sop20);
+        driver.update_display_buffer(LedLocation::new(1, 1).unwrap(), true);
+        let mut delay = Delay { total: 0 };
+
+        match self_test(&mut driver, 10, &mut delay) {
+            Err(Error::I2c(error)) => assert_eq!(ErrorKind::Nack, error.kind()),
+            result => panic!("unexpected result {:?}", result),
+        }
+        // Stopped during the dimming sweep.
+        assert!(delay.total > 10 * (1 + 8));
+
+        let i2c = driver.destroy();
+        assert_eq!(Display::OFF, i2c.display());
+        assert_eq!(Dimming::BRIGHTNESS_MAX, i2c.dimming());
+        assert_eq!(0b0000_0010, i2c.device(Address::DEFAULT).data_values[1]);
+        assert_eq!(0, i2c.device(Address::DEFAULT).data_values[0]);
+    }
+
+    #[test]
+    fn failed_rows() {
+        let mut report = SelfTestReport {
+            patterns: 1,
+            mismatches: [DisplayData::empty(); ROWS_SIZE],
+        };
+        report.mismatches[3] = DisplayData::COMMON_0;
+
+        assert!(!report.passed());
+        assert_eq!(0b1000, report.failed_rows());
+    }
+}
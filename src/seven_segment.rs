@@ -0,0 +1,1825 @@
+//! # seven_segment
+//!
+//! Support for the Adafruit 0.56" and 1.2" 4-digit 7-segment backpacks.
+//!
+//! The backpacks wire each digit's segments to one row of the display RAM, with the colon and other
+//! indicators sharing a row between the second and third digits:
+//!
+//! | Row | Contents         |
+//! |-----|------------------|
+//! | 0   | Digit 0          |
+//! | 2   | Digit 1          |
+//! | 4   | Colon/indicators |
+//! | 6   | Digit 2          |
+//! | 8   | Digit 3          |
+//!
+//! Within a digit's row, the segments `A`-`G` are commons 0-6 and the decimal point is common 7.
+use bitflags::bitflags;
+use core::fmt;
+
+use crate::constants::ROWS_SIZE;
+use crate::errors::ValidationError;
+use crate::hal::blocking::i2c::Write;
+use crate::types::DisplayData;
+use crate::units::{self, Unit};
+use crate::HT16K33;
+
+/// The number of digits on the display.
+pub const DIGITS_SIZE: usize = 4;
+
+/// The display RAM rows of each digit, from left to right.
+pub const DIGIT_ROWS: [usize; DIGITS_SIZE] = [0, 2, 6, 8];
+
+/// The display RAM row of the colon and other indicators.
+pub const INDICATOR_ROW: usize = 4;
+
+// The digits and the indicators must fit in the display RAM.
+const _: () = assert!(DIGIT_ROWS[DIGITS_SIZE - 1] < ROWS_SIZE);
+const _: () = assert!(INDICATOR_ROW < ROWS_SIZE);
+
+/// Segments for the hexadecimal digits `0`-`F`.
+pub const HEX_DIGITS: [Segments; 16] = [
+    Segments::DIGIT_0,
+    Segments::DIGIT_1,
+    Segments::DIGIT_2,
+    Segments::DIGIT_3,
+    Segments::DIGIT_4,
+    Segments::DIGIT_5,
+    Segments::DIGIT_6,
+    Segments::DIGIT_7,
+    Segments::DIGIT_8,
+    Segments::DIGIT_9,
+    Segments::DIGIT_A,
+    Segments::DIGIT_B,
+    Segments::DIGIT_C,
+    Segments::DIGIT_D,
+    Segments::DIGIT_E,
+    Segments::DIGIT_F,
+];
+
+bitflags! {
+    /// The segments of a single digit.
+    ///
+    /// ```text
+    ///  -A-
+    /// F   B
+    ///  -G-
+    /// E   C
+    ///  -D-  DP
+    /// ```
+    pub struct Segments: u8 {
+        /// Top segment.
+        const A = 0b0000_0001;
+        /// Top-right segment.
+        const B = 0b0000_0010;
+        /// Bottom-right segment.
+        const C = 0b0000_0100;
+        /// Bottom segment.
+        const D = 0b0000_1000;
+        /// Bottom-left segment.
+        const E = 0b0001_0000;
+        /// Top-left segment.
+        const F = 0b0010_0000;
+        /// Middle segment.
+        const G = 0b0100_0000;
+        /// Decimal point.
+        const DP = 0b1000_0000;
+
+        /// Digit `0`.
+        const DIGIT_0 = Self::A.bits | Self::B.bits | Self::C.bits | Self::D.bits | Self::E.bits | Self::F.bits;
+        /// Digit `1`.
+        const DIGIT_1 = Self::B.bits | Self::C.bits;
+        /// Digit `2`.
+        const DIGIT_2 = Self::A.bits | Self::B.bits | Self::D.bits | Self::E.bits | Self::G.bits;
+        /// Digit `3`.
+        const DIGIT_3 = Self::A.bits | Self::B.bits | Self::C.bits | Self::D.bits | Self::G.bits;
+        /// Digit `4`.
+        const DIGIT_4 = Self::B.bits | Self::C.bits | Self::F.bits | Self::G.bits;
+        /// Digit `5`.
+        const DIGIT_5 = Self::A.bits | Self::C.bits | Self::D.bits | Self::F.bits | Self::G.bits;
+        /// Digit `6`.
+        const DIGIT_6 = Self::A.bits | Self::C.bits | Self::D.bits | Self::E.bits | Self::F.bits | Self::G.bits;
+        /// Digit `7`.
+        const DIGIT_7 = Self::A.bits | Self::B.bits | Self::C.bits;
+        /// Digit `8`.
+        const DIGIT_8 = Self::DIGIT_0.bits | Self::G.bits;
+        /// Digit `9`.
+        const DIGIT_9 = Self::A.bits | Self::B.bits | Self::C.bits | Self::D.bits | Self::F.bits | Self::G.bits;
+        /// Digit `A`.
+        const DIGIT_A = Self::A.bits | Self::B.bits | Self::C.bits | Self::E.bits | Self::F.bits | Self::G.bits;
+        /// Digit `b`.
+        const DIGIT_B = Self::C.bits | Self::D.bits | Self::E.bits | Self::F.bits | Self::G.bits;
+        /// Digit `C`.
+        const DIGIT_C = Self::A.bits | Self::D.bits | Self::E.bits | Self::F.bits;
+        /// Digit `d`.
+        const DIGIT_D = Self::B.bits | Self::C.bits | Self::D.bits | Self::E.bits | Self::G.bits;
+        /// Digit `E`.
+        const DIGIT_E = Self::A.bits | Self::D.bits | Self::E.bits | Self::F.bits | Self::G.bits;
+        /// Digit `F`.
+        const DIGIT_F = Self::A.bits | Self::E.bits | Self::F.bits | Self::G.bits;
+        /// Minus sign.
+        const MINUS = Self::G.bits;
+        /// Kilo unit suffix, `k`.
+        const KILO = Self::A.bits | Self::C.bits | Self::E.bits | Self::F.bits | Self::G.bits;
+        /// Mega unit suffix, `M`.
+        const MEGA = Self::A.bits | Self::C.bits | Self::E.bits;
+        /// Giga unit suffix, `G`.
+        const GIGA = Self::A.bits | Self::C.bits | Self::D.bits | Self::E.bits | Self::F.bits;
+    }
+}
+
+/// The number of digits in each half of the display, see
+/// [SevenSegment::display_half()](struct.SevenSegment.html#method.display_half).
+pub const HALF_DIGITS_SIZE: usize = DIGITS_SIZE / 2;
+
+/// One half of the display, see [SevenSegment::display_half()](struct.SevenSegment.html#method.display_half).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Half {
+    /// Digits 0 and 1, left of the colon.
+    Left,
+    /// Digits 2 and 3, right of the colon.
+    Right,
+}
+
+/// Unit suffixes for [SevenSegment::display_ranged()](struct.SevenSegment.html#method.display_ranged): kilo
+/// (`k`), mega (`M`) and giga (`G`).
+pub const SI_SUFFIXES: [Segments; 3] = [Segments::KILO, Segments::MEGA, Segments::GIGA];
+
+bitflags! {
+    /// The colon and extra indicators, stored in the [`INDICATOR_ROW`].
+    ///
+    /// The 0.56" backpack only has the center `COLON`, the others are only wired on the 1.2" backpack.
+    ///
+    /// [`INDICATOR_ROW`]: constant.INDICATOR_ROW.html
+    pub struct Indicators: u8 {
+        /// Center colon.
+        const COLON = 0b0000_0010;
+        /// Upper dot of the left colon.
+        const LEFT_COLON_UPPER = 0b0000_0100;
+        /// Lower dot of the left colon.
+        const LEFT_COLON_LOWER = 0b0000_1000;
+        /// Top-right decimal point, often used as a degree symbol.
+        const DECIMAL_POINT = 0b0001_0000;
+    }
+}
+
+impl Segments {
+    /// Return the segments for the given hexadecimal digit value.
+    ///
+    /// # Errors
+    ///
+    /// If the `value` is larger than `0xF` then [`ht16k33::ValidationError::ValueTooLarge`] is returned.
+    ///
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    pub fn from_hex_digit(value: u8) -> Result<Self, ValidationError> {
+        if value as usize >= HEX_DIGITS.len() {
+            return Err(ValidationError::ValueTooLarge {
+                name: "digit",
+                value,
+                limit: HEX_DIGITS.len() as u8,
+                inclusive: false,
+            });
+        }
+
+        Ok(HEX_DIGITS[value as usize])
+    }
+
+    /// Return the segments that best approximate the given character, if any.
+    ///
+    /// Digits, the letters that can be told apart on 7 segments, space, `-`, `_`, `=` and `.` are
+    /// supported. Letters with both an upper and a lower case form, e.g. `C` and `c`, keep their case, other
+    /// letters are shown in whichever case is readable.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ht16k33::seven_segment::Segments;
+    ///
+    /// assert_eq!(Some(Segments::DIGIT_E), Segments::from_char('E'));
+    /// assert_eq!(Some(Segments::E | Segments::G), Segments::from_char('r'));
+    /// assert_eq!(None, Segments::from_char('%'));
+    /// ```
+    pub const fn from_char(c: char) -> Option<Self> {
+        Font::BUILT_IN.encode(c)
+    }
+
+    /// Return the segments remapped for a module mounted at 90°, moving each outer segment one step
+    /// around the digit: `A`→`F`→`E`→`D`→`C`→`B`→`A`.
+    ///
+    /// The middle segment and the decimal point are unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ht16k33::seven_segment::Segments;
+    ///
+    /// assert_eq!(Segments::F | Segments::G, (Segments::A | Segments::G).rotated());
+    /// ```
+    pub fn rotated(self) -> Self {
+        self.remap(&ROTATION)
+    }
+
+    /// Return the segments with the [rotated()](struct.Segments.html#method.rotated) remap undone.
+    pub fn unrotated(self) -> Self {
+        let mut inverse = [Segments::empty(); 8];
+        for (index, segments) in ROTATION.iter().enumerate() {
+            inverse[segments.bits().trailing_zeros() as usize] =
+                Segments::from_bits_truncate(1 << index);
+        }
+
+        self.remap(&inverse)
+    }
+
+    // Move each segment bit to the segments in the same position of `map`.
+    fn remap(self, map: &[Segments; 8]) -> Self {
+        let mut remapped = Segments::empty();
+        for (index, segments) in map.iter().enumerate() {
+            if self.bits() & (1 << index) != 0 {
+                remapped |= *segments;
+            }
+        }
+
+        remapped
+    }
+}
+
+/// The segments of the ASCII characters, as returned by [Segments::from_char()](struct.Segments.html#method.from_char).
+///
+/// The table is built at compile time, so looking up a character costs a single index.
+pub const ASCII_FONT: [Option<Segments>; 128] = ascii_font();
+
+/// Return the segment bits of the character `c`, or `0` if it cannot be shown.
+///
+/// The bits are returned as a `u16`, the width shared by the glyphs of all segment displays. For the 7-segment
+/// digits, only the low byte is used, with the bits of [`Segments`](struct.Segments.html).
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::seven_segment::{encode_char, Segments};
+///
+/// const DASH: u16 = encode_char('-');
+///
+/// assert_eq!(Segments::MINUS.bits() as u16, DASH);
+/// assert_eq!(0, encode_char('%'));
+/// ```
+pub const fn encode_char(c: char) -> u16 {
+    match Font::BUILT_IN.encode(c) {
+        Some(segments) => segments.bits() as u16,
+        None => 0,
+    }
+}
+
+/// The built-in font with a table of custom glyphs, e.g. for a degree sign or a battery icon.
+///
+/// The custom glyphs are checked before the built-in font, so they can also replace built-in characters.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use core::fmt::Write;
+/// use ht16k33::HT16K33;
+/// use ht16k33::seven_segment::{Font, Segments, SevenSegment};
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// const DEGREE: Segments = Segments::A.union(Segments::B).union(Segments::F).union(Segments::G);
+/// const FONT: Font = Font::with_glyphs(&[('°', DEGREE)]);
+///
+/// let mut display = SevenSegment::new(HT16K33::new(i2c, address).initialize()?);
+/// display.set_font(FONT);
+///
+/// write!(display, "21°C")?;
+/// assert_eq!(DEGREE, display.segments(2)?);
+///
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Font {
+    glyphs: &'static [(char, Segments)],
+}
+
+impl Font {
+    /// The built-in font, without custom glyphs.
+    pub const BUILT_IN: Font = Font { glyphs: &[] };
+
+    /// Create a `Font` with the given custom glyphs.
+    pub const fn with_glyphs(glyphs: &'static [(char, Segments)]) -> Self {
+        Font { glyphs }
+    }
+
+    /// Return the custom glyphs.
+    pub const fn glyphs(&self) -> &'static [(char, Segments)] {
+        self.glyphs
+    }
+
+    /// Return the segments of the character `c`, if any, from the custom glyphs or else the built-in font.
+    pub const fn encode(&self, c: char) -> Option<Segments> {
+        let mut index = 0;
+        while index < self.glyphs.len() {
+            let (glyph, segments) = self.glyphs[index];
+            if glyph as u32 == c as u32 {
+                return Some(segments);
+            }
+            index += 1;
+        }
+
+        if (c as u32) < ASCII_FONT.len() as u32 {
+            ASCII_FONT[c as usize]
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for Font {
+    fn default() -> Self {
+        Font::BUILT_IN
+    }
+}
+
+// Build the `ASCII_FONT` table.
+const fn ascii_font() -> [Option<Segments>; 128] {
+    let mut font = [None; 128];
+    let mut code = 0;
+    while code < font.len() {
+        font[code] = ascii_glyph(code as u8 as char);
+        code += 1;
+    }
+
+    font
+}
+
+// Join the given segments.
+const fn join(parts: &[Segments]) -> Segments {
+    let mut segments = Segments::empty();
+    let mut index = 0;
+    while index < parts.len() {
+        segments = segments.union(parts[index]);
+        index += 1;
+    }
+
+    segments
+}
+
+// Return the segments of the ASCII character `c`, if any.
+const fn ascii_glyph(c: char) -> Option<Segments> {
+    use Segments as S;
+
+    let segments = match c {
+        '0'..='9' => HEX_DIGITS[c as usize - '0' as usize],
+        'A' | 'a' => S::DIGIT_A,
+        'B' | 'b' => S::DIGIT_B,
+        'C' => S::DIGIT_C,
+        'c' => join(&[S::D, S::E, S::G]),
+        'D' | 'd' => S::DIGIT_D,
+        'E' | 'e' => S::DIGIT_E,
+        'F' | 'f' => S::DIGIT_F,
+        'G' | 'g' => join(&[S::A, S::C, S::D, S::E, S::F]),
+        'H' => join(&[S::B, S::C, S::E, S::F, S::G]),
+        'h' => join(&[S::C, S::E, S::F, S::G]),
+        'I' | 'i' => join(&[S::E, S::F]),
+        'J' | 'j' => join(&[S::B, S::C, S::D, S::E]),
+        'K' | 'k' => S::KILO,
+        'L' | 'l' => join(&[S::D, S::E, S::F]),
+        'M' => S::MEGA,
+        'N' | 'n' => join(&[S::C, S::E, S::G]),
+        'O' => S::DIGIT_0,
+        'o' => join(&[S::C, S::D, S::E, S::G]),
+        'P' | 'p' => join(&[S::A, S::B, S::E, S::F, S::G]),
+        'R' | 'r' => join(&[S::E, S::G]),
+        'S' | 's' => S::DIGIT_5,
+        'T' | 't' => join(&[S::D, S::E, S::F, S::G]),
+        'U' => join(&[S::B, S::C, S::D, S::E, S::F]),
+        'u' | 'v' => join(&[S::C, S::D, S::E]),
+        'Y' | 'y' => join(&[S::B, S::C, S::D, S::F, S::G]),
+        ' ' => S::empty(),
+        '-' => S::MINUS,
+        '_' => S::D,
+        '=' => join(&[S::D, S::G]),
+        '.' => S::DP,
+        _ => return None,
+    };
+
+    Some(segments)
+}
+
+/// How the last shown digit of a value is derived, see
+/// [SevenSegment::set_rounding()](struct.SevenSegment.html#method.set_rounding).
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Rounding {
+    /// Round to the nearest, halves away from zero, e.g. `2.5` to `3` and `-2.5` to `-3`.
+    #[default]
+    HalfUp,
+    /// Drop the remaining digits, rounding towards zero.
+    Truncate,
+    /// Round to the nearest, halves to the even digit, e.g. `2.5` to `2` and `3.5` to `4`.
+    HalfEven,
+}
+
+impl Rounding {
+    // Round the `value` to an integer, values too large to show are returned unchanged.
+    pub(crate) fn round(self, value: f32) -> f32 {
+        // Also leaves NaN unchanged, to be rejected by the caller.
+        if !(value > -1_000_000_000.0 && value < 1_000_000_000.0) {
+            return value;
+        }
+
+        let truncated = value as i32;
+        let remainder = value - truncated as f32;
+        let away = if value < 0.0 { -1 } else { 1 };
+
+        let rounded = match self {
+            Rounding::Truncate => truncated,
+            Rounding::HalfUp if remainder.abs() >= 0.5 => truncated + away,
+            Rounding::HalfEven if remainder.abs() > 0.5 => truncated + away,
+            Rounding::HalfEven if remainder.abs() == 0.5 && truncated % 2 != 0 => truncated + away,
+            _ => truncated,
+        };
+
+        rounded as f32
+    }
+
+    // Divide the `value` by the `divisor`, rounding the quotient.
+    fn divide(self, value: i64, divisor: i64) -> i64 {
+        let quotient = value / divisor;
+        let twice_remainder = 2 * (value % divisor).abs();
+        let away = value.signum();
+
+        match self {
+            Rounding::Truncate => quotient,
+            Rounding::HalfUp if twice_remainder >= divisor => quotient + away,
+            Rounding::HalfEven if twice_remainder > divisor => quotient + away,
+            Rounding::HalfEven if twice_remainder == divisor && quotient % 2 != 0 => {
+                quotient + away
+            }
+            _ => quotient,
+        }
+    }
+}
+
+// The rotated segment of each segment bit, `A` through `DP`.
+const ROTATION: [Segments; 8] = [
+    Segments::F,
+    Segments::A,
+    Segments::B,
+    Segments::C,
+    Segments::D,
+    Segments::E,
+    Segments::G,
+    Segments::DP,
+];
+
+/// A 4-digit 7-segment display driven by an [`HT16K33`].
+///
+/// The helpers update the display buffer, which must be written using
+/// [write_display_buffer()](struct.SevenSegment.html#method.write_display_buffer) for the change to be
+/// displayed.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use ht16k33::HT16K33;
+/// use ht16k33::seven_segment::SevenSegment;
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+///
+/// let mut display = SevenSegment::new(ht16k33);
+/// display.display_float(-1.25, 2)?;
+/// display.set_colon(true);
+/// display.write_display_buffer()?;
+///
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Formatting
+///
+/// The display implements [`core::fmt::Write`], so text can be formatted straight onto the digits. Text is
+/// written left to right from a cursor, which [clear()](struct.SevenSegment.html#method.clear) moves back to
+/// the first digit:
+///
+/// * Characters are shown as by [Segments::from_char()](struct.Segments.html#method.from_char), or by the
+///   [set_font()](struct.SevenSegment.html#method.set_font) custom glyphs.
+/// * A `.` sets the decimal point of the previous digit, unless it is already set.
+/// * A `:` enables the colon.
+/// * Characters past the last digit are dropped.
+///
+/// A character that cannot be shown returns [`core::fmt::Error`].
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use core::fmt::Write;
+/// use ht16k33::HT16K33;
+/// use ht16k33::seven_segment::SevenSegment;
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+/// # let rpm = 850;
+///
+/// let mut display = SevenSegment::new(HT16K33::new(i2c, address).initialize()?);
+///
+/// display.clear();
+/// write!(display, "{:>4}", rpm)?;
+/// display.write_display_buffer()?;
+///
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`HT16K33`]: ../struct.HT16K33.html
+/// [`core::fmt::Write`]: https://doc.rust-lang.org/core/fmt/trait.Write.html
+/// [`core::fmt::Error`]: https://doc.rust-lang.org/core/fmt/struct.Error.html
+pub struct SevenSegment<I2C> {
+    driver: HT16K33<I2C>,
+
+    // Whether the digits are remapped for a module mounted at 90°.
+    rotated: bool,
+
+    rounding: Rounding,
+
+    // The font of the characters written by `fmt::Write`.
+    font: Font,
+
+    // The digit written next by `fmt::Write`.
+    cursor: u8,
+}
+
+impl<I2C, E> SevenSegment<I2C>
+where
+    I2C: Write<Error = E>,
+{
+    /// Create a `SevenSegment` display from the given driver.
+    pub fn new(driver: HT16K33<I2C>) -> Self {
+        SevenSegment {
+            driver,
+            rotated: false,
+            rounding: Rounding::HalfUp,
+            font: Font::BUILT_IN,
+            cursor: 0,
+        }
+    }
+
+    /// Return the driver.
+    pub fn driver(&self) -> &HT16K33<I2C> {
+        &self.driver
+    }
+
+    /// Return the driver for modification, e.g. to change the dimming.
+    pub fn driver_mut(&mut self) -> &mut HT16K33<I2C> {
+        &mut self.driver
+    }
+
+    /// Return the driver, consuming the display.
+    pub fn into_driver(self) -> HT16K33<I2C> {
+        self.driver
+    }
+
+    /// Return whether the digits are remapped for a module mounted at 90°.
+    pub fn rotated(&self) -> bool {
+        self.rotated
+    }
+
+    /// Remap the digits for a module mounted at 90°, see [Segments::rotated()](struct.Segments.html#method.rotated).
+    ///
+    /// Only digits set after the change are remapped.
+    pub fn set_rotated(&mut self, rotated: bool) {
+        self.rotated = rotated;
+    }
+
+    /// Return how values are rounded to the shown digits.
+    pub fn rounding(&self) -> Rounding {
+        self.rounding
+    }
+
+    /// Set how values are rounded to the shown digits by
+    /// [display_float()](struct.SevenSegment.html#method.display_float) and
+    /// [display_fixed()](struct.SevenSegment.html#method.display_fixed).
+    pub fn set_rounding(&mut self, rounding: Rounding) {
+        self.rounding = rounding;
+    }
+
+    /// Return the font of the characters written as text.
+    pub fn font(&self) -> Font {
+        self.font
+    }
+
+    /// Set the font of the characters written as text, see the [formatting](struct.SevenSegment.html#formatting)
+    /// rules.
+    pub fn set_font(&mut self, font: Font) {
+        self.font = font;
+    }
+
+    /// Write the display buffer to the HT16K33 chip.
+    pub fn write_display_buffer(&mut self) -> Result<(), E> {
+        self.driver.write_display_buffer()
+    }
+
+    /// Blank all digits and indicators, and move the formatting cursor back to the first digit.
+    pub fn clear(&mut self) {
+        self.driver.clear_display_buffer();
+        self.cursor = 0;
+    }
+
+    /// Set the raw segments of a digit.
+    ///
+    /// # Errors
+    ///
+    /// If the `index` is not less than [`DIGITS_SIZE`] then [`ht16k33::ValidationError::ValueTooLarge`] is
+    /// returned.
+    ///
+    /// [`DIGITS_SIZE`]: constant.DIGITS_SIZE.html
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    pub fn set_segments(&mut self, index: u8, segments: Segments) -> Result<(), ValidationError> {
+        let row = digit_row(index)?;
+        let segments = if self.rotated {
+            segments.rotated()
+        } else {
+            segments
+        };
+
+        self.driver.display_buffer_mut()[row] = DisplayData::from_bits_truncate(segments.bits());
+
+        Ok(())
+    }
+
+    /// Return the raw segments of a digit.
+    ///
+    /// # Errors
+    ///
+    /// If the `index` is not less than [`DIGITS_SIZE`] then [`ht16k33::ValidationError::ValueTooLarge`] is
+    /// returned.
+    ///
+    /// [`DIGITS_SIZE`]: constant.DIGITS_SIZE.html
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    pub fn segments(&self, index: u8) -> Result<Segments, ValidationError> {
+        let row = digit_row(index)?;
+        let segments = Segments::from_bits_truncate(self.driver.display_buffer()[row].bits());
+
+        if self.rotated {
+            Ok(segments.unrotated())
+        } else {
+            Ok(segments)
+        }
+    }
+
+    /// Show a hexadecimal digit value (`0`-`F`) on a digit.
+    ///
+    /// # Errors
+    ///
+    /// If the `index` is not less than [`DIGITS_SIZE`], or the `value` is larger than `0xF`, then
+    /// [`ht16k33::ValidationError::ValueTooLarge`] is returned.
+    ///
+    /// [`DIGITS_SIZE`]: constant.DIGITS_SIZE.html
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    pub fn set_digit(
+        &mut self,
+        index: u8,
+        value: u8,
+        decimal_point: bool,
+    ) -> Result<(), ValidationError> {
+        let mut segments = Segments::from_hex_digit(value)?;
+        segments.set(Segments::DP, decimal_point);
+
+        self.set_segments(index, segments)
+    }
+
+    /// Enable/disable the decimal point of a digit, leaving its other segments unchanged.
+    ///
+    /// # Errors
+    ///
+    /// If the `index` is not less than [`DIGITS_SIZE`] then [`ht16k33::ValidationError::ValueTooLarge`] is
+    /// returned.
+    ///
+    /// [`DIGITS_SIZE`]: constant.DIGITS_SIZE.html
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    pub fn set_decimal_point(&mut self, index: u8, enabled: bool) -> Result<(), ValidationError> {
+        let mut segments = self.segments(index)?;
+        segments.set(Segments::DP, enabled);
+
+        self.set_segments(index, segments)
+    }
+
+    /// Return the enabled indicators.
+    pub fn indicators(&self) -> Indicators {
+        Indicators::from_bits_truncate(self.driver.display_buffer()[INDICATOR_ROW].bits())
+    }
+
+    /// Set the enabled indicators, replacing the current ones.
+    pub fn set_indicators(&mut self, indicators: Indicators) {
+        self.driver.display_buffer_mut()[INDICATOR_ROW] =
+            DisplayData::from_bits_truncate(indicators.bits());
+    }
+
+    /// Enable/disable the given indicators, leaving the others unchanged.
+    pub fn set_indicator(&mut self, indicator: Indicators, enabled: bool) {
+        let mut indicators = self.indicators();
+        indicators.set(indicator, enabled);
+
+        self.set_indicators(indicators);
+    }
+
+    /// Enable/disable the center colon.
+    pub fn set_colon(&mut self, enabled: bool) {
+        self.set_indicator(Indicators::COLON, enabled);
+    }
+
+    /// Enable/disable both dots of the left colon, only wired on the 1.2" backpack.
+    pub fn set_left_colon(&mut self, enabled: bool) {
+        self.set_indicator(
+            Indicators::LEFT_COLON_UPPER | Indicators::LEFT_COLON_LOWER,
+            enabled,
+        );
+    }
+
+    /// Enable/disable the top-right decimal point, used as a degree symbol, only wired on the 1.2" backpack.
+    pub fn set_degree(&mut self, enabled: bool) {
+        self.set_indicator(Indicators::DECIMAL_POINT, enabled);
+    }
+
+    /// Show an unsigned decimal value, right-aligned without leading zeros.
+    ///
+    /// The indicators are left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// If the `value` is larger than `9999` then [`ht16k33::ValidationError::Overflow`] is returned and the
+    /// display buffer is left unchanged.
+    ///
+    /// [`ht16k33::ValidationError::Overflow`]: ../enum.ValidationError.html#variant.Overflow
+    pub fn display_u16(&mut self, value: u16) -> Result<(), ValidationError> {
+        self.display_integer(i32::from(value), None)
+    }
+
+    /// Show a value as 4 hexadecimal digits, with leading zeros.
+    ///
+    /// The indicators are left unchanged.
+    pub fn display_hex(&mut self, value: u16) {
+        for (index, &row) in DIGIT_ROWS.iter().enumerate() {
+            let shift = 4 * (DIGITS_SIZE - 1 - index);
+            let digit = HEX_DIGITS[((value >> shift) & 0xF) as usize];
+
+            self.driver.display_buffer_mut()[row] = DisplayData::from_bits_truncate(digit.bits());
+        }
+    }
+
+    /// Show a decimal value with `precision` digits after the decimal point, right-aligned.
+    ///
+    /// The value is rounded to the given `precision`, see
+    /// [set_rounding()](struct.SevenSegment.html#method.set_rounding). If there are not enough digits then the
+    /// precision is reduced until the value fits. The indicators are left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// If the integer part of the `value` does not fit on the display, or the `value` is not a number, then
+    /// [`ht16k33::ValidationError::Overflow`] is returned and the display buffer is left unchanged.
+    ///
+    /// [`ht16k33::ValidationError::Overflow`]: ../enum.ValidationError.html#variant.Overflow
+    pub fn display_float(&mut self, value: f32, precision: u8) -> Result<(), ValidationError> {
+        let overflow = ValidationError::Overflow {
+            name: "value",
+            digits: DIGITS_SIZE as u8,
+        };
+
+        let mut precision = precision.min(DIGITS_SIZE as u8 - 1);
+        loop {
+            // `core` lacks `f32::powi()`, scale by hand.
+            let mut scaled = value;
+            for _ in 0..precision {
+                scaled *= 10.0;
+            }
+
+            let rounded = self.rounding.round(scaled);
+
+            // Compare against the bounds first so the cast cannot saturate, this also rejects NaN.
+            if rounded > -10_000.0 && rounded < 10_000.0 {
+                let rounded = rounded as i32;
+
+                if fits(rounded, precision, DIGITS_SIZE) {
+                    let decimal_point = if precision > 0 {
+                        Some(DIGITS_SIZE - 1 - precision as usize)
+                    } else {
+                        None
+                    };
+
+                    return self.display_integer(rounded, decimal_point);
+                }
+            }
+
+            if precision == 0 {
+                return Err(overflow);
+            }
+            precision -= 1;
+        }
+    }
+
+    /// Show a fixed-point value, the `value` divided by `10^decimals`, right-aligned.
+    ///
+    /// E.g. a temperature in tenths of a degree is shown with `decimals = 1`. As with
+    /// [display_float()](struct.SevenSegment.html#method.display_float), the value is rounded to fewer decimals
+    /// if it does not fit. The indicators are left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// If the integer part of the value does not fit on the display then [`ht16k33::ValidationError::Overflow`]
+    /// is returned and the display buffer is left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// use ht16k33::HT16K33;
+    /// use ht16k33::seven_segment::SevenSegment;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut display = SevenSegment::new(HT16K33::new(i2c, address).initialize()?);
+    ///
+    /// // -12.5, in tenths.
+    /// display.display_fixed(-125, 1)?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ht16k33::ValidationError::Overflow`]: ../enum.ValidationError.html#variant.Overflow
+    pub fn display_fixed(&mut self, value: i32, decimals: u8) -> Result<(), ValidationError> {
+        let mut precision = decimals.min(DIGITS_SIZE as u8 - 1);
+        loop {
+            let rounded = match 10i64.checked_pow((decimals - precision) as u32) {
+                Some(divisor) => self.rounding.divide(value as i64, divisor),
+                None => 0,
+            };
+
+            if rounded > -10_000 && rounded < 10_000 && fits(rounded as i32, precision, DIGITS_SIZE)
+            {
+                let decimal_point = if precision > 0 {
+                    Some(DIGITS_SIZE - 1 - precision as usize)
+                } else {
+                    None
+                };
+
+                return self.display_integer(rounded as i32, decimal_point);
+            }
+
+            if precision == 0 {
+                return Err(ValidationError::Overflow {
+                    name: "value",
+                    digits: DIGITS_SIZE as u8,
+                });
+            }
+            precision -= 1;
+        }
+    }
+
+    /// Show a value auto-ranged with a unit suffix on the last digit, e.g. `12345` as `12.3k` and
+    /// `1234567` as `1.23M`.
+    ///
+    /// Values that fit on the display are shown unchanged, as by
+    /// [display_fixed()](struct.SevenSegment.html#method.display_fixed) with no decimals. Larger values are
+    /// divided by `1000` for each suffix in `suffixes`, e.g. [`SI_SUFFIXES`], until they fit on the first 3
+    /// digits with as many decimals as possible. The value is rounded, see
+    /// [set_rounding()](struct.SevenSegment.html#method.set_rounding). The indicators are left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// If the value does not fit even with the last suffix then [`ht16k33::ValidationError::Overflow`] is
+    /// returned and the display buffer is left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// use ht16k33::HT16K33;
+    /// use ht16k33::seven_segment::{SevenSegment, SI_SUFFIXES};
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut display = SevenSegment::new(HT16K33::new(i2c, address).initialize()?);
+    ///
+    /// // Shown as `12.3k`.
+    /// display.display_ranged(12_345, &SI_SUFFIXES)?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`SI_SUFFIXES`]: constant.SI_SUFFIXES.html
+    /// [`ht16k33::ValidationError::Overflow`]: ../enum.ValidationError.html#variant.Overflow
+    pub fn display_ranged(
+        &mut self,
+        value: i32,
+        suffixes: &[Segments],
+    ) -> Result<(), ValidationError> {
+        // Checked first so `integer_digits()` never sees values too large to negate.
+        if value > -10_000 && value < 10_000 {
+            if let Some(digits) = integer_digits(value, None, DIGITS_SIZE) {
+                self.set_digits(&digits);
+                return Ok(());
+            }
+        }
+
+        let width = DIGITS_SIZE - 1;
+        let mut scale = 1i64;
+        for &suffix in suffixes {
+            scale *= 1000;
+
+            for precision in (0..width as u8).rev() {
+                let divisor = scale / 10i64.pow(precision as u32);
+                let rounded = self.rounding.divide(value as i64, divisor);
+
+                if rounded <= -10_000 || rounded >= 10_000 {
+                    continue;
+                }
+
+                let decimal_point = if precision > 0 {
+                    Some(width - 1 - precision as usize)
+                } else {
+                    None
+                };
+
+                if let Some(mut digits) = integer_digits(rounded as i32, decimal_point, width) {
+                    digits[width] = suffix;
+                    self.set_digits(&digits);
+                    return Ok(());
+                }
+            }
+        }
+
+        Err(ValidationError::Overflow {
+            name: "value",
+            digits: DIGITS_SIZE as u8,
+        })
+    }
+
+    /// Show a measured value with its unit, right-aligned and auto-ranged with an SI prefix, e.g. `21.46` degrees
+    /// as `21.5C` and `12345` as `12.3k`.
+    ///
+    /// The value is shown with as many decimals as fit, see [`units`](../units/index.html). The unit symbol and
+    /// the prefix are shown with the [font](struct.SevenSegment.html#method.set_font), and are blank if it
+    /// cannot show them. The indicators are left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// If the value does not fit even with the last prefix, or is not a number, then
+    /// [`ht16k33::ValidationError::Overflow`] is returned and the display buffer is left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// use ht16k33::HT16K33;
+    /// use ht16k33::seven_segment::SevenSegment;
+    /// use ht16k33::units::Unit;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut display = SevenSegment::new(HT16K33::new(i2c, address).initialize()?);
+    ///
+    /// // Shown as `21.5C`.
+    /// display.display_value(21.46, Unit::Symbol('C'))?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ht16k33::ValidationError::Overflow`]: ../enum.ValidationError.html#variant.Overflow
+    pub fn display_value(&mut self, value: f32, unit: Unit) -> Result<(), ValidationError> {
+        let text = units::format_value(value, unit, DIGITS_SIZE, self.rounding)?;
+
+        let mut digits = [Segments::empty(); DIGITS_SIZE];
+        let mut index = DIGITS_SIZE - text.width();
+        for &c in text.chars() {
+            if c == '.' {
+                // Always follows a digit.
+                digits[index - 1].insert(Segments::DP);
+                continue;
+            }
+
+            digits[index] = self.font.encode(c).unwrap_or_else(Segments::empty);
+            index += 1;
+        }
+        self.set_digits(&digits);
+
+        Ok(())
+    }
+
+    /// Show a value on one half of the display, right-aligned on its 2 digits, leaving the other half
+    /// unchanged.
+    ///
+    /// Use this to show two values side by side, e.g. a temperature and a humidity, optionally separated by
+    /// the [colon](struct.SevenSegment.html#method.set_colon). The indicators are left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// If the `value` does not fit on 2 digits, i.e. it is not within `-9..=99`, then
+    /// [`ht16k33::ValidationError::Overflow`] is returned and the display buffer is left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// use ht16k33::HT16K33;
+    /// use ht16k33::seven_segment::{Half, SevenSegment};
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut display = SevenSegment::new(HT16K33::new(i2c, address).initialize()?);
+    ///
+    /// // Set point and actual temperature, shown as `21:19`.
+    /// display.display_half(Half::Left, 21)?;
+    /// display.display_half(Half::Right, 19)?;
+    /// display.set_colon(true);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ht16k33::ValidationError::Overflow`]: ../enum.ValidationError.html#variant.Overflow
+    pub fn display_half(&mut self, half: Half, value: i32) -> Result<(), ValidationError> {
+        let digits = half_digits(value)?;
+        self.set_half(half, &digits);
+
+        Ok(())
+    }
+
+    /// Show two values side by side, each right-aligned on its half of the display, and enable/disable the
+    /// separating colon.
+    ///
+    /// See [display_half()](struct.SevenSegment.html#method.display_half).
+    ///
+    /// # Errors
+    ///
+    /// If either value does not fit on 2 digits then [`ht16k33::ValidationError::Overflow`] is returned and
+    /// the display buffer is left unchanged.
+    ///
+    /// [`ht16k33::ValidationError::Overflow`]: ../enum.ValidationError.html#variant.Overflow
+    pub fn display_split(
+        &mut self,
+        left: i32,
+        right: i32,
+        colon: bool,
+    ) -> Result<(), ValidationError> {
+        let left = half_digits(left)?;
+        let right = half_digits(right)?;
+
+        self.set_half(Half::Left, &left);
+        self.set_half(Half::Right, &right);
+        self.set_colon(colon);
+
+        Ok(())
+    }
+
+    // Store the segments of the digits of one half.
+    fn set_half(&mut self, half: Half, digits: &[Segments; HALF_DIGITS_SIZE]) {
+        let offset = match half {
+            Half::Left => 0,
+            Half::Right => HALF_DIGITS_SIZE,
+        };
+
+        for (index, segments) in digits.iter().enumerate() {
+            self.driver.display_buffer_mut()[DIGIT_ROWS[offset + index]] =
+                DisplayData::from_bits_truncate(segments.bits());
+        }
+    }
+
+    // Show a right-aligned integer, with an optional decimal point after the given digit index.
+    fn display_integer(
+        &mut self,
+        value: i32,
+        decimal_point: Option<usize>,
+    ) -> Result<(), ValidationError> {
+        let digits =
+            integer_digits(value, decimal_point, DIGITS_SIZE).ok_or(ValidationError::Overflow {
+                name: "value",
+                digits: DIGITS_SIZE as u8,
+            })?;
+
+        self.set_digits(&digits);
+
+        Ok(())
+    }
+
+    // Store the segments of every digit.
+    fn set_digits(&mut self, digits: &[Segments; DIGITS_SIZE]) {
+        for (index, segments) in digits.iter().enumerate() {
+            self.driver.display_buffer_mut()[DIGIT_ROWS[index]] =
+                DisplayData::from_bits_truncate(segments.bits());
+        }
+    }
+}
+
+impl<I2C, E> fmt::Write for SevenSegment<I2C>
+where
+    I2C: Write<Error = E>,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            match c {
+                ':' => {
+                    self.set_colon(true);
+                    continue;
+                }
+                '.' if self.cursor > 0 => {
+                    let previous = self.segments(self.cursor - 1).map_err(|_| fmt::Error)?;
+                    if !previous.contains(Segments::DP) {
+                        self.set_segments(self.cursor - 1, previous | Segments::DP)
+                            .map_err(|_| fmt::Error)?;
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+
+            let segments = self.font.encode(c).ok_or(fmt::Error)?;
+            if (self.cursor as usize) < DIGITS_SIZE {
+                self.set_segments(self.cursor, segments)
+                    .map_err(|_| fmt::Error)?;
+                self.cursor += 1;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Return the segments of a right-aligned integer on the first `width` digits, with an optional decimal point
+// after the given digit index, or `None` if it does not fit.
+//
+// Leading zeros are shown up to the digit with the decimal point.
+fn integer_digits(
+    value: i32,
+    decimal_point: Option<usize>,
+    width: usize,
+) -> Option<[Segments; DIGITS_SIZE]> {
+    let precision = match decimal_point {
+        Some(index) => (width - 1 - index) as u8,
+        None => 0,
+    };
+
+    if !fits(value, precision, width) {
+        return None;
+    }
+
+    let mut remaining = value.abs();
+    let mut digits = [Segments::empty(); DIGITS_SIZE];
+
+    for index in (0..width).rev() {
+        let leading = decimal_point.map_or(index + 1 < width, |dp| index < dp);
+
+        if remaining == 0 && leading {
+            if value < 0 {
+                digits[index] = Segments::MINUS;
+            }
+            break;
+        }
+
+        digits[index] = HEX_DIGITS[(remaining % 10) as usize];
+        remaining /= 10;
+    }
+
+    if let Some(index) = decimal_point {
+        digits[index].insert(Segments::DP);
+    }
+
+    Some(digits)
+}
+
+// Return the segments of a value right-aligned on the digits of one half.
+fn half_digits(value: i32) -> Result<[Segments; HALF_DIGITS_SIZE], ValidationError> {
+    // Checked first so `integer_digits()` never sees values too large to negate.
+    let digits = if value > -100 && value < 100 {
+        integer_digits(value, None, HALF_DIGITS_SIZE)
+    } else {
+        None
+    };
+
+    match digits {
+        Some(digits) => Ok([digits[0], digits[1]]),
+        None => Err(ValidationError::Overflow {
+            name: "value",
+            digits: HALF_DIGITS_SIZE as u8,
+        }),
+    }
+}
+
+// Return whether the integer fits on `width` digits, with at least one digit before the decimal point and a
+// leading digit for the minus sign.
+fn fits(value: i32, precision: u8, width: usize) -> bool {
+    let mut used = 1;
+    let mut remaining = value.abs() / 10;
+    while remaining > 0 {
+        used += 1;
+        remaining /= 10;
+    }
+
+    // There must be at least one digit before the decimal point.
+    let used = used.max(precision as usize + 1);
+    let used = if value < 0 { used + 1 } else { used };
+
+    used <= width
+}
+
+fn digit_row(index: u8) -> Result<usize, ValidationError> {
+    if index as usize >= DIGITS_SIZE {
+        return Err(ValidationError::ValueTooLarge {
+            name: "digit index",
+            value: index,
+            limit: DIGITS_SIZE as u8,
+            inclusive: false,
+        });
+    }
+
+    let row = DIGIT_ROWS[index as usize];
+    debug_assert!(row < ROWS_SIZE);
+
+    Ok(row)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c_mock::I2cMock;
+    use crate::types::Address;
+
+    fn display() -> SevenSegment<I2cMock> {
+        SevenSegment::new(
+            HT16K33::new(I2cMock::new(), Address::DEFAULT)
+                .initialize()
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn rotated() {
+        assert_eq!(Segments::F, Segments::A.rotated());
+        assert_eq!(Segments::A, Segments::B.rotated());
+        assert_eq!(Segments::E, Segments::F.rotated());
+        assert_eq!(
+            Segments::G | Segments::DP,
+            (Segments::G | Segments::DP).rotated()
+        );
+        assert_eq!(Segments::all(), Segments::all().rotated());
+
+        for &digit in HEX_DIGITS.iter() {
+            assert_eq!(digit, digit.rotated().unrotated());
+        }
+    }
+
+    #[test]
+    fn set_rotated() {
+        let mut display = display();
+        display.set_rotated(true);
+
+        display.set_segments(0, Segments::A | Segments::B).unwrap();
+
+        // Stored rotated, read back as set.
+        assert_eq!(
+            (Segments::F | Segments::A).bits(),
+            display.driver().display_buffer()[DIGIT_ROWS[0]].bits()
+        );
+        assert_eq!(Segments::A | Segments::B, display.segments(0).unwrap());
+    }
+
+    fn digits(display: &SevenSegment<I2cMock>) -> [Segments; DIGITS_SIZE] {
+        let mut digits = [Segments::empty(); DIGITS_SIZE];
+        for (index, digit) in digits.iter_mut().enumerate() {
+            *digit = display.segments(index as u8).unwrap();
+        }
+        digits
+    }
+
+    #[test]
+    fn hex_digits() {
+        let expected = [
+            0x3F, 0x06, 0x5B, 0x4F, 0x66, 0x6D, 0x7D, 0x07, 0x7F, 0x6F, 0x77, 0x7C, 0x39, 0x5E,
+            0x79, 0x71,
+        ];
+
+        for (digit, bits) in HEX_DIGITS.iter().zip(expected.iter()) {
+            assert_eq!(digit.bits(), *bits);
+        }
+    }
+
+    #[test]
+    fn font() {
+        const BATTERY: Segments = Segments::A.union(Segments::D);
+        const FONT: Font = Font::with_glyphs(&[('b', BATTERY), ('°', Segments::A)]);
+
+        assert_eq!(Some(BATTERY), FONT.encode('b'));
+        assert_eq!(Some(Segments::A), FONT.encode('°'));
+        assert_eq!(Some(Segments::DIGIT_B), FONT.encode('B'));
+        assert_eq!(None, FONT.encode('%'));
+        assert_eq!(None, Font::BUILT_IN.encode('°'));
+
+        assert_eq!(Segments::DIGIT_8.bits() as u16, encode_char('8'));
+        assert_eq!(0, encode_char(' '));
+    }
+
+    #[test]
+    fn display_value() {
+        let mut display = display();
+
+        display.display_value(-1.5, Unit::Symbol('C')).unwrap();
+        assert_eq!(
+            [
+                Segments::MINUS,
+                Segments::DIGIT_1 | Segments::DP,
+                Segments::DIGIT_5,
+                Segments::DIGIT_C
+            ],
+            digits(&display)
+        );
+
+        display.display_value(12_345.0, Unit::None).unwrap();
+        assert_eq!(
+            [
+                Segments::DIGIT_1,
+                Segments::DIGIT_2 | Segments::DP,
+                Segments::DIGIT_3,
+                Segments::KILO
+            ],
+            digits(&display)
+        );
+
+        assert!(display.display_value(f32::NAN, Unit::None).is_err());
+    }
+
+    #[test]
+    fn set_digit() {
+        let mut display = display();
+
+        display.set_digit(3, 7, true).unwrap();
+
+        assert_eq!(
+            display.driver().display_buffer()[8].bits(),
+            (Segments::DIGIT_7 | Segments::DP).bits()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_digit_index_too_large() {
+        display().set_digit(4, 0, false).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_digit_value_too_large() {
+        display().set_digit(0, 16, false).unwrap();
+    }
+
+    #[test]
+    fn set_colon() {
+        let mut display = display();
+
+        display.set_colon(true);
+        assert_eq!(display.driver().display_buffer()[4].bits(), 0b0000_0010);
+
+        display.set_colon(false);
+        assert_eq!(display.driver().display_buffer()[4].bits(), 0);
+    }
+
+    #[test]
+    fn set_indicators() {
+        let mut display = display();
+
+        display.set_left_colon(true);
+        display.set_degree(true);
+        assert_eq!(display.driver().display_buffer()[4].bits(), 0b0001_1100);
+
+        display.set_colon(true);
+        display.set_left_colon(false);
+        assert_eq!(
+            Indicators::COLON | Indicators::DECIMAL_POINT,
+            display.indicators()
+        );
+
+        display.set_decimal_point(2, true).unwrap();
+        assert_eq!(Segments::DP, display.segments(2).unwrap());
+        assert_eq!(
+            Indicators::COLON | Indicators::DECIMAL_POINT,
+            display.indicators()
+        );
+    }
+
+    #[test]
+    fn display_u16() {
+        let mut display = display();
+
+        display.display_u16(42).unwrap();
+        assert_eq!(
+            digits(&display),
+            [
+                Segments::empty(),
+                Segments::empty(),
+                Segments::DIGIT_4,
+                Segments::DIGIT_2
+            ]
+        );
+
+        display.display_u16(0).unwrap();
+        assert_eq!(
+            digits(&display),
+            [
+                Segments::empty(),
+                Segments::empty(),
+                Segments::empty(),
+                Segments::DIGIT_0
+            ]
+        );
+    }
+
+    #[test]
+    fn display_u16_too_large() {
+        let mut display = display();
+
+        display.display_u16(1234).unwrap();
+        assert!(display.display_u16(10_000).is_err());
+
+        // The previous value is still shown.
+        assert_eq!(
+            digits(&display),
+            [
+                Segments::DIGIT_1,
+                Segments::DIGIT_2,
+                Segments::DIGIT_3,
+                Segments::DIGIT_4
+            ]
+        );
+    }
+
+    #[test]
+    fn display_hex() {
+        let mut display = display();
+
+        display.display_hex(0x0BEF);
+        assert_eq!(
+            digits(&display),
+            [
+                Segments::DIGIT_0,
+                Segments::DIGIT_B,
+                Segments::DIGIT_E,
+                Segments::DIGIT_F
+            ]
+        );
+    }
+
+    #[test]
+    fn display_float() {
+        let mut display = display();
+
+        display.display_float(1.23456, 2).unwrap();
+        assert_eq!(
+            digits(&display),
+            [
+                Segments::empty(),
+                Segments::DIGIT_1 | Segments::DP,
+                Segments::DIGIT_2,
+                Segments::DIGIT_3
+            ]
+        );
+
+        display.display_float(0.05, 1).unwrap();
+        assert_eq!(
+            digits(&display),
+            [
+                Segments::empty(),
+                Segments::empty(),
+                Segments::DIGIT_0 | Segments::DP,
+                Segments::DIGIT_1
+            ]
+        );
+    }
+
+    #[test]
+    fn display_float_negative() {
+        let mut display = display();
+
+        display.display_float(-2.5, 1).unwrap();
+        assert_eq!(
+            digits(&display),
+            [
+                Segments::empty(),
+                Segments::MINUS,
+                Segments::DIGIT_2 | Segments::DP,
+                Segments::DIGIT_5
+            ]
+        );
+    }
+
+    #[test]
+    fn display_float_reduces_precision() {
+        let mut display = display();
+
+        display.display_float(123.456, 3).unwrap();
+        assert_eq!(
+            digits(&display),
+            [
+                Segments::DIGIT_1,
+                Segments::DIGIT_2,
+                Segments::DIGIT_3 | Segments::DP,
+                Segments::DIGIT_5
+            ]
+        );
+    }
+
+    #[test]
+    fn display_float_too_large() {
+        let mut display = display();
+
+        assert!(display.display_float(10_000.0, 0).is_err());
+        assert!(display.display_float(-1_000.0, 0).is_err());
+        assert!(display.display_float(f32::NAN, 0).is_err());
+    }
+
+    #[test]
+    fn display_fixed() {
+        let mut display = display();
+
+        display.display_fixed(-125, 1).unwrap();
+        assert_eq!(
+            digits(&display),
+            [
+                Segments::MINUS,
+                Segments::DIGIT_1,
+                Segments::DIGIT_2 | Segments::DP,
+                Segments::DIGIT_5
+            ]
+        );
+
+        // Hundredths, rounded to fit.
+        display.display_fixed(123_456, 2).unwrap();
+        assert_eq!(
+            digits(&display),
+            [
+                Segments::DIGIT_1,
+                Segments::DIGIT_2,
+                Segments::DIGIT_3,
+                Segments::DIGIT_5
+            ]
+        );
+
+        display.display_fixed(5, 2).unwrap();
+        assert_eq!(
+            digits(&display),
+            [
+                Segments::empty(),
+                Segments::DIGIT_0 | Segments::DP,
+                Segments::DIGIT_0,
+                Segments::DIGIT_5
+            ]
+        );
+    }
+
+    #[test]
+    fn display_fixed_too_large() {
+        let mut display = display();
+
+        assert!(display.display_fixed(100_000, 1).is_err());
+        assert!(display.display_fixed(i32::MIN, 0).is_err());
+        display.display_fixed(i32::MAX, 30).unwrap();
+    }
+
+    #[test]
+    fn rounding() {
+        let shown = |rounding, value: f32| {
+            let mut display = display();
+            display.set_rounding(rounding);
+            display.display_float(value, 0).unwrap();
+            digits(&display)[2..].to_vec()
+        };
+        let number = |tens: Segments, ones: Segments| vec![tens, ones];
+        let minus = Segments::MINUS;
+
+        assert_eq!(
+            number(Segments::empty(), Segments::DIGIT_3),
+            shown(Rounding::HalfUp, 2.5)
+        );
+        assert_eq!(
+            number(minus, Segments::DIGIT_3),
+            shown(Rounding::HalfUp, -2.5)
+        );
+        assert_eq!(
+            number(Segments::empty(), Segments::DIGIT_2),
+            shown(Rounding::Truncate, 2.9)
+        );
+        assert_eq!(
+            number(minus, Segments::DIGIT_2),
+            shown(Rounding::Truncate, -2.9)
+        );
+        assert_eq!(
+            number(Segments::empty(), Segments::DIGIT_2),
+            shown(Rounding::HalfEven, 2.5)
+        );
+        assert_eq!(
+            number(Segments::empty(), Segments::DIGIT_4),
+            shown(Rounding::HalfEven, 3.5)
+        );
+        assert_eq!(
+            number(Segments::empty(), Segments::DIGIT_3),
+            shown(Rounding::HalfEven, 2.6)
+        );
+    }
+
+    #[test]
+    fn rounding_fixed() {
+        assert_eq!(3, Rounding::HalfUp.divide(25, 10));
+        assert_eq!(-3, Rounding::HalfUp.divide(-25, 10));
+        assert_eq!(2, Rounding::Truncate.divide(29, 10));
+        assert_eq!(-2, Rounding::Truncate.divide(-29, 10));
+        assert_eq!(2, Rounding::HalfEven.divide(25, 10));
+        assert_eq!(-4, Rounding::HalfEven.divide(-35, 10));
+        assert_eq!(3, Rounding::HalfEven.divide(251, 100));
+    }
+
+    #[test]
+    fn write_fmt() {
+        use core::fmt::Write;
+
+        let mut display = display();
+
+        write!(display, "{:>4}", 42).unwrap();
+        assert_eq!(Segments::empty(), display.segments(1).unwrap());
+        assert_eq!(Segments::DIGIT_4, display.segments(2).unwrap());
+        assert_eq!(Segments::DIGIT_2, display.segments(3).unwrap());
+
+        // Decimal points join the previous digit, extra digits are dropped.
+        display.clear();
+        write!(display, "{:.3}:", 1.5).unwrap();
+        assert_eq!(
+            Segments::DIGIT_1 | Segments::DP,
+            display.segments(0).unwrap()
+        );
+        assert_eq!(Segments::DIGIT_5, display.segments(1).unwrap());
+        assert_eq!(Segments::DIGIT_0, display.segments(3).unwrap());
+        assert_eq!(Indicators::COLON, display.indicators());
+
+        display.clear();
+        write!(display, "..").unwrap();
+        assert_eq!(Segments::DP, display.segments(0).unwrap());
+        assert_eq!(Segments::DP, display.segments(1).unwrap());
+
+        assert!(write!(display, "%").is_err());
+    }
+
+    #[test]
+    fn display_ranged() {
+        let mut display = display();
+
+        display.display_ranged(999, &SI_SUFFIXES).unwrap();
+        assert_eq!(
+            digits(&display),
+            [
+                Segments::empty(),
+                Segments::DIGIT_9,
+                Segments::DIGIT_9,
+                Segments::DIGIT_9
+            ]
+        );
+
+        display.display_ranged(12_345, &SI_SUFFIXES).unwrap();
+        assert_eq!(
+            digits(&display),
+            [
+                Segments::DIGIT_1,
+                Segments::DIGIT_2 | Segments::DP,
+                Segments::DIGIT_3,
+                Segments::KILO
+            ]
+        );
+
+        display.display_ranged(1_234_567, &SI_SUFFIXES).unwrap();
+        assert_eq!(
+            digits(&display),
+            [
+                Segments::DIGIT_1 | Segments::DP,
+                Segments::DIGIT_2,
+                Segments::DIGIT_3,
+                Segments::MEGA
+            ]
+        );
+
+        display.display_ranged(-45_678, &SI_SUFFIXES).unwrap();
+        assert_eq!(
+            digits(&display),
+            [
+                Segments::MINUS,
+                Segments::DIGIT_4,
+                Segments::DIGIT_6,
+                Segments::KILO
+            ]
+        );
+
+        // Rounding up moves to the next suffix.
+        display.display_ranged(999_999, &SI_SUFFIXES).unwrap();
+        assert_eq!(
+            digits(&display),
+            [
+                Segments::DIGIT_1 | Segments::DP,
+                Segments::DIGIT_0,
+                Segments::DIGIT_0,
+                Segments::MEGA
+            ]
+        );
+
+        display.display_ranged(i32::MIN, &SI_SUFFIXES).unwrap();
+        assert_eq!(Segments::GIGA, digits(&display)[3]);
+    }
+
+    #[test]
+    fn display_ranged_too_large() {
+        let mut display = display();
+
+        display.display_ranged(1234, &[]).unwrap();
+        assert!(display.display_ranged(12_345, &[]).is_err());
+        assert!(display
+            .display_ranged(1_234_567, &[Segments::KILO])
+            .is_err());
+
+        // The previous value is still shown.
+        assert_eq!(
+            digits(&display),
+            [
+                Segments::DIGIT_1,
+                Segments::DIGIT_2,
+                Segments::DIGIT_3,
+                Segments::DIGIT_4
+            ]
+        );
+    }
+
+    #[test]
+    fn display_half() {
+        let mut display = display();
+
+        display.display_half(Half::Left, 21).unwrap();
+        display.display_half(Half::Right, -5).unwrap();
+        assert_eq!(
+            digits(&display),
+            [
+                Segments::DIGIT_2,
+                Segments::DIGIT_1,
+                Segments::MINUS,
+                Segments::DIGIT_5
+            ]
+        );
+
+        // Only the given half is changed.
+        display.display_half(Half::Left, 7).unwrap();
+        assert_eq!(
+            digits(&display),
+            [
+                Segments::empty(),
+                Segments::DIGIT_7,
+                Segments::MINUS,
+                Segments::DIGIT_5
+            ]
+        );
+
+        assert!(display.display_half(Half::Right, 100).is_err());
+        assert!(display.display_half(Half::Right, -10).is_err());
+        assert!(display.display_half(Half::Right, i32::MIN).is_err());
+        assert_eq!(Segments::DIGIT_5, digits(&display)[3]);
+    }
+
+    #[test]
+    fn display_split() {
+        let mut display = display();
+
+        display.display_split(45, 60, true).unwrap();
+        assert_eq!(
+            digits(&display),
+            [
+                Segments::DIGIT_4,
+                Segments::DIGIT_5,
+                Segments::DIGIT_6,
+                Segments::DIGIT_0
+            ]
+        );
+        assert_eq!(Indicators::COLON, display.indicators());
+
+        // Neither half is changed if one does not fit.
+        assert!(display.display_split(1, 123, false).is_err());
+        assert_eq!(Segments::DIGIT_4, digits(&display)[0]);
+        assert_eq!(Indicators::COLON, display.indicators());
+    }
+}
@@ -0,0 +1,332 @@
+//! # simulator
+//!
+//! Render the display RAM as a board would show it, to iterate on a UI without hardware and to attach
+//! screenshots to bug reports.
+//!
+//! A [`Renderer`](struct.Renderer.html) draws a [`Frame`](../struct.Frame.html) through a
+//! [layout](../layouts/index.html), either as a PNG image or as colored text for a terminal. The frame comes
+//! from the driver, see [frame()](../struct.HT16K33.html#method.frame), or from the chip RAM of an
+//! [`I2cMock`](../i2c_mock/struct.I2cMock.html), see [frame()](../i2c_mock/struct.Device.html#method.frame).
+//!
+//! The PNG images are uncompressed, which keeps the encoder small and free of dependencies; a 16x8 matrix at the
+//! default scale is about 100 KiB.
+//!
+//! *Requires the `simulator` feature.*
+use crate::layouts::Layout;
+use crate::types::Frame;
+
+use core::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::vec::Vec;
+
+/// The default size of a pixel, in image pixels.
+pub const DEFAULT_SCALE: u8 = 16;
+
+// The largest block of a stored (uncompressed) deflate stream.
+const STORED_BLOCK_SIZE: usize = 0xFFFF;
+
+// The PNG file signature.
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+/// An RGB color.
+pub type Color = [u8; 3];
+
+/// Draws frames through a layout, as PNG images or terminal text.
+///
+/// Each pixel of the layout is a square of [scale()](struct.Renderer.html#method.scale) image pixels, with a
+/// dark border so neighbouring LEDs stay apart.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// use ht16k33::i2c_mock::I2cMock;
+/// use ht16k33::layouts::Matrix8x8;
+/// use ht16k33::simulator::Renderer;
+/// use ht16k33::{Address, LedLocation, HT16K33};
+/// # fn main() -> Result<(), Error> {
+///
+/// let mut ht16k33 = HT16K33::new(I2cMock::new(), Address::DEFAULT).initialize()?;
+/// ht16k33.set_led(LedLocation::new(0, 0)?, true)?;
+///
+/// let i2c = ht16k33.destroy();
+/// let frame = i2c.device(Address::DEFAULT).frame();
+///
+/// let renderer = Renderer::new(Matrix8x8);
+/// let png = renderer.render_png(&frame);
+/// assert_eq!(b"PNG", &png[1..4]);
+///
+/// // In a terminal, e.g. `print!("{}", text)`.
+/// let mut text = String::new();
+/// renderer.render_text(&frame, &mut text)?;
+///
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Renderer<L> {
+    layout: L,
+    scale: u8,
+    on: Color,
+    off: Color,
+}
+
+impl<L> Renderer<L>
+where
+    L: Layout,
+{
+    /// Create a `Renderer` drawing red LEDs at the [`DEFAULT_SCALE`](constant.DEFAULT_SCALE.html).
+    pub fn new(layout: L) -> Self {
+        Renderer {
+            layout,
+            scale: DEFAULT_SCALE,
+            on: [0xFF, 0x30, 0x20],
+            off: [0x30, 0x10, 0x10],
+        }
+    }
+
+    /// Return the layout.
+    pub fn layout(&self) -> &L {
+        &self.layout
+    }
+
+    /// Return the size of a pixel, in image pixels.
+    pub fn scale(&self) -> u8 {
+        self.scale
+    }
+
+    /// Set the size of a pixel, in image pixels, at least 1.
+    pub fn set_scale(&mut self, scale: u8) {
+        self.scale = scale.max(1);
+    }
+
+    /// Set the colors of the enabled and disabled LEDs, e.g. for a green or blue matrix.
+    pub fn set_colors(&mut self, on: Color, off: Color) {
+        self.on = on;
+        self.off = off;
+    }
+
+    /// Return the width and height of the images, in image pixels.
+    pub fn size(&self) -> (u32, u32) {
+        let scale = self.scale as u32;
+        (
+            self.layout.width() as u32 * scale,
+            self.layout.height() as u32 * scale,
+        )
+    }
+
+    /// Return the `frame` as a PNG image.
+    pub fn render_png(&self, frame: &Frame) -> Vec<u8> {
+        let (width, height) = self.size();
+        let scale = self.scale as u32;
+        // The dark border around each LED, none for the smallest scales.
+        let border = scale / 8;
+
+        let mut pixels = Vec::with_capacity(((width * 3 + 1) * height) as usize);
+        for y in 0..height {
+            // Each scanline starts with its filter type, none.
+            pixels.push(0);
+
+            for x in 0..width {
+                let (inner_x, inner_y) = (x % scale, y % scale);
+                let inside = inner_x >= border
+                    && inner_y >= border
+                    && inner_x < scale - border
+                    && inner_y < scale - border;
+
+                let color = match self.enabled(frame, (x / scale) as u8, (y / scale) as u8) {
+                    Some(true) if inside => self.on,
+                    Some(false) if inside => self.off,
+                    _ => [0, 0, 0],
+                };
+                pixels.extend_from_slice(&color);
+            }
+        }
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&width.to_be_bytes());
+        header.extend_from_slice(&height.to_be_bytes());
+        // 8-bit RGB, deflate, no filters, no interlacing.
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+        let mut png = PNG_SIGNATURE.to_vec();
+        write_chunk(&mut png, b"IHDR", &header);
+        write_chunk(&mut png, b"IDAT", &zlib_stored(&pixels));
+        write_chunk(&mut png, b"IEND", &[]);
+
+        png
+    }
+
+    /// Write the `frame` as a PNG image to the file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// The error of writing the file is returned as is.
+    pub fn save_png<P>(&self, frame: &Frame, path: P) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        fs::write(path, self.render_png(frame))
+    }
+
+    /// Write the `frame` as text for a terminal, a line per row of the layout.
+    ///
+    /// LEDs are drawn as `●` in the enabled color, or `·` in the disabled color, using 24-bit ANSI colors. Print
+    /// `"\x1b[H"` before each frame to redraw it in place.
+    pub fn render_text<W>(&self, frame: &Frame, out: &mut W) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        for y in 0..self.layout.height() {
+            for x in 0..self.layout.width() {
+                match self.enabled(frame, x, y) {
+                    Some(true) => write_colored(out, self.on, '●')?,
+                    Some(false) => write_colored(out, self.off, '·')?,
+                    None => write!(out, "  ")?,
+                }
+            }
+            writeln!(out, "\x1b[0m")?;
+        }
+
+        Ok(())
+    }
+
+    // Return whether the pixel at `(x, y)` is enabled, or `None` if it is not wired.
+    fn enabled(&self, frame: &Frame, x: u8, y: u8) -> Option<bool> {
+        self.layout
+            .location(x, y)
+            .map(|location| frame.led(location))
+    }
+}
+
+fn write_colored<W>(out: &mut W, color: Color, c: char) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    write!(
+        out,
+        "\x1b[38;2;{};{};{}m{} ",
+        color[0], color[1], color[2], c
+    )
+}
+
+// Append a PNG chunk: the length, the type, the data and the CRC of the type and data.
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+// Wrap the `data` in a zlib stream of stored deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let blocks = data.len() / STORED_BLOCK_SIZE + 1;
+    let mut stream = Vec::with_capacity(data.len() + blocks * 5 + 6);
+
+    // Deflate with a 32 KiB window, no preset dictionary.
+    stream.extend_from_slice(&[0x78, 0x01]);
+
+    let mut chunks = data.chunks(STORED_BLOCK_SIZE).peekable();
+    if chunks.peek().is_none() {
+        stream.extend_from_slice(&[1, 0x00, 0x00, 0xFF, 0xFF]);
+    }
+    while let Some(chunk) = chunks.next() {
+        let last = chunks.peek().is_none();
+        let length = chunk.len() as u16;
+
+        stream.push(last as u8);
+        stream.extend_from_slice(&length.to_le_bytes());
+        stream.extend_from_slice(&(!length).to_le_bytes());
+        stream.extend_from_slice(chunk);
+    }
+
+    stream.extend_from_slice(&adler32(data).to_be_bytes());
+
+    stream
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MODULUS: u32 = 65521;
+
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data {
+        a = (a + *byte as u32) % MODULUS;
+        b = (b + a) % MODULUS;
+    }
+
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layouts::Matrix8x8;
+    use crate::types::LedLocation;
+
+    #[test]
+    fn checksums() {
+        assert_eq!(0xCBF4_3926, crc32(b"123456789"));
+        assert_eq!(0x091E_01DE, adler32(b"123456789"));
+    }
+
+    #[test]
+    fn render_png() {
+        let mut renderer = Renderer::new(Matrix8x8);
+        renderer.set_scale(2);
+
+        let mut frame = Frame::empty();
+        let location = Matrix8x8.location(0, 0).unwrap();
+        frame.set_led(location, true);
+
+        let png = renderer.render_png(&frame);
+        assert_eq!(PNG_SIGNATURE, png[..8]);
+        // The IHDR chunk, 16x16 pixels.
+        assert_eq!(b"IHDR", &png[12..16]);
+        assert_eq!(16u32.to_be_bytes(), png[16..20]);
+        assert_eq!(16u32.to_be_bytes(), png[20..24]);
+        // The IEND chunk has a fixed CRC.
+        assert_eq!([0xAE, 0x42, 0x60, 0x82], png[png.len() - 4..]);
+
+        // The stored block holds the scanlines as-is: the filter type, then the first pixel.
+        let idat = 8 + 25 + 8;
+        let pixels = &png[idat + 2 + 5..];
+        assert_eq!(0, pixels[0]);
+        assert_eq!([0xFF, 0x30, 0x20], pixels[1..4]);
+        assert_eq!([0xFF, 0x30, 0x20], pixels[4..7]);
+        assert_eq!([0x30, 0x10, 0x10], pixels[7..10]);
+    }
+
+    #[test]
+    fn render_text() {
+        let renderer = Renderer::new(Matrix8x8);
+
+        let mut frame = Frame::empty();
+        frame.set_led(LedLocation::new(0, 0).unwrap(), true);
+
+        let mut text = String::new();
+        renderer.render_text(&frame, &mut text).unwrap();
+
+        assert_eq!(8, text.lines().count());
+        assert_eq!(1, text.matches('●').count());
+        assert_eq!(63, text.matches('·').count());
+    }
+}
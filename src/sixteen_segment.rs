@@ -0,0 +1,546 @@
+//! # sixteen_segment
+//!
+//! Support for 16-segment display modules wired to the HT16K33.
+//!
+//! Unlike the 14-segment [`Alphanumeric4`](../layouts/struct.Alphanumeric4.html) backpacks, 16-segment digits split
+//! the top and bottom segments in two, and each character takes two full bytes of the display RAM, so up to
+//! [`DIGITS_SIZE`](constant.DIGITS_SIZE.html) characters fit:
+//!
+//! | Row         | Contents                                     |
+//! |-------------|----------------------------------------------|
+//! | `2*n`       | Segments `A1` to `F` of digit `n`, bits 0-7  |
+//! | `2*n + 1`   | Segments `G1` to `M` of digit `n`, bits 8-15 |
+//!
+//! Within each row, the segment with the lowest bit is common 0, see [`Segments`](struct.Segments.html). There
+//! is no room for a decimal point in the two bytes, so a point takes a digit of its own.
+use bitflags::bitflags;
+use core::fmt;
+
+use crate::constants::ROWS_SIZE;
+use crate::errors::ValidationError;
+use crate::hal::blocking::i2c::Write;
+use crate::seven_segment::Rounding;
+use crate::types::DisplayData;
+use crate::units::{self, Unit};
+use crate::HT16K33;
+
+/// The number of digits that fit in the display RAM.
+pub const DIGITS_SIZE: usize = ROWS_SIZE / 2;
+
+bitflags! {
+    /// The segments of a single digit.
+    ///
+    /// ```text
+    ///  -A1- -A2-
+    /// |\   |   /|
+    /// F H  I  J B
+    /// |  \ | /  |
+    ///  -G1- -G2-
+    /// |  / | \  |
+    /// E K  L  M C
+    /// |/   |   \|
+    ///  -D1- -D2-
+    /// ```
+    pub struct Segments: u16 {
+        /// Top-left segment.
+        const A1 = 1 << 0;
+        /// Top-right segment.
+        const A2 = 1 << 1;
+        /// Right upper segment.
+        const B = 1 << 2;
+        /// Right lower segment.
+        const C = 1 << 3;
+        /// Bottom-right segment.
+        const D2 = 1 << 4;
+        /// Bottom-left segment.
+        const D1 = 1 << 5;
+        /// Left lower segment.
+        const E = 1 << 6;
+        /// Left upper segment.
+        const F = 1 << 7;
+        /// Middle-left segment.
+        const G1 = 1 << 8;
+        /// Middle-right segment.
+        const G2 = 1 << 9;
+        /// Upper-left diagonal segment.
+        const H = 1 << 10;
+        /// Upper vertical segment.
+        const I = 1 << 11;
+        /// Upper-right diagonal segment.
+        const J = 1 << 12;
+        /// Lower-right diagonal segment.
+        const M = 1 << 13;
+        /// Lower vertical segment.
+        const L = 1 << 14;
+        /// Lower-left diagonal segment.
+        const K = 1 << 15;
+
+        /// Both top segments.
+        const TOP = Self::A1.bits | Self::A2.bits;
+        /// Both middle segments.
+        const MIDDLE = Self::G1.bits | Self::G2.bits;
+        /// Both bottom segments.
+        const BOTTOM = Self::D1.bits | Self::D2.bits;
+        /// Both vertical segments, e.g. for `I` and `T`.
+        const CENTER = Self::I.bits | Self::L.bits;
+        /// The outline of the digit, e.g. for `O`.
+        const OUTLINE = Self::TOP.bits | Self::B.bits | Self::C.bits | Self::BOTTOM.bits | Self::E.bits | Self::F.bits;
+    }
+}
+
+impl Segments {
+    /// Return the segments of the given character, if any.
+    ///
+    /// Digits, upper case letters and most ASCII punctuation are supported, lower case letters are shown in upper
+    /// case.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ht16k33::sixteen_segment::Segments;
+    ///
+    /// assert_eq!(Some(Segments::TOP | Segments::CENTER), Segments::from_char('T'));
+    /// assert_eq!(Segments::from_char('X'), Segments::from_char('x'));
+    /// assert_eq!(None, Segments::from_char('€'));
+    /// ```
+    pub const fn from_char(c: char) -> Option<Self> {
+        if (c as u32) < ASCII_FONT.len() as u32 {
+            ASCII_FONT[c as usize]
+        } else {
+            None
+        }
+    }
+
+    /// Return the two display RAM bytes of the segments, for rows `2*n` and `2*n + 1` of digit `n`.
+    pub const fn to_rows(self) -> [u8; 2] {
+        let bits = self.bits();
+        [bits as u8, (bits >> 8) as u8]
+    }
+
+    /// Return the segments of the two display RAM bytes of a digit.
+    pub const fn from_rows(rows: [u8; 2]) -> Self {
+        Segments::from_bits_truncate(rows[0] as u16 | (rows[1] as u16) << 8)
+    }
+}
+
+/// The segments of the ASCII characters, as returned by
+/// [Segments::from_char()](struct.Segments.html#method.from_char).
+///
+/// The table is built at compile time, so looking up a character costs a single index.
+pub const ASCII_FONT: [Option<Segments>; 128] = ascii_font();
+
+/// Return the segment bits of the character `c`, or `0` if it cannot be shown.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::sixteen_segment::{encode_char, Segments};
+///
+/// const PLUS: u16 = encode_char('+');
+///
+/// assert_eq!((Segments::MIDDLE | Segments::CENTER).bits(), PLUS);
+/// assert_eq!(0, encode_char('€'));
+/// ```
+pub const fn encode_char(c: char) -> u16 {
+    match Segments::from_char(c) {
+        Some(segments) => segments.bits(),
+        None => 0,
+    }
+}
+
+const fn ascii_font() -> [Option<Segments>; 128] {
+    let mut font = [None; 128];
+    let mut code = 0;
+    while code < font.len() {
+        font[code] = ascii_glyph(code as u8 as char);
+        code += 1;
+    }
+
+    font
+}
+
+// Join the given segments.
+const fn join(parts: &[Segments]) -> Segments {
+    let mut segments = Segments::empty();
+    let mut index = 0;
+    while index < parts.len() {
+        segments = segments.union(parts[index]);
+        index += 1;
+    }
+
+    segments
+}
+
+// Return the segments of the ASCII character `c`, if any.
+const fn ascii_glyph(c: char) -> Option<Segments> {
+    use Segments as S;
+
+    let segments = match c.to_ascii_uppercase() {
+        ' ' => S::empty(),
+        '0' => join(&[S::OUTLINE, S::J, S::K]),
+        '1' => join(&[S::B, S::C, S::J]),
+        '2' => join(&[S::TOP, S::B, S::MIDDLE, S::E, S::BOTTOM]),
+        '3' => join(&[S::TOP, S::B, S::G2, S::C, S::BOTTOM]),
+        '4' => join(&[S::F, S::MIDDLE, S::B, S::C]),
+        '5' => join(&[S::TOP, S::F, S::MIDDLE, S::C, S::BOTTOM]),
+        '6' => join(&[S::TOP, S::F, S::E, S::BOTTOM, S::C, S::MIDDLE]),
+        '7' => join(&[S::TOP, S::B, S::C]),
+        '8' => join(&[S::OUTLINE, S::MIDDLE]),
+        '9' => join(&[S::TOP, S::B, S::C, S::BOTTOM, S::F, S::MIDDLE]),
+        'A' => join(&[S::TOP, S::B, S::C, S::E, S::F, S::MIDDLE]),
+        'B' => join(&[S::TOP, S::B, S::C, S::BOTTOM, S::CENTER, S::G2]),
+        'C' => join(&[S::TOP, S::F, S::E, S::BOTTOM]),
+        'D' => join(&[S::TOP, S::B, S::C, S::BOTTOM, S::CENTER]),
+        'E' => join(&[S::TOP, S::F, S::E, S::BOTTOM, S::G1]),
+        'F' => join(&[S::TOP, S::F, S::E, S::G1]),
+        'G' => join(&[S::TOP, S::F, S::E, S::BOTTOM, S::C, S::G2]),
+        'H' => join(&[S::F, S::E, S::B, S::C, S::MIDDLE]),
+        'I' => join(&[S::TOP, S::CENTER, S::BOTTOM]),
+        'J' => join(&[S::B, S::C, S::BOTTOM, S::E]),
+        'K' => join(&[S::F, S::E, S::G1, S::J, S::M]),
+        'L' => join(&[S::F, S::E, S::BOTTOM]),
+        'M' => join(&[S::F, S::E, S::B, S::C, S::H, S::J]),
+        'N' => join(&[S::F, S::E, S::B, S::C, S::H, S::M]),
+        'O' => S::OUTLINE,
+        'P' => join(&[S::TOP, S::B, S::F, S::E, S::MIDDLE]),
+        'Q' => join(&[S::OUTLINE, S::M]),
+        'R' => join(&[S::TOP, S::B, S::F, S::E, S::MIDDLE, S::M]),
+        'S' => join(&[S::TOP, S::F, S::MIDDLE, S::C, S::BOTTOM]),
+        'T' => join(&[S::TOP, S::CENTER]),
+        'U' => join(&[S::F, S::E, S::BOTTOM, S::C, S::B]),
+        'V' => join(&[S::F, S::E, S::K, S::J]),
+        'W' => join(&[S::F, S::E, S::B, S::C, S::K, S::M]),
+        'X' => join(&[S::H, S::J, S::K, S::M]),
+        'Y' => join(&[S::H, S::J, S::L]),
+        'Z' => join(&[S::TOP, S::J, S::K, S::BOTTOM]),
+        '-' => S::MIDDLE,
+        '+' => join(&[S::MIDDLE, S::CENTER]),
+        '*' => join(&[S::MIDDLE, S::CENTER, S::H, S::J, S::K, S::M]),
+        '/' => join(&[S::J, S::K]),
+        '\\' => join(&[S::H, S::M]),
+        '_' => S::BOTTOM,
+        '=' => join(&[S::MIDDLE, S::BOTTOM]),
+        '|' => S::CENTER,
+        '(' | '<' => join(&[S::J, S::M]),
+        ')' | '>' => join(&[S::H, S::K]),
+        '[' => join(&[S::A2, S::CENTER, S::D2]),
+        ']' => join(&[S::A1, S::CENTER, S::D1]),
+        '\'' => S::I,
+        '"' => join(&[S::F, S::I]),
+        ',' => S::K,
+        '.' => S::D1,
+        '^' => join(&[S::K, S::M]),
+        '$' => join(&[S::TOP, S::F, S::MIDDLE, S::C, S::BOTTOM, S::CENTER]),
+        '?' => join(&[S::TOP, S::B, S::G2, S::L]),
+        _ => return None,
+    };
+
+    Some(segments)
+}
+
+/// A row of 16-segment digits, wrapping an initialized [`HT16K33`] driver.
+///
+/// The digit helpers update the display buffer, which must be written using
+/// [write_display_buffer()](struct.SixteenSegment.html#method.write_display_buffer) for the change to be
+/// displayed.
+///
+/// # Formatting
+///
+/// The display implements [`core::fmt::Write`], so text can be written with `write!`. Characters are shown from
+/// the first digit on, using [Segments::from_char()](struct.Segments.html#method.from_char), and characters past
+/// the last digit are dropped. Writing a character without a glyph returns a [`core::fmt::Error`].
+/// [clear()](struct.SixteenSegment.html#method.clear) blanks the display and moves back to the first digit.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use core::fmt::Write;
+/// use ht16k33::sixteen_segment::{Segments, SixteenSegment};
+/// use ht16k33::HT16K33;
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// let mut display = SixteenSegment::new(HT16K33::new(i2c, address).initialize()?);
+///
+/// write!(display, "RUST")?;
+/// display.write_display_buffer()?;
+///
+/// assert_eq!(Segments::from_char('T'), Some(display.segments(3)?));
+///
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`HT16K33`]: ../struct.HT16K33.html
+/// [`core::fmt::Write`]: https://doc.rust-lang.org/core/fmt/trait.Write.html
+/// [`core::fmt::Error`]: https://doc.rust-lang.org/core/fmt/struct.Error.html
+pub struct SixteenSegment<I2C> {
+    driver: HT16K33<I2C>,
+
+    // The digit written next by `fmt::Write`.
+    cursor: u8,
+}
+
+impl<I2C, E> SixteenSegment<I2C>
+where
+    I2C: Write<Error = E>,
+{
+    /// Create a `SixteenSegment` display from the given driver.
+    pub fn new(driver: HT16K33<I2C>) -> Self {
+        SixteenSegment { driver, cursor: 0 }
+    }
+
+    /// Return the driver.
+    pub fn driver(&self) -> &HT16K33<I2C> {
+        &self.driver
+    }
+
+    /// Return the driver for modification, e.g. to change the dimming.
+    pub fn driver_mut(&mut self) -> &mut HT16K33<I2C> {
+        &mut self.driver
+    }
+
+    /// Return the driver, consuming the display.
+    pub fn into_driver(self) -> HT16K33<I2C> {
+        self.driver
+    }
+
+    /// Write the display buffer to the HT16K33 chip.
+    pub fn write_display_buffer(&mut self) -> Result<(), E> {
+        self.driver.write_display_buffer()
+    }
+
+    /// Blank all digits, and move the formatting cursor back to the first digit.
+    pub fn clear(&mut self) {
+        self.driver.clear_display_buffer();
+        self.cursor = 0;
+    }
+
+    /// Set the raw segments of a digit.
+    ///
+    /// # Errors
+    ///
+    /// If the `index` is not less than [`DIGITS_SIZE`] then [`ht16k33::ValidationError::ValueTooLarge`] is
+    /// returned.
+    ///
+    /// [`DIGITS_SIZE`]: constant.DIGITS_SIZE.html
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    pub fn set_segments(&mut self, index: u8, segments: Segments) -> Result<(), ValidationError> {
+        let row = digit_row(index)?;
+        let [low, high] = segments.to_rows();
+
+        let buffer = self.driver.display_buffer_mut();
+        buffer[row] = DisplayData::from_bits_truncate(low);
+        buffer[row + 1] = DisplayData::from_bits_truncate(high);
+
+        Ok(())
+    }
+
+    /// Return the raw segments of a digit.
+    ///
+    /// # Errors
+    ///
+    /// If the `index` is not less than [`DIGITS_SIZE`] then [`ht16k33::ValidationError::ValueTooLarge`] is
+    /// returned.
+    ///
+    /// [`DIGITS_SIZE`]: constant.DIGITS_SIZE.html
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    pub fn segments(&self, index: u8) -> Result<Segments, ValidationError> {
+        let row = digit_row(index)?;
+        let buffer = self.driver.display_buffer();
+
+        Ok(Segments::from_rows([
+            buffer[row].bits(),
+            buffer[row + 1].bits(),
+        ]))
+    }
+
+    /// Show a character on a digit.
+    ///
+    /// # Errors
+    ///
+    /// If the `index` is not less than [`DIGITS_SIZE`] then [`ht16k33::ValidationError::ValueTooLarge`] is
+    /// returned. If the character has no glyph, see [Segments::from_char()](struct.Segments.html#method.from_char),
+    /// then [`ht16k33::ValidationError::OutOfRange`] is returned.
+    ///
+    /// [`DIGITS_SIZE`]: constant.DIGITS_SIZE.html
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+    /// [`ht16k33::ValidationError::OutOfRange`]: ../enum.ValidationError.html#variant.OutOfRange
+    pub fn set_char(&mut self, index: u8, c: char) -> Result<(), ValidationError> {
+        let segments = Segments::from_char(c).ok_or(ValidationError::OutOfRange {
+            name: "character",
+            value: (c as u32).min(u8::MAX as u32) as u8,
+            min: b' ',
+            max: b'~',
+        })?;
+
+        self.set_segments(index, segments)
+    }
+
+    /// Show a measured value with its unit, right-aligned and auto-ranged with an SI prefix, e.g. `21.5` degrees
+    /// as `21.5000C` and `12345678` as `12345.7k`.
+    ///
+    /// The value is formatted on one digit less than [`DIGITS_SIZE`], leaving a digit for the decimal point, with
+    /// as many decimals as fit, see [`units`](../units/index.html). The unit symbol is blank if it has no glyph,
+    /// see [Segments::from_char()](struct.Segments.html#method.from_char).
+    ///
+    /// # Errors
+    ///
+    /// If the value does not fit even with the last prefix, or is not a number, then
+    /// [`ht16k33::ValidationError::Overflow`] is returned and the display buffer is left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # use ht16k33::i2c_mock::I2cMock;
+    /// use ht16k33::HT16K33;
+    /// use ht16k33::sixteen_segment::SixteenSegment;
+    /// use ht16k33::units::Unit;
+    /// # fn main() -> Result<(), Error> {
+    /// # let mut i2c = I2cMock::new();
+    /// # let address = ht16k33::Address::DEFAULT;
+    ///
+    /// let mut display = SixteenSegment::new(HT16K33::new(i2c, address).initialize()?);
+    ///
+    /// // Shown as `21.5000C`.
+    /// display.display_value(21.5, Unit::Symbol('C'))?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`DIGITS_SIZE`]: constant.DIGITS_SIZE.html
+    /// [`ht16k33::ValidationError::Overflow`]: ../enum.ValidationError.html#variant.Overflow
+    pub fn display_value(&mut self, value: f32, unit: Unit) -> Result<(), ValidationError> {
+        let text = units::format_value(value, unit, DIGITS_SIZE - 1, Rounding::HalfUp)?;
+
+        let mut digits = [Segments::empty(); DIGITS_SIZE];
+        let start = DIGITS_SIZE - text.chars().len();
+        for (digit, &c) in digits[start..].iter_mut().zip(text.chars()) {
+            *digit = Segments::from_char(c).unwrap_or_else(Segments::empty);
+        }
+
+        for (index, segments) in digits.iter().enumerate() {
+            self.set_segments(index as u8, *segments)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<I2C, E> fmt::Write for SixteenSegment<I2C>
+where
+    I2C: Write<Error = E>,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            let segments = Segments::from_char(c).ok_or(fmt::Error)?;
+            if (self.cursor as usize) < DIGITS_SIZE {
+                self.set_segments(self.cursor, segments)
+                    .map_err(|_| fmt::Error)?;
+                self.cursor += 1;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Return the first display RAM row of the digit at `index`.
+fn digit_row(index: u8) -> Result<usize, ValidationError> {
+    if index as usize >= DIGITS_SIZE {
+        return Err(ValidationError::ValueTooLarge {
+            name: "digit",
+            value: index,
+            limit: DIGITS_SIZE as u8,
+            inclusive: false,
+        });
+    }
+
+    Ok(index as usize * 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c_mock::I2cMock;
+    use crate::types::Address;
+    use core::fmt::Write as _;
+
+    fn display() -> SixteenSegment<I2cMock> {
+        SixteenSegment::new(
+            HT16K33::new(I2cMock::new(), Address::DEFAULT)
+                .initialize()
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn font() {
+        // Every printable glyph is distinct from the blank.
+        for c in '!'..='~' {
+            if let Some(segments) = Segments::from_char(c) {
+                assert!(!segments.is_empty(), "{:?} is blank", c);
+            }
+        }
+
+        assert_eq!(Some(Segments::empty()), Segments::from_char(' '));
+        assert_eq!(Segments::from_char('A'), Segments::from_char('a'));
+        assert_ne!(Segments::from_char('0'), Segments::from_char('O'));
+        assert_eq!(None, Segments::from_char('\n'));
+    }
+
+    #[test]
+    fn set_segments() {
+        let mut display = display();
+
+        display.set_segments(1, Segments::A1 | Segments::K).unwrap();
+
+        let buffer = display.driver().display_buffer();
+        assert_eq!(0b0000_0001, buffer[2].bits());
+        assert_eq!(0b1000_0000, buffer[3].bits());
+        assert_eq!(Segments::A1 | Segments::K, display.segments(1).unwrap());
+
+        assert!(display.set_segments(8, Segments::A1).is_err());
+        assert!(display.set_char(0, '€').is_err());
+    }
+
+    #[test]
+    fn display_value() {
+        let mut display = display();
+
+        display.display_value(12_345_678.0, Unit::None).unwrap();
+        let expected = ['1', '2', '3', '4', '5', '.', '7', 'K'];
+        for (index, c) in expected.iter().enumerate() {
+            assert_eq!(
+                Segments::from_char(*c),
+                Some(display.segments(index as u8).unwrap())
+            );
+        }
+
+        display.display_value(-1.5, Unit::Symbol('V')).unwrap();
+        assert_eq!(Segments::from_char('-'), Some(display.segments(0).unwrap()));
+        assert_eq!(Segments::from_char('V'), Some(display.segments(7).unwrap()));
+
+        assert!(display.display_value(f32::NAN, Unit::None).is_err());
+        assert_eq!(Segments::from_char('-'), Some(display.segments(0).unwrap()));
+    }
+
+    #[test]
+    fn write_text() {
+        let mut display = display();
+
+        write!(display, "HELLO WORLD").unwrap();
+        assert_eq!(Segments::from_char('W'), Some(display.segments(6).unwrap()));
+        assert_eq!(Segments::from_char('O'), Some(display.segments(7).unwrap()));
+
+        display.clear();
+        assert!(write!(display, "\t").is_err());
+        write!(display, "X").unwrap();
+        assert_eq!(Segments::from_char('X'), Some(display.segments(0).unwrap()));
+    }
+}
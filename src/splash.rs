@@ -0,0 +1,118 @@
+//! # splash
+//!
+//! Play a short boot animation with a single call.
+use crate::hal::blocking::delay::DelayMs;
+use crate::hal::blocking::i2c::Write;
+use crate::types::{Display, Frame};
+use crate::{Uninitialized, HT16K33};
+
+/// Initialize the `driver`, play the `frames` and return the initialized driver.
+///
+/// The display is turned on while the frames play, and left on with a cleared display buffer afterwards.
+///
+/// # Arguments
+///
+/// * `driver` - The uninitialized driver.
+/// * `frames` - The frames to play, in order.
+/// * `frame_duration` - How long each frame is shown, in milliseconds.
+/// * `delay` - The delay used to time the frames.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use embedded_hal::blocking::delay::DelayMs;
+/// use ht16k33::splash::show_splash;
+/// use ht16k33::{DisplayData, Frame, HT16K33, ROWS_SIZE};
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// # struct Delay;
+/// # impl DelayMs<u16> for Delay {
+/// #     fn delay_ms(&mut self, _ms: u16) {}
+/// # }
+/// # let mut delay = Delay;
+/// let frames = [
+///     Frame::from_rows([DisplayData::COMMON_0; ROWS_SIZE]),
+///     Frame::from_rows([DisplayData::all(); ROWS_SIZE]),
+/// ];
+///
+/// let ht16k33 = show_splash(HT16K33::new(i2c, address), &frames, 250, &mut delay)?;
+///
+/// # Ok(())
+/// # }
+/// ```
+pub fn show_splash<I2C, E, D>(
+    driver: HT16K33<I2C, Uninitialized>,
+    frames: &[Frame],
+    frame_duration: u16,
+    delay: &mut D,
+) -> Result<HT16K33<I2C>, E>
+where
+    I2C: Write<Error = E>,
+    D: DelayMs<u16>,
+{
+    let mut driver = driver.initialize()?;
+    driver.set_display(Display::ON)?;
+
+    for frame in frames {
+        *driver.display_buffer_mut() = *frame.rows();
+        driver.flush()?;
+        delay.delay_ms(frame_duration);
+    }
+
+    driver.clear_display_buffer();
+    driver.flush()?;
+
+    Ok(driver)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::ROWS_SIZE;
+    use crate::i2c_mock::I2cMock;
+    use crate::types::Address;
+    use crate::types::DisplayData;
+
+    struct Delay {
+        total: u32,
+    }
+
+    impl DelayMs<u16> for Delay {
+        fn delay_ms(&mut self, ms: u16) {
+            self.total += ms as u32;
+        }
+    }
+
+    #[test]
+    fn show() {
+        let frames = [
+            Frame::from_rows([DisplayData::COMMON_0; ROWS_SIZE]),
+            Frame::from_rows([DisplayData::COMMON_1; ROWS_SIZE]),
+            Frame::from_rows([DisplayData::COMMON_2; ROWS_SIZE]),
+        ];
+        let mut delay = Delay { total: 0 };
+
+        let driver = show_splash(
+            HT16K33::new(I2cMock::new(), Address::DEFAULT),
+            &frames,
+            100,
+            &mut delay,
+        )
+        .unwrap();
+
+        assert_eq!(300, delay.total);
+        assert_eq!(Display::ON, *driver.display());
+        assert_eq!(
+            [DisplayData::COMMON_NONE; ROWS_SIZE],
+            *driver.display_buffer()
+        );
+
+        let i2c = driver.destroy();
+        assert_eq!(Display::ON, i2c.display());
+        assert_eq!([0; ROWS_SIZE], i2c.device(Address::DEFAULT).data_values);
+    }
+}
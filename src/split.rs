@@ -0,0 +1,175 @@
+//! # split
+//!
+//! Separate display and keypad handles sharing one driver, so that one task can refresh the display while
+//! another polls the keys.
+//!
+//! A [`SharedDriver`](struct.SharedDriver.html) keeps the driver in a `RefCell`, and
+//! [split()](struct.SharedDriver.html#method.split) lends it to a [`DisplayHandle`](struct.DisplayHandle.html)
+//! and a [`KeypadHandle`](struct.KeypadHandle.html). Each call of a handle borrows the driver only for that
+//! call, so the handles can be used in any order from the same thread, e.g. from tasks of a cooperative
+//! executor.
+use crate::hal::blocking::i2c::{Write, WriteRead};
+use crate::keypad::Keypad;
+use crate::types::{Dimming, Display, Frame, LedLocation};
+use crate::{HT16K33, KEYS_SIZE};
+
+use core::cell::RefCell;
+
+/// An initialized driver shared by a [`DisplayHandle`] and a [`KeypadHandle`].
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use ht16k33::split::SharedDriver;
+/// use ht16k33::keypad::Keypad;
+/// use ht16k33::{LedLocation, HT16K33};
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// let shared = SharedDriver::new(HT16K33::new(i2c, address).initialize()?);
+/// let (display, keypad) = shared.split();
+///
+/// // The display task.
+/// display.update_display_buffer(LedLocation::new(0, 0)?, true);
+/// display.flush()?;
+///
+/// // The keypad task.
+/// let mut events: Keypad<8> = Keypad::new(2);
+/// keypad.poll(&mut events)?;
+///
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`DisplayHandle`]: struct.DisplayHandle.html
+/// [`KeypadHandle`]: struct.KeypadHandle.html
+pub struct SharedDriver<I2C> {
+    driver: RefCell<HT16K33<I2C>>,
+}
+
+impl<I2C> SharedDriver<I2C> {
+    /// Create a `SharedDriver` from the given driver.
+    pub fn new(driver: HT16K33<I2C>) -> Self {
+        SharedDriver {
+            driver: RefCell::new(driver),
+        }
+    }
+
+    /// Return the display and keypad handles.
+    pub fn split(&self) -> (DisplayHandle<'_, I2C>, KeypadHandle<'_, I2C>) {
+        (
+            DisplayHandle {
+                driver: &self.driver,
+            },
+            KeypadHandle {
+                driver: &self.driver,
+            },
+        )
+    }
+
+    /// Return the driver, consuming the shared driver and its handles.
+    pub fn into_driver(self) -> HT16K33<I2C> {
+        self.driver.into_inner()
+    }
+}
+
+/// The display half of a [`SharedDriver`](struct.SharedDriver.html).
+#[derive(Clone, Copy)]
+pub struct DisplayHandle<'a, I2C> {
+    driver: &'a RefCell<HT16K33<I2C>>,
+}
+
+impl<I2C, E> DisplayHandle<'_, I2C>
+where
+    I2C: Write<Error = E>,
+{
+    /// Enable/disable an LED in the display buffer, see
+    /// [HT16K33::update_display_buffer()](../struct.HT16K33.html#method.update_display_buffer).
+    pub fn update_display_buffer(&self, location: LedLocation, enabled: bool) {
+        self.driver
+            .borrow_mut()
+            .update_display_buffer(location, enabled);
+    }
+
+    /// Disable all LEDs in the display buffer.
+    pub fn clear_display_buffer(&self) {
+        self.driver.borrow_mut().clear_display_buffer();
+    }
+
+    /// Return the display buffer as a [`Frame`](../struct.Frame.html).
+    pub fn frame(&self) -> Frame {
+        self.driver.borrow().frame()
+    }
+
+    /// Replace the display buffer with the given `frame`.
+    pub fn set_frame(&self, frame: &Frame) {
+        self.driver.borrow_mut().set_frame(frame);
+    }
+
+    /// Write the changed rows of the display buffer, see [HT16K33::flush()](../struct.HT16K33.html#method.flush).
+    pub fn flush(&self) -> Result<(), E> {
+        self.driver.borrow_mut().flush()
+    }
+
+    /// Control the display dimming.
+    pub fn set_dimming(&self, dimming: Dimming) -> Result<(), E> {
+        self.driver.borrow_mut().set_dimming(dimming)
+    }
+
+    /// Control the display.
+    pub fn set_display(&self, display: Display) -> Result<(), E> {
+        self.driver.borrow_mut().set_display(display)
+    }
+}
+
+/// The keypad half of a [`SharedDriver`](struct.SharedDriver.html).
+#[derive(Clone, Copy)]
+pub struct KeypadHandle<'a, I2C> {
+    driver: &'a RefCell<HT16K33<I2C>>,
+}
+
+impl<I2C, E> KeypadHandle<'_, I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    /// Read the key RAM from the HT16K33 chip, see
+    /// [HT16K33::read_keys()](../struct.HT16K33.html#method.read_keys).
+    pub fn read_keys(&self) -> Result<[u8; KEYS_SIZE], E> {
+        self.driver.borrow_mut().read_keys()
+    }
+
+    /// Read the key RAM and update the `keypad`, see [Keypad::poll()](../keypad/struct.Keypad.html#method.poll).
+    pub fn poll<const Q: usize>(&self, keypad: &mut Keypad<Q>) -> Result<(), E> {
+        keypad.poll(&mut self.driver.borrow_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c_mock::I2cMock;
+    use crate::keypad::KeyEvent;
+    use crate::types::Address;
+
+    #[test]
+    fn split() {
+        let mut i2c = I2cMock::new();
+        i2c.device_mut(Address::DEFAULT).key_values[0] = 0b0000_0001;
+
+        let shared = SharedDriver::new(HT16K33::new(i2c, Address::DEFAULT).initialize().unwrap());
+        let (display, keypad) = shared.split();
+        let mut events: Keypad<4> = Keypad::new(1);
+
+        display.update_display_buffer(LedLocation::new(1, 1).unwrap(), true);
+        keypad.poll(&mut events).unwrap();
+        display.flush().unwrap();
+
+        assert_eq!(Some(KeyEvent::Pressed(0)), events.next_event());
+
+        let i2c = shared.into_driver().destroy();
+        assert_eq!(0b0000_0010, i2c.device(Address::DEFAULT).data_values[1]);
+    }
+}
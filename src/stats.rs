@@ -0,0 +1,91 @@
+//! # stats
+//!
+//! Optional tracking of the time spent in I2C transfers, enabled by the `stats` feature.
+//!
+//! Flush with [flush_timed()](../struct.HT16K33.html#method.flush_timed) to accumulate the time each flush
+//! spends on the bus, read with [stats()](../struct.HT16K33.html#method.stats). Use it to check a frame fits
+//! its time budget on a slow bus, and to compare [`FlushStrategy`](../enum.FlushStrategy.html) settings.
+
+/// A monotonic time source, in microseconds, wrapping on overflow.
+///
+/// Closures of the form `Fn() -> u32` are also clocks.
+pub trait Clock {
+    /// Return the current time, in microseconds.
+    fn now_us(&self) -> u32;
+}
+
+impl<F> Clock for F
+where
+    F: Fn() -> u32,
+{
+    fn now_us(&self) -> u32 {
+        self()
+    }
+}
+
+/// Counters of the display writes of a driver.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Stats {
+    /// The number of display buffer writes, each a single I2C transfer.
+    pub transfers: u32,
+    /// The number of bytes written by the display buffer writes, including the address byte of each.
+    pub bytes: u32,
+    /// The number of timed flushes.
+    pub flushes: u32,
+    /// The total time spent in timed flushes, in microseconds, saturating.
+    pub total_us: u32,
+    /// The time spent in the last timed flush, in microseconds.
+    pub last_us: u32,
+    /// The longest time spent in a timed flush, in microseconds.
+    pub max_us: u32,
+}
+
+impl Stats {
+    // All counters at zero, usable in a `const` context unlike `default()`.
+    pub(crate) const EMPTY: Stats = Stats {
+        transfers: 0,
+        bytes: 0,
+        flushes: 0,
+        total_us: 0,
+        last_us: 0,
+        max_us: 0,
+    };
+
+    /// Return the average time spent in a timed flush, in microseconds.
+    pub fn average_us(&self) -> u32 {
+        self.total_us.checked_div(self.flushes).unwrap_or(0)
+    }
+
+    // Count a display buffer write of `bytes` bytes.
+    pub(crate) fn record_transfer(&mut self, bytes: usize) {
+        self.transfers = self.transfers.wrapping_add(1);
+        self.bytes = self.bytes.wrapping_add(bytes as u32);
+    }
+
+    // Count a timed flush that took `elapsed` microseconds.
+    pub(crate) fn record_flush(&mut self, elapsed: u32) {
+        self.flushes = self.flushes.wrapping_add(1);
+        self.total_us = self.total_us.saturating_add(elapsed);
+        self.last_us = elapsed;
+        self.max_us = self.max_us.max(elapsed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_flush() {
+        let mut stats = Stats::default();
+        assert_eq!(0, stats.average_us());
+
+        stats.record_flush(100);
+        stats.record_flush(300);
+
+        assert_eq!(2, stats.flushes);
+        assert_eq!(300, stats.last_us);
+        assert_eq!(300, stats.max_us);
+        assert_eq!(200, stats.average_us());
+    }
+}
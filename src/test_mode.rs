@@ -0,0 +1,104 @@
+//! # test_mode
+//!
+//! Support for the undocumented test mode of the HT16K33 chip, for factory diagnostics only.
+//!
+//! The datasheet reserves the test mode command for Holtek use, and does not describe its effect. It is only
+//! available through the `unsafe` [enter_test_mode()](../struct.HT16K33.html#method.enter_test_mode), which
+//! returns a [`TestMode`](struct.TestMode.html) guard restoring normal operation when it is dropped.
+use crate::hal::blocking::i2c::Write;
+use crate::HT16K33;
+
+/// The test mode command.
+pub const TEST_MODE_COMMAND: u8 = 0b1101_1001;
+
+/// A guard keeping the HT16K33 chip in test mode, see
+/// [enter_test_mode()](../struct.HT16K33.html#method.enter_test_mode).
+///
+/// Normal operation is restored on [exit()](struct.TestMode.html#method.exit), or on drop, ignoring any error.
+pub struct TestMode<'a, I2C, E>
+where
+    I2C: Write<Error = E>,
+{
+    driver: &'a mut HT16K33<I2C>,
+    exited: bool,
+}
+
+impl<'a, I2C, E> TestMode<'a, I2C, E>
+where
+    I2C: Write<Error = E>,
+{
+    pub(crate) fn new(driver: &'a mut HT16K33<I2C>) -> Self {
+        TestMode {
+            driver,
+            exited: false,
+        }
+    }
+
+    /// Leave the test mode, rewriting the oscillator, display and dimming settings of the driver and the whole
+    /// display buffer to the chip.
+    pub fn exit(mut self) -> Result<(), E> {
+        self.exited = true;
+        self.restore()
+    }
+
+    fn restore(&mut self) -> Result<(), E> {
+        let driver = &mut *self.driver;
+
+        driver.set_oscillator(*driver.oscillator())?;
+        driver.set_display(*driver.display())?;
+        driver.set_dimming(*driver.dimming())?;
+        driver.flush_full()
+    }
+}
+
+impl<'a, I2C, E> Drop for TestMode<'a, I2C, E>
+where
+    I2C: Write<Error = E>,
+{
+    fn drop(&mut self) {
+        if !self.exited {
+            // There is no way to report the error, the driver state is still written by the next flush.
+            let _ = self.restore();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c_mock::I2cMock;
+    use crate::types::{Address, Dimming, Display, Oscillator};
+
+    #[test]
+    fn restore_on_drop() {
+        let mut i2c = I2cMock::new();
+        let mut ht16k33 = HT16K33::new(&mut i2c, Address::DEFAULT)
+            .initialize()
+            .unwrap();
+        ht16k33.set_display(Display::ON).unwrap();
+
+        {
+            let _test_mode = unsafe { ht16k33.enter_test_mode() }.unwrap();
+        }
+
+        let i2c = ht16k33.destroy();
+        let registers: std::vec::Vec<u8> = i2c
+            .transactions()
+            .iter()
+            .rev()
+            .skip(1)
+            .take(4)
+            .map(|transaction| transaction.register())
+            .collect();
+
+        assert_eq!(
+            [
+                (Dimming::COMMAND | Dimming::BRIGHTNESS_MAX).bits(),
+                (Display::COMMAND | Display::ON).bits(),
+                (Oscillator::COMMAND | Oscillator::ON).bits(),
+                TEST_MODE_COMMAND,
+            ],
+            registers[..]
+        );
+    }
+}
@@ -0,0 +1,255 @@
+//! # text_buffer
+//!
+//! Text longer than the display, e.g. a sensor name or an SSID, shown through a movable window.
+//!
+//! A [`TextBuffer`](struct.TextBuffer.html) owns up to `N` characters in a fixed array, so it needs no allocator
+//! and can be built up at runtime, unlike the borrowed text of a [`Scroller`](../scroller/struct.Scroller.html).
+//! The window shows [`WINDOW_SIZE`](constant.WINDOW_SIZE.html) characters, and is moved by the user, e.g. with
+//! buttons, instead of on a timer.
+//!
+//! *Requires the `text_buffer` feature.*
+use core::fmt;
+
+use crate::constants::ROWS_SIZE;
+use crate::errors::ValidationError;
+use crate::scroller::{ScrollDisplay, MAX_GLYPH_WIDTH};
+use crate::types::DisplayData;
+
+/// The number of characters visible through the window, as on a 4-character backpack.
+pub const WINDOW_SIZE: usize = 4;
+
+/// Up to `N` characters of text, with a window of [`WINDOW_SIZE`](constant.WINDOW_SIZE.html) characters.
+///
+/// The window starts on the first character, and stays within the text: it only moves while there are hidden
+/// characters in the direction of the move.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::scroller::SegmentDisplay;
+/// use ht16k33::seven_segment::Segments;
+/// use ht16k33::text_buffer::TextBuffer;
+/// use ht16k33::Frame;
+///
+/// let mut text = TextBuffer::<32>::new();
+/// text.push_str("HOME-5G").unwrap();
+///
+/// assert!(text.scroll_left());
+/// assert_eq!("OME-", text.visible().collect::<String>());
+///
+/// let mut frame = Frame::empty();
+/// text.render(&SegmentDisplay, frame.rows_mut());
+/// assert_eq!(Segments::from_char('O').unwrap().bits(), frame.rows()[0].bits());
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct TextBuffer<const N: usize> {
+    chars: [char; N],
+    length: usize,
+
+    // The index of the first visible character.
+    window: usize,
+}
+
+impl<const N: usize> Default for TextBuffer<N> {
+    fn default() -> Self {
+        TextBuffer::new()
+    }
+}
+
+impl<const N: usize> TextBuffer<N> {
+    /// Create an empty `TextBuffer`.
+    pub const fn new() -> Self {
+        TextBuffer {
+            chars: [' '; N],
+            length: 0,
+            window: 0,
+        }
+    }
+
+    /// Return the maximum number of characters.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Return the number of characters.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Return whether there are no characters.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Return the characters.
+    pub fn chars(&self) -> &[char] {
+        &self.chars[..self.length]
+    }
+
+    /// Append the string `s`.
+    ///
+    /// # Errors
+    ///
+    /// If the characters do not fit then [`ht16k33::ValidationError::Overflow`] is returned, and the text is
+    /// left unchanged.
+    ///
+    /// [`ht16k33::ValidationError::Overflow`]: ../enum.ValidationError.html#variant.Overflow
+    pub fn push_str(&mut self, s: &str) -> Result<(), ValidationError> {
+        if s.chars().count() > N - self.length {
+            return Err(ValidationError::Overflow {
+                name: "text",
+                digits: N.min(u8::MAX as usize) as u8,
+            });
+        }
+
+        for c in s.chars() {
+            self.chars[self.length] = c;
+            self.length += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Remove all characters, and move the window back to the start.
+    pub fn clear(&mut self) {
+        self.length = 0;
+        self.window = 0;
+    }
+
+    /// Return the index of the first visible character.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Move the window to start at the character `index`, limited to the last full window of the text.
+    pub fn set_window(&mut self, index: usize) {
+        self.window = index.min(self.last_window());
+    }
+
+    /// Move the text one character to the left, showing the next character on the right.
+    ///
+    /// Returns whether the window moved, `false` once the last character is visible.
+    pub fn scroll_left(&mut self) -> bool {
+        if self.window >= self.last_window() {
+            return false;
+        }
+
+        self.window += 1;
+        true
+    }
+
+    /// Move the text one character to the right, showing the previous character on the left.
+    ///
+    /// Returns whether the window moved, `false` once the first character is visible.
+    pub fn scroll_right(&mut self) -> bool {
+        if self.window == 0 {
+            return false;
+        }
+
+        self.window -= 1;
+        true
+    }
+
+    /// Return the visible characters, fewer than [`WINDOW_SIZE`](constant.WINDOW_SIZE.html) for a short text.
+    pub fn visible(&self) -> impl Iterator<Item = char> + '_ {
+        self.chars()
+            .iter()
+            .skip(self.window)
+            .take(WINDOW_SIZE)
+            .copied()
+    }
+
+    /// Draw the visible characters into the display `buffer`, blanking the rest of the display.
+    ///
+    /// The glyphs are drawn from the first cell on, and are cut at the width of the `display`.
+    pub fn render<D>(&self, display: &D, buffer: &mut [DisplayData; ROWS_SIZE])
+    where
+        D: ScrollDisplay,
+    {
+        let width = display.width();
+        let mut cells = [0; MAX_GLYPH_WIDTH];
+        let mut slot = 0;
+
+        for c in self.visible() {
+            let count = display.glyph(c, &mut cells);
+            for cell in &cells[..count] {
+                if slot < width {
+                    display.draw(slot, *cell, buffer);
+                    slot += 1;
+                }
+            }
+        }
+
+        for slot in slot..width {
+            display.draw(slot, 0, buffer);
+        }
+    }
+
+    // Return the window showing the last characters of the text.
+    fn last_window(&self) -> usize {
+        self.length.saturating_sub(WINDOW_SIZE)
+    }
+}
+
+impl<const N: usize> fmt::Write for TextBuffer<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s).map_err(|_| fmt::Error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scroller::SegmentDisplay;
+    use crate::seven_segment::{Segments, DIGIT_ROWS};
+    use core::fmt::Write;
+
+    #[test]
+    fn push_str() {
+        let mut text = TextBuffer::<6>::new();
+
+        text.push_str("TEMP").unwrap();
+        assert!(text.push_str("-OUT").is_err());
+        assert_eq!(['T', 'E', 'M', 'P'], text.chars());
+
+        write!(text, "{}", 21).unwrap();
+        assert_eq!(6, text.len());
+        assert!(write!(text, "C").is_err());
+
+        text.clear();
+        assert!(text.is_empty());
+    }
+
+    #[test]
+    fn scroll() {
+        let mut text = TextBuffer::<8>::new();
+        text.push_str("ABCDEF").unwrap();
+
+        assert!(!text.scroll_right());
+        assert!(text.scroll_left());
+        assert!(text.scroll_left());
+        assert!(!text.scroll_left());
+        assert_eq!(2, text.window());
+
+        assert!(text.scroll_right());
+        assert!(text.visible().eq("BCDE".chars()));
+
+        text.set_window(10);
+        assert_eq!(2, text.window());
+    }
+
+    #[test]
+    fn render() {
+        let mut text = TextBuffer::<8>::new();
+        text.push_str("HI").unwrap();
+
+        let mut buffer = [DisplayData::all(); ROWS_SIZE];
+        text.render(&SegmentDisplay, &mut buffer);
+
+        let digit = |c| Segments::from_char(c).unwrap().bits();
+        assert_eq!(digit('H'), buffer[DIGIT_ROWS[0]].bits());
+        assert_eq!(digit('I'), buffer[DIGIT_ROWS[1]].bits());
+        assert_eq!(0, buffer[DIGIT_ROWS[2]].bits());
+        assert_eq!(0, buffer[DIGIT_ROWS[3]].bits());
+    }
+}
@@ -0,0 +1,221 @@
+use core::convert::TryFrom;
+use core::fmt;
+
+use crate::errors::ValidationError;
+
+/// The 7-bit I2C address of an HT16K33, `0x70` to `0x77`.
+///
+/// The low 3 bits are set by the `A0`-`A2` address pins, see [from_pins()](struct.Address.html#method.from_pins).
+///
+/// # Examples
+///
+/// ```
+/// use ht16k33::Address;
+///
+/// // Checked when compiling.
+/// const DISPLAY: Address = Address::new(0x71);
+///
+/// assert_eq!(0x71, DISPLAY.value());
+/// assert_eq!(DISPLAY, Address::from_pins(true, false, false));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Ord, PartialOrd)]
+pub struct Address(u8);
+
+impl Address {
+    /// The lowest address.
+    pub const MIN: u8 = 0x70;
+
+    /// The highest address.
+    pub const MAX: u8 = 0x77;
+
+    /// The address with all address pins low.
+    ///
+    /// *This is the default of the Adafruit backpacks.*
+    pub const DEFAULT: Address = Address(Address::MIN);
+
+    /// Create an `Address`.
+    ///
+    /// # Panics
+    ///
+    /// If the `address` is not within [`MIN`] and [`MAX`], e.g. an 8-bit address including the read/write bit.
+    /// In a `const` this fails to compile instead.
+    ///
+    /// [`MIN`]: struct.Address.html#associatedconstant.MIN
+    /// [`MAX`]: struct.Address.html#associatedconstant.MAX
+    pub const fn new(address: u8) -> Self {
+        assert!(
+            address >= Address::MIN && address <= Address::MAX,
+            "HT16K33 addresses are 0x70 to 0x77"
+        );
+
+        Address(address)
+    }
+
+    /// Create an `Address` from the levels of the `A0`-`A2` address pins.
+    pub const fn from_pins(a0: bool, a1: bool, a2: bool) -> Self {
+        Address(Address::MIN | (a0 as u8) | (a1 as u8) << 1 | (a2 as u8) << 2)
+    }
+
+    /// Create an `Address` from a value only known at runtime.
+    ///
+    /// # Errors
+    ///
+    /// If the `value` is not within [`MIN`] and [`MAX`] then [`ht16k33::ValidationError::OutOfRange`] is
+    /// returned.
+    ///
+    /// [`MIN`]: struct.Address.html#associatedconstant.MIN
+    /// [`MAX`]: struct.Address.html#associatedconstant.MAX
+    /// [`ht16k33::ValidationError::OutOfRange`]: enum.ValidationError.html#variant.OutOfRange
+    pub fn from_u8(value: u8) -> Result<Self, ValidationError> {
+        if !(Address::MIN..=Address::MAX).contains(&value) {
+            return Err(ValidationError::OutOfRange {
+                name: "address",
+                value,
+                min: Address::MIN,
+                max: Address::MAX,
+            });
+        }
+
+        Ok(Address(value))
+    }
+
+    /// Return the 7-bit address.
+    pub const fn value(self) -> u8 {
+        self.0
+    }
+}
+
+impl Default for Address {
+    fn default() -> Address {
+        Address::DEFAULT
+    }
+}
+
+impl TryFrom<u8> for Address {
+    type Error = ValidationError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Address::from_u8(value)
+    }
+}
+
+impl From<Address> for u8 {
+    fn from(address: Address) -> u8 {
+        address.0
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Address({:#04x})", self.0)
+    }
+}
+
+/// A set of HT16K33 addresses, e.g. the responding chips found by
+/// [detect()](struct.HT16K33.html#method.detect).
+///
+/// # Examples
+///
+/// ```
+/// use ht16k33::{Address, AddressSet};
+///
+/// let mut addresses = AddressSet::new();
+/// addresses.insert(Address::new(0x72));
+/// addresses.insert(Address::DEFAULT);
+///
+/// assert_eq!(2, addresses.len());
+/// assert!(addresses.contains(Address::new(0x72)));
+/// assert_eq!(Some(Address::DEFAULT), addresses.iter().next());
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct AddressSet(u8);
+
+impl AddressSet {
+    /// Create an empty `AddressSet`.
+    pub const fn new() -> Self {
+        AddressSet(0)
+    }
+
+    /// Add the `address` to the set.
+    pub fn insert(&mut self, address: Address) {
+        self.0 |= AddressSet::bit(address);
+    }
+
+    /// Remove the `address` from the set.
+    pub fn remove(&mut self, address: Address) {
+        self.0 &= !AddressSet::bit(address);
+    }
+
+    /// Return whether the set contains the `address`.
+    pub const fn contains(&self, address: Address) -> bool {
+        self.0 & AddressSet::bit(address) != 0
+    }
+
+    /// Return the number of addresses in the set.
+    pub const fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Return whether the set is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Return the addresses in the set, lowest first.
+    pub fn iter(&self) -> impl Iterator<Item = Address> {
+        let bits = self.0;
+
+        (Address::MIN..=Address::MAX)
+            .map(Address)
+            .filter(move |&address| bits & AddressSet::bit(address) != 0)
+    }
+
+    const fn bit(address: Address) -> u8 {
+        1 << (address.0 - Address::MIN)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Address;
+
+    #[test]
+    fn from_pins() {
+        assert_eq!(Address::DEFAULT, Address::from_pins(false, false, false));
+        assert_eq!(Address::new(0x75), Address::from_pins(true, false, true));
+        assert_eq!(Address::new(0x77), Address::from_pins(true, true, true));
+    }
+
+    #[test]
+    fn from_u8() {
+        assert_eq!(Address::new(0x72), Address::from_u8(0x72).unwrap());
+        assert!(Address::from_u8(0x6F).is_err());
+        assert!(Address::try_from(0xE0).is_err());
+    }
+
+    #[test]
+    fn address_set() {
+        let mut addresses = AddressSet::new();
+        assert!(addresses.is_empty());
+
+        addresses.insert(Address::new(0x77));
+        addresses.insert(Address::new(0x70));
+        addresses.insert(Address::new(0x70));
+        assert_eq!(2, addresses.len());
+
+        addresses.remove(Address::new(0x70));
+        assert!(!addresses.contains(Address::new(0x70)));
+        assert_eq!(
+            Some(Address::new(0x77)),
+            addresses.iter().next(),
+            "AddressSet iterates the remaining address"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_8_bit() {
+        Address::new(0xE0);
+    }
+}
@@ -0,0 +1,74 @@
+use core::fmt;
+
+use super::Display;
+
+/// The blink rate of the display, see [`Display::new()`](struct.Display.html#method.new).
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum BlinkRate {
+    /// Not blinking.
+    ///
+    /// *This is the Power-on Reset default.*
+    #[default]
+    Off,
+    /// Blinking @ 2Hz.
+    Hz2,
+    /// Blinking @ 1Hz.
+    Hz1,
+    /// Blinking @ 0.5Hz.
+    HalfHz,
+}
+
+impl fmt::Display for BlinkRate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BlinkRate::Off => write!(f, "BlinkRate::Off"),
+            BlinkRate::Hz2 => write!(f, "BlinkRate::Hz2"),
+            BlinkRate::Hz1 => write!(f, "BlinkRate::Hz1"),
+            BlinkRate::HalfHz => write!(f, "BlinkRate::HalfHz"),
+        }
+    }
+}
+
+impl BlinkRate {
+    // Return the display setting of a display that is on and blinking at this rate.
+    pub(crate) const fn display(self) -> Display {
+        match self {
+            BlinkRate::Off => Display::ON,
+            BlinkRate::Hz2 => Display::TWO_HZ,
+            BlinkRate::Hz1 => Display::ONE_HZ,
+            BlinkRate::HalfHz => Display::HALF_HZ,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default() {
+        assert_eq!(
+            BlinkRate::Off,
+            BlinkRate::default(),
+            "BlinkRate default is Off"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        for blink in [
+            BlinkRate::Off,
+            BlinkRate::Hz2,
+            BlinkRate::Hz1,
+            BlinkRate::HalfHz,
+        ] {
+            let display = Display::new(true, blink);
+            assert!(display.is_on());
+            assert_eq!(blink, display.blink_rate());
+        }
+
+        assert_eq!(Display::OFF, Display::new(false, BlinkRate::Hz1));
+        assert_eq!(BlinkRate::Off, Display::OFF.blink_rate());
+    }
+}
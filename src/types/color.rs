@@ -0,0 +1,68 @@
+use core::fmt;
+
+/// The color of a bicolor LED, made of a green and a red LED.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Color {
+    /// Both LEDs off.
+    #[default]
+    Off,
+    /// The green LED on.
+    Green,
+    /// The red LED on.
+    Red,
+    /// Both LEDs on.
+    Yellow,
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Color::Off => write!(f, "Color::Off"),
+            Color::Green => write!(f, "Color::Green"),
+            Color::Red => write!(f, "Color::Red"),
+            Color::Yellow => write!(f, "Color::Yellow"),
+        }
+    }
+}
+
+impl Color {
+    /// Create a `Color` from the state of its green and red LEDs.
+    pub fn from_leds(green: bool, red: bool) -> Self {
+        match (green, red) {
+            (false, false) => Color::Off,
+            (true, false) => Color::Green,
+            (false, true) => Color::Red,
+            (true, true) => Color::Yellow,
+        }
+    }
+
+    /// Return whether the green LED is on.
+    pub fn green(self) -> bool {
+        self == Color::Green || self == Color::Yellow
+    }
+
+    /// Return whether the red LED is on.
+    pub fn red(self) -> bool {
+        self == Color::Red || self == Color::Yellow
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default() {
+        assert_eq!(Color::Off, Color::default(), "Color default is off");
+    }
+
+    #[test]
+    fn leds() {
+        for &color in [Color::Off, Color::Green, Color::Red, Color::Yellow].iter() {
+            assert_eq!(color, Color::from_leds(color.green(), color.red()));
+        }
+
+        assert!(Color::Yellow.green() && Color::Yellow.red());
+        assert!(!Color::Off.green() && !Color::Off.red());
+    }
+}
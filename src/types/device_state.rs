@@ -0,0 +1,30 @@
+use super::{BlinkRate, Dimming, Display, Frame, Oscillator};
+
+/// A copy of the driver's view of the chip, see [state()](struct.HT16K33.html#method.state).
+///
+/// Keep it across a suspend or a crash, e.g. in retained RAM, and replay it to the chip with
+/// [restore_state()](struct.HT16K33.html#method.restore_state).
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct DeviceState {
+    /// The oscillator setting.
+    pub oscillator: Oscillator,
+    /// The display setting, including the blink rate.
+    pub display: Display,
+    /// The dimming setting.
+    pub dimming: Dimming,
+    /// The display buffer.
+    pub buffer: Frame,
+}
+
+impl DeviceState {
+    /// Return whether the display is on.
+    pub fn is_on(&self) -> bool {
+        self.display.is_on()
+    }
+
+    /// Return the blink rate, `BlinkRate::Off` for a display that is off.
+    pub fn blink_rate(&self) -> BlinkRate {
+        self.display.blink_rate()
+    }
+}
@@ -2,6 +2,12 @@ use crate::errors::ValidationError;
 use bitflags::bitflags;
 use core::fmt;
 
+// The lowest brightness percentage shown at each dimming step, following the CIE 1931 lightness curve, so
+// that equal steps in percent look like equal steps in brightness.
+const CIE1931: [u8; 16] = [
+    0, 37, 47, 54, 61, 66, 70, 75, 78, 82, 85, 88, 91, 94, 97, 99,
+];
+
 bitflags! {
     /// Display dimming.
     ///
@@ -13,6 +19,7 @@ bitflags! {
     /// [`BRIGHTNESS_MIN`]: struct.Dimming.html#associatedconstant.BRIGHTNESS_MIN
     /// [`BRIGHTNESS_MAX`]: struct.Dimming.html#associatedconstant.BRIGHTNESS_MAX
     /// [`from_u8`]: struct.Dimming.html#method.from_u8
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct Dimming: u8 {
         /// Command to set the digital dimming.
         const COMMAND = 0b1110_0000;
@@ -133,6 +140,49 @@ impl Dimming {
 
         Ok(Dimming::from_bits_truncate(value))
     }
+
+    /// Return the `Dimming` value closest to the given perceived brightness, in percent.
+    ///
+    /// The PWM duty cycle is mapped through the CIE 1931 lightness curve, so that the 16 uneven hardware steps
+    /// look evenly spaced. Even `0` shows at the `1/16` duty cycle of [`BRIGHTNESS_MIN`], turn the display off to
+    /// hide it.
+    ///
+    /// # Errors
+    ///
+    /// If the `percent` value is larger than `100` then [`ht16k33::ValidationError::ValueTooLarge`] is
+    /// returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ht16k33::Dimming;
+    /// # use ht16k33::ValidationError;
+    /// # fn main() -> Result<(), ValidationError> {
+    ///
+    /// // Half the perceived brightness is only a 3/16 duty cycle.
+    /// assert_eq!(Dimming::BRIGHTNESS_3_16, Dimming::from_percent(50)?);
+    /// assert_eq!(Dimming::BRIGHTNESS_MAX, Dimming::from_percent(100)?);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`BRIGHTNESS_MIN`]: struct.Dimming.html#associatedconstant.BRIGHTNESS_MIN
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: enum.ValidationError.html#variant.ValueTooLarge
+    pub fn from_percent(percent: u8) -> Result<Self, ValidationError> {
+        if percent > 100 {
+            return Err(ValidationError::ValueTooLarge {
+                name: "brightness",
+                value: percent,
+                limit: 100,
+                inclusive: true,
+            });
+        }
+
+        let step = CIE1931.iter().rposition(|&min| min <= percent).unwrap_or(0);
+
+        Ok(Dimming::from_bits_truncate(step as u8))
+    }
 }
 
 #[cfg(test)]
@@ -179,4 +229,32 @@ mod tests {
     fn from_u8_too_large() {
         let _ = Dimming::from_u8(16u8).unwrap();
     }
+
+    #[test]
+    fn from_percent() {
+        assert_eq!(Dimming::BRIGHTNESS_MIN, Dimming::from_percent(0).unwrap());
+        assert_eq!(Dimming::BRIGHTNESS_MIN, Dimming::from_percent(36).unwrap());
+        assert_eq!(Dimming::BRIGHTNESS_2_16, Dimming::from_percent(37).unwrap());
+        assert_eq!(
+            Dimming::BRIGHTNESS_15_16,
+            Dimming::from_percent(98).unwrap()
+        );
+        assert_eq!(Dimming::BRIGHTNESS_MAX, Dimming::from_percent(100).unwrap());
+
+        let mut previous = Dimming::BRIGHTNESS_MIN;
+        for percent in 0..=100 {
+            let dimming = Dimming::from_percent(percent).unwrap();
+            assert!(
+                dimming.bits() >= previous.bits(),
+                "Dimming increases with percent"
+            );
+            previous = dimming;
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_percent_too_large() {
+        let _ = Dimming::from_percent(101).unwrap();
+    }
 }
@@ -1,10 +1,13 @@
 use bitflags::bitflags;
 use core::fmt;
 
+use super::BlinkRate;
+
 bitflags! {
     /// The LED display state.
     ///
     /// The LEDs can be all off (default), all on, or all blinking at 1/2Hz, 1Hz, or 2Hz.
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct Display: u8 {
         /// Command to set the display.
         const COMMAND = 0b1000_0000;
@@ -29,6 +32,47 @@ impl Default for Display {
     }
 }
 
+impl Display {
+    /// Create the `Display` setting for a display that is on or off, and blinking at the given rate.
+    ///
+    /// A display that is off does not blink, so the `blink` rate is ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ht16k33::{BlinkRate, Display};
+    ///
+    /// assert_eq!(Display::ONE_HZ, Display::new(true, BlinkRate::Hz1));
+    /// assert_eq!(Display::OFF, Display::new(false, BlinkRate::Hz1));
+    /// ```
+    pub const fn new(on: bool, blink: BlinkRate) -> Self {
+        if on {
+            blink.display()
+        } else {
+            Display::OFF
+        }
+    }
+
+    /// Return whether the display is on.
+    pub fn is_on(&self) -> bool {
+        self.contains(Display::ON)
+    }
+
+    /// Return the blink rate, `BlinkRate::Off` for a display that is off.
+    pub fn blink_rate(&self) -> BlinkRate {
+        if !self.is_on() {
+            return BlinkRate::Off;
+        }
+
+        match *self & Display::HALF_HZ {
+            Display::TWO_HZ => BlinkRate::Hz2,
+            Display::ONE_HZ => BlinkRate::Hz1,
+            Display::HALF_HZ => BlinkRate::HalfHz,
+            _ => BlinkRate::Off,
+        }
+    }
+}
+
 impl fmt::Display for Display {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -5,6 +5,7 @@ bitflags! {
     /// RAM data for LED display.
     ///
     /// The LED for the corresponding bitflag will be enabled if the flag is `1`.
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct DisplayData: u8 {
         /// No LEDs enabled.
         const COMMON_NONE = 0b0000_0000;
@@ -3,6 +3,7 @@ use core::fmt;
 
 bitflags! {
     /// Display RAM data address.
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct DisplayDataAddress: u8 {
         /// Row 0
         const ROW_0 = 0;
@@ -0,0 +1,40 @@
+/// How [flush()](struct.HT16K33.html#method.flush) groups the changed display buffer rows into writes.
+///
+/// Each write to the chip costs the I2C address and the display RAM address on top of the row values, so
+/// writing a few unchanged rows between two changes is cheaper than starting another write.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum FlushStrategy {
+    /// Write the smallest contiguous range of rows covering all changes, in a single write.
+    ///
+    /// *This is the default.*
+    #[default]
+    Span,
+    /// Write each run of changed rows separately, merging runs separated by at most `max_gap` unchanged
+    /// rows into a single write.
+    ///
+    /// A `max_gap` of `2` matches the two bytes of overhead of a write, see
+    /// [`FlushStrategy::COALESCE`](enum.FlushStrategy.html#associatedconstant.COALESCE).
+    Coalesce {
+        /// The largest number of unchanged rows written to join two runs of changed rows.
+        max_gap: usize,
+    },
+}
+
+impl FlushStrategy {
+    /// Merge runs of changed rows only when the unchanged rows between them cost no more than a new write.
+    pub const COALESCE: FlushStrategy = FlushStrategy::Coalesce { max_gap: 2 };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default() {
+        assert_eq!(
+            FlushStrategy::Span,
+            FlushStrategy::default(),
+            "FlushStrategy default is Span"
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use crate::constants::ROWS_SIZE;
+use crate::errors::ValidationError;
+use crate::types::DisplayData;
+use crate::types::LedLocation;
+
+use core::fmt;
+
+/// A complete set of display RAM values, one [`DisplayData`] per row.
+///
+/// Frames are rendered and post-processed off-device, then copied into the driver's display buffer.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::Frame;
+/// use ht16k33::LedLocation;
+/// # use ht16k33::ValidationError;
+/// # fn main() -> Result<(), ValidationError> {
+///
+/// let mut frame = Frame::empty();
+///
+/// let location = LedLocation::new(1, 2)?;
+/// frame.set_led(location, true);
+///
+/// assert!(frame.led(location));
+///
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Binary format
+///
+/// [to_bytes()](struct.Frame.html#method.to_bytes) encodes a frame in a stable binary format, so stored frames
+/// stay readable across releases. Version `1` of the format is [`ENCODED_SIZE`] bytes:
+///
+/// | Offset | Size | Contents                                                  |
+/// |--------|------|-----------------------------------------------------------|
+/// | 0      | 1    | The format version, [`FORMAT_VERSION`]                    |
+/// | 1      | 16   | The display RAM rows `0` to `15`, with common `n` in bit `n` |
+///
+/// Changes to the format will increment the version, and
+/// [from_bytes()](struct.Frame.html#method.from_bytes) will keep reading every earlier version.
+///
+/// [`DisplayData`]: struct.DisplayData.html
+/// [`ENCODED_SIZE`]: struct.Frame.html#associatedconstant.ENCODED_SIZE
+/// [`FORMAT_VERSION`]: struct.Frame.html#associatedconstant.FORMAT_VERSION
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Frame {
+    rows: [DisplayData; ROWS_SIZE],
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Frame(")?;
+
+        for (index, row) in self.rows.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{:02x}", row.bits())?;
+        }
+
+        write!(f, ")")
+    }
+}
+
+impl Frame {
+    /// Create a `Frame` with all LEDs disabled.
+    pub const fn empty() -> Self {
+        Frame {
+            rows: [DisplayData::COMMON_NONE; ROWS_SIZE],
+        }
+    }
+
+    /// Create a `Frame` from the given row values.
+    pub const fn from_rows(rows: [DisplayData; ROWS_SIZE]) -> Self {
+        Frame { rows }
+    }
+
+    /// Return the row values.
+    pub fn rows(&self) -> &[DisplayData; ROWS_SIZE] {
+        &self.rows
+    }
+
+    /// Return the mutable row values.
+    pub fn rows_mut(&mut self) -> &mut [DisplayData; ROWS_SIZE] {
+        &mut self.rows
+    }
+
+    /// Return whether the LED at `location` is enabled.
+    pub fn led(&self, location: LedLocation) -> bool {
+        self.rows[location.row_as_index()].contains(location.common)
+    }
+
+    /// Enable/disable the LED at `location`.
+    pub fn set_led(&mut self, location: LedLocation, enabled: bool) {
+        self.rows[location.row_as_index()].set(location.common, enabled);
+    }
+
+    /// Disable all LEDs.
+    pub fn clear(&mut self) {
+        self.rows = [DisplayData::COMMON_NONE; ROWS_SIZE];
+    }
+
+    /// The version of the binary format written by [to_bytes()](struct.Frame.html#method.to_bytes).
+    pub const FORMAT_VERSION: u8 = 1;
+
+    /// The size of a frame encoded by [to_bytes()](struct.Frame.html#method.to_bytes), in bytes.
+    pub const ENCODED_SIZE: usize = ROWS_SIZE + 1;
+
+    /// Return the frame in the current version of the binary format, see [`Frame`](struct.Frame.html).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ht16k33::{DisplayData, Frame, ROWS_SIZE};
+    ///
+    /// let frame = Frame::from_rows([DisplayData::COMMON_0; ROWS_SIZE]);
+    ///
+    /// let bytes = frame.to_bytes();
+    /// assert_eq!(Frame::FORMAT_VERSION, bytes[0]);
+    /// assert_eq!(0b0000_0001, bytes[1]);
+    ///
+    /// assert_eq!(frame, Frame::from_bytes(&bytes).unwrap());
+    /// ```
+    pub fn to_bytes(&self) -> [u8; Frame::ENCODED_SIZE] {
+        let mut bytes = [0u8; Frame::ENCODED_SIZE];
+        bytes[0] = Frame::FORMAT_VERSION;
+
+        for (byte, row) in bytes[1..].iter_mut().zip(self.rows.iter()) {
+            *byte = row.bits();
+        }
+
+        bytes
+    }
+
+    /// Return the frame encoded in the binary format, see [`Frame`](struct.Frame.html).
+    ///
+    /// # Errors
+    ///
+    /// If the format version is not supported then [`ht16k33::ValidationError::OutOfRange`] is returned. If
+    /// there are not exactly as many bytes as the version requires then
+    /// [`ht16k33::ValidationError::ValueTooLarge`] is returned.
+    ///
+    /// [`ht16k33::ValidationError::OutOfRange`]: enum.ValidationError.html#variant.OutOfRange
+    /// [`ht16k33::ValidationError::ValueTooLarge`]: enum.ValidationError.html#variant.ValueTooLarge
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ValidationError> {
+        let version = bytes.first().copied().unwrap_or(0);
+        if version != Frame::FORMAT_VERSION {
+            return Err(ValidationError::OutOfRange {
+                name: "format version",
+                value: version,
+                min: 1,
+                max: Frame::FORMAT_VERSION,
+            });
+        }
+
+        if bytes.len() != Frame::ENCODED_SIZE {
+            return Err(ValidationError::ValueTooLarge {
+                name: "frame length",
+                value: bytes.len().min(u8::MAX as usize) as u8,
+                limit: Frame::ENCODED_SIZE as u8,
+                inclusive: true,
+            });
+        }
+
+        let mut frame = Frame::empty();
+        for (row, byte) in frame.rows.iter_mut().zip(bytes[1..].iter()) {
+            *row = DisplayData::from_bits_truncate(*byte);
+        }
+
+        Ok(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default() {
+        assert_eq!(Frame::empty(), Frame::default(), "Frame default is empty");
+    }
+
+    #[test]
+    fn set_led() {
+        let mut frame = Frame::empty();
+
+        let first = LedLocation::new(3, 1).unwrap();
+        let second = LedLocation::new(3, 6).unwrap();
+
+        frame.set_led(first, true);
+        frame.set_led(second, true);
+        assert_eq!(frame.rows()[3].bits(), 0b0100_0010);
+        assert!(frame.led(first));
+
+        frame.set_led(first, false);
+        assert_eq!(frame.rows()[3].bits(), 0b0100_0000);
+        assert!(!frame.led(first));
+    }
+
+    #[test]
+    fn clear() {
+        let mut frame = Frame::from_rows([DisplayData::all(); ROWS_SIZE]);
+
+        frame.clear();
+
+        assert_eq!(frame, Frame::empty());
+    }
+
+    #[test]
+    fn binary_format() {
+        let mut rows = [DisplayData::COMMON_NONE; ROWS_SIZE];
+        for (index, row) in rows.iter_mut().enumerate() {
+            *row = DisplayData::from_bits_truncate(index as u8 * 0x11);
+        }
+        let frame = Frame::from_rows(rows);
+
+        // Version 1 is the version byte followed by the rows in order, this must never change.
+        let expected = [
+            1, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD,
+            0xEE, 0xFF,
+        ];
+        assert_eq!(expected, frame.to_bytes());
+        assert_eq!(frame, Frame::from_bytes(&expected).unwrap());
+    }
+
+    #[test]
+    fn from_bytes_invalid() {
+        let mut bytes = Frame::empty().to_bytes();
+
+        assert!(Frame::from_bytes(&[]).is_err());
+        assert!(Frame::from_bytes(&bytes[..ROWS_SIZE]).is_err());
+
+        bytes[0] = 2;
+        assert!(Frame::from_bytes(&bytes).is_err());
+    }
+}
@@ -1,13 +1,33 @@
+mod address;
+mod blink_rate;
+mod color;
+mod device_state;
 mod dimming;
 mod display;
 mod display_data;
 mod display_data_address;
+mod flush_strategy;
+mod frame;
 mod led_location;
 mod oscillator;
+mod package;
+mod rotation;
+mod write_mode;
 
+pub use self::address::{Address, AddressSet};
+pub use self::blink_rate::BlinkRate;
+pub use self::color::Color;
+pub use self::device_state::DeviceState;
 pub use self::dimming::Dimming;
 pub use self::display::Display;
 pub use self::display_data::DisplayData;
 pub use self::display_data_address::DisplayDataAddress;
+pub use self::flush_strategy::FlushStrategy;
+pub use self::frame::Frame;
 pub use self::led_location::LedLocation;
 pub use self::oscillator::Oscillator;
+pub use self::package::Package;
+pub use self::rotation::{Flip, Rotation};
+pub use self::write_mode::WriteMode;
+
+pub(crate) use self::rotation::{orient, orient_location};
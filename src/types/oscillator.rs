@@ -3,6 +3,7 @@ use core::fmt;
 
 bitflags! {
     /// System oscillator setup and control.
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct Oscillator: u8 {
         /// Command to set system setup.
         const COMMAND = 0b0010_0000;
@@ -0,0 +1,124 @@
+use crate::constants::{COMMONS_SIZE, ROWS_SIZE};
+use crate::errors::ValidationError;
+use crate::types::LedLocation;
+
+/// The SOP package variant of the HT16K33 chip, see [set_package()](struct.HT16K33.html#method.set_package).
+///
+/// The smaller packages do not bond out all the `ROW` outputs, so the display RAM rows past
+/// [rows()](enum.Package.html#method.rows) drive nothing. All packages have the 8 `COM` outputs.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Package {
+    /// The 20-pin SOP package, with `ROW0` to `ROW7`.
+    Sop20,
+    /// The 24-pin SOP package, with `ROW0` to `ROW11`.
+    Sop24,
+    /// The 28-pin SOP package, with `ROW0` to `ROW15`.
+    ///
+    /// *This is the default.*
+    #[default]
+    Sop28,
+}
+
+impl Package {
+    /// Return the number of `ROW` outputs of the package.
+    pub const fn rows(self) -> usize {
+        match self {
+            Package::Sop20 => 8,
+            Package::Sop24 => 12,
+            Package::Sop28 => ROWS_SIZE,
+        }
+    }
+
+    /// Return the number of `COM` outputs of the package.
+    pub const fn commons(self) -> usize {
+        COMMONS_SIZE
+    }
+
+    /// Create an `LedLocation` with the given `row` and `common` values, validated against the outputs of
+    /// the package.
+    ///
+    /// # Errors
+    ///
+    /// If the `row` or `common` does not exist on the package, then [`ValidationError::ValueTooLarge`] is
+    /// returned.
+    ///
+    /// [`ValidationError::ValueTooLarge`]: enum.ValidationError.html#variant.ValueTooLarge
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ht16k33::Package;
+    ///
+    /// assert!(Package::Sop24.location(11, 7).is_ok());
+    /// assert!(Package::Sop24.location(12, 0).is_err());
+    /// ```
+    pub fn location(self, row: u8, common: u8) -> Result<LedLocation, ValidationError> {
+        self.validate_row(row)?;
+
+        LedLocation::new(row, common)
+    }
+
+    /// Validate that the `location` is wired to an output of the package.
+    ///
+    /// # Errors
+    ///
+    /// If the row of the `location` does not exist on the package, then
+    /// [`ValidationError::ValueTooLarge`] is returned.
+    ///
+    /// [`ValidationError::ValueTooLarge`]: enum.ValidationError.html#variant.ValueTooLarge
+    pub fn validate(self, location: LedLocation) -> Result<LedLocation, ValidationError> {
+        self.validate_row(location.row.bits())?;
+
+        Ok(location)
+    }
+
+    fn validate_row(self, row: u8) -> Result<(), ValidationError> {
+        if row >= self.rows() as u8 {
+            return Err(ValidationError::ValueTooLarge {
+                name: "row",
+                value: row,
+                limit: self.rows() as u8,
+                inclusive: false,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default() {
+        assert_eq!(
+            Package::Sop28,
+            Package::default(),
+            "Package default is Sop28"
+        );
+    }
+
+    #[test]
+    fn rows() {
+        assert_eq!(8, Package::Sop20.rows());
+        assert_eq!(12, Package::Sop24.rows());
+        assert_eq!(16, Package::Sop28.rows());
+    }
+
+    #[test]
+    fn location() {
+        assert!(Package::Sop20.location(7, 7).is_ok());
+        assert!(Package::Sop20.location(8, 0).is_err());
+        assert!(Package::Sop28.location(15, 0).is_ok());
+        assert!(Package::Sop28.location(0, 8).is_err());
+    }
+
+    #[test]
+    fn validate() {
+        let location = LedLocation::new(12, 0).unwrap();
+
+        assert!(Package::Sop24.validate(location).is_err());
+        assert!(Package::Sop28.validate(location).is_ok());
+    }
+}
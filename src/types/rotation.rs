@@ -0,0 +1,213 @@
+use crate::constants::ROWS_SIZE;
+use crate::layouts::{Layout, Matrix8x8};
+use crate::types::{DisplayData, LedLocation};
+
+use bitflags::bitflags;
+
+// The width and height of the 8x8 matrix.
+const SIZE: u8 = 8;
+
+/// The clockwise rotation of an 8x8 matrix backpack, see
+/// [set_rotation()](struct.HT16K33.html#method.set_rotation).
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Rotation {
+    /// Not rotated.
+    ///
+    /// *This is the default.*
+    #[default]
+    Deg0,
+    /// Rotated by 90°, clockwise.
+    Deg90,
+    /// Rotated by 180°.
+    Deg180,
+    /// Rotated by 270° clockwise, i.e. 90° counter-clockwise.
+    Deg270,
+}
+
+bitflags! {
+    /// Mirroring of an 8x8 matrix backpack, see [set_flip()](struct.HT16K33.html#method.set_flip).
+    #[derive(Default)]
+    pub struct Flip: u8 {
+        /// Mirror left to right.
+        const HORIZONTAL = 0b0000_0001;
+        /// Mirror top to bottom.
+        const VERTICAL = 0b0000_0010;
+    }
+}
+
+impl Rotation {
+    /// Return the rotation undoing this rotation.
+    pub fn inverse(self) -> Rotation {
+        match self {
+            Rotation::Deg0 => Rotation::Deg0,
+            Rotation::Deg90 => Rotation::Deg270,
+            Rotation::Deg180 => Rotation::Deg180,
+            Rotation::Deg270 => Rotation::Deg90,
+        }
+    }
+
+    // Return where the matrix pixel at `(x, y)` is moved to.
+    fn rotate(self, x: u8, y: u8) -> (u8, u8) {
+        let last = SIZE - 1;
+
+        match self {
+            Rotation::Deg0 => (x, y),
+            Rotation::Deg90 => (last - y, x),
+            Rotation::Deg180 => (last - x, last - y),
+            Rotation::Deg270 => (y, last - x),
+        }
+    }
+}
+
+impl Flip {
+    // Return where the matrix pixel at `(x, y)` is moved to.
+    fn flip(self, x: u8, y: u8) -> (u8, u8) {
+        let last = SIZE - 1;
+
+        let x = if self.contains(Flip::HORIZONTAL) {
+            last - x
+        } else {
+            x
+        };
+        let y = if self.contains(Flip::VERTICAL) {
+            last - y
+        } else {
+            y
+        };
+
+        (x, y)
+    }
+}
+
+// Return the LED location showing the 8x8 matrix pixel at `location`, flipped then rotated, or flipped back
+// after undoing the rotation when `inverse`.
+//
+// Locations outside the matrix, i.e. in the odd rows, are unchanged.
+pub(crate) fn orient_location(
+    location: LedLocation,
+    rotation: Rotation,
+    flip: Flip,
+    inverse: bool,
+) -> LedLocation {
+    let row = location.row_as_index();
+    // `is_multiple_of()` would need Rust 1.87.
+    #[allow(clippy::manual_is_multiple_of)]
+    if row % 2 != 0 {
+        return location;
+    }
+
+    // Undo the Matrix8x8 layout, where column 0 is common 7.
+    let common = location.common.bits().trailing_zeros() as u8;
+    let (x, y) = ((common + 1) % SIZE, row as u8 / 2);
+
+    let (x, y) = if inverse {
+        let (x, y) = rotation.inverse().rotate(x, y);
+        flip.flip(x, y)
+    } else {
+        let (x, y) = flip.flip(x, y);
+        rotation.rotate(x, y)
+    };
+
+    Matrix8x8.location(x, y).unwrap_or(location)
+}
+
+// Return the `rows` with the 8x8 matrix pixels oriented, see `orient_location()`.
+pub(crate) fn orient(
+    rows: &[DisplayData; ROWS_SIZE],
+    rotation: Rotation,
+    flip: Flip,
+    inverse: bool,
+) -> [DisplayData; ROWS_SIZE] {
+    if rotation == Rotation::Deg0 && flip.is_empty() {
+        return *rows;
+    }
+
+    let mut oriented = [DisplayData::COMMON_NONE; ROWS_SIZE];
+    for (row, value) in rows.iter().enumerate() {
+        for common in (0..8).filter(|common| value.bits() & (1 << common) != 0) {
+            let location = match LedLocation::new(row as u8, common) {
+                Ok(location) => orient_location(location, rotation, flip, inverse),
+                Err(_) => continue,
+            };
+            oriented[location.row_as_index()].insert(location.common);
+        }
+    }
+
+    oriented
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixel(x: u8, y: u8) -> LedLocation {
+        Matrix8x8.location(x, y).unwrap()
+    }
+
+    #[test]
+    fn rotate() {
+        let top_left = pixel(0, 0);
+
+        assert_eq!(
+            pixel(0, 0),
+            orient_location(top_left, Rotation::Deg0, Flip::empty(), false)
+        );
+        assert_eq!(
+            pixel(7, 0),
+            orient_location(top_left, Rotation::Deg90, Flip::empty(), false)
+        );
+        assert_eq!(
+            pixel(7, 7),
+            orient_location(top_left, Rotation::Deg180, Flip::empty(), false)
+        );
+        assert_eq!(
+            pixel(0, 7),
+            orient_location(top_left, Rotation::Deg270, Flip::empty(), false)
+        );
+    }
+
+    #[test]
+    fn flip_before_rotation() {
+        // Mirrored to the top-right, then rotated to the bottom-right.
+        assert_eq!(
+            pixel(7, 7),
+            orient_location(pixel(0, 0), Rotation::Deg90, Flip::HORIZONTAL, false)
+        );
+        assert_eq!(
+            pixel(2, 6),
+            orient_location(pixel(2, 1), Rotation::Deg0, Flip::VERTICAL, false)
+        );
+    }
+
+    #[test]
+    fn odd_rows_unchanged() {
+        let location = LedLocation::new(3, 5).unwrap();
+
+        assert_eq!(
+            location,
+            orient_location(location, Rotation::Deg90, Flip::all(), false)
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut rows = [DisplayData::COMMON_NONE; ROWS_SIZE];
+        rows[0] = DisplayData::from_bits_truncate(0b1000_0011);
+        rows[6] = DisplayData::from_bits_truncate(0b0101_0000);
+        rows[7] = DisplayData::all();
+
+        for &rotation in [
+            Rotation::Deg0,
+            Rotation::Deg90,
+            Rotation::Deg180,
+            Rotation::Deg270,
+        ]
+        .iter()
+        {
+            for &flip in [Flip::empty(), Flip::HORIZONTAL, Flip::all()].iter() {
+                let oriented = orient(&rows, rotation, flip, false);
+                assert_eq!(rows, orient(&oriented, rotation, flip, true));
+            }
+        }
+    }
+}
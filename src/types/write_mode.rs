@@ -0,0 +1,26 @@
+/// Whether [set_led()](struct.HT16K33.html#method.set_led) writes to the chip, see
+/// [set_write_mode()](struct.HT16K33.html#method.set_write_mode).
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum WriteMode {
+    /// Write the row of the changed LED to the chip right away.
+    ///
+    /// *This is the default.*
+    #[default]
+    WriteThrough,
+    /// Only update the display buffer, written by the next [flush()](struct.HT16K33.html#method.flush).
+    Buffered,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default() {
+        assert_eq!(
+            WriteMode::WriteThrough,
+            WriteMode::default(),
+            "WriteMode default is WriteThrough"
+        );
+    }
+}
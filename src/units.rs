@@ -0,0 +1,251 @@
+//! # units
+//!
+//! Format measured values with a unit for segment displays, auto-ranged with an SI prefix when they do not fit,
+//! e.g. `12345` as `12.3k` and `1234.5` volts as `1.2kV`.
+//!
+//! [format_value()](fn.format_value.html) only produces the text, for any number of digits, and
+//! [SevenSegment::display_value()](../seven_segment/struct.SevenSegment.html#method.display_value) shows it on a
+//! 4-digit 7-segment display, and
+//! [SixteenSegment::display_value()](../sixteen_segment/struct.SixteenSegment.html#method.display_value) on an
+//! alphanumeric display. A decimal point shares the digit before it, as on the 7-segment displays.
+use crate::errors::ValidationError;
+use crate::seven_segment::Rounding;
+
+use core::fmt;
+
+/// The largest number of digits of a [`FormattedValue`](struct.FormattedValue.html).
+pub const MAX_WIDTH: usize = 8;
+
+// The SI prefixes of each power of 1000, after the first.
+const PREFIXES: [char; 3] = ['k', 'M', 'G'];
+
+// The longest text: a digit per position, and a decimal point.
+const MAX_TEXT_SIZE: usize = MAX_WIDTH + 1;
+
+/// The unit of a value, shown after the value and its SI prefix.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Unit {
+    /// No unit.
+    ///
+    /// *This is the default.*
+    #[default]
+    None,
+    /// A unit shown as a single character, e.g. `'C'` for degrees or `'V'` for volts.
+    Symbol(char),
+}
+
+impl Unit {
+    // Return the number of digits used by the unit.
+    fn width(self) -> usize {
+        match self {
+            Unit::None => 0,
+            Unit::Symbol(_) => 1,
+        }
+    }
+}
+
+/// The text of a formatted value, see [format_value()](fn.format_value.html).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct FormattedValue {
+    text: [char; MAX_TEXT_SIZE],
+    len: usize,
+}
+
+impl FormattedValue {
+    /// Return the characters of the text, left to right.
+    pub fn chars(&self) -> &[char] {
+        &self.text[..self.len]
+    }
+
+    /// Return the number of digits used by the text, not counting the decimal point.
+    pub fn width(&self) -> usize {
+        self.chars().iter().filter(|&&c| c != '.').count()
+    }
+
+    fn push(&mut self, c: char) {
+        self.text[self.len] = c;
+        self.len += 1;
+    }
+}
+
+impl fmt::Display for FormattedValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for c in self.chars() {
+            write!(f, "{}", c)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Format the `value` with its `unit` on at most `width` digits, with as many decimals as fit.
+///
+/// Values that do not fit are divided by `1000` for each of the SI prefixes `k`, `M` and `G`, which takes one more
+/// digit, until they fit. The value is rounded to the last shown digit with the given `rounding`.
+///
+/// # Errors
+///
+/// If the `width` is larger than [`MAX_WIDTH`] then [`ValidationError::ValueTooLarge`] is returned. If the value
+/// does not fit even with the last prefix, or is not a number, then [`ValidationError::Overflow`] is returned.
+///
+/// # Example
+///
+/// ```
+/// use ht16k33::seven_segment::Rounding;
+/// use ht16k33::units::{format_value, Unit};
+/// # fn main() -> Result<(), ht16k33::ValidationError> {
+///
+/// let text = format_value(12_345.0, Unit::None, 4, Rounding::HalfUp)?;
+/// assert_eq!("12.3k", format!("{}", text));
+///
+/// let text = format_value(21.46, Unit::Symbol('C'), 4, Rounding::HalfUp)?;
+/// assert_eq!("21.5C", format!("{}", text));
+///
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`MAX_WIDTH`]: constant.MAX_WIDTH.html
+/// [`ValidationError::ValueTooLarge`]: ../enum.ValidationError.html#variant.ValueTooLarge
+/// [`ValidationError::Overflow`]: ../enum.ValidationError.html#variant.Overflow
+pub fn format_value(
+    value: f32,
+    unit: Unit,
+    width: usize,
+    rounding: Rounding,
+) -> Result<FormattedValue, ValidationError> {
+    if width > MAX_WIDTH {
+        return Err(ValidationError::ValueTooLarge {
+            name: "width",
+            value: width.min(u8::MAX as usize) as u8,
+            limit: MAX_WIDTH as u8,
+            inclusive: true,
+        });
+    }
+
+    let mut scaled = value;
+    for scale in 0..=PREFIXES.len() {
+        let prefix = scale.checked_sub(1).map(|index| PREFIXES[index]);
+        let digits = width.saturating_sub(unit.width() + prefix.map_or(0, |_| 1));
+
+        if let Some(mut text) = format_number(scaled, digits, rounding) {
+            if let Some(prefix) = prefix {
+                text.push(prefix);
+            }
+            if let Unit::Symbol(symbol) = unit {
+                text.push(symbol);
+            }
+
+            return Ok(text);
+        }
+
+        scaled /= 1000.0;
+    }
+
+    Err(ValidationError::Overflow {
+        name: "value",
+        digits: width as u8,
+    })
+}
+
+// Format the `value` on at most `width` digits with as many decimals as fit, including a digit for the minus
+// sign and one before the decimal point, or return `None` if it does not fit.
+fn format_number(value: f32, width: usize, rounding: Rounding) -> Option<FormattedValue> {
+    for decimals in (0..width).rev() {
+        // `core` lacks `f32::powi()`, scale by hand.
+        let mut scaled = value;
+        for _ in 0..decimals {
+            scaled *= 10.0;
+        }
+
+        let rounded = rounding.round(scaled);
+        // Compare against the bounds first so the cast cannot saturate, this also rejects NaN.
+        if !(rounded > -1_000_000_000.0 && rounded < 1_000_000_000.0) {
+            continue;
+        }
+        let rounded = rounded as i32;
+
+        let mut digits = [0u8; MAX_WIDTH];
+        let mut len = 0;
+        let mut remaining = rounded.unsigned_abs();
+        while len < MAX_WIDTH && (remaining > 0 || len <= decimals) {
+            digits[len] = (remaining % 10) as u8;
+            remaining /= 10;
+            len += 1;
+        }
+
+        let sign = if rounded < 0 { 1 } else { 0 };
+        if remaining > 0 || len + sign > width {
+            continue;
+        }
+
+        let mut text = FormattedValue {
+            text: [' '; MAX_TEXT_SIZE],
+            len: 0,
+        };
+        if rounded < 0 {
+            text.push('-');
+        }
+        for index in (0..len).rev() {
+            text.push((b'0' + digits[index]) as char);
+            if index == decimals && decimals > 0 {
+                text.push('.');
+            }
+        }
+
+        return Some(text);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::{String, ToString};
+
+    fn format(value: f32, unit: Unit, width: usize) -> String {
+        format_value(value, unit, width, Rounding::HalfUp)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn decimals() {
+        assert_eq!("1.000", format(1.0, Unit::None, 4));
+        assert_eq!("-1.25", format(-1.25, Unit::None, 4));
+        assert_eq!("0.050", format(0.05, Unit::None, 4));
+        assert_eq!("9999", format(9999.0, Unit::None, 4));
+        assert_eq!("-999", format(-999.0, Unit::None, 4));
+        assert_eq!("12.5", format(12.46, Unit::None, 3));
+    }
+
+    #[test]
+    fn prefixes() {
+        assert_eq!("10.0k", format(9999.9, Unit::None, 4));
+        assert_eq!("12.3k", format(12_345.0, Unit::None, 4));
+        assert_eq!("-1.2k", format(-1234.0, Unit::None, 4));
+        assert_eq!("1.23M", format(1_234_567.0, Unit::None, 4));
+        assert_eq!("12345", format(12_345.0, Unit::None, 5));
+    }
+
+    #[test]
+    fn symbol() {
+        assert_eq!("21.5C", format(21.46, Unit::Symbol('C'), 4));
+        assert_eq!("1.2kV", format(1234.5, Unit::Symbol('V'), 4));
+        assert_eq!(
+            4,
+            format_value(1234.5, Unit::Symbol('V'), 4, Rounding::HalfUp)
+                .unwrap()
+                .width()
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        assert!(format_value(1e12, Unit::None, 4, Rounding::HalfUp).is_err());
+        assert!(format_value(f32::NAN, Unit::None, 4, Rounding::HalfUp).is_err());
+        assert!(format_value(1.0, Unit::Symbol('V'), 1, Rounding::HalfUp).is_err());
+        assert!(format_value(1.0, Unit::None, MAX_WIDTH + 1, Rounding::HalfUp).is_err());
+    }
+}
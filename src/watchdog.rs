@@ -0,0 +1,186 @@
+//! # watchdog
+//!
+//! Helpers for firmware that supervises its display task with a hardware watchdog.
+//!
+//! A [`Watchdog`](struct.Watchdog.html) records each successful flush, optionally calling a callback to pet
+//! the hardware watchdog, and flags when flushes stall for longer than a configured timeout.
+use crate::content::elapsed;
+use crate::hal::blocking::i2c::Write;
+use crate::HT16K33;
+
+/// Tracks successful flushes of a display task.
+///
+/// # Example
+///
+/// ```
+/// # use failure::Error;
+/// # use ht16k33::i2c_mock::I2cMock;
+/// use ht16k33::HT16K33;
+/// use ht16k33::watchdog::Watchdog;
+/// use std::cell::Cell;
+/// # fn main() -> Result<(), Error> {
+/// # let mut i2c = I2cMock::new();
+/// # let address = ht16k33::Address::DEFAULT;
+///
+/// let mut ht16k33 = HT16K33::new(i2c, address).initialize()?;
+///
+/// let pets = Cell::new(0);
+/// let mut watchdog = Watchdog::with_callback(500, || pets.set(pets.get() + 1));
+///
+/// watchdog.flush(&mut ht16k33, 0)?;
+/// assert!(!watchdog.is_stalled(400));
+/// assert!(watchdog.is_stalled(600));
+///
+/// assert_eq!(1, pets.get());
+///
+/// # Ok(())
+/// # }
+/// ```
+pub struct Watchdog<F = fn()> {
+    // Called after each successful flush.
+    callback: Option<F>,
+
+    // Milliseconds without a flush before the display task is considered stalled.
+    timeout: u32,
+
+    // When the last flush succeeded, `None` until the first flush or `reset()`.
+    last_flush: Option<u32>,
+}
+
+impl Watchdog {
+    /// Create a `Watchdog` without a callback.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - Milliseconds without a flush before the display task is considered stalled.
+    pub fn new(timeout: u32) -> Self {
+        Watchdog {
+            callback: None,
+            timeout,
+            last_flush: None,
+        }
+    }
+}
+
+impl<F> Watchdog<F>
+where
+    F: FnMut(),
+{
+    /// Create a `Watchdog` that calls `callback` after each successful flush, e.g. to pet a hardware watchdog.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - Milliseconds without a flush before the display task is considered stalled.
+    /// * `callback` - Called after each successful flush.
+    pub fn with_callback(timeout: u32, callback: F) -> Self {
+        Watchdog {
+            callback: Some(callback),
+            timeout,
+            last_flush: None,
+        }
+    }
+
+    /// Return the stall timeout, in milliseconds.
+    pub fn timeout(&self) -> u32 {
+        self.timeout
+    }
+
+    /// Return when the last flush succeeded, if any.
+    pub fn last_flush(&self) -> Option<u32> {
+        self.last_flush
+    }
+
+    /// Start the stall timeout at `now` without calling the callback.
+    ///
+    /// Call this when the display task starts, so that a task that never flushes is also flagged.
+    pub fn reset(&mut self, now: u32) {
+        self.last_flush = Some(now);
+    }
+
+    /// Record a successful flush at `now`, and call the callback.
+    ///
+    /// Use this after flushing by other means, e.g. after a successful
+    /// [Pipeline::tick()](../pipeline/struct.Pipeline.html#method.tick).
+    pub fn flushed(&mut self, now: u32) {
+        self.last_flush = Some(now);
+
+        if let Some(callback) = self.callback.as_mut() {
+            callback();
+        }
+    }
+
+    /// Return whether no flush succeeded for at least the timeout.
+    ///
+    /// Never stalled before the first flush or [reset()](struct.Watchdog.html#method.reset).
+    pub fn is_stalled(&self, now: u32) -> bool {
+        match self.last_flush {
+            Some(last_flush) => elapsed(now, last_flush, self.timeout),
+            None => false,
+        }
+    }
+
+    /// Flush the `driver`, recording the flush if it succeeded.
+    ///
+    /// See [HT16K33::flush()](../struct.HT16K33.html#method.flush).
+    pub fn flush<I2C, E>(&mut self, driver: &mut HT16K33<I2C>, now: u32) -> Result<(), E>
+    where
+        I2C: Write<Error = E>,
+    {
+        driver.flush()?;
+        self.flushed(now);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c_mock::I2cMock;
+    use crate::types::Address;
+    use core::cell::Cell;
+
+    #[test]
+    fn not_stalled_before_first_flush() {
+        let watchdog = Watchdog::new(100);
+
+        assert_eq!(None, watchdog.last_flush());
+        assert!(!watchdog.is_stalled(1_000_000));
+    }
+
+    #[test]
+    fn reset() {
+        let calls = Cell::new(0);
+        let mut watchdog = Watchdog::with_callback(100, || calls.set(calls.get() + 1));
+
+        watchdog.reset(1000);
+        assert!(!watchdog.is_stalled(1099));
+        assert!(watchdog.is_stalled(1100));
+
+        assert_eq!(0, calls.get(), "reset does not call the callback");
+    }
+
+    #[test]
+    fn stall_wraps() {
+        let mut watchdog = Watchdog::new(100);
+
+        watchdog.flushed(u32::MAX - 10);
+        assert!(!watchdog.is_stalled(50));
+        assert!(watchdog.is_stalled(90));
+    }
+
+    #[test]
+    fn flush() {
+        let calls = Cell::new(0);
+        let mut watchdog = Watchdog::with_callback(100, || calls.set(calls.get() + 1));
+        let mut driver = HT16K33::new(I2cMock::new(), Address::DEFAULT)
+            .initialize()
+            .unwrap();
+
+        watchdog.flush(&mut driver, 10).unwrap();
+        watchdog.flush(&mut driver, 20).unwrap();
+        assert_eq!(Some(20), watchdog.last_flush());
+
+        assert_eq!(2, calls.get());
+    }
+}